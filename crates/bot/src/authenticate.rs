@@ -21,10 +21,9 @@ pub struct Authenticator {
 impl Authenticator {
     pub async fn authenticate() -> Result<Self> {
         if std::env::var(AUTH_CLEAN_VAR).map_or(false, |s| s == "1") {
-            let Ok(_) = std::fs::remove_file(OAUTH_TOKEN_CACHE_PATH)
-                else {
-                    panic!("Could not remove the cache file at {OAUTH_TOKEN_CACHE_PATH}.");
-                };
+            let Ok(_) = std::fs::remove_file(OAUTH_TOKEN_CACHE_PATH) else {
+                panic!("Could not remove the cache file at {OAUTH_TOKEN_CACHE_PATH}.");
+            };
         }
         let secret = yup_oauth2::read_application_secret(APPLICATION_SECRET_PATH)
             .await
@@ -1,13 +1,19 @@
 use crate::{common::Data, lib::error::Error};
 
 mod autocomplete;
-mod embeds;
+mod availability;
+pub(crate) mod embeds;
+mod feedback;
 mod forms;
 mod loadmentors;
+mod mentor;
 mod modal;
 mod modals;
+mod notifications;
 mod ping;
+mod programman;
 mod register;
+mod reschedule;
 mod restart;
 mod schedule;
 mod sessionman;
@@ -17,11 +23,18 @@ mod testform;
 mod unschedule;
 mod userman;
 mod userup;
+mod waitlist;
 
+pub use availability::availability;
+pub use feedback::feedback;
 pub use loadmentors::loadmentors;
+pub use mentor::mentor;
 pub use modal::modal;
+pub use notifications::notifications;
 pub use ping::ping;
+pub use programman::programman;
 pub use register::register;
+pub use reschedule::reschedule;
 pub use restart::restart;
 pub use schedule::schedule;
 pub use sessionman::sessionman;
@@ -31,6 +44,7 @@ pub use testform::testform;
 pub use unschedule::unschedule;
 pub use userman::userman;
 pub use userup::userup;
+pub use waitlist::waitlist;
 
 pub fn get_commands() -> Vec<poise::Command<Data, Error>> {
     vec![
@@ -47,5 +61,12 @@ pub fn get_commands() -> Vec<poise::Command<Data, Error>> {
         sessions(),
         sessionman(),
         schedule(),
+        reschedule(),
+        mentor(),
+        availability(),
+        programman(),
+        feedback(),
+        waitlist(),
+        notifications(),
     ]
 }
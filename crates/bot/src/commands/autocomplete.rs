@@ -1,6 +1,9 @@
 //! Autocompletion methods.
 
-use crate::common::Context;
+use crate::{common::Context, lib::model::Timezone};
+
+/// The maximum amount of choices Discord accepts for autocompletion.
+const MAX_AUTOCOMPLETE_CHOICES: usize = 25;
 
 /// Autocomplete a user's session IDs as student.
 pub async fn autocomplete_student_sessions(
@@ -8,11 +11,15 @@ pub async fn autocomplete_student_sessions(
     partial: &str,
     only_active: bool,
 ) -> Vec<u32> {
-    let Ok(sessions) = ctx.data()
-        .db.session_repository()
+    let Ok(sessions) = ctx
+        .data()
+        .db
+        .session_repository()
         .find_student_autocomplete(ctx.author().id.into(), partial, only_active)
         .await
-        .map_err(|err| tracing::warn!("Student session autocomplete couldn't talk with the DB: {err}."))
+        .map_err(|err| {
+            tracing::warn!("Student session autocomplete couldn't talk with the DB: {err}.")
+        })
     else {
         return Vec::new();
     };
@@ -32,3 +39,88 @@ pub async fn autocomplete_any_student_sessions(ctx: Context<'_>, partial: &str)
 pub async fn autocomplete_active_student_sessions(ctx: Context<'_>, partial: &str) -> Vec<u32> {
     autocomplete_student_sessions(ctx, partial, true).await
 }
+
+/// Autocomplete the IDs of the upcoming sessions of the mentor linked to the user.
+pub async fn autocomplete_mentor_sessions(ctx: Context<'_>, partial: &str) -> Vec<u32> {
+    let db = &ctx.data().db;
+    let sessions = async {
+        let Some(teacher) = db
+            .teacher_repository()
+            .find_by_discord_id(ctx.author().id.into())
+            .await?
+        else {
+            return Ok(Vec::new());
+        };
+
+        db.session_repository()
+            .find_upcoming_by_teacher_with_students(teacher.id)
+            .await
+    }
+    .await;
+
+    let Ok(sessions) = sessions.map_err(|err: crate::lib::error::Error| {
+        tracing::warn!("Mentor session autocomplete couldn't talk with the DB: {err}.")
+    }) else {
+        return Vec::new();
+    };
+
+    sessions
+        .into_iter()
+        .map(|(session, _)| session.id as u32)
+        .filter(|id| id.to_string().starts_with(partial))
+        .collect()
+}
+
+/// Autocomplete the IDs of the sessions the user attended as student or, if their Discord
+/// account is linked to a mentor, the IDs of that mentor's sessions.
+pub async fn autocomplete_participant_sessions(ctx: Context<'_>, partial: &str) -> Vec<u32> {
+    let db = &ctx.data().db;
+    let sessions = async {
+        let mut sessions = db
+            .session_repository()
+            .find_student_autocomplete(ctx.author().id.into(), partial, false)
+            .await?;
+
+        if let Some(teacher) = db
+            .teacher_repository()
+            .find_by_discord_id(ctx.author().id.into())
+            .await?
+        {
+            sessions.extend(
+                db.session_repository()
+                    .find_by_teacher(teacher.id)
+                    .await?
+                    .into_iter()
+                    .filter(|session| session.id.to_string().starts_with(partial)),
+            );
+        }
+
+        Ok(sessions)
+    }
+    .await;
+
+    let Ok(mut sessions) = sessions.map_err(|err: crate::lib::error::Error| {
+        tracing::warn!("Participant session autocomplete couldn't talk with the DB: {err}.")
+    }) else {
+        return Vec::new();
+    };
+
+    // the latest sessions first, as they are the most likely to be picked
+    sessions.sort_unstable_by_key(|session| std::cmp::Reverse((session.start_at, session.id)));
+    let mut ids = sessions
+        .into_iter()
+        .map(|session| session.id as u32)
+        .collect::<Vec<_>>();
+    // a mentor may also have attended their own session as student
+    ids.dedup();
+    ids.truncate(MAX_AUTOCOMPLETE_CHOICES);
+    ids
+}
+
+/// Autocomplete the names of the timezones containing the partial input.
+pub async fn autocomplete_timezones(_ctx: Context<'_>, partial: &str) -> Vec<String> {
+    Timezone::search(partial)
+        .take(MAX_AUTOCOMPLETE_CHOICES)
+        .map(|timezone| timezone.name().to_owned())
+        .collect()
+}
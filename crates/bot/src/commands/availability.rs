@@ -0,0 +1,362 @@
+use super::{
+    forms::availability::{
+        availability_label, AddAvailabilityForm, AvailabilityFormData, ExpireAvailabilityForm,
+    },
+    mentor::resolve_target_teacher,
+};
+use crate::{
+    common::{ApplicationContext, Context},
+    forms::InteractionForm,
+    lib::{
+        error::Result,
+        util::{self, tr, HumanParseableDate},
+    },
+};
+
+const AVAILABILITIES_PER_PAGE: usize = 10;
+
+/// Manages a mentor's weekly availability.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    name_localized("pt-BR", "disponibilidade"),
+    description_localized("pt-BR", "Gerencia a disponibilidade semanal de um mentor."),
+    subcommands("list", "add", "expire", "window")
+)]
+pub async fn availability(ctx: Context<'_>) -> Result<()> {
+    ctx.send(|reply| {
+        reply
+            .content(tr!("commands.general.specify_subcommand", ctx = ctx))
+            .ephemeral(true)
+    })
+    .await?;
+    Ok(())
+}
+
+/// Lists a mentor's weekly availability.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    name_localized("pt-BR", "listar"),
+    description_localized("pt-BR", "Lista a disponibilidade semanal de um mentor.")
+)]
+pub async fn list(
+    ctx: ApplicationContext<'_>,
+
+    #[description = "Email of the mentor (admins only; defaults to yourself)"]
+    #[description_localized("pt-BR", "E-mail do mentor (apenas admins; por padrão, você).")]
+    mentor_email: Option<String>,
+) -> Result<()> {
+    let Some(teacher) = resolve_target_teacher(ctx, mentor_email).await? else {
+        return Ok(());
+    };
+
+    let mut availabilities = ctx
+        .data
+        .db
+        .availability_repository()
+        .find_by_teacher(&teacher)
+        .await?;
+
+    if availabilities.is_empty() {
+        ctx.say(tr!("commands.availability.list.no_availabilities", ctx = ctx, "mentor" => teacher.name))
+            .await?;
+        return Ok(());
+    }
+
+    availabilities.sort_unstable_by_key(|avail| (avail.expired, avail.weekday, avail.time_start));
+
+    let locale = util::locale::get_defaulted_app_ctx_locale(ctx);
+    let pages = availabilities
+        .chunks(AVAILABILITIES_PER_PAGE)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .map(|availability| {
+                    let entry_key = if availability.expired {
+                        "commands.availability.list.expired_entry"
+                    } else {
+                        "commands.availability.list.entry"
+                    };
+                    tr!(
+                        entry_key,
+                        ctx = ctx,
+                        "id" => availability.id,
+                        "availability" => availability_label(availability, locale),
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect::<Vec<_>>();
+
+    let page_count = pages.len();
+    let titles = (1..=page_count)
+        .map(|page| tr!("commands.availability.list.title", ctx = ctx, "mentor" => teacher.name, "page" => page, "pages" => page_count))
+        .collect::<Vec<_>>();
+
+    // convert to str references
+    let pages = pages.iter().map(|s| &**s).collect::<Vec<_>>();
+    let titles = titles.iter().map(|s| &**s).collect::<Vec<_>>();
+
+    crate::commands::forms::paginate(ctx.into(), Some(&titles), None, &pages).await?;
+
+    Ok(())
+}
+
+/// Adds a weekly time slot to a mentor's availability.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    name_localized("pt-BR", "adicionar"),
+    description_localized("pt-BR", "Adiciona um horário semanal à disponibilidade de um mentor.")
+)]
+pub async fn add(
+    ctx: ApplicationContext<'_>,
+
+    #[description = "Email of the mentor (admins only; defaults to yourself)"]
+    #[description_localized("pt-BR", "E-mail do mentor (apenas admins; por padrão, você).")]
+    mentor_email: Option<String>,
+
+    #[description = "How many students can attend each session at this time (default: 1)"]
+    #[description_localized(
+        "pt-BR",
+        "Quantos alunos podem participar de cada mentoria neste horário (padrão: 1)."
+    )]
+    #[min = 1]
+    #[max = 25]
+    capacity: Option<i16>,
+
+    #[description = "First date in which the time can be scheduled (DD/MM/YYYY)"]
+    #[description_localized(
+        "pt-BR",
+        "Primeira data em que o horário pode ser marcado (DD/MM/AAAA)."
+    )]
+    valid_from: Option<HumanParseableDate>,
+
+    #[description = "Last date in which the time can be scheduled (DD/MM/YYYY)"]
+    #[description_localized(
+        "pt-BR",
+        "Última data em que o horário pode ser marcado (DD/MM/AAAA)."
+    )]
+    valid_until: Option<HumanParseableDate>,
+) -> Result<()> {
+    let (valid_from, valid_until) = (valid_from.map(Into::into), valid_until.map(Into::into));
+    if !check_validity_window(ctx, valid_from, valid_until).await? {
+        return Ok(());
+    }
+
+    let Some(teacher) = resolve_target_teacher(ctx, mentor_email).await? else {
+        return Ok(());
+    };
+
+    ctx.defer_ephemeral().await?;
+    let form = AddAvailabilityForm::execute_with_defaults(
+        ctx,
+        AvailabilityFormData {
+            mentor_name: teacher.name.clone(),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    let new_availability =
+        form.generate_new_availability(&teacher, capacity.unwrap_or(1), valid_from, valid_until)?;
+    let availability_repository = ctx.data.db.availability_repository();
+    let locale = util::locale::get_defaulted_app_ctx_locale(ctx);
+
+    if let Some(overlapping) = availability_repository
+        .find_by_teacher(&teacher)
+        .await?
+        .into_iter()
+        .filter(|avail| !avail.expired)
+        .find(|avail| avail.overlaps(&new_availability))
+    {
+        ctx.say(tr!(
+            "commands.availability.add.overlaps",
+            ctx = ctx,
+            "id" => overlapping.id,
+            "availability" => availability_label(&overlapping, locale),
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    let availability = availability_repository.insert(&new_availability).await?;
+
+    let mut message = tr!(
+        "commands.availability.add.success",
+        ctx = ctx,
+        "id" => availability.id,
+        "availability" => availability_label(&availability, locale),
+        "mentor" => teacher.name,
+    );
+    // times outside of a program are never offered to students
+    if availability.program_id.is_none() {
+        message.push_str("\n\n");
+        message.push_str(&tr!(
+            "commands.availability.add.no_program",
+            ctx = ctx,
+            "mentor" => teacher.name,
+        ));
+    }
+    ctx.say(message).await?;
+
+    Ok(())
+}
+
+/// Expires time slots in a mentor's availability, so they can no longer be scheduled.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    name_localized("pt-BR", "expirar"),
+    description_localized(
+        "pt-BR",
+        "Expira horários da disponibilidade de um mentor, para que não possam mais ser marcados."
+    )
+)]
+pub async fn expire(
+    ctx: ApplicationContext<'_>,
+
+    #[description = "Email of the mentor (admins only; defaults to yourself)"]
+    #[description_localized("pt-BR", "E-mail do mentor (apenas admins; por padrão, você).")]
+    mentor_email: Option<String>,
+) -> Result<()> {
+    let Some(teacher) = resolve_target_teacher(ctx, mentor_email).await? else {
+        return Ok(());
+    };
+
+    let availabilities = ctx
+        .data
+        .db
+        .availability_repository()
+        .find_by_teacher(&teacher)
+        .await?
+        .into_iter()
+        .filter(|avail| !avail.expired)
+        .collect::<Vec<_>>();
+
+    if availabilities.is_empty() {
+        ctx.say(tr!("commands.availability.list.no_availabilities", ctx = ctx, "mentor" => teacher.name))
+            .await?;
+        return Ok(());
+    }
+
+    ctx.defer_ephemeral().await?;
+    let form = ExpireAvailabilityForm::execute_with_defaults(
+        ctx,
+        AvailabilityFormData {
+            mentor_name: teacher.name.clone(),
+            availabilities,
+        },
+    )
+    .await?;
+
+    let expired_count = ctx
+        .data
+        .db
+        .availability_repository()
+        .expire_by_teacher_and_ids(teacher.id, &form.select_availabilities.selected_ids)
+        .await?;
+
+    ctx.say(tr!(
+        "commands.availability.expire.success",
+        ctx = ctx,
+        "amount" => expired_count,
+        "mentor" => teacher.name,
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Sets the dates in which available times can be scheduled.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    owners_only,
+    name_localized("pt-BR", "periodo"),
+    description_localized(
+        "pt-BR",
+        "Define as datas em que os horários disponíveis podem ser marcados."
+    )
+)]
+pub async fn window(
+    ctx: ApplicationContext<'_>,
+
+    #[description = "First date in which times can be scheduled (DD/MM/YYYY; none = no limit)"]
+    #[description_localized(
+        "pt-BR",
+        "Primeira data em que os horários podem ser marcados (DD/MM/AAAA; vazio = sem limite)."
+    )]
+    valid_from: Option<HumanParseableDate>,
+
+    #[description = "Last date in which times can be scheduled (DD/MM/YYYY; none = no limit)"]
+    #[description_localized(
+        "pt-BR",
+        "Última data em que os horários podem ser marcados (DD/MM/AAAA; vazio = sem limite)."
+    )]
+    valid_until: Option<HumanParseableDate>,
+
+    #[description = "Email of the mentor whose times will be changed (defaults to all mentors)"]
+    #[description_localized(
+        "pt-BR",
+        "E-mail do mentor cujos horários serão alterados (por padrão, todos os mentores)."
+    )]
+    mentor_email: Option<String>,
+) -> Result<()> {
+    let (valid_from, valid_until) = (valid_from.map(Into::into), valid_until.map(Into::into));
+    if !check_validity_window(ctx, valid_from, valid_until).await? {
+        return Ok(());
+    }
+
+    let teacher = if let Some(mentor_email) = mentor_email {
+        let Some(teacher) = resolve_target_teacher(ctx, Some(mentor_email)).await? else {
+            return Ok(());
+        };
+        Some(teacher)
+    } else {
+        None
+    };
+
+    let updated_count = ctx
+        .data
+        .db
+        .availability_repository()
+        .set_validity_window(teacher.map(|teacher| teacher.id), valid_from, valid_until)
+        .await?;
+
+    let display_date = |date: Option<chrono::NaiveDate>| {
+        date.map(|date| util::time::day_month_year_display(&date).to_string())
+            .unwrap_or_else(|| "-".to_owned())
+    };
+
+    ctx.say(tr!(
+        "commands.availability.window.success",
+        ctx = ctx,
+        "amount" => updated_count,
+        "valid_from" => display_date(valid_from),
+        "valid_until" => display_date(valid_until),
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Checks if the given validity window's start is not after its end,
+/// warning the user otherwise.
+async fn check_validity_window(
+    ctx: ApplicationContext<'_>,
+    valid_from: Option<chrono::NaiveDate>,
+    valid_until: Option<chrono::NaiveDate>,
+) -> Result<bool> {
+    if let Some((valid_from, valid_until)) = valid_from.zip(valid_until) {
+        if valid_from > valid_until {
+            ctx.say(tr!("commands.availability.invalid_window", ctx = ctx))
+                .await?;
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
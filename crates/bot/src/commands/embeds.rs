@@ -1,11 +1,11 @@
 use poise::serenity_prelude as serenity;
 
 use crate::lib::{
-    model::{Session, Teacher},
-    util::{self, BRAZIL_TIMEZONE},
+    model::{Session, Teacher, Timezone},
+    util,
 };
 
-/// Generates an embed displaying info for a session.
+/// Generates an embed displaying info for a session, with times in the given timezone.
 /// Ensure the teacher passed was obtained together with the session,
 /// using e.g. [get_session_with_teacher].
 ///
@@ -14,6 +14,7 @@ pub fn session_embed<'embed>(
     embed: &'embed mut serenity::CreateEmbed,
     session: &Session,
     teacher: &Teacher,
+    timezone: Timezone,
     locale: Option<&str>,
     show_meet_link: bool,
 ) -> &'embed mut serenity::CreateEmbed {
@@ -23,6 +24,8 @@ pub fn session_embed<'embed>(
         meet_id,
         start_at,
         end_at,
+        status,
+        cancel_reason,
         ..
     } = session;
 
@@ -34,8 +37,9 @@ pub fn session_embed<'embed>(
         .as_ref()
         .map(|s| format!("\"{}\"", s))
         .unwrap_or("".to_string());
-    let start_at = start_at.with_timezone(&*BRAZIL_TIMEZONE);
+    let start_at = start_at.with_timezone(&timezone.0);
     let duration = end_at.signed_duration_since(start_at);
+    let status = status.to_locale_string(locale.unwrap_or("en"));
 
     let start_at_string = format!(
         "{}, {}",
@@ -45,7 +49,7 @@ pub fn session_embed<'embed>(
 
     if locale == Some("pt-BR") {
         let duration = util::locale::convert_chrono_duration_to_brazilian_string(duration);
-        let starts_at_label = if start_at < util::time::brazil_now() {
+        let starts_at_label = if start_at < chrono::Utc::now() {
             "Começou em"
         } else {
             "Começa em"
@@ -55,9 +59,14 @@ pub fn session_embed<'embed>(
             .field("Mentor", teacher_name.to_string(), true)
             .field(starts_at_label, start_at_string, false)
             .field("Duração", duration, true)
+            .field("Status", status, true)
             .description(summary)
             .color(serenity::Colour::BLITZ_BLUE);
 
+        if let Some(cancel_reason) = cancel_reason {
+            embed.field("Motivo do cancelamento", cancel_reason, false);
+        }
+
         if show_meet_link {
             embed.field(
                 "Link do Meet",
@@ -72,7 +81,7 @@ pub fn session_embed<'embed>(
         }
     } else {
         let duration = util::locale::convert_chrono_duration_to_string(duration);
-        let starts_at_label = if start_at < util::time::brazil_now() {
+        let starts_at_label = if start_at < chrono::Utc::now() {
             "Started at"
         } else {
             "Starts at"
@@ -82,9 +91,14 @@ pub fn session_embed<'embed>(
             .field("Mentor", teacher_name.to_string(), true)
             .field(starts_at_label, start_at_string, false)
             .field("Duration", duration, true)
+            .field("Status", status, true)
             .description(summary)
             .color(serenity::Colour::BLITZ_BLUE);
 
+        if let Some(cancel_reason) = cancel_reason {
+            embed.field("Cancellation reason", cancel_reason, false);
+        }
+
         if show_meet_link {
             embed.field(
                 "Meet Link",
@@ -117,7 +131,8 @@ pub fn teacher_embed<'embed>(
             })
             .field("Nome", &teacher.name, true)
             .field("E-mail", &teacher.email, true)
-            .field("Especialidade", &teacher.specialty, true);
+            .field("Especialidade", &teacher.specialty, true)
+            .field("Fuso horário", teacher.timezone.name(), true);
     } else {
         embed = embed
             .description(if let Some(bio) = &teacher.bio {
@@ -127,7 +142,8 @@ pub fn teacher_embed<'embed>(
             })
             .field("Name", &teacher.name, true)
             .field("Email", &teacher.email, true)
-            .field("Specialty", &teacher.specialty, true);
+            .field("Specialty", &teacher.specialty, true)
+            .field("Timezone", teacher.timezone.name(), true);
     }
 
     if let Some(whatsapp) = &teacher.whatsapp {
@@ -136,6 +152,9 @@ pub fn teacher_embed<'embed>(
     if let Some(linkedin) = &teacher.linkedin {
         embed = embed.field("Linkedin", linkedin, true);
     }
+    if let Some(discord_id) = &teacher.discord_id {
+        embed = embed.field("Discord", format!("<@{}>", discord_id.0), true);
+    }
 
     embed
 }
@@ -0,0 +1,89 @@
+use super::{
+    autocomplete::autocomplete_participant_sessions,
+    forms::feedback::{FeedbackForm, FeedbackFormData},
+};
+use crate::{
+    common::ApplicationContext,
+    forms::InteractionForm,
+    lib::{
+        error::Result,
+        model::DiscordId,
+        util::{self, tr},
+    },
+};
+
+/// Gives feedback on (and rates) a mentorship session after it ended.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    name_localized("pt-BR", "avaliar"),
+    description_localized("pt-BR", "Avalia uma sessão de mentoria após o seu término.")
+)]
+pub async fn feedback(
+    ctx: ApplicationContext<'_>,
+
+    #[description = "The session's number."]
+    #[description_localized("pt-BR", "O número da mentoria.")]
+    #[autocomplete = "autocomplete_participant_sessions"]
+    number: u32,
+) -> Result<()> {
+    let author_id: DiscordId = ctx.author().id.into();
+    let session_repository = ctx.data.db.session_repository();
+
+    let Some((session, teacher)) = session_repository.get_with_teacher(number as i64).await? else {
+        ctx.say(tr!("commands.sessions.info.no_such_session", ctx = ctx, "id" => number))
+            .await?;
+        return Ok(());
+    };
+
+    let from_mentor = teacher.discord_id == Some(author_id);
+    if !from_mentor
+        && !session_repository
+            .find_students(session.id)
+            .await?
+            .iter()
+            .any(|student| student.discord_id == author_id)
+    {
+        ctx.say(tr!("commands.sessions.info.not_your_session", ctx = ctx, "id" => number))
+            .await?;
+        return Ok(());
+    }
+
+    if session.status.is_cancelled() {
+        ctx.say(tr!(
+            "commands.feedback.session_cancelled",
+            ctx = ctx,
+            "status" => session.status.to_locale_string(util::locale::get_defaulted_app_ctx_locale(ctx)),
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    if session.end_at > chrono::Utc::now() {
+        ctx.say(tr!("commands.feedback.session_not_ended", ctx = ctx, "id" => number))
+            .await?;
+        return Ok(());
+    }
+
+    // no deferring here, as the form starts with a modal
+    let form = FeedbackForm::execute_with_defaults(
+        ctx,
+        FeedbackFormData {
+            session_id: session.id,
+        },
+    )
+    .await?;
+
+    let feedback = form.generate_new_feedback(session.id, author_id, from_mentor);
+    let feedback = ctx.data.db.feedback_repository().submit(&feedback).await?;
+
+    ctx.say(tr!(
+        "commands.feedback.success",
+        ctx = ctx,
+        "id" => session.id,
+        "rating" => feedback.rating,
+    ))
+    .await?;
+
+    Ok(())
+}
@@ -1,4 +1,30 @@
+use std::sync::Arc;
+
+use poise::serenity_prelude::MessageComponentInteraction;
+
+use crate::forms::{error::Result as FormResult, FormError};
+
+pub(crate) mod availability;
+pub(crate) mod feedback;
 mod paginate;
 pub(crate) mod schedule;
 
-pub(crate) use paginate::paginate;
+pub(crate) use paginate::{paginate, paginate_lazily, LazyPage};
+
+/// The maximum amount of options in a Discord select menu.
+const MAX_SELECT_MENU_OPTIONS: usize = 25;
+
+/// Given an interaction and a parser, attempts to apply the parser on the first received value.
+/// This is usually an ID or something that is inserted into a select menu option's value key.
+/// If parsing fails, an Invalid User Response error is returned.
+fn parse_interaction_response_or_error<T>(
+    interaction: Arc<MessageComponentInteraction>,
+    parser: impl FnOnce(&String) -> Option<T>,
+) -> FormResult<T> {
+    interaction
+        .data
+        .values
+        .first()
+        .and_then(parser)
+        .ok_or(FormError::InvalidUserResponse)
+}
@@ -0,0 +1,417 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use poise::serenity_prelude::MessageComponentInteraction;
+
+use super::{parse_interaction_response_or_error, MAX_SELECT_MENU_OPTIONS};
+use crate::{
+    common::{ApplicationContext, ContextualResult, Data},
+    forms::{
+        Buildable, CustomId, FormError, FormState, GenerateReply, InteractionForm,
+        MessageFormComponent, SelectMenuOptionSpec, SelectMenuSpec, SelectValue,
+    },
+    lib::{
+        error::{Error, Result},
+        model::{Availability, NewAvailability, Teacher, Weekday, MINUTES_PER_DURATION_UNIT},
+        util::{self, time::hour_minute_display, tr},
+    },
+};
+
+/// The minutes (within the selected hour) at which a new availability may start.
+const AVAILABILITY_START_MINUTES: [u32; 6] = [0, 10, 20, 30, 40, 50];
+
+/// The maximum amount of duration units a new availability may have.
+const MAX_AVAILABILITY_DURATION: i16 = 4;
+
+/// Form which asks for the weekday, the starting time and the duration
+/// of a new availability.
+#[derive(Debug, InteractionForm)]
+#[form_data(data(AvailabilityFormData), ctx(Data, Error))]
+pub(crate) struct AddAvailabilityForm {
+    #[component]
+    pub(crate) select_weekday: SelectAvailabilityWeekdayComponent,
+
+    #[component]
+    pub(crate) select_hour: SelectAvailabilityHourComponent,
+
+    #[component]
+    pub(crate) select_minute: SelectAvailabilityMinuteComponent,
+
+    #[component]
+    pub(crate) select_duration: SelectAvailabilityDurationComponent,
+}
+
+/// Form which asks for which of a mentor's availabilities should be expired.
+#[derive(Debug, InteractionForm)]
+#[form_data(data(AvailabilityFormData), ctx(Data, Error))]
+pub(crate) struct ExpireAvailabilityForm {
+    #[component]
+    pub(crate) select_availabilities: SelectAvailabilitiesToExpireComponent,
+}
+
+/// Component which allows the user to pick the weekday of a new availability.
+#[derive(Debug, Clone, GenerateReply)]
+#[form_data(data(AvailabilityFormData), ctx(Data, Error))]
+#[reply(content = (
+    tr!("commands.availability.add.please_select_weekday", ctx = context, mentor = data.mentor_name)
+), ephemeral)]
+pub(crate) struct SelectAvailabilityWeekdayComponent {
+    pub(crate) weekday: Weekday,
+}
+
+/// Component which allows the user to pick the hour at which a new availability starts.
+#[derive(Debug, Clone, GenerateReply)]
+#[form_data(data(AvailabilityFormData), ctx(Data, Error))]
+#[reply(content = (
+    tr!("commands.availability.add.please_select_hour", ctx = context)
+), ephemeral)]
+pub(crate) struct SelectAvailabilityHourComponent {
+    pub(crate) hour: u32,
+}
+
+/// Component which allows the user to pick the minute at which a new availability starts.
+#[derive(Debug, Clone, GenerateReply)]
+#[form_data(data(AvailabilityFormData), ctx(Data, Error))]
+#[reply(content = (
+    tr!("commands.availability.add.please_select_minute", ctx = context)
+), ephemeral)]
+pub(crate) struct SelectAvailabilityMinuteComponent {
+    pub(crate) minute: u32,
+}
+
+/// Component which allows the user to pick the duration of a new availability.
+#[derive(Debug, Clone, GenerateReply)]
+#[form_data(data(AvailabilityFormData), ctx(Data, Error))]
+#[reply(content = (
+    tr!("commands.availability.add.please_select_duration", ctx = context)
+), ephemeral)]
+pub(crate) struct SelectAvailabilityDurationComponent {
+    pub(crate) duration: i16,
+}
+
+/// Component which allows the user to pick one or more availabilities to expire.
+#[derive(Debug, Clone, GenerateReply)]
+#[form_data(data(AvailabilityFormData), ctx(Data, Error))]
+#[reply(content = (
+    tr!("commands.availability.expire.please_select", ctx = context, mentor = data.mentor_name)
+), ephemeral)]
+pub(crate) struct SelectAvailabilitiesToExpireComponent {
+    pub(crate) selected_ids: Vec<i64>,
+}
+
+/// Stores data while an availability form is still being constructed.
+#[derive(Debug, Default)]
+pub(crate) struct AvailabilityFormData {
+    /// The name of the mentor whose availabilities are being managed.
+    pub(crate) mentor_name: String,
+
+    /// The mentor's current (non-expired) availabilities, which may be expired.
+    pub(crate) availabilities: Vec<Availability>,
+}
+
+// --- impls ---
+
+impl AddAvailabilityForm {
+    /// Converts the user's selections into a new availability for the given teacher,
+    /// attached to their program, accepting up to 'capacity' students per session
+    /// and valid within the given dates.
+    pub fn generate_new_availability(
+        &self,
+        teacher: &Teacher,
+        capacity: i16,
+        valid_from: Option<chrono::NaiveDate>,
+        valid_until: Option<chrono::NaiveDate>,
+    ) -> Result<NewAvailability> {
+        let time_start =
+            chrono::NaiveTime::from_hms_opt(self.select_hour.hour, self.select_minute.minute, 0)
+                .ok_or_else(|| Error::Other("invalid availability time selected"))?;
+
+        Ok(NewAvailability {
+            teacher_id: teacher.id,
+            weekday: self.select_weekday.weekday,
+            time_start,
+            expired: false,
+            duration: self.select_duration.duration,
+            valid_from,
+            valid_until,
+            program_id: teacher.program_id,
+            capacity,
+        })
+    }
+}
+
+/// Generates a short description of an availability, such as "Mon 10:00 (40 minutes)"
+/// (or "Mon 10:00 (40 minutes, up to 5 students)" for group sessions),
+/// followed by its validity window, if any (e.g. "[11/09/2023 - 16/09/2023]").
+pub(crate) fn availability_label(availability: &Availability, locale: &str) -> String {
+    let weekday = availability.weekday.to_locale_shorthand_string(locale);
+    let time = hour_minute_display(availability.time_start);
+    let duration = duration_display(availability.duration, locale);
+    let duration = if availability.capacity > 1 {
+        let capacity = availability.capacity;
+        if locale == "pt-BR" {
+            format!("{duration}, até {capacity} alunos")
+        } else {
+            format!("{duration}, up to {capacity} students")
+        }
+    } else {
+        duration
+    };
+
+    if availability.valid_from.is_none() && availability.valid_until.is_none() {
+        format!("{weekday} {time} ({duration})")
+    } else {
+        let date_or_ellipsis = |date: Option<chrono::NaiveDate>| {
+            date.map(|date| util::time::day_month_year_display(&date).to_string())
+                .unwrap_or_else(|| "...".to_owned())
+        };
+        let valid_from = date_or_ellipsis(availability.valid_from);
+        let valid_until = date_or_ellipsis(availability.valid_until);
+
+        format!("{weekday} {time} ({duration}) [{valid_from} - {valid_until}]")
+    }
+}
+
+/// Displays the given amount of duration units according to the locale.
+fn duration_display(duration: i16, locale: &str) -> String {
+    let duration = chrono::Duration::minutes(i64::from(duration) * MINUTES_PER_DURATION_UNIT);
+    if locale == "pt-BR" {
+        util::locale::convert_chrono_duration_to_brazilian_string(duration)
+    } else {
+        util::locale::convert_chrono_duration_to_string(duration)
+    }
+}
+
+/// Sends the given select menu along with the component's reply.
+async fn send_select_menu<C: GenerateReply<Data, Error, AvailabilityFormData>>(
+    context: ApplicationContext<'_>,
+    data: &mut FormState<AvailabilityFormData>,
+    options: Vec<SelectMenuOptionSpec>,
+    max_values: Option<u64>,
+) -> ContextualResult<Vec<CustomId>> {
+    let custom_id = CustomId::generate();
+    let select_menu = SelectMenuSpec {
+        custom_id: custom_id.clone(),
+        options: options
+            .into_iter()
+            .map(util::apply_limits_to_select_option_spec)
+            .collect(),
+        max_values,
+        ..Default::default()
+    };
+
+    let reply = C::create_reply(context, data).await?;
+
+    context
+        .send(|b| {
+            reply.on_build(b.components(|b| {
+                b.create_action_row(|b| b.create_select_menu(|b| select_menu.on_build(b)))
+            }))
+        })
+        .await?;
+
+    Ok(vec![custom_id])
+}
+
+#[async_trait]
+impl MessageFormComponent<Data, Error, AvailabilityFormData>
+    for SelectAvailabilityWeekdayComponent
+{
+    async fn send_component(
+        context: ApplicationContext<'_>,
+        data: &mut FormState<AvailabilityFormData>,
+    ) -> ContextualResult<Vec<CustomId>> {
+        let locale = util::locale::get_defaulted_app_ctx_locale(context);
+
+        // start the week at monday
+        let options = Weekday::Monday
+            .next_7_days()
+            .into_iter()
+            .map(|weekday| SelectMenuOptionSpec {
+                label: weekday.to_locale_shorthand_string(locale).to_string(),
+                // value should be the weekday number so we can parse back later
+                value_key: SelectValue::from(i16::from(weekday).to_string()),
+                ..Default::default()
+            })
+            .collect();
+
+        send_select_menu::<Self>(context, data, options, None).await
+    }
+
+    async fn on_response(
+        _context: ApplicationContext<'_>,
+        interaction: Arc<MessageComponentInteraction>,
+        _data: &mut FormState<AvailabilityFormData>,
+    ) -> ContextualResult<Option<Box<Self>>> {
+        let weekday = parse_interaction_response_or_error(interaction, |selection| {
+            selection
+                .parse::<i16>()
+                .ok()
+                .map(Weekday::try_from)
+                .and_then(Result::ok)
+        })?;
+
+        Ok(Some(Box::new(Self { weekday })))
+    }
+}
+
+#[async_trait]
+impl MessageFormComponent<Data, Error, AvailabilityFormData> for SelectAvailabilityHourComponent {
+    async fn send_component(
+        context: ApplicationContext<'_>,
+        data: &mut FormState<AvailabilityFormData>,
+    ) -> ContextualResult<Vec<CustomId>> {
+        let options = (0..24)
+            .map(|hour| SelectMenuOptionSpec {
+                label: format!("{hour:02}h"),
+                value_key: SelectValue::from(hour.to_string()),
+                ..Default::default()
+            })
+            .collect();
+
+        send_select_menu::<Self>(context, data, options, None).await
+    }
+
+    async fn on_response(
+        _context: ApplicationContext<'_>,
+        interaction: Arc<MessageComponentInteraction>,
+        _data: &mut FormState<AvailabilityFormData>,
+    ) -> ContextualResult<Option<Box<Self>>> {
+        let hour = parse_interaction_response_or_error(interaction, |selection| {
+            selection.parse::<u32>().ok().filter(|hour| *hour < 24)
+        })?;
+
+        Ok(Some(Box::new(Self { hour })))
+    }
+}
+
+#[async_trait]
+impl MessageFormComponent<Data, Error, AvailabilityFormData> for SelectAvailabilityMinuteComponent {
+    async fn send_component(
+        context: ApplicationContext<'_>,
+        data: &mut FormState<AvailabilityFormData>,
+    ) -> ContextualResult<Vec<CustomId>> {
+        let options = AVAILABILITY_START_MINUTES
+            .into_iter()
+            .map(|minute| SelectMenuOptionSpec {
+                label: format!(":{minute:02}"),
+                value_key: SelectValue::from(minute.to_string()),
+                ..Default::default()
+            })
+            .collect();
+
+        send_select_menu::<Self>(context, data, options, None).await
+    }
+
+    async fn on_response(
+        _context: ApplicationContext<'_>,
+        interaction: Arc<MessageComponentInteraction>,
+        _data: &mut FormState<AvailabilityFormData>,
+    ) -> ContextualResult<Option<Box<Self>>> {
+        let minute = parse_interaction_response_or_error(interaction, |selection| {
+            selection
+                .parse::<u32>()
+                .ok()
+                .filter(|minute| AVAILABILITY_START_MINUTES.contains(minute))
+        })?;
+
+        Ok(Some(Box::new(Self { minute })))
+    }
+}
+
+#[async_trait]
+impl MessageFormComponent<Data, Error, AvailabilityFormData>
+    for SelectAvailabilityDurationComponent
+{
+    async fn send_component(
+        context: ApplicationContext<'_>,
+        data: &mut FormState<AvailabilityFormData>,
+    ) -> ContextualResult<Vec<CustomId>> {
+        let locale = util::locale::get_defaulted_app_ctx_locale(context);
+        let options = (1..=MAX_AVAILABILITY_DURATION)
+            .map(|duration| SelectMenuOptionSpec {
+                label: duration_display(duration, locale),
+                value_key: SelectValue::from(duration.to_string()),
+                ..Default::default()
+            })
+            .collect();
+
+        send_select_menu::<Self>(context, data, options, None).await
+    }
+
+    async fn on_response(
+        _context: ApplicationContext<'_>,
+        interaction: Arc<MessageComponentInteraction>,
+        _data: &mut FormState<AvailabilityFormData>,
+    ) -> ContextualResult<Option<Box<Self>>> {
+        let duration = parse_interaction_response_or_error(interaction, |selection| {
+            selection
+                .parse::<i16>()
+                .ok()
+                .filter(|duration| (1..=MAX_AVAILABILITY_DURATION).contains(duration))
+        })?;
+
+        Ok(Some(Box::new(Self { duration })))
+    }
+}
+
+#[async_trait]
+impl MessageFormComponent<Data, Error, AvailabilityFormData>
+    for SelectAvailabilitiesToExpireComponent
+{
+    async fn send_component(
+        context: ApplicationContext<'_>,
+        data: &mut FormState<AvailabilityFormData>,
+    ) -> ContextualResult<Vec<CustomId>> {
+        if data.availabilities.is_empty() {
+            return Err(FormError::InvalidUserResponse.into());
+        }
+
+        let locale = util::locale::get_defaulted_app_ctx_locale(context);
+
+        // Discord limits the amount of options in a select menu
+        data.availabilities
+            .sort_unstable_by_key(|avail| (avail.weekday, avail.time_start));
+        data.availabilities.truncate(MAX_SELECT_MENU_OPTIONS);
+
+        let options: Vec<_> = data
+            .availabilities
+            .iter()
+            .map(|availability| SelectMenuOptionSpec {
+                label: availability_label(availability, locale),
+                // encode the availability id in the option's value key
+                value_key: SelectValue::from(availability.id.to_string()),
+                description: Some(format!("#{}", availability.id)),
+                ..Default::default()
+            })
+            .collect();
+        let max_values = options.len() as u64;
+
+        send_select_menu::<Self>(context, data, options, Some(max_values)).await
+    }
+
+    async fn on_response(
+        _context: ApplicationContext<'_>,
+        interaction: Arc<MessageComponentInteraction>,
+        data: &mut FormState<AvailabilityFormData>,
+    ) -> ContextualResult<Option<Box<Self>>> {
+        let selected_ids = interaction
+            .data
+            .values
+            .iter()
+            .map(|selection| {
+                selection
+                    .parse::<i64>()
+                    .ok()
+                    .filter(|id| data.availabilities.iter().any(|avail| avail.id == *id))
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or(FormError::InvalidUserResponse)?;
+
+        if selected_ids.is_empty() {
+            return Err(FormError::InvalidUserResponse.into());
+        }
+
+        Ok(Some(Box::new(Self { selected_ids })))
+    }
+}
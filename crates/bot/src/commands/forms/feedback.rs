@@ -0,0 +1,198 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use poise::{serenity_prelude::MessageComponentInteraction, Modal};
+
+use super::parse_interaction_response_or_error;
+use crate::{
+    common::{ApplicationContext, ContextualResult, Data},
+    forms::{
+        Buildable, CustomId, FormError, FormState, GenerateReply, InteractionForm,
+        MessageFormComponent, ModalFormComponent, SelectMenuOptionSpec, SelectMenuSpec,
+        SelectValue,
+    },
+    lib::{
+        error::Error,
+        model::{DiscordId, NewSessionFeedback, MAX_RATING, MIN_RATING},
+        util::{self, tr},
+    },
+};
+
+/// Form which asks for an optional comment and a rating about a session.
+#[derive(Debug, InteractionForm)]
+#[form_data(data(FeedbackFormData), ctx(Data, Error))]
+pub(crate) struct FeedbackForm {
+    #[modal]
+    pub(crate) comment: FeedbackCommentModal,
+
+    #[component]
+    pub(crate) select_rating: SelectRatingComponent,
+}
+
+/// Modal which asks for an optional comment about a session.
+#[derive(Modal, Debug, Clone)]
+#[name = "Session feedback"]
+pub(crate) struct FeedbackEnglishModal {
+    #[name = "Comment (optional)"]
+    #[placeholder = "How was the session? Anything worth improving?"]
+    #[min_length = 0]
+    #[max_length = 1024]
+    #[paragraph]
+    comment: Option<String>,
+}
+
+/// Same as [`FeedbackEnglishModal`], but in portuguese.
+#[derive(Modal, Debug, Clone)]
+#[name = "Avaliação da mentoria"]
+pub(crate) struct FeedbackPortugueseModal {
+    #[name = "Comentário (opcional)"]
+    #[placeholder = "Como foi a mentoria? Algo a melhorar?"]
+    #[min_length = 0]
+    #[max_length = 1024]
+    #[paragraph]
+    comment: Option<String>,
+}
+
+/// The comment given in either version of the feedback modal
+/// (shown according to the user's locale).
+#[derive(Debug, Clone)]
+pub(crate) struct FeedbackCommentModal {
+    pub(crate) comment: Option<String>,
+}
+
+/// Component which allows the user to rate a session.
+#[derive(Debug, Clone, GenerateReply)]
+#[form_data(data(FeedbackFormData), ctx(Data, Error))]
+#[reply(content = (
+    tr!("commands.feedback.please_select_rating", ctx = context, "id" => data.session_id)
+), ephemeral)]
+pub(crate) struct SelectRatingComponent {
+    pub(crate) rating: i16,
+}
+
+/// Stores data while a feedback form is still being constructed.
+#[derive(Debug, Default)]
+pub(crate) struct FeedbackFormData {
+    /// The ID of the session being rated.
+    pub(crate) session_id: i64,
+}
+
+// --- impls ---
+
+impl FeedbackForm {
+    /// Converts the user's answers into feedback on the given session.
+    pub fn generate_new_feedback(
+        &self,
+        session_id: i64,
+        author_id: DiscordId,
+        from_mentor: bool,
+    ) -> NewSessionFeedback {
+        NewSessionFeedback {
+            session_id,
+            author_id,
+            from_mentor,
+            rating: self.select_rating.rating,
+            comment: self
+                .comment
+                .comment
+                .as_deref()
+                .map(str::trim)
+                .filter(|comment| !comment.is_empty())
+                .map(ToOwned::to_owned),
+        }
+    }
+}
+
+impl From<FeedbackEnglishModal> for FeedbackCommentModal {
+    fn from(FeedbackEnglishModal { comment }: FeedbackEnglishModal) -> Self {
+        Self { comment }
+    }
+}
+
+impl From<FeedbackPortugueseModal> for FeedbackCommentModal {
+    fn from(FeedbackPortugueseModal { comment }: FeedbackPortugueseModal) -> Self {
+        Self { comment }
+    }
+}
+
+#[async_trait]
+impl ModalFormComponent<Data, Error, FeedbackFormData> for FeedbackCommentModal {
+    type Modal = FeedbackEnglishModal;
+
+    async fn on_response(
+        modal: FeedbackEnglishModal,
+        _data: &mut FormState<FeedbackFormData>,
+    ) -> ContextualResult<Box<Self>> {
+        Ok(Box::new(modal.into()))
+    }
+
+    /// Executes either the English version of the Modal or
+    /// the Portuguese one, based on the current context locale.
+    async fn run(
+        context: ApplicationContext<'_>,
+        _data: &mut FormState<FeedbackFormData>,
+    ) -> ContextualResult<Box<Self>> {
+        let response = match context.locale() {
+            Some("pt-BR") => FeedbackPortugueseModal::execute(context)
+                .await?
+                .map(Self::from),
+            _ => FeedbackEnglishModal::execute(context)
+                .await?
+                .map(Self::from),
+        };
+
+        response
+            .map(Box::new)
+            .ok_or_else(|| FormError::NoResponse.into())
+    }
+}
+
+#[async_trait]
+impl MessageFormComponent<Data, Error, FeedbackFormData> for SelectRatingComponent {
+    async fn send_component(
+        context: ApplicationContext<'_>,
+        data: &mut FormState<FeedbackFormData>,
+    ) -> ContextualResult<Vec<CustomId>> {
+        let custom_id = CustomId::generate();
+        let select_menu = SelectMenuSpec {
+            custom_id: custom_id.clone(),
+            options: (MIN_RATING..=MAX_RATING)
+                .rev()
+                .map(|rating| SelectMenuOptionSpec {
+                    label: format!("{} ({rating})", "⭐".repeat(rating as usize)),
+                    value_key: SelectValue::from(rating.to_string()),
+                    ..Default::default()
+                })
+                .map(util::apply_limits_to_select_option_spec)
+                .collect(),
+            ..Default::default()
+        };
+
+        let reply = Self::create_reply(context, data).await?;
+
+        context
+            .send(|b| {
+                reply.on_build(b.components(|b| {
+                    b.create_action_row(|b| b.create_select_menu(|b| select_menu.on_build(b)))
+                }))
+            })
+            .await?;
+
+        Ok(vec![custom_id])
+    }
+
+    async fn on_response(
+        _context: ApplicationContext<'_>,
+        interaction: Arc<MessageComponentInteraction>,
+        _data: &mut FormState<FeedbackFormData>,
+    ) -> ContextualResult<Option<Box<Self>>> {
+        let rating = parse_interaction_response_or_error(interaction, |selection| {
+            selection
+                .parse::<i16>()
+                .ok()
+                .filter(|rating| (MIN_RATING..=MAX_RATING).contains(rating))
+        })?;
+
+        Ok(Some(Box::new(Self { rating })))
+    }
+}
@@ -1,7 +1,19 @@
 //! Taken from [poise::builtins::paginate]
-//! The only modifications include adding titles, footers and color to the embed.
+//! The only modifications include adding titles, footers and color to the embed,
+//! as well as a variant loading each page only when it is first shown.
+use std::future::Future;
+
 use poise::serenity_prelude as serenity;
 
+use crate::lib::error::Result as LibResult;
+
+/// A page shown by [`paginate_lazily`], along with the request for the page after it
+/// ('None' if this is the last page).
+pub struct LazyPage<R> {
+    pub content: String,
+    pub next: Option<R>,
+}
+
 /// # Example
 ///
 /// ```rust,no_run
@@ -131,3 +143,120 @@ pub async fn paginate<U, E>(
 
     Ok(())
 }
+
+/// Like [`paginate`], but each page is only loaded once it is first shown, by calling the
+/// given loader with the request for it (starting with the given request for the first page).
+/// As the amount of pages isn't known upfront, pages are titled "{title} (Page N)" and
+/// don't wrap around from the first page to the last one (or vice versa).
+/// Returns 'false', without sending anything, if the first page is empty.
+pub async fn paginate_lazily<U, E, R, F, Fut>(
+    ctx: poise::Context<'_, U, E>,
+    title: &str,
+    first_request: R,
+    mut load: F,
+) -> LibResult<bool>
+where
+    F: FnMut(R) -> Fut,
+    Fut: Future<Output = LibResult<LazyPage<R>>>,
+{
+    let first_page = load(first_request).await?;
+    if first_page.content.is_empty() {
+        return Ok(false);
+    }
+
+    let ctx_id = ctx.id();
+    let prev_button_id = format!("{}prev", ctx.id());
+    let next_button_id = format!("{}next", ctx.id());
+
+    let mut pages = vec![first_page.content];
+    let mut next_request = first_page.next;
+    let mut current_page = 0;
+
+    fn create_buttons<'components>(
+        b: &'components mut serenity::CreateComponents,
+        prev_button_id: &str,
+        next_button_id: &str,
+        current_page: usize,
+        is_last_page: bool,
+    ) -> &'components mut serenity::CreateComponents {
+        b.create_action_row(|b| {
+            b.create_button(|b| {
+                b.custom_id(prev_button_id)
+                    .emoji('◀')
+                    .disabled(current_page == 0)
+            })
+            .create_button(|b| {
+                b.custom_id(next_button_id)
+                    .emoji('▶')
+                    .disabled(is_last_page)
+            })
+        })
+    }
+
+    fn create_embed<'embed>(
+        b: &'embed mut serenity::CreateEmbed,
+        title: &str,
+        current_page: usize,
+        page: &str,
+    ) -> &'embed mut serenity::CreateEmbed {
+        b.title(format!("{title} (Page {})", current_page + 1))
+            .description(page)
+            .color(serenity::Color::BLITZ_BLUE)
+    }
+
+    let last = next_request.is_none();
+    ctx.send(|b| {
+        b.embed(|b| create_embed(b, title, current_page, &pages[current_page]))
+            .components(|b| create_buttons(b, &prev_button_id, &next_button_id, current_page, last))
+    })
+    .await?;
+
+    // Loop through incoming interactions with the navigation buttons
+    while let Some(press) = serenity::CollectComponentInteraction::new(ctx)
+        .filter(move |press| press.data.custom_id.starts_with(&ctx_id.to_string()))
+        // Timeout when no navigation button has been pressed for 24 hours
+        .timeout(std::time::Duration::from_secs(3600 * 24))
+        .await
+    {
+        if press.data.custom_id == next_button_id {
+            if current_page + 1 == pages.len() {
+                // the next page wasn't loaded yet
+                if let Some(request) = next_request.take() {
+                    let page = load(request).await?;
+                    // the remaining entries may have been removed in the meantime
+                    if !page.content.is_empty() {
+                        pages.push(page.content);
+                        next_request = page.next;
+                    }
+                }
+            }
+            current_page = (current_page + 1).min(pages.len() - 1);
+        } else if press.data.custom_id == prev_button_id {
+            current_page = current_page.saturating_sub(1);
+        } else {
+            // This is an unrelated button interaction
+            continue;
+        }
+
+        let last = current_page + 1 == pages.len() && next_request.is_none();
+        press
+            .create_interaction_response(ctx, |b| {
+                b.kind(serenity::InteractionResponseType::UpdateMessage)
+                    .interaction_response_data(|b| {
+                        b.embed(|b| create_embed(b, title, current_page, &pages[current_page]))
+                            .components(|b| {
+                                create_buttons(
+                                    b,
+                                    &prev_button_id,
+                                    &next_button_id,
+                                    current_page,
+                                    last,
+                                )
+                            })
+                    })
+            })
+            .await?;
+    }
+
+    Ok(true)
+}
@@ -1,10 +1,10 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use chrono::{TimeZone, Timelike};
-use once_cell::sync::Lazy;
+use chrono::Timelike;
 use poise::serenity_prelude::MessageComponentInteraction;
 
+use super::{parse_interaction_response_or_error, MAX_SELECT_MENU_OPTIONS};
 use crate::{
     common::{ApplicationContext, ContextualResult, Data},
     forms::{
@@ -13,7 +13,7 @@ use crate::{
     },
     lib::{
         error::Error,
-        model::{Availability, Teacher, Weekday},
+        model::{Availability, DiscordId, Tag, Teacher, Timezone, Weekday},
         util::{
             self,
             time::{brazil_now, hour_minute_display},
@@ -22,24 +22,16 @@ use crate::{
     },
 };
 
-// Arbitrarily restrict availabilities to between 11/09 and 16/09
-static MIN_AVAILABILITY_DATE: Lazy<chrono::DateTime<chrono::FixedOffset>> = Lazy::new(|| {
-    util::BRAZIL_TIMEZONE
-        .with_ymd_and_hms(2023, 9, 11, 0, 0, 0)
-        .unwrap()
-});
-static MAX_AVAILABILITY_DATE: Lazy<chrono::DateTime<chrono::FixedOffset>> = Lazy::new(|| {
-    util::BRAZIL_TIMEZONE
-        .with_ymd_and_hms(2023, 9, 16, 23, 59, 0)
-        .unwrap()
-});
-
 #[derive(Debug, InteractionForm)]
 #[form_data(data(ScheduleFormData), ctx(Data, Error))]
 pub(crate) struct ScheduleForm {
     #[from_data_field = "form_start_datetime"]
     pub(crate) form_start_datetime: Option<chrono::DateTime<chrono::FixedOffset>>,
 
+    #[component]
+    #[allow(dead_code)]
+    pub(crate) select_area: SelectAreaComponent,
+
     #[component]
     #[allow(dead_code)]
     pub(crate) select_mentor: SelectMentorComponent,
@@ -53,6 +45,20 @@ pub(crate) struct ScheduleForm {
     pub(crate) select_time: SelectTimeComponent,
 }
 
+/// Component which allows the user to narrow down the mentors available
+/// in the next 7 days to those of a certain area (tag).
+/// Skipped if there aren't at least two areas to choose from.
+#[derive(Debug, Clone, GenerateReply)]
+#[form_data(data(ScheduleFormData), ctx(Data, Error))]
+#[reply(content = (
+    tr!("commands.schedule.please_select_area", ctx = context)
+), ephemeral)]
+pub(crate) struct SelectAreaComponent {
+    /// The selected area, or 'None' if mentors of any area may be picked.
+    #[allow(dead_code)]
+    pub(crate) selected_tag: Option<Tag>,
+}
+
 /// Component which allows the user to pick a mentor
 /// available in the next 7 days.
 #[derive(Debug, Clone, GenerateReply)]
@@ -86,11 +92,21 @@ pub(crate) struct SelectTimeComponent {
 /// Stores data while the ScheduleForm is still being constructed.
 #[derive(Debug, Default)]
 pub(crate) struct ScheduleFormData {
+    // if set, only this mentor's available times are offered (e.g. when rescheduling)
+    only_mentor_id: Option<i64>,
+
+    // the student's timezone, in which available times are displayed to them
+    timezone: Timezone,
+
     form_start_datetime: Option<chrono::DateTime<chrono::FixedOffset>>,
 
     // updated by each component as availabilities get filtered further
     availabilities: Vec<Availability>,
 
+    // used by SelectArea to store the tags of the available mentors
+    // (pairs of mentor IDs and tags)
+    teacher_tags: Vec<(i64, Tag)>,
+
     // used by SelectTeacher to store the retrieved teachers from the DB
     available_mentors: Vec<Teacher>,
 
@@ -114,6 +130,16 @@ impl ScheduleForm {
 }
 
 impl ScheduleFormData {
+    /// Creates form data displaying times in the student's given timezone, which only
+    /// offers the given mentor's available times, if any.
+    pub(crate) fn new(timezone: Timezone, only_mentor_id: Option<i64>) -> Self {
+        Self {
+            only_mentor_id,
+            timezone,
+            ..Default::default()
+        }
+    }
+
     /// Reduces the global set of availabilities, thus refining the pool based on user input.
     /// Only keeps the availabilities for which the 'filter' function returns true.
     fn filter_availabilities(&mut self, filter: impl FnMut(&Availability) -> bool) {
@@ -135,6 +161,15 @@ impl ScheduleFormData {
         }
     }
 
+    /// Returns the timezone of the selected mentor, in which their availabilities are.
+    /// Returns a Form Error if no mentor was selected yet.
+    fn mentor_timezone(&self) -> FormResult<Timezone> {
+        self.selected_mentor
+            .as_ref()
+            .map(|mentor| mentor.timezone)
+            .ok_or(FormError::InvalidUserResponse)
+    }
+
     /// Clears the list of availabilities and returns the availability with the given ID,
     /// if it exists. Otherwise, returns a FormError.
     fn select_availability(&mut self, avail_id: i64) -> FormResult<Availability> {
@@ -165,11 +200,23 @@ async fn init_form_data(
         .find_nontaken_within_a_week_of_date(now)
         .await?;
 
-    // ensure only availabilities within the expected period are shown
-    availabilities.retain(|avail| {
-        let avail_date = avail.first_possible_date_after(&now);
-        *MIN_AVAILABILITY_DATE <= avail_date && avail_date <= *MAX_AVAILABILITY_DATE
-    });
+    if let Some(mentor_id) = data.only_mentor_id {
+        availabilities.retain(|avail| avail.teacher_id == mentor_id);
+    }
+
+    // times offered to other students in the waitlist are held for them
+    let author_id: DiscordId = context.author().id.into();
+    let held_availability_ids = context
+        .data
+        .db
+        .waitlist_repository()
+        .find_offered()
+        .await?
+        .into_iter()
+        .filter(|entry| entry.student_id != author_id)
+        .filter_map(|entry| entry.offered_availability_id)
+        .collect::<Vec<_>>();
+    availabilities.retain(|avail| !held_availability_ids.contains(&avail.id));
 
     // No mentors have time available for sessions in the next week
     if availabilities.is_empty() {
@@ -190,21 +237,6 @@ async fn init_form_data(
     Ok(())
 }
 
-/// Given an interaction and a parser, attempts to apply the parser on the first received value.
-/// This is usually an ID or something that is inserted into a select menu option's value key.
-/// If parsing fails, an Invalid User Response error is returned.
-fn parse_interaction_response_or_error<T>(
-    interaction: Arc<MessageComponentInteraction>,
-    parser: impl FnOnce(&String) -> Option<T>,
-) -> FormResult<T> {
-    interaction
-        .data
-        .values
-        .first()
-        .and_then(parser)
-        .ok_or(FormError::InvalidUserResponse)
-}
-
 async fn select_mentor_reply_content(
     context: ApplicationContext<'_>,
     data: &FormState<ScheduleFormData>,
@@ -251,9 +283,15 @@ async fn select_time_reply_content(
     data: &FormState<ScheduleFormData>,
 ) -> ContextualResult<String> {
     let sample_availability = data.availabilities.first();
+    let mentor_timezone = data.mentor_timezone()?;
     let (selected_weekday, selected_date) = sample_availability
         .zip(data.form_start_datetime.as_ref())
-        .map(|(avail, initial_date)| (avail.weekday, avail.first_possible_date_after(initial_date)))
+        .map(|(avail, initial_date)| {
+            (
+                avail.weekday,
+                avail.first_possible_date_after(mentor_timezone, initial_date),
+            )
+        })
         .ok_or_else(|| Error::Other("Couldn't get the selected date at 'schedule' form."))?;
     let date_string = util::time::day_month_display(&selected_date.date_naive());
     let weekday_string = selected_weekday
@@ -280,14 +318,167 @@ async fn select_time_reply_content(
     })
 }
 
+/// Displays when an availability (in its mentor's timezone) starts in the student's
+/// timezone, as HOUR:MINUTE, followed by the student's date if it differs from the
+/// mentor's (e.g. "02:00 (24/10)").
+fn student_time_label(
+    availability: &Availability,
+    mentor_timezone: Timezone,
+    now: &chrono::DateTime<chrono::FixedOffset>,
+    timezone: Timezone,
+) -> String {
+    let mentor_date = availability
+        .first_possible_date_after(mentor_timezone, now)
+        .date_naive();
+    let start_at = availability
+        .first_start_after(mentor_timezone, now)
+        .with_timezone(&timezone.0);
+    let time_string = hour_minute_display(start_at.time()).to_string();
+
+    if start_at.date_naive() == mentor_date {
+        time_string
+    } else {
+        let date_string = util::time::day_month_display(&start_at.date_naive());
+        format!("{time_string} ({date_string})")
+    }
+}
+
 #[async_trait]
-impl MessageFormComponent<Data, Error, ScheduleFormData> for SelectMentorComponent {
+impl MessageFormComponent<Data, Error, ScheduleFormData> for SelectAreaComponent {
+    async fn skip(
+        context: ApplicationContext<'_>,
+        data: &mut FormState<ScheduleFormData>,
+    ) -> ContextualResult<Option<Box<Self>>> {
+        init_form_data(context, data).await?;
+
+        let mut teacher_ids = data
+            .availabilities
+            .iter()
+            .map(|avail| avail.teacher_id)
+            .collect::<Vec<_>>();
+        teacher_ids.sort_unstable();
+        teacher_ids.dedup();
+
+        data.teacher_tags = context
+            .data
+            .db
+            .tag_repository()
+            .find_by_teachers(&teacher_ids)
+            .await?;
+
+        if teacher_ids.len() < 2
+            || util::iter::count_unique(data.teacher_tags.iter().map(|(_, tag)| tag.id)) < 2
+        {
+            Ok(Some(Box::new(Self { selected_tag: None })))
+        } else {
+            Ok(None)
+        }
+    }
+
     async fn send_component(
         context: ApplicationContext<'_>,
         data: &mut FormState<ScheduleFormData>,
     ) -> ContextualResult<Vec<CustomId>> {
-        init_form_data(context, data).await?;
+        let mut areas: Vec<(&Tag, usize)> =
+            util::iter::group_by_count(data.teacher_tags.iter(), |(_, tag)| tag)
+                .into_iter()
+                .collect();
+
+        // areas with the most mentors first, as not all of them may fit
+        areas.sort_unstable_by(|(a, a_count), (b, b_count)| {
+            b_count.cmp(a_count).then_with(|| a.name.cmp(&b.name))
+        });
 
+        let any_area = SelectMenuOptionSpec {
+            label: tr!("commands.schedule.any_area", ctx = context),
+            value_key: SelectValue::from("any".to_string()),
+            ..Default::default()
+        };
+        let area_options =
+            areas
+                .into_iter()
+                .take(MAX_SELECT_MENU_OPTIONS - 1)
+                .map(|(tag, mentor_count)| SelectMenuOptionSpec {
+                    label: tag.name.clone(),
+                    value_key: SelectValue::from(tag.id.to_string()),
+                    description: Some(if mentor_count == 1 {
+                        tr!("commands.schedule.one_mentor", ctx = context)
+                    } else {
+                        tr!(
+                            "commands.schedule.n_mentors",
+                            ctx = context,
+                            amount = mentor_count
+                        )
+                    }),
+                    ..Default::default()
+                });
+
+        let custom_id = CustomId::generate();
+        let select_menu = SelectMenuSpec {
+            custom_id: custom_id.clone(),
+            options: std::iter::once(any_area)
+                .chain(area_options)
+                .map(util::apply_limits_to_select_option_spec)
+                .collect(),
+            ..Default::default()
+        };
+
+        let reply =
+            <Self as GenerateReply<Data, Error, ScheduleFormData>>::create_reply(context, data)
+                .await?;
+
+        context
+            .send(|b| {
+                reply.on_build(b.components(|b| {
+                    b.create_action_row(|b| b.create_select_menu(|b| select_menu.on_build(b)))
+                }))
+            })
+            .await?;
+
+        Ok(vec![custom_id])
+    }
+
+    async fn on_response(
+        _: ApplicationContext<'_>,
+        interaction: Arc<MessageComponentInteraction>,
+        data: &mut FormState<ScheduleFormData>,
+    ) -> ContextualResult<Option<Box<Self>>> {
+        let tag_id: Option<i64> = parse_interaction_response_or_error(interaction, |value| {
+            if value == "any" {
+                Some(None)
+            } else {
+                value.parse::<i64>().ok().map(Some)
+            }
+        })?;
+
+        let Some(tag_id) = tag_id else {
+            return Ok(Some(Box::new(Self { selected_tag: None })));
+        };
+
+        let teacher_tags = std::mem::take(&mut data.teacher_tags);
+        let (teacher_ids, tags): (Vec<i64>, Vec<Tag>) = teacher_tags
+            .into_iter()
+            .filter(|(_, tag)| tag.id == tag_id)
+            .unzip();
+
+        let Some(tag) = tags.into_iter().next() else {
+            return Err(FormError::InvalidUserResponse.into());
+        };
+
+        data.filter_availabilities(|avail| teacher_ids.contains(&avail.teacher_id));
+
+        Ok(Some(Box::new(Self {
+            selected_tag: Some(tag),
+        })))
+    }
+}
+
+#[async_trait]
+impl MessageFormComponent<Data, Error, ScheduleFormData> for SelectMentorComponent {
+    async fn send_component(
+        context: ApplicationContext<'_>,
+        data: &mut FormState<ScheduleFormData>,
+    ) -> ContextualResult<Vec<CustomId>> {
         let availabilities = &data.availabilities;
 
         let unique_teachers: Vec<(i64, usize)> =
@@ -377,6 +568,8 @@ impl MessageFormComponent<Data, Error, ScheduleFormData> for SelectWeekdayCompon
         let now = data
             .form_start_datetime
             .ok_or_else(|| Error::Other("could not get the form's starting datetime"))?;
+        // weekdays are those of the mentor's timezone, in which their availabilities are
+        let now = now.with_timezone(&data.mentor_timezone()?.0);
 
         let availabilities = &data.availabilities;
 
@@ -477,6 +670,11 @@ impl MessageFormComponent<Data, Error, ScheduleFormData> for SelectTimeComponent
             return Err(FormError::Cancelled.into());
         }
 
+        // 'init_form_data' should have set this.
+        let now = data
+            .form_start_datetime
+            .ok_or_else(|| Error::Other("could not get the form's starting datetime"))?;
+
         let mut availability_times: Vec<(chrono::NaiveTime, &Availability)> = availabilities
             .iter()
             .map(|avail| (avail.time_start.with_second(0).unwrap(), avail))
@@ -485,15 +683,17 @@ impl MessageFormComponent<Data, Error, ScheduleFormData> for SelectTimeComponent
         // sort by increasing times
         availability_times.sort_unstable_by_key(|(time, _)| *time);
 
+        let timezone = data.timezone;
+        let mentor_timezone = data.mentor_timezone()?;
+
         let custom_id = CustomId::generate();
         let select_menu = SelectMenuSpec {
             custom_id: custom_id.clone(),
             options: availability_times
                 .iter()
-                .map(|(time, availability)| {
-                    let time_string = hour_minute_display(*time).to_string();
+                .map(|(_, availability)| {
                     util::apply_limits_to_select_option_spec(SelectMenuOptionSpec {
-                        label: time_string.clone(),
+                        label: student_time_label(availability, mentor_timezone, &now, timezone),
                         // encode the availability id in the option's value key
                         value_key: SelectValue::from(availability.id.to_string()),
                         description: None,
@@ -558,6 +758,10 @@ mod tests {
             time_start: chrono::NaiveTime::from_hms_opt(12, 30, 30).unwrap(),
             expired: false,
             duration: 1,
+            valid_from: None,
+            valid_until: None,
+            program_id: None,
+            capacity: 1,
         }
     }
 
@@ -576,6 +780,11 @@ mod tests {
             linkedin: None,
             comment_general: None,
             comment_experience: None,
+            discord_id: None,
+            program_id: None,
+            timezone: Default::default(),
+            max_sessions_per_week: None,
+            locale: None,
         }
     }
 
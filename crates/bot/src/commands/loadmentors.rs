@@ -1,10 +1,52 @@
 use poise::serenity_prelude::Attachment;
 
-use crate::{common::ApplicationContext, lib::error::Error};
+use crate::{
+    common::ApplicationContext,
+    lib::{error::Error, util::HumanParseableDate},
+};
 
 /// Load mentors from a CSV file
 #[poise::command(slash_command, ephemeral, owners_only)]
-pub async fn loadmentors(ctx: ApplicationContext<'_>, csv_file: Attachment) -> Result<(), Error> {
+pub async fn loadmentors(
+    ctx: ApplicationContext<'_>,
+    csv_file: Attachment,
+
+    #[description = "Name of the program the mentors will be attached to"] program: Option<String>,
+
+    #[description = "First date in which the mentors' times can be scheduled (DD/MM/YYYY)"]
+    valid_from: Option<HumanParseableDate>,
+
+    #[description = "Last date in which the mentors' times can be scheduled (DD/MM/YYYY)"]
+    valid_until: Option<HumanParseableDate>,
+) -> Result<(), Error> {
+    if let Some((valid_from, valid_until)) = valid_from.zip(valid_until) {
+        if valid_from > valid_until {
+            ctx.say("The first date in which the mentors' times can be scheduled must not be after the last one!")
+                .await?;
+
+            return Ok(());
+        }
+    }
+
+    let program_id = if let Some(program) = program {
+        let Some(program) = ctx
+            .data
+            .db
+            .program_repository()
+            .find_by_name(program.trim())
+            .await?
+        else {
+            ctx.say(format!("There is no program named '{program}'!"))
+                .await?;
+
+            return Ok(());
+        };
+
+        Some(program.id)
+    } else {
+        None
+    };
+
     let csv_contents = match csv_file.download().await {
         Ok(file_bytes) => match String::from_utf8(file_bytes) {
             Ok(contents) => contents,
@@ -28,17 +70,33 @@ pub async fn loadmentors(ctx: ApplicationContext<'_>, csv_file: Attachment) -> R
         }
     };
 
-    match crate::loadmentors::load_teachers_to_db(&csv_contents, &ctx.data.db).await {
+    match crate::loadmentors::load_teachers_to_db(
+        &csv_contents,
+        &ctx.data.db,
+        program_id,
+        valid_from.map(Into::into),
+        valid_until.map(Into::into),
+    )
+    .await
+    {
         Ok(res) => match res {
             Ok(lines) => {
                 let teacher_count = lines.len();
                 let avail_count: usize = lines
-                    .into_iter()
+                    .iter()
                     .map(|(_, availabilities)| availabilities.len())
                     .sum();
+                let no_program_count = lines
+                    .iter()
+                    .flat_map(|(_, availabilities)| availabilities)
+                    .filter(|availability| availability.program_id.is_none())
+                    .count();
 
-                ctx.say(format!("Successfully added {teacher_count} teachers from CSV, with a total of {avail_count} availabilities."))
-                    .await?;
+                let mut message = format!("Successfully added {teacher_count} teachers from CSV, with a total of {avail_count} availabilities.");
+                if no_program_count > 0 {
+                    message.push_str(&format!("\n\n**Warning:** {no_program_count} of those availabilities are not attached to any program, so students cannot schedule them until their mentors are attached to an open program (see '/programman assign')."));
+                }
+                ctx.say(message).await?;
             }
             Err(errs) => {
                 let lines = errs
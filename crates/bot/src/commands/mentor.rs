@@ -0,0 +1,985 @@
+use rand::Rng;
+
+use super::autocomplete::{autocomplete_mentor_sessions, autocomplete_timezones};
+use crate::{
+    commands::{embeds, modals::teacher::TeacherModal},
+    common::{ApplicationContext, Context, MentorLinkRequest},
+    dm::{self, SessionEvent},
+    lib::{
+        error::Result,
+        model::{
+            Availability, DiscordId, PartialTeacher, Session, SessionStatus, Teacher, Timezone,
+        },
+        util::{self, tr, HumanParseableDate},
+    },
+};
+
+const SESSIONS_PER_PAGE: usize = 10;
+
+/// How many alternative times, at most, are offered to students of cancelled sessions.
+const MAX_ALTERNATIVE_TIMES: usize = 5;
+
+/// For how long a link verification code remains valid.
+const LINK_CODE_VALIDITY_MINUTES: i64 = 15;
+
+/// How many wrong codes can be given before a link request is dropped.
+const MAX_LINK_CODE_ATTEMPTS: u8 = 5;
+
+/// Manages your mentor profile.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    description_localized("pt-BR", "Gerencia seu perfil de mentor."),
+    subcommands(
+        "link",
+        "verify",
+        "profile",
+        "edit",
+        "timezone",
+        "weeklycap",
+        "sessions",
+        "cancel",
+        "cancelrange"
+    )
+)]
+pub async fn mentor(ctx: Context<'_>) -> Result<()> {
+    ctx.send(|reply| {
+        reply
+            .content(tr!("commands.general.specify_subcommand", ctx = ctx))
+            .ephemeral(true)
+    })
+    .await?;
+    Ok(())
+}
+
+/// Requests to link your Discord account to your mentor profile.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    name_localized("pt-BR", "vincular"),
+    description_localized(
+        "pt-BR",
+        "Pede para vincular sua conta do Discord ao seu perfil de mentor."
+    )
+)]
+pub async fn link(
+    ctx: ApplicationContext<'_>,
+
+    #[description = "The email registered in your mentor profile."]
+    #[description_localized("pt-BR", "O e-mail cadastrado em seu perfil de mentor.")]
+    email: String,
+) -> Result<()> {
+    // don't timeout the interaction if this takes a bit
+    ctx.defer_ephemeral().await?;
+
+    let author_id: DiscordId = ctx.author().id.into();
+    let teacher_repository = ctx.data.db.teacher_repository();
+
+    if let Some(teacher) = teacher_repository.find_by_discord_id(author_id).await? {
+        ctx.say(tr!("commands.mentor.already_linked", ctx = ctx, "email" => teacher.email))
+            .await?;
+        return Ok(());
+    }
+
+    let Some(teacher) = teacher_repository.find_by_email(email.trim()).await? else {
+        ctx.say(tr!("commands.mentor.link.no_such_mentor", ctx = ctx, "email" => email))
+            .await?;
+        return Ok(());
+    };
+
+    if teacher.discord_id.is_some() {
+        ctx.say(
+            tr!("commands.mentor.link.mentor_already_claimed", ctx = ctx, "email" => teacher.email),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let code = format!("{:06}", rand::thread_rng().gen_range(0..1_000_000));
+
+    if let Err(err) = ctx
+        .data
+        .google
+        .email
+        .send_mentor_link_code_email(
+            &teacher,
+            &code,
+            LINK_CODE_VALIDITY_MINUTES,
+            util::locale::get_defaulted_app_ctx_locale(ctx),
+        )
+        .await
+    {
+        tracing::warn!("Couldn't send mentor link code email: {err:?}");
+        ctx.say(tr!("commands.mentor.link.email_failed", ctx = ctx))
+            .await?;
+        return Ok(());
+    }
+
+    ctx.data.mentor_link_requests.lock().await.insert(
+        author_id,
+        MentorLinkRequest {
+            teacher_id: teacher.id,
+            code,
+            expires_at: chrono::Utc::now() + chrono::Duration::minutes(LINK_CODE_VALIDITY_MINUTES),
+            failed_attempts: 0,
+        },
+    );
+
+    ctx.say(tr!(
+        "commands.mentor.link.code_sent",
+        ctx = ctx,
+        "email" => teacher.email,
+        "minutes" => LINK_CODE_VALIDITY_MINUTES
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Confirms the link of your Discord account to your mentor profile.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    name_localized("pt-BR", "verificar"),
+    description_localized(
+        "pt-BR",
+        "Confirma o vínculo da sua conta do Discord ao seu perfil de mentor."
+    )
+)]
+pub async fn verify(
+    ctx: ApplicationContext<'_>,
+
+    #[description = "The code sent to your email."]
+    #[description_localized("pt-BR", "O código enviado ao seu e-mail.")]
+    code: String,
+) -> Result<()> {
+    let author_id: DiscordId = ctx.author().id.into();
+
+    let request = {
+        let mut requests = ctx.data.mentor_link_requests.lock().await;
+        match requests.get_mut(&author_id) {
+            Some(request) if request.expires_at < chrono::Utc::now() => {
+                requests.remove(&author_id);
+                None
+            }
+            Some(request) if request.code != code.trim() => {
+                request.failed_attempts += 1;
+                let message = if request.failed_attempts >= MAX_LINK_CODE_ATTEMPTS {
+                    // prevent the code from being guessed
+                    requests.remove(&author_id);
+                    tr!("commands.mentor.verify.too_many_attempts", ctx = ctx)
+                } else {
+                    tr!("commands.mentor.verify.wrong_code", ctx = ctx)
+                };
+                ctx.say(message).await?;
+                return Ok(());
+            }
+            Some(_) => requests.remove(&author_id),
+            None => None,
+        }
+    };
+
+    let Some(request) = request else {
+        ctx.say(tr!("commands.mentor.verify.no_pending_request", ctx = ctx))
+            .await?;
+        return Ok(());
+    };
+
+    let teacher_repository = ctx.data.db.teacher_repository();
+    let Some(teacher) = teacher_repository.get(request.teacher_id).await? else {
+        ctx.say(tr!("commands.mentor.verify.no_pending_request", ctx = ctx))
+            .await?;
+        return Ok(());
+    };
+
+    if teacher.discord_id.is_some() {
+        ctx.say(
+            tr!("commands.mentor.link.mentor_already_claimed", ctx = ctx, "email" => teacher.email),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let teacher = teacher_repository
+        .link_discord_id(&teacher, author_id)
+        .await?;
+
+    // the mentor's e-mails are sent in their locale from now on
+    let teacher = teacher_repository
+        .update(
+            &teacher,
+            PartialTeacher {
+                locale: Some(ctx.locale().map(str::to_owned)),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+    ctx.say(tr!(
+        "commands.mentor.verify.success",
+        ctx = ctx,
+        "name" => teacher.name,
+        "email" => teacher.email
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Shows your mentor profile.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    name_localized("pt-BR", "perfil"),
+    description_localized("pt-BR", "Mostra seu perfil de mentor.")
+)]
+pub async fn profile(ctx: ApplicationContext<'_>) -> Result<()> {
+    let Some(teacher) = get_linked_teacher(ctx).await? else {
+        return Ok(());
+    };
+
+    ctx.send(|f| {
+        f.ephemeral(true)
+            .embed(|f| embeds::teacher_embed(f, &teacher, ctx.locale()))
+    })
+    .await?;
+
+    Ok(())
+}
+
+/// Edits your mentor profile.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    name_localized("pt-BR", "editar"),
+    description_localized("pt-BR", "Edita seu perfil de mentor.")
+)]
+pub async fn edit(ctx: ApplicationContext<'_>) -> Result<()> {
+    let Some(teacher) = get_linked_teacher(ctx).await? else {
+        return Ok(());
+    };
+
+    let Some(modal) = TeacherModal::ask_with_defaults(
+        ctx,
+        teacher.name.clone(),
+        teacher.email.clone(),
+        teacher.specialty.clone(),
+        teacher.bio.clone(),
+    )
+    .await?
+    else {
+        return Ok(());
+    };
+
+    let teacher_repository = ctx.data.db.teacher_repository();
+
+    if modal.email != teacher.email
+        && teacher_repository
+            .find_by_email(&modal.email)
+            .await?
+            .is_some()
+    {
+        ctx.say(tr!(
+            "commands.teacherman.email_already_exists",
+            ctx = ctx,
+            email = modal.email
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    let TeacherModal {
+        name,
+        email,
+        specialty,
+        bio,
+    } = modal;
+
+    let teacher = teacher_repository
+        .update(
+            &teacher,
+            PartialTeacher {
+                name: Some(name),
+                email: Some(email),
+                specialty: Some(specialty),
+                bio: Some(bio),
+                ..Default::default()
+            },
+        )
+        .await?;
+    ctx.data
+        .db
+        .tag_repository()
+        .tag_from_specialty(&teacher)
+        .await?;
+
+    ctx.say(tr!("commands.mentor.edit.success", ctx = ctx))
+        .await?;
+
+    Ok(())
+}
+
+/// Sets the timezone in which a mentor's available times are.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    name_localized("pt-BR", "fusohorario"),
+    description_localized(
+        "pt-BR",
+        "Define o fuso horário em que estão os horários disponíveis de um mentor."
+    )
+)]
+pub async fn timezone(
+    ctx: ApplicationContext<'_>,
+
+    #[name_localized("pt-BR", "fuso_horario")]
+    #[description = "The timezone (e.g. America/Sao_Paulo)"]
+    #[description_localized("pt-BR", "O fuso horário (por exemplo, America/Sao_Paulo).")]
+    #[autocomplete = "autocomplete_timezones"]
+    timezone: String,
+
+    #[description = "Email of the mentor (admins only; defaults to yourself)"]
+    #[description_localized("pt-BR", "E-mail do mentor (apenas admins; por padrão, você).")]
+    mentor_email: Option<String>,
+) -> Result<()> {
+    let Ok(new_timezone) = timezone.parse::<Timezone>() else {
+        ctx.say(tr!(
+            "commands.register.invalid_timezone",
+            ctx = ctx,
+            "timezone" => timezone,
+        ))
+        .await?;
+        return Ok(());
+    };
+
+    let Some(teacher) = resolve_target_teacher(ctx, mentor_email).await? else {
+        return Ok(());
+    };
+
+    let teacher = ctx
+        .data
+        .db
+        .teacher_repository()
+        .update(
+            &teacher,
+            PartialTeacher {
+                timezone: Some(new_timezone),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+    ctx.say(tr!(
+        "commands.mentor.timezone.success",
+        ctx = ctx,
+        "mentor" => teacher.name,
+        "timezone" => teacher.timezone,
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Sets the most sessions a mentor gives per week.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    name_localized("pt-BR", "limitesemanal"),
+    description_localized(
+        "pt-BR",
+        "Define o máximo de mentorias que um mentor dá por semana, ocultando seus demais horários."
+    )
+)]
+pub async fn weeklycap(
+    ctx: ApplicationContext<'_>,
+
+    #[name_localized("pt-BR", "maximo")]
+    #[description = "The most sessions per week (leave empty to remove the cap)"]
+    #[description_localized(
+        "pt-BR",
+        "O máximo de mentorias por semana (deixe vazio para remover o limite)."
+    )]
+    #[min = 1]
+    #[max = 100]
+    max_sessions: Option<i16>,
+
+    #[description = "Email of the mentor (admins only; defaults to yourself)"]
+    #[description_localized("pt-BR", "E-mail do mentor (apenas admins; por padrão, você).")]
+    mentor_email: Option<String>,
+) -> Result<()> {
+    let Some(teacher) = resolve_target_teacher(ctx, mentor_email).await? else {
+        return Ok(());
+    };
+
+    let teacher = ctx
+        .data
+        .db
+        .teacher_repository()
+        .update(
+            &teacher,
+            PartialTeacher {
+                max_sessions_per_week: Some(max_sessions),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+    let response = match teacher.max_sessions_per_week {
+        Some(max) => tr!(
+            "commands.mentor.weeklycap.success",
+            ctx = ctx,
+            "mentor" => teacher.name,
+            "max" => max,
+        ),
+        None => tr!(
+            "commands.mentor.weeklycap.removed",
+            ctx = ctx,
+            "mentor" => teacher.name,
+        ),
+    };
+
+    ctx.say(response).await?;
+
+    Ok(())
+}
+
+/// Lists your upcoming mentorship sessions as a mentor.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    name_localized("pt-BR", "mentorias"),
+    description_localized("pt-BR", "Lista suas próximas mentorias como mentor.")
+)]
+pub async fn sessions(ctx: ApplicationContext<'_>) -> Result<()> {
+    let Some(teacher) = get_linked_teacher(ctx).await? else {
+        return Ok(());
+    };
+
+    let sessions = ctx
+        .data
+        .db
+        .session_repository()
+        .find_upcoming_by_teacher_with_students(teacher.id)
+        .await?;
+
+    if sessions.is_empty() {
+        ctx.say(tr!("commands.mentor.sessions.no_sessions", ctx = ctx))
+            .await?;
+    } else {
+        let pages = sessions
+            .chunks(SESSIONS_PER_PAGE)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .map(|(session, student)| {
+                        let start_at = session.start_at.with_timezone(&teacher.timezone.0);
+                        tr!(
+                            "commands.mentor.sessions.session_list_entry",
+                            ctx = ctx,
+                            "id" => session.id,
+                            "date" => util::time::day_month_year_display(&start_at.date_naive()),
+                            "time" => util::time::hour_minute_display(start_at.time()),
+                            "student" => student.name,
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .collect::<Vec<_>>();
+
+        let page_count = pages.len();
+        let titles = (1..=page_count)
+            .map(|page| tr!("commands.mentor.sessions.session_list_title", ctx = ctx, "page" => page, "pages" => page_count))
+            .collect::<Vec<_>>();
+
+        // convert to str references
+        let pages = pages.iter().map(|s| &**s).collect::<Vec<_>>();
+        let titles = titles.iter().map(|s| &**s).collect::<Vec<_>>();
+
+        crate::commands::forms::paginate(ctx.into(), Some(&titles), None, &pages).await?;
+    }
+
+    Ok(())
+}
+
+/// Cancels one of your upcoming mentorship sessions as a mentor, notifying its students.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    name_localized("pt-BR", "cancelar"),
+    description_localized(
+        "pt-BR",
+        "Cancela uma de suas próximas mentorias como mentor, notificando seus alunos."
+    )
+)]
+pub async fn cancel(
+    ctx: ApplicationContext<'_>,
+
+    #[description = "The session's number."]
+    #[description_localized("pt-BR", "O número da mentoria.")]
+    #[autocomplete = "autocomplete_mentor_sessions"]
+    number: u32,
+
+    #[description = "Why the session is being cancelled (sent to its students)."]
+    #[description_localized(
+        "pt-BR",
+        "Por que a mentoria está sendo cancelada (enviado aos alunos)."
+    )]
+    reason: String,
+
+    #[description = "Whether to offer your other available times to the students (default: no)."]
+    #[description_localized(
+        "pt-BR",
+        "Se seus outros horários disponíveis devem ser oferecidos aos alunos (padrão: não)."
+    )]
+    offer_alternatives: Option<bool>,
+) -> Result<()> {
+    // don't timeout the interaction if this takes a bit
+    ctx.defer_ephemeral().await?;
+
+    let Some((session, teacher)) = ctx
+        .data
+        .db
+        .session_repository()
+        .get_with_teacher(number as i64)
+        .await?
+    else {
+        ctx.say(tr!("commands.sessions.info.no_such_session", ctx = ctx, "id" => number))
+            .await?;
+        return Ok(());
+    };
+
+    let author_id: DiscordId = ctx.author().id.into();
+    if teacher.discord_id != Some(author_id) && !ctx.data.admin_userids.contains(&ctx.author().id.0)
+    {
+        ctx.say(tr!("commands.mentor.cancel.not_your_session", ctx = ctx, "id" => number))
+            .await?;
+        return Ok(());
+    }
+
+    if session.status != SessionStatus::Scheduled {
+        ctx.say(tr!(
+            "commands.mentor.cancel.session_not_scheduled",
+            ctx = ctx,
+            "id" => number,
+            "status" => session.status.to_locale_string(util::locale::get_defaulted_app_ctx_locale(ctx)),
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    if session.start_at < chrono::Utc::now() {
+        ctx.say(tr!(
+            "commands.mentor.cancel.session_already_started",
+            ctx = ctx
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    let alternatives = if offer_alternatives.unwrap_or(false) {
+        let session_date = session
+            .start_at
+            .with_timezone(&teacher.timezone.0)
+            .date_naive();
+        find_alternative_times(ctx, &teacher, session_date, session_date).await?
+    } else {
+        Vec::new()
+    };
+
+    let response = if cancel_session_and_notify(
+        ctx,
+        &session,
+        &teacher,
+        SessionStatus::CancelledByMentor,
+        Some(&reason),
+        &alternatives,
+    )
+    .await?
+    {
+        "commands.mentor.cancel.success"
+    } else {
+        "commands.mentor.cancel.success_no_email"
+    };
+
+    ctx.say(tr!(response, ctx = ctx, "id" => number)).await?;
+
+    Ok(())
+}
+
+/// Cancels all of your upcoming mentorship sessions within a period, notifying their students.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    name_localized("pt-BR", "cancelarperiodo"),
+    description_localized(
+        "pt-BR",
+        "Cancela todas as suas próximas mentorias em um período, notificando seus alunos."
+    )
+)]
+pub async fn cancelrange(
+    ctx: ApplicationContext<'_>,
+
+    #[description = "First day whose sessions will be cancelled (DD/MM/YYYY)."]
+    #[description_localized(
+        "pt-BR",
+        "Primeiro dia cujas mentorias serão canceladas (DD/MM/AAAA)."
+    )]
+    start_date: HumanParseableDate,
+
+    #[description = "Last day whose sessions will be cancelled (DD/MM/YYYY)."]
+    #[description_localized("pt-BR", "Último dia cujas mentorias serão canceladas (DD/MM/AAAA).")]
+    end_date: HumanParseableDate,
+
+    #[description = "Why the sessions are being cancelled (sent to their students)."]
+    #[description_localized(
+        "pt-BR",
+        "Por que as mentorias estão sendo canceladas (enviado aos alunos)."
+    )]
+    reason: String,
+
+    #[description = "Whether to offer your other available times to the students (default: no)."]
+    #[description_localized(
+        "pt-BR",
+        "Se seus outros horários disponíveis devem ser oferecidos aos alunos (padrão: não)."
+    )]
+    offer_alternatives: Option<bool>,
+
+    #[description = "Email of the mentor (admins only; defaults to yourself)"]
+    #[description_localized("pt-BR", "E-mail do mentor (apenas admins; por padrão, você).")]
+    mentor_email: Option<String>,
+) -> Result<()> {
+    let (start_date, end_date): (chrono::NaiveDate, chrono::NaiveDate) =
+        (start_date.into(), end_date.into());
+    if start_date > end_date {
+        ctx.say(tr!("commands.mentor.cancel_range.invalid_dates", ctx = ctx))
+            .await?;
+        return Ok(());
+    }
+
+    let Some(teacher) = resolve_target_teacher(ctx, mentor_email).await? else {
+        return Ok(());
+    };
+
+    // don't timeout the interaction if this takes a bit
+    ctx.defer_ephemeral().await?;
+
+    // sessions which already started can't be cancelled
+    // (the dates are taken in the mentor's timezone)
+    let starts_from =
+        util::time::day_start_as_utc(start_date, &teacher.timezone.0).max(chrono::Utc::now());
+    let starts_until = util::time::day_end_as_utc(end_date, &teacher.timezone.0);
+
+    let sessions = ctx
+        .data
+        .db
+        .session_repository()
+        .find_scheduled_by_teacher_starting_between(teacher.id, starts_from, starts_until)
+        .await?;
+
+    if sessions.is_empty() {
+        ctx.say(
+            tr!("commands.mentor.cancel_range.no_sessions", ctx = ctx, "mentor" => teacher.name),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let alternatives = if offer_alternatives.unwrap_or(false) {
+        find_alternative_times(ctx, &teacher, start_date, end_date).await?
+    } else {
+        Vec::new()
+    };
+
+    // a session which can't be cancelled doesn't prevent cancelling the others
+    let mut cancelled = 0;
+    let mut failed_emails = 0;
+    let mut failed_sessions = Vec::new();
+    for session in &sessions {
+        match cancel_session_and_notify(
+            ctx,
+            session,
+            &teacher,
+            SessionStatus::CancelledByMentor,
+            Some(&reason),
+            &alternatives,
+        )
+        .await
+        {
+            Ok(notified) => {
+                cancelled += 1;
+                if !notified {
+                    failed_emails += 1;
+                }
+            }
+            Err(err) => {
+                tracing::warn!("Couldn't cancel session #{}: {err:?}", session.id);
+                failed_sessions.push(session.id);
+            }
+        }
+    }
+
+    let response = if failed_emails == 0 {
+        "commands.mentor.cancel_range.success"
+    } else {
+        "commands.mentor.cancel_range.success_some_emails_failed"
+    };
+
+    let mut message = tr!(
+        response,
+        ctx = ctx,
+        "amount" => cancelled,
+        "failed" => failed_emails,
+        "mentor" => teacher.name,
+    );
+    if !failed_sessions.is_empty() {
+        let session_ids = failed_sessions
+            .iter()
+            .map(|id| format!("#{id}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        message.push('\n');
+        message.push_str(&tr!(
+            "commands.mentor.cancel_range.some_failed",
+            ctx = ctx,
+            "sessions" => session_ids,
+        ));
+    }
+    ctx.say(message).await?;
+
+    Ok(())
+}
+
+/// Cancels a session with the given status (and reason, if any), cancelling its Google
+/// Calendar event and notifying its participants through DMs and by email. If the teacher
+/// cancelled it, its students are told the reason and the given alternative times by email;
+/// otherwise, the teacher and its students are told it was unscheduled.
+/// Returns whether the emails could be sent.
+pub(super) async fn cancel_session_and_notify(
+    ctx: ApplicationContext<'_>,
+    session: &Session,
+    teacher: &Teacher,
+    status: SessionStatus,
+    reason: Option<&str>,
+    alternatives: &[chrono::DateTime<chrono::Utc>],
+) -> Result<bool> {
+    // the event is cancelled first, so that a failure leaves the session untouched
+    ctx.data
+        .google
+        .calendar
+        .cancel_event_for_session(session)
+        .await?;
+
+    let session_repository = ctx.data.db.session_repository();
+    let reason = reason.map(str::trim).filter(|reason| !reason.is_empty());
+    let session = session_repository
+        .cancel(session, status, reason.map(str::to_owned))
+        .await?;
+
+    let students = session_repository.find_students(session.id).await?;
+
+    dm::notify_session_event(
+        &ctx.serenity_context.http,
+        &ctx.data.db,
+        SessionEvent::Cancelled,
+        &session,
+        teacher,
+        &students,
+    )
+    .await;
+
+    let email = &ctx.data.google.email;
+    let result = if status == SessionStatus::CancelledByMentor {
+        email
+            .send_mentor_cancel_emails_for_session(
+                teacher,
+                &students,
+                &session,
+                reason.unwrap_or("-"),
+                alternatives,
+            )
+            .await
+    } else {
+        let mut result = Ok(());
+        for student in &students {
+            result = result.and(
+                email
+                    .send_cancel_emails_for_session(teacher, student, &session)
+                    .await,
+            );
+        }
+        result
+    };
+
+    if let Err(err) = result {
+        tracing::warn!("Couldn't send session cancellation email: {err:?}");
+        Ok(false)
+    } else {
+        Ok(true)
+    }
+}
+
+/// Lists when the teacher's non-taken available times within the next week start, except
+/// for those occurring between the given dates (inclusive), earliest first.
+async fn find_alternative_times(
+    ctx: ApplicationContext<'_>,
+    teacher: &Teacher,
+    excluded_from: chrono::NaiveDate,
+    excluded_until: chrono::NaiveDate,
+) -> Result<Vec<chrono::DateTime<chrono::Utc>>> {
+    let now = util::time::brazil_now();
+    let availabilities = ctx
+        .data
+        .db
+        .availability_repository()
+        .find_nontaken_within_a_week_of_date(now)
+        .await?;
+
+    Ok(alternative_start_times(
+        &availabilities,
+        teacher,
+        now.with_timezone(&chrono::Utc),
+        excluded_from,
+        excluded_until,
+    ))
+}
+
+/// Lists when the given available times of the teacher next start after the given moment,
+/// except for those occurring between the given dates (inclusive), earliest first.
+/// Times whose start on the current day has already passed are not offered.
+fn alternative_start_times(
+    availabilities: &[Availability],
+    teacher: &Teacher,
+    now: chrono::DateTime<chrono::Utc>,
+    excluded_from: chrono::NaiveDate,
+    excluded_until: chrono::NaiveDate,
+) -> Vec<chrono::DateTime<chrono::Utc>> {
+    let mut alternatives = availabilities
+        .iter()
+        .filter(|avail| avail.teacher_id == teacher.id)
+        .filter_map(|avail| {
+            let date = avail
+                .first_possible_date_after(teacher.timezone, &now)
+                .date_naive();
+            let start_at = avail.first_start_after(teacher.timezone, &now);
+            (start_at > now && (date < excluded_from || excluded_until < date)).then_some(start_at)
+        })
+        .collect::<Vec<_>>();
+
+    alternatives.sort_unstable();
+    alternatives.truncate(MAX_ALTERNATIVE_TIMES);
+    alternatives
+}
+
+/// Determines the mentor to be managed: either the one with the given email, which
+/// requires the author to be an admin, or the one linked to the author.
+/// Warns the author and returns 'None' if there is no such mentor.
+pub(super) async fn resolve_target_teacher(
+    ctx: ApplicationContext<'_>,
+    mentor_email: Option<String>,
+) -> Result<Option<Teacher>> {
+    if let Some(email) = mentor_email {
+        if !ctx.data.admin_userids.contains(&ctx.author().id.0) {
+            ctx.say(tr!("commands.mentor.admins_only", ctx = ctx))
+                .await?;
+            return Ok(None);
+        }
+
+        let teacher = ctx
+            .data
+            .db
+            .teacher_repository()
+            .find_by_email(email.trim())
+            .await?;
+        if teacher.is_none() {
+            ctx.say(tr!("commands.mentor.link.no_such_mentor", ctx = ctx, "email" => email))
+                .await?;
+        }
+        Ok(teacher)
+    } else {
+        get_linked_teacher(ctx).await
+    }
+}
+
+/// Gets the mentor linked to the command's author, warning them if there is none.
+async fn get_linked_teacher(ctx: ApplicationContext<'_>) -> Result<Option<Teacher>> {
+    let teacher = ctx
+        .data
+        .db
+        .teacher_repository()
+        .find_by_discord_id(ctx.author().id.into())
+        .await?;
+
+    if teacher.is_none() {
+        ctx.say(tr!("commands.mentor.not_linked", ctx = ctx))
+            .await?;
+    }
+
+    Ok(teacher)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::lib::model::Weekday;
+
+    fn make_availability(id: i64, weekday: Weekday, hour: u32) -> Availability {
+        Availability {
+            id,
+            teacher_id: 1,
+            weekday,
+            time_start: chrono::NaiveTime::from_hms_opt(hour, 0, 0).unwrap(),
+            expired: false,
+            duration: 1,
+            valid_from: None,
+            valid_until: None,
+            program_id: None,
+            capacity: 1,
+        }
+    }
+
+    fn make_mentor() -> Teacher {
+        Teacher {
+            id: 1,
+            name: "Teacher".to_owned(),
+            email: "exists@at.com".to_owned(),
+            specialty: "Everything".to_owned(),
+            applied_at: None,
+            bio: None,
+            course_info: None,
+            company: None,
+            company_role: None,
+            whatsapp: None,
+            linkedin: None,
+            comment_general: None,
+            comment_experience: None,
+            discord_id: None,
+            program_id: None,
+            timezone: Timezone::DEFAULT,
+            max_sessions_per_week: None,
+            locale: None,
+        }
+    }
+
+    #[test]
+    fn test_alternative_start_times_skip_times_which_already_started_today() {
+        // Wednesday, 15:00 in Brasília time
+        let now = chrono::Utc.with_ymd_and_hms(2023, 9, 13, 18, 0, 0).unwrap();
+        let excluded = chrono::NaiveDate::from_ymd_opt(2000, 1, 1).unwrap();
+        let availabilities = vec![
+            make_availability(1, Weekday::Thursday, 10),
+            make_availability(2, Weekday::Wednesday, 14),
+            make_availability(3, Weekday::Wednesday, 16),
+        ];
+
+        assert_eq!(
+            alternative_start_times(&availabilities, &make_mentor(), now, excluded, excluded),
+            vec![
+                chrono::Utc.with_ymd_and_hms(2023, 9, 13, 19, 0, 0).unwrap(),
+                chrono::Utc.with_ymd_and_hms(2023, 9, 14, 13, 0, 0).unwrap(),
+            ]
+        );
+    }
+}
@@ -8,7 +8,7 @@ use crate::{
     common::ApplicationContext,
     lib::{
         error::Result,
-        model::{DiscordId, NewUser},
+        model::{DiscordId, NewUser, Timezone},
     },
 };
 
@@ -160,13 +160,21 @@ impl RegisterModal {
         })
     }
 
-    /// Converts this modal response into a 'NewUser' instance.
-    pub fn generate_new_user(self, discord_id: DiscordId) -> NewUser {
+    /// Converts this modal response into a 'NewUser' instance, with the given timezone
+    /// and locale (if known).
+    pub fn generate_new_user(
+        self,
+        discord_id: DiscordId,
+        timezone: Timezone,
+        locale: Option<String>,
+    ) -> NewUser {
         NewUser {
             discord_id,
             name: self.name,
             email: self.email,
             bio: self.bio,
+            timezone,
+            locale,
         }
     }
 
@@ -2,7 +2,7 @@ use poise::Modal;
 
 use crate::{
     common::ApplicationContext,
-    lib::{error::Result, util::HumanParseableDateTime},
+    lib::{error::Result, model::Timezone, util::HumanParseableDateTime},
 };
 
 /// Represents a Session creation modal,
@@ -87,17 +87,16 @@ impl SessionCreateModals {
         }
     }
 
-    /// Attempts to parse the given Session 'starts_at' timestamp string
-    /// as a [`chrono::DateTime`] with the [`chrono::Utc`] timezone.
+    /// Attempts to parse the given Session 'starts_at' timestamp string, as a time in
+    /// the given timezone, to a [`chrono::DateTime`] with the [`chrono::Utc`] timezone.
     ///
     /// # See also
     ///
     /// [`HumanParseableDateTime`]
-    pub fn parsed_starts_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+    pub fn parsed_starts_at(&self, timezone: Timezone) -> Option<chrono::DateTime<chrono::Utc>> {
         let starts_at = self.starts_at();
 
-        starts_at
-            .parse::<HumanParseableDateTime>()
+        HumanParseableDateTime::parse_in(starts_at, &timezone.0)
             .ok()
             .map(Into::into)
     }
@@ -152,7 +152,6 @@ impl TeacherModal {
     /// Executes either the English version of the Modal or
     /// the Portuguese one, based on the current context locale,
     /// with some defaults.
-    #[allow(dead_code)]
     pub async fn execute_with_defaults_based_on_locale(
         ctx: ApplicationContext<'_>,
         name: String,
@@ -228,6 +227,11 @@ impl TeacherModal {
             linkedin: None,
             comment_general: None,
             comment_experience: None,
+            discord_id: None,
+            program_id: None,
+            timezone: Default::default(),
+            max_sessions_per_week: None,
+            locale: None,
         }
     }
 
@@ -239,7 +243,6 @@ impl TeacherModal {
 
     /// Present the modal to the user with some defaults, and notify them of
     /// any possible validation errors.
-    #[allow(dead_code)]
     pub async fn ask_with_defaults(
         ctx: ApplicationContext<'_>,
         name: String,
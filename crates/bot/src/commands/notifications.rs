@@ -0,0 +1,44 @@
+use crate::{
+    common::ApplicationContext,
+    lib::{error::Result, model::DiscordId, util::tr},
+};
+
+/// Views or changes whether you get DMs about your sessions.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    name_localized("pt-BR", "notificacoes"),
+    description_localized("pt-BR", "Mostra ou altera se você recebe DMs sobre suas mentorias.")
+)]
+pub async fn notifications(
+    ctx: ApplicationContext<'_>,
+
+    #[description = "Whether to get DMs about your sessions (you always get e-mails)."]
+    #[description_localized(
+        "pt-BR",
+        "Se você quer receber DMs sobre suas mentorias (e-mails são sempre enviados)."
+    )]
+    dms: Option<bool>,
+) -> Result<()> {
+    let author_id: DiscordId = ctx.author().id.into();
+    let user_repository = ctx.data.db.user_repository();
+
+    let response = match dms {
+        Some(dms) => {
+            user_repository.set_dm_opt_out(author_id, !dms).await?;
+            if dms {
+                "commands.notifications.enabled"
+            } else {
+                "commands.notifications.disabled"
+            }
+        }
+        None if user_repository.has_dm_opt_out(author_id).await? => {
+            "commands.notifications.status_disabled"
+        }
+        None => "commands.notifications.status_enabled",
+    };
+
+    ctx.say(tr!(response, ctx = ctx)).await?;
+
+    Ok(())
+}
@@ -0,0 +1,405 @@
+use crate::{
+    common::{ApplicationContext, Context},
+    lib::{
+        error::Result,
+        model::{NewProgram, Program, ProgramStatus},
+        util::{self, tr, HumanParseableDate, BRAZIL_TIMEZONE},
+    },
+};
+
+const PROGRAMS_PER_PAGE: usize = 10;
+const SESSIONS_PER_PAGE: usize = 10;
+
+/// Manages mentoring programs.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    description_localized("pt-BR", "Gerencia programas de mentoria."),
+    subcommands("create", "open", "close", "archive", "assign", "list", "sessions")
+)]
+pub async fn programman(ctx: Context<'_>) -> Result<()> {
+    ctx.send(|reply| {
+        reply
+            .content(tr!("commands.general.specify_subcommand", ctx = ctx))
+            .ephemeral(true)
+    })
+    .await?;
+    Ok(())
+}
+
+/// Creates a mentoring program.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    owners_only,
+    name_localized("pt-BR", "criar"),
+    description_localized("pt-BR", "Cria um programa de mentoria.")
+)]
+async fn create(
+    ctx: ApplicationContext<'_>,
+
+    #[description = "Name of the program"]
+    #[description_localized("pt-BR", "Nome do programa.")]
+    name: String,
+
+    #[description = "Date in which the program starts (DD/MM/YYYY)"]
+    #[description_localized("pt-BR", "Data em que o programa começa (DD/MM/AAAA).")]
+    start_date: HumanParseableDate,
+
+    #[description = "Date in which the program ends (DD/MM/YYYY)"]
+    #[description_localized("pt-BR", "Data em que o programa termina (DD/MM/AAAA).")]
+    end_date: HumanParseableDate,
+
+    #[description = "Whether students can already schedule sessions (default: no)"]
+    #[description_localized(
+        "pt-BR",
+        "Se alunos já podem marcar mentorias no programa (padrão: não)."
+    )]
+    enrollment_open: Option<bool>,
+) -> Result<()> {
+    let name = name.trim().to_owned();
+    if start_date > end_date {
+        ctx.say(tr!("commands.programman.invalid_dates", ctx = ctx))
+            .await?;
+        return Ok(());
+    }
+
+    let program_repository = ctx.data.db.program_repository();
+    if program_repository.find_by_name(&name).await?.is_some() {
+        ctx.say(tr!("commands.programman.already_exists", ctx = ctx, "name" => name))
+            .await?;
+        return Ok(());
+    }
+
+    let program = program_repository
+        .insert(&NewProgram {
+            name,
+            start_date: start_date.into(),
+            end_date: end_date.into(),
+            status: ProgramStatus::Active,
+            enrollment_open: enrollment_open.unwrap_or(false),
+        })
+        .await?;
+
+    ctx.say(tr!(
+        "commands.programman.create.success",
+        ctx = ctx,
+        "name" => program.name,
+        "start_date" => util::time::day_month_year_display(&program.start_date),
+        "end_date" => util::time::day_month_year_display(&program.end_date),
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Opens a program's enrollment, allowing students to schedule sessions with its mentors.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    owners_only,
+    name_localized("pt-BR", "abrir"),
+    description_localized(
+        "pt-BR",
+        "Abre as inscrições de um programa, permitindo que alunos marquem mentorias nele."
+    )
+)]
+async fn open(
+    ctx: ApplicationContext<'_>,
+
+    #[description = "Name of the program"]
+    #[description_localized("pt-BR", "Nome do programa.")]
+    name: String,
+) -> Result<()> {
+    let Some(program) = get_program_by_name(ctx, &name).await? else {
+        return Ok(());
+    };
+
+    if program.status == ProgramStatus::Archived {
+        ctx.say(tr!("commands.programman.open.archived", ctx = ctx, "name" => program.name))
+            .await?;
+        return Ok(());
+    }
+
+    let program = ctx
+        .data
+        .db
+        .program_repository()
+        .set_enrollment_open(&program, true)
+        .await?;
+
+    ctx.say(tr!("commands.programman.open.success", ctx = ctx, "name" => program.name))
+        .await?;
+
+    Ok(())
+}
+
+/// Closes a program's enrollment, so students can no longer schedule sessions with its mentors.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    owners_only,
+    name_localized("pt-BR", "fechar"),
+    description_localized(
+        "pt-BR",
+        "Fecha as inscrições de um programa, para que alunos não possam mais marcar mentorias nele."
+    )
+)]
+async fn close(
+    ctx: ApplicationContext<'_>,
+
+    #[description = "Name of the program"]
+    #[description_localized("pt-BR", "Nome do programa.")]
+    name: String,
+) -> Result<()> {
+    let Some(program) = get_program_by_name(ctx, &name).await? else {
+        return Ok(());
+    };
+
+    let program = ctx
+        .data
+        .db
+        .program_repository()
+        .set_enrollment_open(&program, false)
+        .await?;
+
+    ctx.say(tr!("commands.programman.close.success", ctx = ctx, "name" => program.name))
+        .await?;
+
+    Ok(())
+}
+
+/// Archives a program, closing it for good. Its sessions are kept for future reference.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    owners_only,
+    name_localized("pt-BR", "arquivar"),
+    description_localized(
+        "pt-BR",
+        "Arquiva um programa, fechando-o de vez. Suas mentorias são mantidas para consulta."
+    )
+)]
+async fn archive(
+    ctx: ApplicationContext<'_>,
+
+    #[description = "Name of the program"]
+    #[description_localized("pt-BR", "Nome do programa.")]
+    name: String,
+) -> Result<()> {
+    let Some(program) = get_program_by_name(ctx, &name).await? else {
+        return Ok(());
+    };
+
+    let program = ctx.data.db.program_repository().archive(&program).await?;
+
+    ctx.say(tr!("commands.programman.archive.success", ctx = ctx, "name" => program.name))
+        .await?;
+
+    Ok(())
+}
+
+/// Attaches a mentor, along with their available times, to a program.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    owners_only,
+    name_localized("pt-BR", "vincular"),
+    description_localized(
+        "pt-BR",
+        "Vincula um mentor, junto de seus horários disponíveis, a um programa."
+    )
+)]
+async fn assign(
+    ctx: ApplicationContext<'_>,
+
+    #[description = "Name of the program"]
+    #[description_localized("pt-BR", "Nome do programa.")]
+    name: String,
+
+    #[description = "Email of the mentor"]
+    #[description_localized("pt-BR", "E-mail do mentor.")]
+    mentor_email: String,
+) -> Result<()> {
+    let Some(program) = get_program_by_name(ctx, &name).await? else {
+        return Ok(());
+    };
+
+    let Some(teacher) = ctx
+        .data
+        .db
+        .teacher_repository()
+        .find_by_email(mentor_email.trim())
+        .await?
+    else {
+        ctx.say(tr!("commands.mentor.link.no_such_mentor", ctx = ctx, "email" => mentor_email))
+            .await?;
+        return Ok(());
+    };
+
+    let availability_count = ctx
+        .data
+        .db
+        .program_repository()
+        .attach_teacher(&program, &teacher)
+        .await?;
+
+    ctx.say(tr!(
+        "commands.programman.assign.success",
+        ctx = ctx,
+        "mentor" => teacher.name,
+        "name" => program.name,
+        "amount" => availability_count,
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Lists all mentoring programs.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    owners_only,
+    name_localized("pt-BR", "listar"),
+    description_localized("pt-BR", "Lista todos os programas de mentoria.")
+)]
+async fn list(ctx: ApplicationContext<'_>) -> Result<()> {
+    let programs = ctx.data.db.program_repository().find_all().await?;
+
+    if programs.is_empty() {
+        ctx.say(tr!("commands.programman.list.no_programs", ctx = ctx))
+            .await?;
+        return Ok(());
+    }
+
+    let locale = util::locale::get_defaulted_app_ctx_locale(ctx);
+    let pages = programs
+        .chunks(PROGRAMS_PER_PAGE)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .map(|program| {
+                    let enrollment_key = if program.enrollment_open {
+                        "commands.programman.list.enrollment_open"
+                    } else {
+                        "commands.programman.list.enrollment_closed"
+                    };
+                    tr!(
+                        "commands.programman.list.entry",
+                        ctx = ctx,
+                        "name" => program.name,
+                        "start_date" => util::time::day_month_year_display(&program.start_date),
+                        "end_date" => util::time::day_month_year_display(&program.end_date),
+                        "status" => program.status.to_locale_string(locale),
+                        "enrollment" => tr!(enrollment_key, ctx = ctx),
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect::<Vec<_>>();
+
+    let page_count = pages.len();
+    let titles = (1..=page_count)
+        .map(|page| tr!("commands.programman.list.title", ctx = ctx, "page" => page, "pages" => page_count))
+        .collect::<Vec<_>>();
+
+    // convert to str references
+    let pages = pages.iter().map(|s| &**s).collect::<Vec<_>>();
+    let titles = titles.iter().map(|s| &**s).collect::<Vec<_>>();
+
+    crate::commands::forms::paginate(ctx.into(), Some(&titles), None, &pages).await?;
+
+    Ok(())
+}
+
+/// Lists all sessions held within a program, including past ones.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    owners_only,
+    name_localized("pt-BR", "mentorias"),
+    description_localized(
+        "pt-BR",
+        "Lista todas as mentorias de um programa, incluindo as passadas."
+    )
+)]
+async fn sessions(
+    ctx: ApplicationContext<'_>,
+
+    #[description = "Name of the program"]
+    #[description_localized("pt-BR", "Nome do programa.")]
+    name: String,
+) -> Result<()> {
+    let Some(program) = get_program_by_name(ctx, &name).await? else {
+        return Ok(());
+    };
+
+    let sessions = ctx
+        .data
+        .db
+        .session_repository()
+        .find_by_program_with_participants(program.id)
+        .await?;
+
+    if sessions.is_empty() {
+        ctx.say(tr!("commands.programman.sessions.no_sessions", ctx = ctx, "name" => program.name))
+            .await?;
+        return Ok(());
+    }
+
+    let locale = util::locale::get_defaulted_app_ctx_locale(ctx);
+    let pages = sessions
+        .chunks(SESSIONS_PER_PAGE)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .map(|(session, teacher, student)| {
+                    let start_at = session.start_at.with_timezone(&*BRAZIL_TIMEZONE);
+                    tr!(
+                        "commands.programman.sessions.entry",
+                        ctx = ctx,
+                        "id" => session.id,
+                        "date" => util::time::day_month_year_display(&start_at.date_naive()),
+                        "time" => util::time::hour_minute_display(start_at.time()),
+                        "mentor" => teacher.name,
+                        "student" => student.name,
+                        "status" => session.status.to_locale_string(locale),
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect::<Vec<_>>();
+
+    let page_count = pages.len();
+    let titles = (1..=page_count)
+        .map(|page| tr!("commands.programman.sessions.title", ctx = ctx, "name" => program.name, "page" => page, "pages" => page_count))
+        .collect::<Vec<_>>();
+
+    // convert to str references
+    let pages = pages.iter().map(|s| &**s).collect::<Vec<_>>();
+    let titles = titles.iter().map(|s| &**s).collect::<Vec<_>>();
+
+    crate::commands::forms::paginate(ctx.into(), Some(&titles), None, &pages).await?;
+
+    Ok(())
+}
+
+/// Gets the program with the given name, warning the user if there is none.
+async fn get_program_by_name(ctx: ApplicationContext<'_>, name: &str) -> Result<Option<Program>> {
+    let program = ctx
+        .data
+        .db
+        .program_repository()
+        .find_by_name(name.trim())
+        .await?;
+
+    if program.is_none() {
+        ctx.say(tr!("commands.programman.no_such_program", ctx = ctx, "name" => name))
+            .await?;
+    }
+
+    Ok(program)
+}
@@ -1,10 +1,9 @@
-use super::modals::register::RegisterModal;
+use super::{autocomplete::autocomplete_timezones, modals::register::RegisterModal};
 use crate::{
     common::ApplicationContext,
     lib::{
-        db::{Repository, UpdatableRepository},
         error::Result,
-        model::User,
+        model::{Timezone, User},
         tr,
     },
 };
@@ -16,7 +15,31 @@ use crate::{
     name_localized("pt-BR", "cadastro"),
     description_localized("pt-BR", "Realiza ou altera seu cadastro de informações no bot.")
 )]
-pub async fn register(ctx: ApplicationContext<'_>) -> Result<()> {
+pub async fn register(
+    ctx: ApplicationContext<'_>,
+
+    #[name_localized("pt-BR", "fuso_horario")]
+    #[description = "Your timezone, in which times are shown to you (default: America/Sao_Paulo)"]
+    #[description_localized(
+        "pt-BR",
+        "Seu fuso horário, no qual os horários são mostrados a você (padrão: America/Sao_Paulo)."
+    )]
+    #[autocomplete = "autocomplete_timezones"]
+    timezone: Option<String>,
+) -> Result<()> {
+    let timezone = match timezone.as_deref().map(str::parse::<Timezone>).transpose() {
+        Ok(timezone) => timezone,
+        Err(_) => {
+            ctx.say(tr!(
+                "commands.register.invalid_timezone",
+                ctx = ctx,
+                "timezone" => timezone.unwrap_or_default(),
+            ))
+            .await?;
+            return Ok(());
+        }
+    };
+
     let author_id = ctx.author().id.into();
     if let Some(user) = ctx.data.db.user_repository().get(author_id).await? {
         let User {
@@ -24,24 +47,30 @@ pub async fn register(ctx: ApplicationContext<'_>) -> Result<()> {
             name,
             email,
             bio,
+            timezone: current_timezone,
+            locale: _,
         } = user.clone();
 
         // user already exists => present a modal with their existing data and let them change
         if let Some(modal) = RegisterModal::ask_with_defaults(ctx, name, email, bio).await? {
-            let new_user = modal.generate_new_user(discord_id);
+            let timezone = timezone.unwrap_or(current_timezone);
+            let new_user =
+                modal.generate_new_user(discord_id, timezone, ctx.locale().map(str::to_owned));
             ctx.data
                 .db
                 .user_repository()
                 .update(&user, new_user.into())
                 .await?;
-            ctx.say(tr!("commands.register.updated_register_success", ctx = ctx))
+            ctx.say(tr!("commands.register.updated_register_success", ctx = ctx, "timezone" => timezone))
                 .await?;
         }
     } else if let Some(modal) = RegisterModal::ask(ctx).await? {
         // user doesn't exist so we asked them for brand new data
-        let new_user = modal.generate_new_user(author_id);
+        let timezone = timezone.unwrap_or_default();
+        let new_user =
+            modal.generate_new_user(author_id, timezone, ctx.locale().map(str::to_owned));
         ctx.data.db.user_repository().insert(&new_user).await?;
-        ctx.say(tr!("commands.register.new_register_success", ctx = ctx))
+        ctx.say(tr!("commands.register.new_register_success", ctx = ctx, "timezone" => timezone))
             .await?;
     }
 
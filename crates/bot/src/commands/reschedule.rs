@@ -0,0 +1,268 @@
+use super::{
+    autocomplete::autocomplete_active_student_sessions,
+    forms::schedule::{ScheduleForm, ScheduleFormData},
+    schedule::{
+        author_booking_policy, check_booking_override, find_booked_sessions, override_policy,
+        send_booking_refusal,
+    },
+};
+use crate::{
+    common::ApplicationContext,
+    dm::{self, SessionEvent},
+    forms::InteractionForm,
+    lib::{
+        error::{Error, Result},
+        model::{Availability, DiscordId, Session, SessionStatus},
+        util::{self, tr},
+    },
+};
+
+/// Reschedules a mentorship session.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    name_localized("pt-BR", "remarcar"),
+    description_localized("pt-BR", "Remarca uma sessão de mentoria.")
+)]
+pub async fn reschedule(
+    ctx: ApplicationContext<'_>,
+
+    #[description = "The session's number."]
+    #[description_localized("pt-BR", "O número da mentoria.")]
+    #[autocomplete = "autocomplete_active_student_sessions"]
+    number: u32,
+
+    #[description = "Whether the session may be moved to another mentor (default: no)."]
+    #[description_localized(
+        "pt-BR",
+        "Se a mentoria pode ser passada para outro mentor (padrão: não)."
+    )]
+    any_mentor: Option<bool>,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let author_id: DiscordId = ctx.author().id.into();
+    let session_repository = ctx.data.db.session_repository();
+
+    let Some((session, teacher, student)) = session_repository
+        .get_with_participants(number as i64)
+        .await?
+    else {
+        ctx.say(tr!("commands.sessions.info.no_such_session", ctx = ctx, "id" => number))
+            .await?;
+        return Ok(());
+    };
+
+    let students = session_repository.find_students(session.id).await?;
+    if !students
+        .iter()
+        .any(|attendee| attendee.discord_id == author_id)
+    {
+        ctx.say(tr!("commands.sessions.info.not_your_session", ctx = ctx, "id" => number))
+            .await?;
+        return Ok(());
+    }
+
+    if session.status != SessionStatus::Scheduled {
+        ctx.say(tr!(
+            "commands.reschedule.session_not_scheduled",
+            ctx = ctx,
+            "status" => session.status.to_locale_string(util::locale::get_defaulted_app_ctx_locale(ctx)),
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    if session.start_at < chrono::Utc::now() {
+        ctx.say(tr!(
+            "commands.reschedule.session_already_started",
+            ctx = ctx
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    // moving a group session would move the other students along with it
+    if students.len() > 1 {
+        ctx.say(tr!("commands.reschedule.group_session", ctx = ctx, "id" => number))
+            .await?;
+        return Ok(());
+    }
+
+    let form = *ScheduleForm::execute_with_defaults(
+        ctx,
+        ScheduleFormData::new(
+            student.timezone,
+            if any_mentor.unwrap_or(false) {
+                None
+            } else {
+                Some(session.teacher_id)
+            },
+        ),
+    )
+    .await?;
+
+    // show the bot as 'typing' while we do things
+    ctx.defer_ephemeral().await?;
+
+    let initial_datetime = form
+        .form_start_datetime
+        .ok_or_else(|| Error::Other("could not get the form's starting datetime"))?;
+
+    let (selected_availability, selected_mentor) = form.retrieve_selection();
+
+    let Availability {
+        id: avail_id,
+        duration,
+        ..
+    } = selected_availability;
+
+    let start_at =
+        selected_availability.first_start_after(selected_mentor.timezone, &initial_datetime);
+    let end_at = Session::generate_end_at_from_duration(start_at, duration as i64);
+
+    if avail_id == session.availability_id && start_at == session.start_at {
+        ctx.say(tr!("commands.reschedule.same_time", ctx = ctx))
+            .await?;
+        return Ok(());
+    }
+
+    // a group session at the new time must be joined through '/schedule' instead
+    if ctx
+        .data
+        .db
+        .availability_repository()
+        .check_is_taken_at(avail_id, &initial_datetime)
+        .await?
+        || session_repository
+            .find_by_availability_starting_at(avail_id, start_at)
+            .await?
+            .is_some()
+    {
+        ctx.say(tr!("commands.schedule.time_already_taken", ctx = ctx))
+            .await?;
+        return Ok(());
+    }
+
+    // the limits on sessions with the same mentor apply to the new time as well
+    // (not counting the session being moved)
+    let policy = author_booking_policy(ctx);
+    let other_sessions = session_repository
+        .find_by_student(author_id)
+        .await?
+        .into_iter()
+        .filter(|other| other.id != session.id)
+        .collect::<Vec<_>>();
+    let booked_sessions =
+        find_booked_sessions(ctx, &policy, &other_sessions, selected_mentor.id).await?;
+
+    let mut needs_override = false;
+    if let Err(refusal) = policy.check_mentor(
+        &booked_sessions,
+        selected_mentor.id,
+        selected_availability.program_id,
+        start_at,
+    ) {
+        if !check_booking_override(ctx, &student, refusal).await? {
+            return Ok(());
+        }
+        needs_override = true;
+    }
+
+    let rescheduled = session_repository
+        .reschedule(
+            &session,
+            override_policy(&policy, needs_override),
+            selected_mentor.id,
+            avail_id,
+            start_at,
+            end_at,
+        )
+        .await;
+    let rescheduled = match rescheduled {
+        Ok(Some(rescheduled)) => rescheduled,
+        Ok(None) => {
+            ctx.say(tr!("commands.schedule.time_already_taken", ctx = ctx))
+                .await?;
+            return Ok(());
+        }
+        Err(Error::BookingRefused(refusal)) => {
+            send_booking_refusal(ctx, &student, refusal).await?;
+            return Ok(());
+        }
+        Err(err) => return Err(err),
+    };
+
+    if needs_override {
+        ctx.data
+            .db
+            .user_repository()
+            .use_booking_override(student.discord_id)
+            .await?;
+    }
+
+    // the session was already moved, so everyone is still notified if the event couldn't be
+    let calendar_updated = if let Err(err) = ctx
+        .data
+        .google
+        .calendar
+        .reschedule_event_for_session(&rescheduled, &teacher, &selected_mentor)
+        .await
+    {
+        tracing::warn!("Couldn't move the event of a rescheduled session: {err:?}");
+        false
+    } else {
+        true
+    };
+
+    let email_sent = if let Err(err) = ctx
+        .data
+        .google
+        .email
+        .send_reschedule_emails_for_session(
+            &teacher,
+            &selected_mentor,
+            &student,
+            &session,
+            &rescheduled,
+        )
+        .await
+    {
+        tracing::warn!("Couldn't send rescheduling email: {err:?}");
+        false
+    } else {
+        true
+    };
+
+    let response = match (calendar_updated, email_sent) {
+        (true, true) => "commands.reschedule.success",
+        (true, false) => "commands.reschedule.success_no_email",
+        (false, true) => "commands.reschedule.success_no_calendar",
+        (false, false) => "commands.reschedule.success_no_calendar_no_email",
+    };
+
+    dm::notify_session_event(
+        &ctx.serenity_context.http,
+        &ctx.data.db,
+        SessionEvent::Rescheduled {
+            previous_teacher: &teacher,
+        },
+        &rescheduled,
+        &selected_mentor,
+        &students,
+    )
+    .await;
+
+    let start_at = rescheduled.start_at.with_timezone(&student.timezone.0);
+    ctx.say(tr!(
+        response,
+        ctx = ctx,
+        "session" => rescheduled.id,
+        "date" => util::time::day_month_year_display(&start_at.date_naive()),
+        "time" => util::time::hour_minute_display(start_at.time()),
+        "mentor" => selected_mentor.name,
+    ))
+    .await?;
+
+    Ok(())
+}
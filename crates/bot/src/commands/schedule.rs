@@ -1,16 +1,18 @@
-use super::{forms::schedule::ScheduleForm, modals::register::RegisterModal};
+use super::{
+    forms::schedule::{ScheduleForm, ScheduleFormData},
+    modals::register::RegisterModal,
+};
 use crate::{
     common::ApplicationContext,
+    dm::{self, SessionEvent},
     forms::InteractionForm,
     lib::{
-        db::Repository,
         error::{Error, Result},
-        model::{Availability, DiscordId, NewSession, Session},
-        util::{
-            self,
-            time::{datetime_as_utc, datetime_with_time},
-            tr,
+        model::{
+            Availability, BookedSession, BookingPolicy, BookingRefusal, DiscordId, NewSession,
+            PartialUser, Session, SessionStatus, Teacher, User,
         },
+        util::{self, tr},
     },
 };
 
@@ -30,14 +32,34 @@ pub async fn schedule(ctx: ApplicationContext<'_>) -> Result<()> {
         let student = ctx.data().db.user_repository().get(author_id).await?;
 
         if let Some(student) = student {
-            student
+            // keep the student's locale up to date, as their e-mails are sent in it
+            let locale = ctx.locale().map(str::to_owned);
+            if locale.is_some() && student.locale != locale {
+                ctx.data()
+                    .db
+                    .user_repository()
+                    .update(
+                        &student,
+                        PartialUser {
+                            locale: Some(locale),
+                            ..Default::default()
+                        },
+                    )
+                    .await?
+            } else {
+                student
+            }
         } else {
             // User not in DB => call registration modal
             if let Some(register) = RegisterModal::ask(ctx).await? {
                 ctx.data()
                     .db
                     .user_repository()
-                    .insert(&register.generate_new_user(author_id))
+                    .insert(&register.generate_new_user(
+                        author_id,
+                        Default::default(),
+                        ctx.locale().map(str::to_owned),
+                    ))
                     .await?
             } else {
                 // modal cancelled
@@ -47,7 +69,27 @@ pub async fn schedule(ctx: ApplicationContext<'_>) -> Result<()> {
     };
 
     ctx.defer_ephemeral().await?;
-    let form = *ScheduleForm::execute(ctx).await?;
+
+    let policy = author_booking_policy(ctx);
+    let student_sessions = ctx
+        .data
+        .db
+        .session_repository()
+        .find_by_student(author_id)
+        .await?;
+
+    // whether the student may only book this session through an administrator's override
+    let mut needs_override = false;
+    if let Err(refusal) = policy.check_student(&student_sessions, chrono::Utc::now()) {
+        if !check_booking_override(ctx, &student, refusal).await? {
+            return Ok(());
+        }
+        needs_override = true;
+    }
+
+    let form =
+        *ScheduleForm::execute_with_defaults(ctx, ScheduleFormData::new(student.timezone, None))
+            .await?;
 
     // after asking for the user's input:
     // show the bot as 'typing' while we do things
@@ -61,12 +103,47 @@ pub async fn schedule(ctx: ApplicationContext<'_>) -> Result<()> {
 
     let Availability {
         id: avail_id,
-        time_start,
         duration,
-        weekday,
         ..
     } = selected_availability;
 
+    let start_at =
+        selected_availability.first_start_after(selected_mentor.timezone, &initial_datetime);
+
+    let booked_sessions =
+        find_booked_sessions(ctx, &policy, &student_sessions, selected_mentor.id).await?;
+    if let Err(refusal) = policy.check_mentor(
+        &booked_sessions,
+        selected_mentor.id,
+        selected_availability.program_id,
+        start_at,
+    ) {
+        if !check_booking_override(ctx, &student, refusal).await? {
+            return Ok(());
+        }
+        needs_override = true;
+    }
+
+    // a (group) session may have already claimed this time, in which case we join it
+    if let Some(session) = ctx
+        .data
+        .db
+        .session_repository()
+        .find_by_availability_starting_at(avail_id, start_at)
+        .await?
+    {
+        return join_group_session(
+            ctx,
+            session,
+            &selected_availability,
+            &selected_mentor,
+            &student,
+            &policy,
+            needs_override,
+        )
+        .await;
+    }
+
     if ctx
         .data
         .db
@@ -79,11 +156,6 @@ pub async fn schedule(ctx: ApplicationContext<'_>) -> Result<()> {
         return Ok(());
     }
 
-    let start_at = weekday.next_day_with_this_weekday(&initial_datetime);
-    let start_at = datetime_as_utc(
-        &datetime_with_time(start_at, time_start)
-            .ok_or_else(|| Error::Other("failed to create session datetime object"))?,
-    );
     // 40 minutes per time unit
     let end_at = Session::generate_end_at_from_duration(start_at, duration as i64);
     let session = NewSession {
@@ -96,6 +168,9 @@ pub async fn schedule(ctx: ApplicationContext<'_>) -> Result<()> {
         start_at,
         end_at,
         notified: false,
+        status: SessionStatus::Scheduled,
+        cancelled_at: None,
+        cancel_reason: None,
     };
 
     // Create Google Calendar event with Google Meet
@@ -106,11 +181,13 @@ pub async fn schedule(ctx: ApplicationContext<'_>) -> Result<()> {
         .create_event_for_session(&student, &selected_mentor, &session)
         .await?;
 
-    let Some(meet_id) = event
-        .conference_data
-        .and_then(|conf| conf.conference_id)
-    else {
-        ctx.send(|b| b.content(tr!("commands.schedule.couldnt_create_google_event", ctx = ctx)))
+    let Some(meet_id) = event.conference_data.and_then(|conf| conf.conference_id) else {
+        ctx.send(|b| {
+            b.content(tr!(
+                "commands.schedule.couldnt_create_google_event",
+                ctx = ctx
+            ))
+        })
         .await?;
         return Ok(());
     };
@@ -121,8 +198,52 @@ pub async fn schedule(ctx: ApplicationContext<'_>) -> Result<()> {
         ..session
     };
 
-    // Now insert the Session between the Teacher and the Student.
-    let session = ctx.data.db.session_repository().insert(&session).await?;
+    // Now book the Session between the Teacher and the Student.
+    // Another student may have taken the time in the meantime (or it may have been offered
+    // to a student in the waitlist), or the student may have booked other sessions
+    // concurrently (going over the limits), in which case the event is dropped.
+    let booked = ctx
+        .data
+        .db
+        .session_repository()
+        .book(&session, override_policy(&policy, needs_override))
+        .await;
+    let session = match booked {
+        Err(err @ (Error::SessionTimeTaken | Error::BookingRefused(_))) => {
+            if let Some(event_id) = session.calendar_event_id.as_deref() {
+                if let Err(err) = ctx.data.google.calendar.cancel_event(event_id).await {
+                    tracing::warn!(
+                        "Couldn't cancel the event of a session which wasn't booked: {err:?}"
+                    );
+                }
+            }
+            match err {
+                Error::BookingRefused(refusal) => {
+                    send_booking_refusal(ctx, &student, refusal).await?;
+                }
+                _ => {
+                    ctx.send(|b| b.content(tr!("commands.schedule.time_already_taken", ctx = ctx)))
+                        .await?;
+                }
+            }
+            return Ok(());
+        }
+        session => session?,
+    };
+
+    ctx.data
+        .db
+        .waitlist_repository()
+        .fulfill(student.discord_id)
+        .await?;
+
+    if needs_override {
+        ctx.data
+            .db
+            .user_repository()
+            .use_booking_override(student.discord_id)
+            .await?;
+    }
 
     let response = if let Err(err) = ctx
         .data
@@ -137,11 +258,22 @@ pub async fn schedule(ctx: ApplicationContext<'_>) -> Result<()> {
         "commands.schedule.success"
     };
 
+    dm::notify_session_event(
+        &ctx.serenity_context.http,
+        &ctx.data.db,
+        SessionEvent::Scheduled,
+        &session,
+        &selected_mentor,
+        std::slice::from_ref(&student),
+    )
+    .await;
+
+    let start_at = session.start_at.with_timezone(&student.timezone.0);
     ctx.send(|b| {
         b.content(tr!(
             response,
             ctx = ctx,
-            time = util::time::hour_minute_display(selected_availability.time_start),
+            time = util::time::hour_minute_display(start_at.time()),
             mentor = selected_mentor.name
         ))
     })
@@ -149,3 +281,230 @@ pub async fn schedule(ctx: ApplicationContext<'_>) -> Result<()> {
 
     Ok(())
 }
+
+/// Gets the booking limits the command's author is subject to
+/// (none, if they are an administrator).
+pub(super) fn author_booking_policy(ctx: ApplicationContext<'_>) -> BookingPolicy {
+    if ctx.data.admin_userids.contains(&ctx.author().id.0) {
+        BookingPolicy::UNLIMITED
+    } else {
+        ctx.data.booking_policy
+    }
+}
+
+/// Gets the limits to be checked when finally booking a session: none, if the student
+/// needed (and has) a booking override, or the given policy otherwise.
+pub(super) fn override_policy(policy: &BookingPolicy, needs_override: bool) -> &BookingPolicy {
+    if needs_override {
+        &BookingPolicy::UNLIMITED
+    } else {
+        policy
+    }
+}
+
+/// Finds the student's sessions with the given mentor (which aren't cancelled),
+/// along with their programs, to check them against the booking limits.
+pub(super) async fn find_booked_sessions<'a>(
+    ctx: ApplicationContext<'_>,
+    policy: &BookingPolicy,
+    sessions: &'a [Session],
+    teacher_id: i64,
+) -> Result<Vec<BookedSession<'a>>> {
+    let mut booked_sessions = Vec::new();
+    for session in sessions
+        .iter()
+        .filter(|session| session.teacher_id == teacher_id && !session.status.is_cancelled())
+    {
+        // the program is only relevant if there is a limit per program
+        let program_id = if policy.max_sessions_per_mentor_per_program.is_some() {
+            ctx.data
+                .db
+                .availability_repository()
+                .get(session.availability_id)
+                .await?
+                .and_then(|avail| avail.program_id)
+        } else {
+            None
+        };
+
+        booked_sessions.push(BookedSession {
+            session,
+            program_id,
+        });
+    }
+
+    Ok(booked_sessions)
+}
+
+/// Checks whether the student was granted an override allowing them to book a session
+/// refused by the booking limits; otherwise, tells them why it was refused.
+pub(super) async fn check_booking_override(
+    ctx: ApplicationContext<'_>,
+    student: &User,
+    refusal: BookingRefusal,
+) -> Result<bool> {
+    if ctx
+        .data
+        .db
+        .user_repository()
+        .has_booking_override(student.discord_id)
+        .await?
+    {
+        return Ok(true);
+    }
+
+    send_booking_refusal(ctx, student, refusal).await?;
+    Ok(false)
+}
+
+/// Tells the student why the booking limits refused their session.
+pub(super) async fn send_booking_refusal(
+    ctx: ApplicationContext<'_>,
+    student: &User,
+    refusal: BookingRefusal,
+) -> Result<()> {
+    let response = match refusal {
+        BookingRefusal::TooManyActiveSessions { max } => tr!(
+            "commands.schedule.booking_limits.too_many_active_sessions",
+            ctx = ctx,
+            "max" => max
+        ),
+        BookingRefusal::TooManyWithMentorPerWeek { max } => tr!(
+            "commands.schedule.booking_limits.too_many_with_mentor_per_week",
+            ctx = ctx,
+            "max" => max
+        ),
+        BookingRefusal::TooManyWithMentorPerProgram { max } => tr!(
+            "commands.schedule.booking_limits.too_many_with_mentor_per_program",
+            ctx = ctx,
+            "max" => max
+        ),
+        BookingRefusal::NoShowCooldown { until } => {
+            let until = until.with_timezone(&student.timezone.0);
+            tr!(
+                "commands.schedule.booking_limits.no_show_cooldown",
+                ctx = ctx,
+                "date" => util::time::day_month_year_display(&until.date_naive()),
+                "time" => util::time::hour_minute_display(until.time()),
+            )
+        }
+        BookingRefusal::HeldForWaitlist => tr!(
+            "commands.schedule.booking_limits.held_for_waitlist",
+            ctx = ctx
+        ),
+    };
+
+    ctx.send(|b| b.content(response)).await?;
+    Ok(())
+}
+
+/// Adds the student to an existing group session claiming the selected availability,
+/// inviting them to its Google Calendar event, as long as it has seats left and the
+/// booking limits allow it. If the student needed a booking override to join it,
+/// the override is used up.
+async fn join_group_session(
+    ctx: ApplicationContext<'_>,
+    session: Session,
+    availability: &Availability,
+    mentor: &Teacher,
+    student: &User,
+    policy: &BookingPolicy,
+    needs_override: bool,
+) -> Result<()> {
+    let session_repository = ctx.data.db.session_repository();
+    let students = session_repository.find_students(session.id).await?;
+
+    if students
+        .iter()
+        .any(|attendee| attendee.discord_id == student.discord_id)
+    {
+        ctx.send(|b| {
+            b.content(tr!(
+                "commands.schedule.already_attending",
+                ctx = ctx,
+                "session" => session.id
+            ))
+        })
+        .await?;
+        return Ok(());
+    }
+
+    let joined = session_repository
+        .add_student_if_seats_left(
+            session.id,
+            student.discord_id,
+            availability.capacity,
+            override_policy(policy, needs_override),
+        )
+        .await;
+    match joined {
+        Ok(true) => {}
+        Ok(false) => {
+            ctx.send(|b| b.content(tr!("commands.schedule.time_already_taken", ctx = ctx)))
+                .await?;
+            return Ok(());
+        }
+        Err(Error::BookingRefused(refusal)) => {
+            send_booking_refusal(ctx, student, refusal).await?;
+            return Ok(());
+        }
+        Err(err) => return Err(err),
+    }
+
+    ctx.data
+        .db
+        .waitlist_repository()
+        .fulfill(student.discord_id)
+        .await?;
+
+    if needs_override {
+        ctx.data
+            .db
+            .user_repository()
+            .use_booking_override(student.discord_id)
+            .await?;
+    }
+
+    ctx.data
+        .google
+        .calendar
+        .add_attendee_to_event_for_session(&session, student)
+        .await?;
+
+    let response = if let Err(err) = ctx
+        .data
+        .google
+        .email
+        .send_join_emails_for_session(mentor, student, &session)
+        .await
+    {
+        tracing::warn!("Couldn't send group session joining email: {err:?}");
+        "commands.schedule.joined_success_no_email"
+    } else {
+        "commands.schedule.joined_success"
+    };
+
+    dm::notify_session_event(
+        &ctx.serenity_context.http,
+        &ctx.data.db,
+        SessionEvent::Joined(student),
+        &session,
+        mentor,
+        &[],
+    )
+    .await;
+
+    let start_at = session.start_at.with_timezone(&student.timezone.0);
+    ctx.send(|b| {
+        b.content(tr!(
+            response,
+            ctx = ctx,
+            time = util::time::hour_minute_display(start_at.time()),
+            mentor = mentor.name,
+            session = session.id
+        ))
+    })
+    .await?;
+
+    Ok(())
+}
@@ -1,22 +1,29 @@
 use std::ops::Add;
 
+use poise::serenity_prelude as serenity;
+
 use crate::{
-    commands::{embeds, modals::sessions::SessionCreateModals},
+    commands::{
+        embeds, forms::LazyPage, mentor::cancel_session_and_notify,
+        modals::sessions::SessionCreateModals,
+    },
     common::{ApplicationContext, Context},
     lib::{
-        db::Repository,
+        db::{PageRequest, SessionFilter, SortOrder},
         error::{Error, Result},
-        model::{NewSession, Session},
-        util::tr,
+        model::{NewSession, Session, SessionStatus},
+        util::{self, tr, HumanParseableDate, BRAZIL_TIMEZONE},
     },
 };
 
+const SESSIONS_PER_PAGE: usize = 15;
+
 /// Manages sessions with mentors.
 #[poise::command(
     slash_command,
     ephemeral,
     description_localized("pt-BR", "Gerencia sessões de mentoria."),
-    subcommands("create", "get", "remove", "all")
+    subcommands("create", "get", "cancel", "complete", "all")
 )]
 pub async fn sessionman(ctx: Context<'_>) -> Result<()> {
     ctx.send(|reply| {
@@ -56,7 +63,15 @@ async fn create(
         return Ok(());
     };
 
-    let Some(start_at) = modal.parsed_starts_at() else {
+    // the session's student is the author, so the time is in their timezone
+    let timezone = ctx
+        .data()
+        .db
+        .user_repository()
+        .get_timezone(ctx.author().id.into())
+        .await?;
+
+    let Some(start_at) = modal.parsed_starts_at(timezone) else {
         ctx.send(|b| {
             b
                 .content(format!(
@@ -123,6 +138,9 @@ async fn create(
             calendar_event_id: None,
             start_at,
             end_at,
+            status: SessionStatus::Scheduled,
+            cancelled_at: None,
+            cancel_reason: None,
         })
         .await?;
 
@@ -172,9 +190,14 @@ pub async fn get(
     let db = &ctx.data.db;
     let session_and_teacher = db.session_repository().get_with_teacher(id).await?;
     if let Some((session, teacher)) = session_and_teacher {
+        let timezone = db
+            .user_repository()
+            .get_timezone(ctx.author().id.into())
+            .await?;
         ctx.send(|f| {
-            f.ephemeral(true)
-                .embed(|f| embeds::session_embed(f, &session, &teacher, ctx.locale(), true))
+            f.ephemeral(true).embed(|f| {
+                embeds::session_embed(f, &session, &teacher, timezone, ctx.locale(), true)
+            })
         })
         .await?;
     } else {
@@ -185,60 +208,257 @@ pub async fn get(
     Ok(())
 }
 
-/// Removes a session from the database
+/// Cancels a session, keeping it in the database
 #[poise::command(
     slash_command,
     ephemeral,
     owners_only,
-    name_localized("pt-BR", "remover"),
-    description_localized("pt-BR", "Remove uma sessão permanentemente do banco de dados.")
+    name_localized("pt-BR", "cancelar"),
+    description_localized("pt-BR", "Cancela uma sessão, mantendo-a no banco de dados.")
 )]
-pub async fn remove(
+pub async fn cancel(
     ctx: ApplicationContext<'_>,
 
-    #[description = "ID of the session to remove"]
-    #[description_localized("pt-BR", "Identificador da sessão a ser removida.")]
+    #[description = "ID of the session to cancel"]
+    #[description_localized("pt-BR", "Identificador da sessão a ser cancelada.")]
     id: i64,
+
+    #[description = "Why the session was cancelled"]
+    #[description_localized("pt-BR", "Por que a sessão foi cancelada.")]
+    reason: Option<String>,
+
+    #[description = "Whether the student (instead of the mentor) cancelled it (default: no)"]
+    #[description_localized(
+        "pt-BR",
+        "Se foi o aluno (e não o mentor) quem cancelou a sessão (padrão: não)."
+    )]
+    by_student: Option<bool>,
 ) -> Result<()> {
-    let session = ctx.data().db.session_repository().get(id).await?;
-    if let Some(session) = session {
-        ctx.data().db.session_repository().remove(&session).await?;
-
-        let removed_msg = format!(
-            "Successfully removed session '{}' from the database.",
-            session.summary.unwrap_or_else(|| "Unnamed".to_string())
-        );
-        ctx.send(|b| b.content(removed_msg).ephemeral(true)).await?;
+    let session_repository = ctx.data().db.session_repository();
+    let Some(session) = session_repository.get(id).await? else {
+        ctx.send(|b| b.content("Unknown session.").ephemeral(true))
+            .await?;
+        return Ok(());
+    };
+
+    if session.status != SessionStatus::Scheduled {
+        ctx.send(|b| {
+            b.content(format!(
+                "Session #{} is not scheduled (status: {}).",
+                session.id,
+                session.status.to_locale_string("en")
+            ))
+            .ephemeral(true)
+        })
+        .await?;
+        return Ok(());
+    }
+
+    let status = if by_student.unwrap_or(false) {
+        SessionStatus::CancelledByStudent
     } else {
+        SessionStatus::CancelledByMentor
+    };
+    let Some(teacher) = ctx
+        .data
+        .db
+        .teacher_repository()
+        .get(session.teacher_id)
+        .await?
+    else {
+        ctx.send(|b| b.content("Unknown mentor.").ephemeral(true))
+            .await?;
+        return Ok(());
+    };
+
+    let notified =
+        cancel_session_and_notify(ctx, &session, &teacher, status, reason.as_deref(), &[]).await?;
+
+    let mut cancelled_msg = format!(
+        "Successfully cancelled session '{}' ({}).",
+        session.summary.unwrap_or_else(|| "Unnamed".to_string()),
+        status.to_locale_string("en")
+    );
+    if !notified {
+        cancelled_msg.push_str(" However, its participants couldn't be notified by email.");
+    }
+    ctx.send(|b| b.content(cancelled_msg).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// Marks a past session as completed (or as a no-show)
+#[poise::command(
+    slash_command,
+    ephemeral,
+    owners_only,
+    name_localized("pt-BR", "concluir"),
+    description_localized(
+        "pt-BR",
+        "Marca uma sessão passada como concluída (ou como falta do aluno)."
+    )
+)]
+pub async fn complete(
+    ctx: ApplicationContext<'_>,
+
+    #[description = "ID of the session to mark"]
+    #[description_localized("pt-BR", "Identificador da sessão a ser marcada.")]
+    id: i64,
+
+    #[description = "Whether the student did not show up (default: no)"]
+    #[description_localized("pt-BR", "Se o aluno não compareceu (padrão: não).")]
+    no_show: Option<bool>,
+) -> Result<()> {
+    let session_repository = ctx.data().db.session_repository();
+    let Some(session) = session_repository.get(id).await? else {
+        ctx.send(|b| b.content("Unknown session.").ephemeral(true))
+            .await?;
+        return Ok(());
+    };
+
+    if session.status.is_cancelled() {
         ctx.send(|b| {
-            b.content("Unknown session (maybe it was already deleted).")
+            b.content(format!("Session #{} was cancelled.", session.id))
                 .ephemeral(true)
         })
         .await?;
+        return Ok(());
     }
 
+    if session.end_at > chrono::Utc::now() {
+        ctx.send(|b| {
+            b.content(format!("Session #{} has not ended yet.", session.id))
+                .ephemeral(true)
+        })
+        .await?;
+        return Ok(());
+    }
+
+    let status = if no_show.unwrap_or(false) {
+        SessionStatus::NoShow
+    } else {
+        SessionStatus::Completed
+    };
+    let session = session_repository.set_status(&session, status).await?;
+
+    ctx.send(|b| {
+        b.content(format!(
+            "Session #{} is now marked as: {}.",
+            session.id,
+            session.status.to_locale_string("en")
+        ))
+        .ephemeral(true)
+    })
+    .await?;
+
     Ok(())
 }
 
+/// Lists sessions in the database, optionally filtered.
 #[poise::command(
     slash_command,
     ephemeral,
     owners_only,
     name_localized("pt-BR", "listar"),
-    description_localized("pt-BR", "Lista todas as sessões no banco de dados.")
+    description_localized("pt-BR", "Lista as sessões no banco de dados.")
 )]
-pub async fn all(ctx: ApplicationContext<'_>) -> Result<()> {
-    let sessions = ctx.data().db.session_repository().find_all().await?;
-    let text = if sessions.is_empty() {
-        "No sessions registered.".to_string()
+pub async fn all(
+    ctx: ApplicationContext<'_>,
+
+    #[description = "Only sessions with the mentor with this email"]
+    #[description_localized("pt-BR", "Apenas sessões com o mentor com este e-mail.")]
+    mentor_email: Option<String>,
+
+    #[description = "Only sessions attended by this student"]
+    #[description_localized("pt-BR", "Apenas sessões com este aluno.")]
+    student: Option<serenity::User>,
+
+    #[description = "Only sessions starting from this date (DD/MM/YYYY)"]
+    #[description_localized("pt-BR", "Apenas sessões a partir desta data (DD/MM/AAAA).")]
+    from: Option<HumanParseableDate>,
+
+    #[description = "Only sessions starting until this date (DD/MM/YYYY)"]
+    #[description_localized("pt-BR", "Apenas sessões até esta data (DD/MM/AAAA).")]
+    until: Option<HumanParseableDate>,
+
+    #[description = "Whether to list the latest sessions first (default: no)"]
+    #[description_localized(
+        "pt-BR",
+        "Se as sessões mais recentes devem vir primeiro (padrão: não)."
+    )]
+    latest_first: Option<bool>,
+) -> Result<()> {
+    let teacher_id = if let Some(mentor_email) = mentor_email {
+        let Some(teacher) = ctx
+            .data
+            .db
+            .teacher_repository()
+            .find_by_email(mentor_email.trim())
+            .await?
+        else {
+            ctx.say(tr!("commands.mentor.link.no_such_mentor", ctx = ctx, "email" => mentor_email))
+                .await?;
+            return Ok(());
+        };
+        Some(teacher.id)
+    } else {
+        None
+    };
+
+    let filter = SessionFilter {
+        teacher_id,
+        student_id: student.map(|student| student.id.into()),
+        starts_from: from.map(|date| util::time::brazil_day_start_as_utc(date.into())),
+        starts_until: until.map(|date| util::time::brazil_day_end_as_utc(date.into())),
+        status: None,
+    };
+    let order = if latest_first.unwrap_or(false) {
+        SortOrder::Descending
     } else {
-        let mut text = String::from("Sessions:");
-        for session in sessions {
-            text.push_str(&format!("\n- {:?}", session));
-        }
-        text
+        SortOrder::Ascending
     };
-    ctx.send(|b| b.content(text).ephemeral(true)).await?;
+
+    let first_request = PageRequest::first(SESSIONS_PER_PAGE).ordered(order);
+    let shown =
+        crate::commands::forms::paginate_lazily(ctx.into(), "Sessions", first_request, |request| {
+            let filter = &filter;
+            async move {
+                let page = ctx
+                    .data
+                    .db
+                    .session_repository()
+                    .find_page(filter, &request)
+                    .await?;
+
+                Ok(LazyPage {
+                    content: page
+                        .items
+                        .iter()
+                        .map(|session| {
+                            let start_at = session.start_at.with_timezone(&*BRAZIL_TIMEZONE);
+                            format!(
+                                "**#{}** ({} {}): mentor #{}, student <@{}> - {}",
+                                session.id,
+                                util::time::day_month_year_display(&start_at.date_naive()),
+                                util::time::hour_minute_display(start_at.time()),
+                                session.teacher_id,
+                                session.student_id,
+                                session.status.to_locale_string("en")
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                    next: page.next_request(&request),
+                })
+            }
+        })
+        .await?;
+
+    if !shown {
+        ctx.send(|b| b.content("No sessions found.").ephemeral(true))
+            .await?;
+    }
 
     Ok(())
 }
@@ -4,7 +4,8 @@ use crate::{
     common::Context,
     lib::{
         error::Result,
-        util::{self, tr, BRAZIL_TIMEZONE},
+        model::DiscordId,
+        util::{self, tr},
     },
 };
 
@@ -36,12 +37,19 @@ pub async fn sessions(ctx: Context<'_>) -> Result<()> {
     description_localized("pt-BR", "Lista todas as suas mentorias.")
 )]
 pub async fn list(ctx: Context<'_>) -> Result<()> {
+    let author_id: DiscordId = ctx.author().id.into();
+    let timezone = ctx
+        .data()
+        .db
+        .user_repository()
+        .get_timezone(author_id)
+        .await?;
     let sessions = {
         let mut sessions = ctx
             .data()
             .db
             .session_repository()
-            .find_by_student(ctx.author().id.into())
+            .find_by_student(author_id)
             .await?;
         sessions.reverse(); // show older sessions first
         sessions
@@ -51,6 +59,7 @@ pub async fn list(ctx: Context<'_>) -> Result<()> {
         ctx.say(tr!("commands.sessions.no_sessions", ctx = ctx))
             .await?;
     } else {
+        let locale = util::locale::get_defaulted_locale(ctx);
         let pages = sessions
             .chunks(SESSIONS_PER_PAGE)  // 10 sessions per page
             .map(|chunk| {
@@ -60,7 +69,8 @@ pub async fn list(ctx: Context<'_>) -> Result<()> {
                         "commands.sessions.session_list_entry",
                         ctx = ctx,
                         "id" => session.id,
-                        "date" => util::time::day_month_year_display(&session.start_at.with_timezone(&*BRAZIL_TIMEZONE).date_naive()),
+                        "date" => util::time::day_month_year_display(&session.start_at.with_timezone(&timezone.0).date_naive()),
+                        "status" => session.status.to_locale_string(locale),
                     ))
                     .collect::<Vec<_>>()
                     .join("\n")
@@ -101,20 +111,28 @@ pub async fn info(
     #[autocomplete = "autocomplete_any_student_sessions"]
     number: u32,
 ) -> Result<()> {
-    if let Some((session, teacher)) = ctx
-        .data()
-        .db
-        .session_repository()
-        .get_with_teacher(number as i64)
-        .await?
-    {
-        if session.student_id != ctx.author().id.into() {
+    let session_repository = ctx.data().db.session_repository();
+    if let Some((session, teacher)) = session_repository.get_with_teacher(number as i64).await? {
+        let author_id: DiscordId = ctx.author().id.into();
+        let timezone = ctx
+            .data()
+            .db
+            .user_repository()
+            .get_timezone(author_id)
+            .await?;
+        if !session_repository
+            .find_students(session.id)
+            .await?
+            .iter()
+            .any(|student| student.discord_id == author_id)
+        {
             ctx.say(tr!("commands.sessions.info.not_your_session", ctx = ctx, "id" => number))
                 .await?;
         } else {
             ctx.send(|b| {
-                b.ephemeral(true)
-                    .embed(|b| embeds::session_embed(b, &session, &teacher, ctx.locale(), true))
+                b.ephemeral(true).embed(|b| {
+                    embeds::session_embed(b, &session, &teacher, timezone, ctx.locale(), true)
+                })
             })
             .await?;
         }
@@ -1,15 +1,24 @@
 use crate::{
-    commands::{embeds, modals::teacher::TeacherModal},
+    commands::{embeds, forms::LazyPage, modals::teacher::TeacherModal},
     common::{ApplicationContext, Context},
-    lib::{db::Repository, error::Result, model::Teacher, util::tr},
+    lib::{
+        db::{PageRequest, TeacherFilter},
+        error::Result,
+        model::Teacher,
+        util::tr,
+    },
 };
 
+const MENTORS_PER_PAGE: usize = 20;
+
+const RATINGS_PER_PAGE: usize = 15;
+
 /// Manages mentors.
 #[poise::command(
     slash_command,
     ephemeral,
     description_localized("pt-BR", "Gerencia mentores."),
-    subcommands("create", "get", "all")
+    subcommands("create", "get", "all", "ratings")
 )]
 pub async fn teacherman(ctx: Context<'_>) -> Result<()> {
     ctx.send(|reply| {
@@ -54,6 +63,11 @@ async fn create(ctx: ApplicationContext<'_>) -> Result<()> {
     }
 
     let teacher = ctx.data().db.teacher_repository().insert(&teacher).await?;
+    ctx.data()
+        .db
+        .tag_repository()
+        .tag_from_specialty(&teacher)
+        .await?;
 
     let Teacher { name, email, .. } = teacher;
 
@@ -134,26 +148,137 @@ pub async fn get(
 //     Ok(())
 // }
 
-/// List all mentors in the database.
+/// Lists mentors in the database, optionally filtered.
 #[poise::command(
     slash_command,
     ephemeral,
     owners_only,
     name_localized("pt-BR", "listar"),
-    description_localized("pt-BR", "Lista todos os mentores no banco de dados.")
+    description_localized("pt-BR", "Lista os mentores no banco de dados.")
 )]
-pub async fn all(ctx: ApplicationContext<'_>) -> Result<()> {
-    let teachers = ctx.data().db.teacher_repository().find_all().await?;
-    let text = if teachers.is_empty() {
-        "No mentors registered.".to_string()
+pub async fn all(
+    ctx: ApplicationContext<'_>,
+
+    #[description = "Only mentors whose name or email contain this text"]
+    #[description_localized("pt-BR", "Apenas mentores cujo nome ou e-mail contenham este texto.")]
+    search: Option<String>,
+
+    #[description = "Only mentors attached to the program with this name"]
+    #[description_localized("pt-BR", "Apenas mentores vinculados ao programa com este nome.")]
+    program: Option<String>,
+) -> Result<()> {
+    let program_id = if let Some(program) = program {
+        let Some(program) = ctx
+            .data
+            .db
+            .program_repository()
+            .find_by_name(program.trim())
+            .await?
+        else {
+            ctx.say(tr!("commands.programman.no_such_program", ctx = ctx, "name" => program))
+                .await?;
+            return Ok(());
+        };
+        Some(program.id)
     } else {
-        let mut text = String::from("Mentors:");
-        for teacher in teachers {
-            text.push_str(&format!("\n- #{}: {}", teacher.id, teacher.email));
-        }
-        text
+        None
     };
-    ctx.send(|b| b.content(text).ephemeral(true)).await?;
+
+    let filter = TeacherFilter { program_id, search };
+
+    let shown = crate::commands::forms::paginate_lazily(
+        ctx.into(),
+        "Mentors",
+        PageRequest::first(MENTORS_PER_PAGE),
+        |request| {
+            let filter = &filter;
+            async move {
+                let page = ctx
+                    .data
+                    .db
+                    .teacher_repository()
+                    .find_page(filter, &request)
+                    .await?;
+
+                Ok(LazyPage {
+                    content: page
+                        .items
+                        .iter()
+                        .map(|teacher| {
+                            format!("- #{}: {} ({})", teacher.id, teacher.name, teacher.email)
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                    next: page.next_request(&request),
+                })
+            }
+        },
+    )
+    .await?;
+
+    if !shown {
+        ctx.send(|b| b.content("No mentors found.").ephemeral(true))
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Lists the average rating given by students to each mentor's sessions.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    owners_only,
+    name_localized("pt-BR", "avaliacoes"),
+    description_localized(
+        "pt-BR",
+        "Lista a avaliação média dada pelos alunos às mentorias de cada mentor."
+    )
+)]
+pub async fn ratings(ctx: ApplicationContext<'_>) -> Result<()> {
+    let teacher_ratings = ctx
+        .data
+        .db
+        .feedback_repository()
+        .find_teacher_ratings()
+        .await?;
+
+    if teacher_ratings.is_empty() {
+        ctx.say(tr!("commands.teacherman.ratings.no_ratings", ctx = ctx))
+            .await?;
+        return Ok(());
+    }
+
+    let pages = teacher_ratings
+        .chunks(RATINGS_PER_PAGE)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .map(|(teacher, rating)| {
+                    tr!(
+                        "commands.teacherman.ratings.entry",
+                        ctx = ctx,
+                        "id" => teacher.id,
+                        "name" => teacher.name,
+                        "average" => format!("{:.2}", rating.average_rating),
+                        "count" => rating.rating_count,
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect::<Vec<_>>();
+
+    let page_count = pages.len();
+    let titles = (1..=page_count)
+        .map(|page| tr!("commands.teacherman.ratings.title", ctx = ctx, "page" => page, "pages" => page_count))
+        .collect::<Vec<_>>();
+
+    // convert to str references
+    let pages = pages.iter().map(|s| &**s).collect::<Vec<_>>();
+    let titles = titles.iter().map(|s| &**s).collect::<Vec<_>>();
+
+    crate::commands::forms::paginate(ctx.into(), Some(&titles), None, &pages).await?;
 
     Ok(())
 }
@@ -1,10 +1,10 @@
 use super::autocomplete::autocomplete_active_student_sessions;
 use crate::{
     common::ApplicationContext,
+    dm::{self, SessionEvent},
     lib::{
-        db::Repository,
         error::{Error, Result},
-        model::DiscordId,
+        model::{DiscordId, PartialSession, SessionStatus},
         util::{self, tr},
     },
 };
@@ -23,6 +23,10 @@ pub async fn unschedule(
     #[description_localized("pt-BR", "O número da mentoria.")]
     #[autocomplete = "autocomplete_active_student_sessions"]
     number: u32,
+
+    #[description = "Why you are unscheduling the session (optional)."]
+    #[description_localized("pt-BR", "Por que você está desmarcando a mentoria (opcional).")]
+    reason: Option<String>,
 ) -> Result<()> {
     // don't timeout the interaction if this takes a bit
     ctx.defer_ephemeral().await?;
@@ -30,15 +34,40 @@ pub async fn unschedule(
     let author = ctx.author();
     let author_id: DiscordId = author.id.into();
 
-    let Some((session, teacher, student)) = ctx.data.db.session_repository().get_with_participants(number as i64).await? else {
-        ctx.say(tr!("commands.sessions.info.no_such_session", ctx = ctx, "id" => number)).await?;
+    let Some((session, teacher, student)) = ctx
+        .data
+        .db
+        .session_repository()
+        .get_with_participants(number as i64)
+        .await?
+    else {
+        ctx.say(tr!("commands.sessions.info.no_such_session", ctx = ctx, "id" => number))
+            .await?;
         return Ok(());
     };
 
-    let Some(availability) = ctx.data.db.availability_repository().get(session.availability_id).await? else {
-        return Err(Error::Other("Expected availability associated to session to exist"));
+    let Some(availability) = ctx
+        .data
+        .db
+        .availability_repository()
+        .get(session.availability_id)
+        .await?
+    else {
+        return Err(Error::Other(
+            "Expected availability associated to session to exist",
+        ));
     };
 
+    if session.status != SessionStatus::Scheduled {
+        ctx.say(tr!(
+            "commands.unschedule.session_not_scheduled",
+            ctx = ctx,
+            "status" => session.status.to_locale_string(util::locale::get_defaulted_app_ctx_locale(ctx)),
+        ))
+        .await?;
+        return Ok(());
+    }
+
     if session.start_at < chrono::Utc::now() {
         ctx.say(tr!(
             "commands.unschedule.session_already_started",
@@ -48,10 +77,83 @@ pub async fn unschedule(
         return Ok(());
     }
 
-    if author_id != session.student_id {
+    let session_repository = ctx.data.db.session_repository();
+    let students = session_repository.find_students(session.id).await?;
+
+    let Some(author_student) = students
+        .iter()
+        .find(|attendee| attendee.discord_id == author_id)
+    else {
         ctx.say(tr!("commands.session.info.not_your_session", ctx = ctx, "id" => number))
             .await?;
         return Ok(());
+    };
+
+    // other students are still attending this group session => only leave it
+    if students.len() > 1 {
+        session_repository
+            .remove_student(session.id, author_id)
+            .await?;
+
+        if session.student_id == author_id {
+            // pass the booking on to the next student to have joined
+            if let Some(next_student) = students
+                .iter()
+                .find(|attendee| attendee.discord_id != author_id)
+            {
+                session_repository
+                    .update(
+                        &session,
+                        PartialSession {
+                            student_id: Some(next_student.discord_id),
+                            ..Default::default()
+                        },
+                    )
+                    .await?;
+            }
+        }
+
+        ctx.data
+            .google
+            .calendar
+            .remove_attendee_from_event_for_session(&session, author_student)
+            .await?;
+
+        let response = if let Err(err) = ctx
+            .data
+            .google
+            .email
+            .send_leave_emails_for_session(&teacher, author_student, &session)
+            .await
+        {
+            tracing::warn!("Couldn't send group session leaving email: {err:?}");
+            "commands.unschedule.left_success_no_email"
+        } else {
+            "commands.unschedule.left_success"
+        };
+
+        dm::notify_session_event(
+            &ctx.serenity_context.http,
+            &ctx.data.db,
+            SessionEvent::Left(author_student),
+            &session,
+            &teacher,
+            &students,
+        )
+        .await;
+
+        ctx.send(|b| {
+            b.content(tr!(
+                response,
+                ctx = ctx,
+                time = util::time::hour_minute_display(availability.time_start),
+                mentor = teacher.name,
+                session = session.id
+            ))
+        })
+        .await?;
+
+        return Ok(());
     }
 
     ctx.data
@@ -60,7 +162,12 @@ pub async fn unschedule(
         .cancel_event_for_session(&session)
         .await?;
 
-    ctx.data.db.session_repository().remove(&session).await?;
+    let reason = reason
+        .map(|reason| reason.trim().to_owned())
+        .filter(|reason| !reason.is_empty());
+    let session = session_repository
+        .cancel(&session, SessionStatus::CancelledByStudent, reason)
+        .await?;
 
     let response = if let Err(err) = ctx
         .data
@@ -75,6 +182,16 @@ pub async fn unschedule(
         "commands.unschedule.success"
     };
 
+    dm::notify_session_event(
+        &ctx.serenity_context.http,
+        &ctx.data.db,
+        SessionEvent::Cancelled,
+        &session,
+        &teacher,
+        &students,
+    )
+    .await;
+
     ctx.send(|b| {
         b.content(tr!(
             response,
@@ -1,16 +1,15 @@
 use poise::serenity_prelude as serenity;
 
 use crate::{
-    commands::modals::register::RegisterModal,
-    common::ApplicationContext,
-    lib::{
-        db::{Repository, UpdatableRepository},
-        error::Result,
-    },
+    commands::modals::register::RegisterModal, common::ApplicationContext, lib::error::Result,
 };
 
 /// Manages users in the database.
-#[poise::command(slash_command, subcommands("add", "get", "remove", "all"), ephemeral)]
+#[poise::command(
+    slash_command,
+    subcommands("add", "get", "remove", "all", "allowbooking"),
+    ephemeral
+)]
 pub async fn userman(ctx: ApplicationContext<'_>) -> Result<()> {
     ctx.send(|b| b.content("Use the 'add' command.").ephemeral(true))
         .await?;
@@ -25,7 +24,7 @@ pub async fn add(
 ) -> Result<()> {
     let data = RegisterModal::ask(ctx).await?;
     if let Some(modal_data) = data {
-        let new_user = modal_data.generate_new_user(user.id.into());
+        let new_user = modal_data.generate_new_user(user.id.into(), Default::default(), None);
         let inserted_user = ctx.data.db.user_repository().upsert(&new_user).await?;
         let response = format!(
             "Successfully added Mr. {} ({}) to the database (with{} a bio).",
@@ -56,11 +55,15 @@ pub async fn get(
     } else {
         let found_user = ctx.data.db.user_repository().get(user.id.into()).await?;
         if let Some(crate::lib::model::User {
-            bio, name, email, ..
+            bio,
+            name,
+            email,
+            timezone,
+            ..
         }) = found_user
         {
             let response = format!(
-                "We have Mr. {name} ({email}) with {}.",
+                "We have Mr. {name} ({email}, timezone {timezone}) with {}.",
                 if bio.is_some() {
                     "the bio below"
                 } else {
@@ -115,6 +118,31 @@ pub async fn remove(
     Ok(())
 }
 
+/// Allows a User to book their next session regardless of the booking limits
+#[poise::command(slash_command, ephemeral, owners_only)]
+pub async fn allowbooking(
+    ctx: ApplicationContext<'_>,
+    #[description = "Who may book their next session regardless of the limits"]
+    user: serenity::User,
+) -> Result<()> {
+    let user_repository = ctx.data().db.user_repository();
+    let response = if let Some(found_user) = user_repository.get(user.id.into()).await? {
+        user_repository
+            .grant_booking_override(found_user.discord_id, ctx.author().id.into())
+            .await?;
+        format!(
+            "{} may now book their next session regardless of the booking limits.",
+            found_user.name
+        )
+    } else {
+        "User not registered.".to_string()
+    };
+
+    ctx.send(|b| b.content(response).ephemeral(true)).await?;
+
+    Ok(())
+}
+
 #[poise::command(slash_command, ephemeral, owners_only)]
 pub async fn all(ctx: ApplicationContext<'_>) -> Result<()> {
     let users = ctx.data().db.user_repository().find_all().await?;
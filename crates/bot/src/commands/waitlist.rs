@@ -0,0 +1,212 @@
+use super::modals::register::RegisterModal;
+use crate::{
+    common::{ApplicationContext, Context},
+    lib::{
+        error::Result,
+        model::{DiscordId, NewWaitlistEntry, WaitlistStatus},
+        util::{self, tr},
+    },
+};
+
+/// Manages your place in the waitlist for sessions.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    name_localized("pt-BR", "listadeespera"),
+    description_localized("pt-BR", "Gerencia seu lugar na lista de espera por mentorias."),
+    subcommands("join", "leave", "status")
+)]
+pub async fn waitlist(ctx: Context<'_>) -> Result<()> {
+    ctx.send(|reply| {
+        reply
+            .content(tr!("commands.general.specify_subcommand", ctx = ctx))
+            .ephemeral(true)
+    })
+    .await?;
+    Ok(())
+}
+
+/// Joins the waitlist, to be offered a time as soon as one is available.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    name_localized("pt-BR", "entrar"),
+    description_localized(
+        "pt-BR",
+        "Entra na lista de espera, para receber um horário assim que houver um disponível."
+    )
+)]
+async fn join(
+    ctx: ApplicationContext<'_>,
+
+    #[description = "Only be offered times of the mentor with this e-mail."]
+    #[description_localized("pt-BR", "Receber apenas horários do mentor com este e-mail.")]
+    mentor_email: Option<String>,
+
+    #[description = "Only be offered times of mentors with this specialty."]
+    #[description_localized(
+        "pt-BR",
+        "Receber apenas horários de mentores com esta especialidade."
+    )]
+    specialty: Option<String>,
+) -> Result<()> {
+    let author_id: DiscordId = ctx.author().id.into();
+
+    let student = if let Some(student) = ctx.data.db.user_repository().get(author_id).await? {
+        student
+    } else {
+        // User not in DB => call registration modal (before deferring)
+        let Some(register) = RegisterModal::ask(ctx).await? else {
+            return Ok(());
+        };
+        ctx.data
+            .db
+            .user_repository()
+            .insert(&register.generate_new_user(
+                author_id,
+                Default::default(),
+                ctx.locale().map(str::to_owned),
+            ))
+            .await?
+    };
+
+    ctx.defer_ephemeral().await?;
+
+    let teacher = if let Some(email) = mentor_email.as_deref().map(str::trim) {
+        let Some(teacher) = ctx
+            .data
+            .db
+            .teacher_repository()
+            .find_by_email(email)
+            .await?
+        else {
+            ctx.say(tr!("commands.waitlist.no_such_mentor", ctx = ctx, "email" => email))
+                .await?;
+            return Ok(());
+        };
+        Some(teacher)
+    } else {
+        None
+    };
+
+    let specialty = specialty
+        .as_deref()
+        .map(str::trim)
+        .filter(|specialty| !specialty.is_empty())
+        .map(ToOwned::to_owned);
+
+    let entry = NewWaitlistEntry {
+        student_id: student.discord_id,
+        teacher_id: teacher.as_ref().map(|teacher| teacher.id),
+        specialty,
+    };
+
+    if ctx
+        .data
+        .db
+        .waitlist_repository()
+        .join(&entry)
+        .await?
+        .is_none()
+    {
+        ctx.say(tr!("commands.waitlist.join.already_in_waitlist", ctx = ctx))
+            .await?;
+        return Ok(());
+    }
+
+    ctx.say(tr!("commands.waitlist.join.success", ctx = ctx))
+        .await?;
+    Ok(())
+}
+
+/// Leaves the waitlist, giving up any time offered to you.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    name_localized("pt-BR", "sair"),
+    description_localized(
+        "pt-BR",
+        "Sai da lista de espera, abrindo mão de qualquer horário oferecido a você."
+    )
+)]
+async fn leave(ctx: ApplicationContext<'_>) -> Result<()> {
+    let author_id: DiscordId = ctx.author().id.into();
+
+    let response = if ctx.data.db.waitlist_repository().leave(author_id).await? == 0 {
+        tr!("commands.waitlist.not_in_waitlist", ctx = ctx)
+    } else {
+        tr!("commands.waitlist.leave.success", ctx = ctx)
+    };
+
+    ctx.say(response).await?;
+    Ok(())
+}
+
+/// Shows your place in the waitlist, or the time offered to you.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    description_localized(
+        "pt-BR",
+        "Mostra seu lugar na lista de espera, ou o horário oferecido a você."
+    )
+)]
+async fn status(ctx: ApplicationContext<'_>) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let author_id: DiscordId = ctx.author().id.into();
+    let db = &ctx.data.db;
+    let waitlist_repository = db.waitlist_repository();
+
+    let Some(entry) = waitlist_repository
+        .find_active_by_student(author_id)
+        .await?
+    else {
+        ctx.say(tr!("commands.waitlist.not_in_waitlist", ctx = ctx))
+            .await?;
+        return Ok(());
+    };
+
+    let response = match (entry.status, entry.offered_start_at, entry.offer_expires_at) {
+        (WaitlistStatus::Offered, Some(start_at), Some(expires_at)) => {
+            let teacher = match entry.offered_availability_id {
+                Some(avail_id) => match db.availability_repository().get(avail_id).await? {
+                    Some(avail) => db.teacher_repository().get(avail.teacher_id).await?,
+                    None => None,
+                },
+                None => None,
+            };
+            let timezone = db
+                .user_repository()
+                .get(author_id)
+                .await?
+                .map(|student| student.timezone)
+                .unwrap_or_default();
+            let start_at = start_at.with_timezone(&timezone.0);
+            let expires_at = expires_at.with_timezone(&timezone.0);
+
+            tr!(
+                "commands.waitlist.status.offered",
+                ctx = ctx,
+                "mentor" => teacher.map(|teacher| teacher.name).unwrap_or_default(),
+                "date" => util::time::day_month_year_display(&start_at.date_naive()),
+                "time" => util::time::hour_minute_display(start_at.time()),
+                "expires_date" => util::time::day_month_year_display(&expires_at.date_naive()),
+                "expires_time" => util::time::hour_minute_display(expires_at.time()),
+            )
+        }
+        _ => {
+            let position = waitlist_repository
+                .find_waiting()
+                .await?
+                .iter()
+                .position(|waiting| waiting.id == entry.id)
+                .map_or(1, |index| index + 1);
+
+            tr!("commands.waitlist.status.waiting", ctx = ctx, "position" => position)
+        }
+    };
+
+    ctx.say(response).await?;
+    Ok(())
+}
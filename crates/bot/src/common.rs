@@ -1,6 +1,15 @@
+use std::{collections::HashMap, sync::Arc};
+
+use tokio::sync::Mutex;
+
 use crate::{
     forms,
-    lib::{db::DatabaseManager, error::Error, notification::GoogleApiManager},
+    lib::{
+        db::DatabaseManager,
+        error::Error,
+        model::{BookingPolicy, DiscordId},
+        notification::GoogleApiManager,
+    },
 };
 
 /// Global command data, which is stored and accessible in all command invocations
@@ -9,18 +18,48 @@ pub struct Data {
     pub db: DatabaseManager,
     pub admin_userids: Vec<u64>,
     pub google: GoogleApiManager,
+
+    /// The limits on how many sessions each student may book.
+    pub booking_policy: BookingPolicy,
+
+    /// Pending requests by Discord users to link their accounts to mentor profiles,
+    /// awaiting email verification.
+    pub mentor_link_requests: Arc<Mutex<HashMap<DiscordId, MentorLinkRequest>>>,
 }
 
 impl Data {
-    pub fn new(db: DatabaseManager, admin_userids: Vec<u64>, google: GoogleApiManager) -> Self {
+    pub fn new(
+        db: DatabaseManager,
+        admin_userids: Vec<u64>,
+        google: GoogleApiManager,
+        booking_policy: BookingPolicy,
+    ) -> Self {
         Self {
             db,
             admin_userids,
             google,
+            booking_policy,
+            mentor_link_requests: Default::default(),
         }
     }
 }
 
+/// A pending request to link a Discord account to a mentor profile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MentorLinkRequest {
+    /// The ID of the mentor being claimed.
+    pub teacher_id: i64,
+
+    /// The verification code sent to the mentor's email.
+    pub code: String,
+
+    /// When the verification code stops being valid.
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+
+    /// How many wrong codes were given for this request so far.
+    pub failed_attempts: u8,
+}
+
 /// The bot's custom general Command Context type
 pub type Context<'a> = poise::Context<'a, Data, Error>;
 
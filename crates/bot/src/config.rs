@@ -1,5 +1,9 @@
+use std::path::PathBuf;
+
 use serde::{Deserialize, Serialize};
 
+use crate::lib::{model::BookingPolicy, notification::SmtpConfig};
+
 /// The bot's parsed config file.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub(crate) struct MentoriaBotConfig {
@@ -12,6 +16,16 @@ pub(crate) struct MentoriaBotConfig {
     /// This bot's database URL.
     pub(crate) database_url: String,
 
+    /// Whether pending database migrations are applied when the bot starts.
+    /// Disable this to manage migrations manually (e.g. with `diesel migration run`).
+    #[serde(default = "default_run_migrations")]
+    pub(crate) run_migrations: bool,
+
+    /// If true, the bot only lists the pending database migrations at startup,
+    /// without applying them, and then exits.
+    #[serde(default)]
+    pub(crate) migrations_dry_run: bool,
+
     /// The Discord User IDs of this bot's administrators.
     #[serde(default)]
     pub(crate) admin_userids: Vec<u64>,
@@ -23,6 +37,36 @@ pub(crate) struct MentoriaBotConfig {
     /// (e.g. "info").
     #[serde(default = "info_variant")]
     pub(crate) default_logging_level: LoggingLevels,
+
+    /// How long before each session its students and mentor are reminded of it,
+    /// in minutes (e.g. `[1440, 60]` for 24 hours and 1 hour before).
+    /// An empty list disables reminders.
+    #[serde(default = "default_reminder_offsets_minutes")]
+    pub(crate) reminder_offsets_minutes: Vec<u32>,
+
+    /// How often (in seconds) the bot checks for sessions whose reminders are due.
+    #[serde(default = "default_reminder_check_interval_secs")]
+    pub(crate) reminder_check_interval_secs: u64,
+
+    /// For how long (in minutes) a time offered to a student in the waitlist is held
+    /// for them, before it is offered to the next student.
+    #[serde(default = "default_waitlist_claim_minutes")]
+    pub(crate) waitlist_claim_minutes: u32,
+
+    /// The limits on how many sessions each student may book with `/schedule`
+    /// (administrators, and students granted an override, aren't limited).
+    #[serde(default)]
+    pub(crate) booking_policy: BookingPolicy,
+
+    /// The SMTP server through which e-mails are sent, if not through
+    /// the Google Account's Gmail.
+    #[serde(default)]
+    pub(crate) smtp: Option<SmtpConfig>,
+
+    /// If set, the bot doesn't connect to Google, instead writing its e-mails and calendar
+    /// events (with fake Google Meet IDs) to this directory. Meant for development.
+    #[serde(default)]
+    pub(crate) local_notifications_dir: Option<PathBuf>,
 }
 
 /// Possible logging levels.
@@ -60,3 +104,19 @@ impl From<LoggingLevels> for tracing::Level {
 fn info_variant() -> LoggingLevels {
     LoggingLevels::Info
 }
+
+fn default_run_migrations() -> bool {
+    true
+}
+
+fn default_reminder_offsets_minutes() -> Vec<u32> {
+    vec![24 * 60, 60]
+}
+
+fn default_reminder_check_interval_secs() -> u64 {
+    60
+}
+
+fn default_waitlist_claim_minutes() -> u32 {
+    60
+}
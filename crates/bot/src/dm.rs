@@ -0,0 +1,236 @@
+//! Notifies students and mentors of their sessions' events (bookings, cancellations,
+//! reschedules and reminders) through Discord DMs, unless they opted out of them
+//! (see '/notifications').
+use poise::serenity_prelude as serenity;
+use tracing::{info, warn};
+
+use crate::{
+    commands::embeds,
+    lib::{
+        db::DatabaseManager,
+        model::{DiscordId, Session, Teacher, Timezone, User},
+        util::{self, tr},
+    },
+};
+
+/// The error code Discord gives when a user can't be sent a DM
+/// (e.g. as they don't accept DMs from the bot's servers).
+const CANNOT_SEND_DM_ERROR_CODE: isize = 50007;
+
+/// Something which happened to a session, which its participants are notified of.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum SessionEvent<'a> {
+    /// The session was booked by its student.
+    Scheduled,
+
+    /// The given student joined the (group) session.
+    Joined(&'a User),
+
+    /// The given student left the (group) session, which goes on for the other students.
+    Left(&'a User),
+
+    /// The session was cancelled, by its student or by its mentor.
+    Cancelled,
+
+    /// The session was moved to another time, possibly with another mentor than
+    /// the given previous one.
+    Rescheduled { previous_teacher: &'a Teacher },
+
+    /// The session starts within the given time.
+    Reminder { time_left: chrono::Duration },
+}
+
+impl SessionEvent<'_> {
+    /// The name of this event's section in the `dms` section of the locale files.
+    fn key(&self) -> &'static str {
+        match self {
+            Self::Scheduled => "scheduled",
+            Self::Joined(_) => "joined",
+            Self::Left(_) => "left",
+            Self::Cancelled => "cancelled",
+            Self::Rescheduled { .. } => "rescheduled",
+            Self::Reminder { .. } => "reminder",
+        }
+    }
+
+    /// Describes this event, in the given locale, to the given kind of recipient
+    /// ("student", "mentor" or "previous_mentor").
+    fn describe(
+        &self,
+        recipient: &str,
+        session: &Session,
+        teacher: &Teacher,
+        student_names: &str,
+        locale: &str,
+    ) -> String {
+        let time_left = match self {
+            Self::Reminder { time_left } => {
+                util::locale::convert_chrono_duration_to_locale_string(*time_left, locale)
+            }
+            _ => String::new(),
+        };
+
+        tr!(
+            &format!("dms.{}.{recipient}", self.key()),
+            locale = locale,
+            "id" => session.id,
+            "mentor" => teacher.name,
+            "students" => student_names,
+            "time_left" => time_left,
+        )
+    }
+}
+
+/// Notifies the participants of a session of an event through DMs: the students concerned by
+/// the event and the session's mentor, if their profile is linked to a Discord account.
+/// Each DM is in its recipient's locale, with an embed describing the session in their
+/// timezone. Recipients who opted out of DMs are skipped, and DMs which couldn't be sent
+/// are only logged, as the participants are also notified by e-mail.
+pub(crate) async fn notify_session_event(
+    http: &serenity::Http,
+    db: &DatabaseManager,
+    event: SessionEvent<'_>,
+    session: &Session,
+    teacher: &Teacher,
+    students: &[User],
+) {
+    // only the student who joined or left is notified, not the whole group
+    let students = match event {
+        SessionEvent::Joined(student) | SessionEvent::Left(student) => {
+            std::slice::from_ref(student)
+        }
+        _ => students,
+    };
+    let student_names = students
+        .iter()
+        .map(|student| &*student.name)
+        .collect::<Vec<_>>()
+        .join(", ");
+    let attending = !session.status.is_cancelled();
+
+    for student in students {
+        let locale = student.notification_locale();
+        let show_meet_link = attending && !matches!(event, SessionEvent::Left(_));
+        let embed = session_dm_embed(session, teacher, student.timezone, locale, show_meet_link);
+
+        send_dm_with_embed(
+            http,
+            db,
+            student.discord_id,
+            event.describe("student", session, teacher, &student_names, locale),
+            Some(embed),
+        )
+        .await;
+    }
+
+    if let SessionEvent::Rescheduled { previous_teacher } = event {
+        if let Some(previous_discord_id) = previous_teacher
+            .discord_id
+            .filter(|_| previous_teacher.id != teacher.id)
+        {
+            let locale = previous_teacher.notification_locale();
+            send_dm(
+                http,
+                db,
+                previous_discord_id,
+                event.describe("previous_mentor", session, teacher, &student_names, locale),
+            )
+            .await;
+        }
+    }
+
+    if let Some(teacher_discord_id) = teacher.discord_id {
+        let locale = teacher.notification_locale();
+        let embed = session_dm_embed(session, teacher, teacher.timezone, locale, attending);
+
+        send_dm_with_embed(
+            http,
+            db,
+            teacher_discord_id,
+            event.describe("mentor", session, teacher, &student_names, locale),
+            Some(embed),
+        )
+        .await;
+    }
+}
+
+/// Generates the embed describing a session in a DM about it, with times in the recipient's
+/// timezone, and explaining how to stop getting such DMs.
+fn session_dm_embed(
+    session: &Session,
+    teacher: &Teacher,
+    timezone: Timezone,
+    locale: &str,
+    show_meet_link: bool,
+) -> serenity::CreateEmbed {
+    let mut embed = serenity::CreateEmbed::default();
+    embeds::session_embed(
+        &mut embed,
+        session,
+        teacher,
+        timezone,
+        Some(locale),
+        show_meet_link,
+    )
+    .footer(|footer| footer.text(tr!("dms.opt_out_hint", locale = locale)));
+
+    embed
+}
+
+/// Sends a DM to the given user, unless they opted out of DMs, logging why it couldn't be sent
+/// otherwise (e.g. if they don't accept DMs from the bot).
+pub(crate) async fn send_dm(
+    http: &serenity::Http,
+    db: &DatabaseManager,
+    user_id: DiscordId,
+    content: String,
+) {
+    send_dm_with_embed(http, db, user_id, content, None).await
+}
+
+/// Sends a DM with an optional embed to the given user, like [`send_dm`].
+async fn send_dm_with_embed(
+    http: &serenity::Http,
+    db: &DatabaseManager,
+    user_id: DiscordId,
+    content: String,
+    embed: Option<serenity::CreateEmbed>,
+) {
+    match db.user_repository().has_dm_opt_out(user_id).await {
+        Ok(true) => return,
+        Ok(false) => {}
+        Err(err) => {
+            warn!("Couldn't check whether user {user_id} opted out of DMs: {err}");
+            return;
+        }
+    }
+
+    let result = async {
+        serenity::UserId(user_id.into())
+            .create_dm_channel(http)
+            .await?
+            .send_message(http, |message| {
+                message.content(content);
+                if let Some(embed) = embed {
+                    message.set_embed(embed);
+                }
+                message
+            })
+            .await
+    }
+    .await;
+
+    match result {
+        Ok(_) => {}
+        Err(serenity::Error::Http(err))
+            if matches!(
+                &*err,
+                serenity::HttpError::UnsuccessfulRequest(response)
+                    if response.error.code == CANNOT_SEND_DM_ERROR_CODE
+            ) =>
+        {
+            info!("Couldn't send DM to user {user_id}, as they don't accept DMs from the bot.");
+        }
+        Err(err) => warn!("Couldn't send DM to user {user_id}: {err}"),
+    }
+}
@@ -1,3 +1,5 @@
+use std::{sync::Arc, time::Duration};
+
 use poise::serenity_prelude as serenity;
 use tracing::{error, info, warn};
 
@@ -7,7 +9,10 @@ mod authenticate;
 mod commands;
 mod common;
 mod config;
+mod dm;
 mod events;
+mod reminders;
+mod waitlist;
 
 use common::Data;
 use config::MentoriaBotConfig as Config;
@@ -263,10 +268,18 @@ config.example.json structure.",
 
     let Config {
         database_url,
+        run_migrations,
+        migrations_dry_run,
         token,
         guild_ids,
         admin_userids,
         default_logging_level,
+        reminder_offsets_minutes,
+        reminder_check_interval_secs,
+        waitlist_claim_minutes,
+        booking_policy,
+        smtp,
+        local_notifications_dir,
         ..
     } = parsed_config;
 
@@ -277,15 +290,66 @@ config.example.json structure.",
     tracing::subscriber::set_global_default(subscriber)
         .expect("Setting default logging level failed.");
 
+    if migrations_dry_run {
+        let pending = db::migrations::pending_migrations(&database_url)
+            .await
+            .expect("Failed to list the pending database migrations.");
+
+        if pending.is_empty() {
+            info!("Dry run: no pending database migrations.");
+        } else {
+            info!(
+                "Dry run: {} pending database migration(s) would be applied: {}",
+                pending.len(),
+                pending.join(", ")
+            );
+        }
+        return;
+    }
+
+    // a newer binary may have changed the schema in ways this one doesn't expect
+    db::migrations::verify_schema_not_ahead(&database_url)
+        .await
+        .expect("Failed to check the database schema against the known migrations.");
+
+    if run_migrations {
+        let applied = db::migrations::run_pending_migrations(&database_url)
+            .await
+            .expect("Failed to apply the pending database migrations.");
+
+        for migration in &applied {
+            info!("Applied database migration {migration}");
+        }
+        if applied.is_empty() {
+            info!("Database schema is up to date.");
+        }
+    }
+
     let db =
         db::DatabaseManager::new(&database_url).expect("Failed to connect to the bot's database.");
 
-    let auth = authenticate::Authenticator::authenticate().await.unwrap();
+    let google = if let Some(dir) = local_notifications_dir {
+        info!(
+            "Writing e-mails and calendar events to {} instead of using Google.",
+            dir.display()
+        );
+        notification::GoogleApiManager::local(dir)
+    } else {
+        let auth = authenticate::Authenticator::authenticate().await.unwrap();
 
-    // FIXME: Google Calendar ID
-    let google = notification::GoogleApiManager::connect(auth, "primary", "me")
-        .await
-        .expect("Failed to connect to the Google API.");
+        // FIXME: Google Calendar ID
+        notification::GoogleApiManager::connect(auth, "primary", "me")
+            .await
+            .expect("Failed to connect to the Google API.")
+    };
+
+    let google = match smtp {
+        Some(smtp) => google.with_email_sender(
+            notification::SmtpEmailSender::new(&smtp)
+                .expect("Failed to set up the SMTP e-mail sender."),
+        ),
+        None => google,
+    };
 
     let framework = poise::Framework::builder()
         .options(poise::FrameworkOptions {
@@ -313,7 +377,17 @@ config.example.json structure.",
                     .await?;
                 }
                 info!("Registered");
-                Ok(Data::new(db, admin_userids, google))
+
+                reminders::spawn_reminder_task(
+                    Arc::clone(&ctx.http),
+                    db.clone(),
+                    google.clone(),
+                    &reminder_offsets_minutes,
+                    Duration::from_secs(reminder_check_interval_secs),
+                    chrono::Duration::minutes(waitlist_claim_minutes.into()),
+                );
+
+                Ok(Data::new(db, admin_userids, google, booking_policy))
             })
         });
 
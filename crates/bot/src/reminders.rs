@@ -0,0 +1,208 @@
+//! Periodically reminds students and mentors of their upcoming sessions,
+//! through e-mail and Discord DMs, asks them for feedback on their
+//! sessions once they end, and offers freed-up times to students in the waitlist.
+use std::{sync::Arc, time::Duration};
+
+use poise::serenity_prelude as serenity;
+use tracing::{error, info, warn};
+
+use crate::{
+    dm::{self, send_dm, SessionEvent},
+    lib::{
+        db::DatabaseManager,
+        error::Result,
+        model::{Session, Teacher},
+        notification::GoogleApiManager,
+        util::tr,
+    },
+    waitlist,
+};
+
+/// Spawns a task which, at every check interval, looks for scheduled sessions starting
+/// within any of the given offsets (in minutes) and reminds their participants of them,
+/// as well as for sessions which ended, asking their participants for feedback.
+/// Sent reminders and feedback requests are registered in the database, so they are
+/// never sent twice, even across restarts. Available times are also offered to the
+/// students in the waitlist, each held for them during the given claim window.
+pub(crate) fn spawn_reminder_task(
+    http: Arc<serenity::Http>,
+    db: DatabaseManager,
+    google: GoogleApiManager,
+    offsets_minutes: &[u32],
+    check_interval: Duration,
+    waitlist_claim_window: chrono::Duration,
+) -> tokio::task::JoinHandle<()> {
+    // closer offsets first, so that a session which is about to start only gets
+    // its closest reminder, instead of all of them at once
+    let mut offsets_minutes = offsets_minutes
+        .iter()
+        .filter_map(|&offset| i32::try_from(offset).ok())
+        .filter(|&offset| offset > 0)
+        .collect::<Vec<_>>();
+    offsets_minutes.sort_unstable();
+    offsets_minutes.dedup();
+
+    tokio::spawn(async move {
+        if offsets_minutes.is_empty() {
+            info!("No session reminder offsets configured; reminders are disabled.");
+        }
+
+        let mut interval = tokio::time::interval(check_interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(err) = send_due_reminders(&http, &db, &google, &offsets_minutes).await {
+                error!("Failed to send session reminders: {err}");
+            }
+
+            if let Err(err) = send_due_feedback_requests(&http, &db).await {
+                error!("Failed to send session feedback requests: {err}");
+            }
+
+            if let Err(err) =
+                waitlist::offer_available_times(&http, &db, &google, waitlist_claim_window).await
+            {
+                error!("Failed to offer available times to the waitlist: {err}");
+            }
+        }
+    })
+}
+
+/// Sends all reminders which are due at this moment.
+/// Expects the offsets to be sorted in ascending order.
+async fn send_due_reminders(
+    http: &serenity::Http,
+    db: &DatabaseManager,
+    google: &GoogleApiManager,
+    offsets_minutes: &[i32],
+) -> Result<()> {
+    let session_repository = db.session_repository();
+    let now = chrono::Utc::now();
+
+    for &offset in offsets_minutes {
+        for (session, teacher) in session_repository
+            .find_needing_reminder(offset, now)
+            .await?
+        {
+            // register the reminder before sending it, so it's never sent twice
+            if session_repository
+                .register_reminder(session.id, offset)
+                .await?
+                == 0
+            {
+                continue;
+            }
+
+            // the closest reminder to the session's start was sent => no more reminders
+            let session = if Some(&offset) == offsets_minutes.first() {
+                session_repository.set_notified(&session).await?
+            } else {
+                session
+            };
+
+            send_reminder(http, db, google, &session, &teacher, now).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Reminds a session's students and mentor of it.
+async fn send_reminder(
+    http: &serenity::Http,
+    db: &DatabaseManager,
+    google: &GoogleApiManager,
+    session: &Session,
+    teacher: &Teacher,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<()> {
+    let students = db.session_repository().find_students(session.id).await?;
+    let time_left = session.start_at.signed_duration_since(now);
+
+    info!(
+        "Reminding participants of session #{} (starting in {} minutes).",
+        session.id,
+        time_left.num_minutes()
+    );
+
+    if let Err(err) = google
+        .email
+        .send_reminder_emails_for_session(teacher, &students, session, time_left)
+        .await
+    {
+        warn!(
+            "Couldn't send reminder emails for session #{}: {err:?}",
+            session.id
+        );
+    }
+
+    dm::notify_session_event(
+        http,
+        db,
+        SessionEvent::Reminder { time_left },
+        session,
+        teacher,
+        &students,
+    )
+    .await;
+
+    Ok(())
+}
+
+/// Asks the participants of all sessions which ended (and weren't cancelled)
+/// for feedback on them, unless they were already asked.
+async fn send_due_feedback_requests(http: &serenity::Http, db: &DatabaseManager) -> Result<()> {
+    let session_repository = db.session_repository();
+
+    for (session, teacher) in session_repository
+        .find_needing_feedback_request(chrono::Utc::now())
+        .await?
+    {
+        // register the request before sending it, so it's never sent twice
+        if session_repository
+            .register_feedback_request(session.id)
+            .await?
+            == 0
+        {
+            continue;
+        }
+
+        info!(
+            "Asking participants of session #{} for feedback.",
+            session.id
+        );
+
+        for student in session_repository.find_students(session.id).await? {
+            send_dm(
+                http,
+                db,
+                student.discord_id,
+                tr!(
+                    "reminders.feedback_student_dm",
+                    locale = student.notification_locale(),
+                    "id" => session.id,
+                    "mentor" => teacher.name,
+                ),
+            )
+            .await;
+        }
+
+        if let Some(teacher_discord_id) = teacher.discord_id {
+            send_dm(
+                http,
+                db,
+                teacher_discord_id,
+                tr!(
+                    "reminders.feedback_mentor_dm",
+                    locale = teacher.notification_locale(),
+                    "id" => session.id,
+                ),
+            )
+            .await;
+        }
+    }
+
+    Ok(())
+}
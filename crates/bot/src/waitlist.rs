@@ -0,0 +1,198 @@
+//! Offers times which became available (e.g. newly added available times, or the times
+//! of cancelled sessions) to the students in the waitlist, in the order they joined it.
+use std::collections::{BTreeMap, HashSet};
+
+use poise::serenity_prelude as serenity;
+use tracing::{info, warn};
+
+use crate::{
+    dm::send_dm,
+    lib::{
+        db::DatabaseManager,
+        error::Result,
+        model::{Availability, Teacher, WaitlistEntry},
+        notification::GoogleApiManager,
+        util::{self, tr},
+    },
+};
+
+/// Expires the offers which weren't claimed in time, then offers each available time
+/// (earliest first) to the first waiting student it matches, holding it for them
+/// during the given claim window (or until it starts, if sooner).
+pub(crate) async fn offer_available_times(
+    http: &serenity::Http,
+    db: &DatabaseManager,
+    google: &GoogleApiManager,
+    claim_window: chrono::Duration,
+) -> Result<()> {
+    let waitlist_repository = db.waitlist_repository();
+    let user_repository = db.user_repository();
+    let now = chrono::Utc::now();
+
+    for entry in waitlist_repository.expire_offers(now).await? {
+        info!(
+            "Offer to waitlist entry #{} expired without being claimed.",
+            entry.id
+        );
+
+        let Some(student) = user_repository.get(entry.student_id).await? else {
+            continue;
+        };
+
+        send_dm(
+            http,
+            db,
+            student.discord_id,
+            tr!(
+                "waitlist.offer_expired_dm",
+                locale = student.notification_locale()
+            ),
+        )
+        .await;
+    }
+
+    let waiting = waitlist_repository.find_waiting().await?;
+    if waiting.is_empty() {
+        return Ok(());
+    }
+
+    let mut times = find_unheld_times(db).await?;
+
+    for entry in waiting {
+        let Some(index) = times
+            .iter()
+            .position(|(start_at, _, teacher)| *start_at > now && entry.matches(teacher))
+        else {
+            continue;
+        };
+
+        let (start_at, avail, teacher) = times.remove(index);
+        let expires_at = (now + claim_window).min(start_at);
+
+        let Some(entry) = waitlist_repository
+            .offer(entry.id, avail.id, start_at, expires_at)
+            .await?
+        else {
+            // the student left the waitlist in the meantime
+            times.insert(index, (start_at, avail, teacher));
+            continue;
+        };
+
+        notify_offer(http, db, google, &entry, &teacher).await?;
+
+        if times.is_empty() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Finds the next occurrences of the available times within a week which aren't held
+/// for a student in the waitlist, along with their mentors, earliest first.
+async fn find_unheld_times(
+    db: &DatabaseManager,
+) -> Result<Vec<(chrono::DateTime<chrono::Utc>, Availability, Teacher)>> {
+    let held_availability_ids = db
+        .waitlist_repository()
+        .find_offered()
+        .await?
+        .into_iter()
+        .filter_map(|entry| entry.offered_availability_id)
+        .collect::<HashSet<_>>();
+
+    let now = util::time::brazil_now();
+    let mut availabilities = db
+        .availability_repository()
+        .find_nontaken_within_a_week_of_date(now)
+        .await?;
+    availabilities.retain(|avail| !held_availability_ids.contains(&avail.id));
+
+    if availabilities.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut teacher_ids = availabilities
+        .iter()
+        .map(|avail| avail.teacher_id)
+        .collect::<Vec<_>>();
+    teacher_ids.sort_unstable();
+    teacher_ids.dedup();
+
+    let teachers = db
+        .teacher_repository()
+        .find_by_ids(&teacher_ids)
+        .await?
+        .into_iter()
+        .map(|teacher| (teacher.id, teacher))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut times = availabilities
+        .into_iter()
+        .filter_map(|avail| {
+            let teacher = teachers.get(&avail.teacher_id)?.clone();
+            Some((
+                avail.first_start_after(teacher.timezone, &now),
+                avail,
+                teacher,
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    times.sort_by_key(|(start_at, avail, _)| (*start_at, avail.id));
+    Ok(times)
+}
+
+/// Tells a student in the waitlist, by DM and e-mail, of the time offered to them.
+async fn notify_offer(
+    http: &serenity::Http,
+    db: &DatabaseManager,
+    google: &GoogleApiManager,
+    entry: &WaitlistEntry,
+    teacher: &Teacher,
+) -> Result<()> {
+    let (Some(start_at), Some(expires_at)) = (entry.offered_start_at, entry.offer_expires_at)
+    else {
+        return Ok(());
+    };
+    let Some(student) = db.user_repository().get(entry.student_id).await? else {
+        return Ok(());
+    };
+
+    info!(
+        "Offering a time with mentor #{} to waitlist entry #{}.",
+        teacher.id, entry.id
+    );
+
+    if let Err(err) = google
+        .email
+        .send_waitlist_offer_email(teacher, &student, start_at, expires_at)
+        .await
+    {
+        warn!(
+            "Couldn't send waitlist offer email for entry #{}: {err:?}",
+            entry.id
+        );
+    }
+
+    let start_at = start_at.with_timezone(&student.timezone.0);
+    let expires_at = expires_at.with_timezone(&student.timezone.0);
+
+    send_dm(
+        http,
+        db,
+        student.discord_id,
+        tr!(
+            "waitlist.offer_dm",
+            locale = student.notification_locale(),
+            "mentor" => teacher.name,
+            "date" => util::time::day_month_year_display(&start_at.date_naive()),
+            "time" => util::time::hour_minute_display(start_at.time()),
+            "expires_date" => util::time::day_month_year_display(&expires_at.date_naive()),
+            "expires_time" => util::time::hour_minute_display(expires_at.time()),
+        ),
+    )
+    .await;
+
+    Ok(())
+}
@@ -45,12 +45,28 @@ where
         data: &mut FormState<FormData>,
     ) -> ContextualResult<Option<Box<Self>>, ContextError>;
 
+    /// Method called before the component is sent, which may skip it (e.g. if the user
+    /// would only have one option to pick) by returning the component directly.
+    ///
+    /// Return None (the default) to send the component as usual.
+    async fn skip(
+        _context: ApplicationContext<'_, ContextData, ContextError>,
+        _data: &mut FormState<FormData>,
+    ) -> ContextualResult<Option<Box<Self>>, ContextError> {
+        Ok(None)
+    }
+
     /// The main method, causes the component to be sent to Discord
-    /// and its response awaited by invoking the other methods.
+    /// and its response awaited by invoking the other methods
+    /// (unless it is skipped).
     async fn run(
         context: ApplicationContext<'_, ContextData, ContextError>,
         data: &mut FormState<FormData>,
     ) -> ContextualResult<Box<Self>, ContextError> {
+        if let Some(skipped) = Self::skip(context, data).await? {
+            return Ok(skipped);
+        }
+
         let ids = Self::send_component(context, data).await?;
 
         // keep waiting for a response until 'on_response' returns something
@@ -1,10 +1,20 @@
 //! Module for all things database in the bot.
 mod connection;
+pub mod memory;
+pub mod migrations;
+mod pagination;
 mod repository;
 pub mod schema;
+mod store;
 
-pub use connection::DatabaseManager;
+pub use connection::{ConnectionSource, DatabaseManager, DbConnection};
+pub use pagination::{Page, PageRequest, SortOrder};
 pub use repository::{
-    AvailabilityRepository, Repository, SessionRepository, TeacherRepository, UpdatableRepository,
-    UserRepository,
+    AvailabilityRepository, FeedbackRepository, ProgramRepository, Repository, SessionCursor,
+    SessionFilter, SessionRepository, TagRepository, TeacherFilter, TeacherRepository,
+    UpdatableRepository, UserRepository, WaitlistRepository,
+};
+pub use store::{
+    AvailabilityStore, FeedbackStore, ProgramStore, SessionStore, TagStore, TeacherStore,
+    UserStore, WaitlistStore,
 };
@@ -1,10 +1,10 @@
 //! Contains structs and functions related to connecting to the database.
-use std::sync::Arc;
-
-use diesel_async::{
-    pooled_connection::{deadpool::Pool, AsyncDieselConnectionManager},
-    AsyncPgConnection,
+use std::{
+    future::Future,
+    ops::{Deref, DerefMut},
+    sync::Arc,
 };
+
 #[cfg(test)]
 use diesel_async::{
     pooled_connection::{
@@ -13,22 +13,168 @@ use diesel_async::{
     },
     AsyncConnection,
 };
+use diesel_async::{
+    pooled_connection::{
+        deadpool::{Object, Pool},
+        AsyncDieselConnectionManager,
+    },
+    AnsiTransactionManager, AsyncPgConnection, TransactionManager,
+};
+use tokio::sync::{Mutex, OwnedMutexGuard};
 
-use super::repository::{
-    AvailabilityRepository, SessionRepository, TeacherRepository, UserRepository,
+use super::{
+    memory::{
+        MemoryAvailabilityRepository, MemoryDatabase, MemoryFeedbackRepository,
+        MemoryProgramRepository, MemorySessionRepository, MemoryTagRepository,
+        MemoryTeacherRepository, MemoryUserRepository, MemoryWaitlistRepository,
+    },
+    repository::{
+        AvailabilityRepository, FeedbackRepository, ProgramRepository, SessionRepository,
+        TagRepository, TeacherRepository, UserRepository, WaitlistRepository,
+    },
+    store::{
+        AvailabilityStore, FeedbackStore, ProgramStore, SessionStore, TagStore, TeacherStore,
+        UserStore, WaitlistStore,
+    },
 };
 use crate::error::Result;
 
 /// Manages database Connection and Repository objects, using a
-/// connection [`Pool`].
+/// connection [`Pool`] (or a single connection, within a transaction).
+/// Alternatively, repositories may keep their entities in memory (see
+/// [`DatabaseManager::in_memory`]).
 #[derive(Clone)]
 pub struct DatabaseManager {
-    #[allow(dead_code)]
-    pool: Arc<Pool<AsyncPgConnection>>,
-    user_repository: UserRepository,
-    session_repository: SessionRepository,
-    teacher_repository: TeacherRepository,
-    availability_repository: AvailabilityRepository,
+    backend: Backend,
+    user_repository: Arc<dyn UserStore>,
+    session_repository: Arc<dyn SessionStore>,
+    teacher_repository: Arc<dyn TeacherStore>,
+    availability_repository: Arc<dyn AvailabilityStore>,
+    program_repository: Arc<dyn ProgramStore>,
+    feedback_repository: Arc<dyn FeedbackStore>,
+    waitlist_repository: Arc<dyn WaitlistStore>,
+    tag_repository: Arc<dyn TagStore>,
+}
+
+/// Where a [`DatabaseManager`]'s repositories keep their entities.
+#[derive(Clone)]
+enum Backend {
+    /// In the database, through the given connection source.
+    Database(ConnectionSource),
+
+    /// In memory, without a database.
+    Memory(MemoryDatabase),
+}
+
+/// Where repositories obtain their database connections from.
+#[derive(Clone)]
+pub enum ConnectionSource {
+    /// Each operation locks any available connection from the pool.
+    Pool(Arc<Pool<AsyncPgConnection>>),
+
+    /// All operations share a single connection, with an ongoing transaction.
+    Transaction(Arc<Mutex<Object<AsyncPgConnection>>>),
+}
+
+/// A database connection locked from a [`ConnectionSource`],
+/// released when dropped.
+pub enum DbConnection {
+    /// A connection from a pool.
+    Pooled(Object<AsyncPgConnection>),
+
+    /// The connection of an ongoing transaction.
+    Transaction(OwnedMutexGuard<Object<AsyncPgConnection>>),
+}
+
+impl ConnectionSource {
+    /// Locks a connection from this source. Within a transaction, this waits
+    /// until its connection is released by whoever is using it.
+    pub async fn get(&self) -> Result<DbConnection> {
+        match self {
+            Self::Pool(pool) => pool
+                .get()
+                .await
+                .map(DbConnection::Pooled)
+                .map_err(From::from),
+            Self::Transaction(connection) => Ok(DbConnection::Transaction(
+                Arc::clone(connection).lock_owned().await,
+            )),
+        }
+    }
+}
+
+impl Deref for DbConnection {
+    type Target = AsyncPgConnection;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Pooled(connection) => connection,
+            Self::Transaction(connection) => connection,
+        }
+    }
+}
+
+impl DerefMut for DbConnection {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            Self::Pooled(connection) => connection,
+            Self::Transaction(connection) => connection,
+        }
+    }
+}
+
+/// Guards a transaction which was begun on a connection, rolling it back when dropped
+/// unless disarmed (once the transaction was committed or rolled back), such that a
+/// pooled connection is never reused with a transaction left open.
+struct TransactionGuard {
+    connection: Option<Arc<Mutex<Object<AsyncPgConnection>>>>,
+}
+
+impl TransactionGuard {
+    fn new(connection: &Arc<Mutex<Object<AsyncPgConnection>>>) -> Self {
+        Self {
+            connection: Some(Arc::clone(connection)),
+        }
+    }
+
+    /// Indicates the transaction was finished, so that dropping the guard does nothing.
+    fn disarm(mut self) {
+        self.connection = None;
+    }
+
+    /// Removes the connection from its pool (closing it once dropped), unless it is
+    /// still being used elsewhere (e.g. by an outer transaction).
+    fn discard(connection: Arc<Mutex<Object<AsyncPgConnection>>>) {
+        if let Ok(connection) = Arc::try_unwrap(connection) {
+            drop(Object::take(connection.into_inner()));
+        }
+    }
+}
+
+impl Drop for TransactionGuard {
+    fn drop(&mut self) {
+        let Some(connection) = self.connection.take() else {
+            return;
+        };
+
+        // rolling back is async, so it is done in a separate task; meanwhile, that task
+        // holds the connection, so it is only returned to the pool afterwards
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => {
+                runtime.spawn(async move {
+                    let rollback = AnsiTransactionManager::rollback_transaction(
+                        &mut **connection.lock().await,
+                    )
+                    .await;
+
+                    if rollback.is_err() {
+                        Self::discard(connection);
+                    }
+                });
+            }
+            Err(_) => Self::discard(connection),
+        }
+    }
 }
 
 /// General function for creating a connection pool to the database.
@@ -63,7 +209,9 @@ impl DatabaseManager {
     pub fn new(database_url: &str) -> Result<Self> {
         let pool = create_connection_pool(database_url)?;
 
-        Ok(Self::with_pool(Arc::new(pool)))
+        Ok(Self::with_connection_source(ConnectionSource::Pool(
+            Arc::new(pool),
+        )))
     }
 
     /// Creates a Database Manager with a test connection
@@ -72,47 +220,163 @@ impl DatabaseManager {
     pub fn test(database_url: &str) -> Result<Self> {
         let pool = create_test_connection_pool(database_url)?;
 
-        Ok(Self::with_pool(Arc::new(pool)))
+        Ok(Self::with_connection_source(ConnectionSource::Pool(
+            Arc::new(pool),
+        )))
     }
 
-    /// Creates a new Database Manager operating on the given connection pool.
-    fn with_pool(pool: Arc<Pool<AsyncPgConnection>>) -> Self {
-        let user_repository = UserRepository::new(&pool);
-        let session_repository = SessionRepository::new(&pool);
-        let teacher_repository = TeacherRepository::new(&pool);
-        let availability_repository = AvailabilityRepository::new(&pool);
+    /// Creates a Database Manager whose repositories keep their entities in memory,
+    /// starting empty, such that no database is needed (e.g. for tests).
+    pub fn in_memory() -> Self {
+        let database = MemoryDatabase::new();
 
         Self {
-            pool,
-            user_repository,
-            session_repository,
-            teacher_repository,
-            availability_repository,
+            user_repository: Arc::new(MemoryUserRepository::new(&database)),
+            session_repository: Arc::new(MemorySessionRepository::new(&database)),
+            teacher_repository: Arc::new(MemoryTeacherRepository::new(&database)),
+            availability_repository: Arc::new(MemoryAvailabilityRepository::new(&database)),
+            program_repository: Arc::new(MemoryProgramRepository::new(&database)),
+            feedback_repository: Arc::new(MemoryFeedbackRepository::new(&database)),
+            waitlist_repository: Arc::new(MemoryWaitlistRepository::new(&database)),
+            tag_repository: Arc::new(MemoryTagRepository::new(&database)),
+            backend: Backend::Memory(database),
         }
     }
 
-    /// Returns this Database Manager's connection pool.
-    pub fn pool(&self) -> Arc<Pool<AsyncPgConnection>> {
-        Arc::clone(&self.pool)
+    /// Creates a new Database Manager operating on the given connection source.
+    fn with_connection_source(connection: ConnectionSource) -> Self {
+        Self {
+            user_repository: Arc::new(UserRepository::new(&connection)),
+            session_repository: Arc::new(SessionRepository::new(&connection)),
+            teacher_repository: Arc::new(TeacherRepository::new(&connection)),
+            availability_repository: Arc::new(AvailabilityRepository::new(&connection)),
+            program_repository: Arc::new(ProgramRepository::new(&connection)),
+            feedback_repository: Arc::new(FeedbackRepository::new(&connection)),
+            waitlist_repository: Arc::new(WaitlistRepository::new(&connection)),
+            tag_repository: Arc::new(TagRepository::new(&connection)),
+            backend: Backend::Database(connection),
+        }
+    }
+
+    /// Returns where this Database Manager's repositories obtain their connections from,
+    /// or 'None' if they keep their entities in memory.
+    pub fn connection_source(&self) -> Option<&ConnectionSource> {
+        match &self.backend {
+            Backend::Database(connection) => Some(connection),
+            Backend::Memory(_) => None,
+        }
+    }
+
+    /// Runs the given closure within a database transaction, giving it a Database Manager
+    /// whose repositories all operate on that transaction's connection. The transaction is
+    /// committed if the closure returns 'Ok', and rolled back otherwise (in which case the
+    /// closure's error is returned). Transactions may be nested (using savepoints).
+    ///
+    /// Only the given Database Manager's repositories take part in the transaction;
+    /// using 'self' within the closure will operate outside of it.
+    ///
+    /// In memory, the transaction is rolled back by restoring all entities to how they were
+    /// when it began, which also undoes changes made concurrently by others. This is fine
+    /// for tests, which are the purpose of keeping entities in memory.
+    pub async fn transaction<T, F, Fut>(&self, callback: F) -> Result<T>
+    where
+        F: FnOnce(DatabaseManager) -> Fut + Send,
+        Fut: Future<Output = Result<T>> + Send,
+        T: Send,
+    {
+        let connection = match &self.backend {
+            Backend::Database(ConnectionSource::Pool(pool)) => {
+                Arc::new(Mutex::new(pool.get().await?))
+            }
+            Backend::Database(ConnectionSource::Transaction(connection)) => Arc::clone(connection),
+            Backend::Memory(database) => {
+                let snapshot = database.snapshot();
+                let result = callback(self.clone()).await;
+                if result.is_err() {
+                    database.restore(snapshot);
+                }
+                return result;
+            }
+        };
+
+        AnsiTransactionManager::begin_transaction(&mut **connection.lock().await).await?;
+        // rolls back if the callback panics or this future is dropped before finishing
+        let guard = TransactionGuard::new(&connection);
+
+        let result = callback(Self::with_connection_source(ConnectionSource::Transaction(
+            Arc::clone(&connection),
+        )))
+        .await;
+
+        let mut locked_connection = connection.lock().await;
+        let result = match result {
+            Ok(value) => {
+                AnsiTransactionManager::commit_transaction(&mut **locked_connection).await?;
+                Ok(value)
+            }
+            Err(err) => {
+                AnsiTransactionManager::rollback_transaction(&mut **locked_connection).await?;
+                Err(err)
+            }
+        };
+
+        guard.disarm();
+        result
+    }
+
+    /// Returns the repository of [`User`] instances.
+    ///
+    /// [`User`]: crate::model::User
+    pub fn user_repository(&self) -> &dyn UserStore {
+        &*self.user_repository
+    }
+
+    /// Returns the repository of [`Session`] instances.
+    ///
+    /// [`Session`]: crate::model::Session
+    pub fn session_repository(&self) -> &dyn SessionStore {
+        &*self.session_repository
+    }
+
+    /// Returns the repository of [`Teacher`] instances.
+    ///
+    /// [`Teacher`]: crate::model::Teacher
+    pub fn teacher_repository(&self) -> &dyn TeacherStore {
+        &*self.teacher_repository
+    }
+
+    /// Returns the repository of [`Availability`] instances.
+    ///
+    /// [`Availability`]: crate::model::Availability
+    pub fn availability_repository(&self) -> &dyn AvailabilityStore {
+        &*self.availability_repository
     }
 
-    /// Returns a [`UserRepository`] object using the current connection pool.
-    pub fn user_repository(&self) -> &UserRepository {
-        &self.user_repository
+    /// Returns the repository of [`Program`] instances.
+    ///
+    /// [`Program`]: crate::model::Program
+    pub fn program_repository(&self) -> &dyn ProgramStore {
+        &*self.program_repository
     }
 
-    /// Returns a [`SessionRepository`] object using the current connection pool.
-    pub fn session_repository(&self) -> &SessionRepository {
-        &self.session_repository
+    /// Returns the repository of [`SessionFeedback`] instances.
+    ///
+    /// [`SessionFeedback`]: crate::model::SessionFeedback
+    pub fn feedback_repository(&self) -> &dyn FeedbackStore {
+        &*self.feedback_repository
     }
 
-    /// Returns a [`TeacherRepository`] object using the current connection pool.
-    pub fn teacher_repository(&self) -> &TeacherRepository {
-        &self.teacher_repository
+    /// Returns the repository of [`WaitlistEntry`] instances.
+    ///
+    /// [`WaitlistEntry`]: crate::model::WaitlistEntry
+    pub fn waitlist_repository(&self) -> &dyn WaitlistStore {
+        &*self.waitlist_repository
     }
 
-    /// Returns an [`AvailabilityRepository`] object using the current connection pool.
-    pub fn availability_repository(&self) -> &AvailabilityRepository {
-        &self.availability_repository
+    /// Returns the repository of [`Tag`] instances (and of the tags of each teacher).
+    ///
+    /// [`Tag`]: crate::model::Tag
+    pub fn tag_repository(&self) -> &dyn TagStore {
+        &*self.tag_repository
     }
 }
@@ -0,0 +1,366 @@
+//! Contains in-memory implementations of each repository's operations, which behave
+//! like the database repositories (including their unique constraints and foreign keys),
+//! such that code using them can be tested without a database.
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+
+use crate::{
+    error::{Error, Result},
+    model::{
+        Availability, DiscordId, Program, Session, SessionFeedback, SessionStatus, SessionStudent,
+        Tag, Teacher, User, WaitlistEntry,
+    },
+};
+
+mod availability;
+pub use availability::MemoryAvailabilityRepository;
+
+mod feedback;
+pub use feedback::MemoryFeedbackRepository;
+
+mod program;
+pub use program::MemoryProgramRepository;
+
+mod session;
+pub use session::MemorySessionRepository;
+
+mod tag;
+pub use tag::MemoryTagRepository;
+
+mod teacher;
+pub use teacher::MemoryTeacherRepository;
+
+mod user;
+pub use user::MemoryUserRepository;
+
+mod waitlist;
+pub use waitlist::MemoryWaitlistRepository;
+
+/// Holds the entities shared by all in-memory repositories created with it.
+/// Cloning it gives another handle to the same entities.
+#[derive(Clone, Default)]
+pub struct MemoryDatabase {
+    state: Arc<Mutex<MemoryState>>,
+}
+
+/// All entities held by a [`MemoryDatabase`], mirroring the database tables.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct MemoryState {
+    users: BTreeMap<u64, User>,
+    /// The Discord IDs of students granted a booking override, and of who granted each.
+    booking_overrides: BTreeMap<u64, u64>,
+    /// The Discord IDs of users who opted out of DM notifications (not necessarily
+    /// registered, as they may be mentors).
+    dm_opt_outs: BTreeSet<u64>,
+    teachers: BTreeMap<i64, Teacher>,
+    availabilities: BTreeMap<i64, Availability>,
+    programs: BTreeMap<i64, Program>,
+    sessions: BTreeMap<i64, Session>,
+    /// Kept in the order students joined their sessions.
+    session_students: Vec<SessionStudent>,
+    /// Pairs of session IDs and offsets (in minutes) of the reminders sent for them.
+    session_reminders: BTreeSet<(i64, i32)>,
+    /// Feedback given on sessions, by session ID and author's Discord ID.
+    session_feedback: BTreeMap<(i64, u64), SessionFeedback>,
+    waitlist_entries: BTreeMap<i64, WaitlistEntry>,
+    tags: BTreeMap<i64, Tag>,
+    /// Pairs of teacher IDs and the IDs of their tags.
+    teacher_tags: BTreeSet<(i64, i64)>,
+    /// The last ID given to each kind of entity, like the database's sequences.
+    last_ids: LastIds,
+}
+
+#[derive(Debug, Default, Clone)]
+struct LastIds {
+    teacher: i64,
+    availability: i64,
+    program: i64,
+    session: i64,
+    waitlist_entry: i64,
+    tag: i64,
+}
+
+impl MemoryDatabase {
+    /// Creates an empty in-memory database.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Locks the entities for own usage. Must not be held across '.await' points.
+    fn lock(&self) -> MutexGuard<'_, MemoryState> {
+        // the state is never left halfway through an operation, so it can be used
+        // even if a thread panicked while holding the lock
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Copies all entities, so they can be restored later (see [`MemoryDatabase::restore`]).
+    pub(crate) fn snapshot(&self) -> MemoryState {
+        self.lock().clone()
+    }
+
+    /// Replaces all entities with a previous snapshot of them.
+    pub(crate) fn restore(&self, state: MemoryState) {
+        *self.lock() = state;
+    }
+}
+
+impl MemoryState {
+    /// Checks that a Teacher's fields don't conflict with other teachers nor refer
+    /// to non-existing entities.
+    fn check_teacher(&self, teacher: &Teacher) -> Result<()> {
+        let others = self
+            .teachers
+            .values()
+            .filter(|other| other.id != teacher.id);
+        for other in others {
+            if other.email == teacher.email {
+                return Err(unique_violation("teachers_email_key"));
+            }
+            if teacher.discord_id.is_some() && other.discord_id == teacher.discord_id {
+                return Err(unique_violation("teachers_discord_id_key"));
+            }
+        }
+
+        if teacher.max_sessions_per_week.is_some_and(|max| max <= 0) {
+            return Err(check_violation("teachers_max_sessions_per_week_check"));
+        }
+
+        self.check_program_exists(teacher.program_id, "teachers_program_id_fkey")
+    }
+
+    /// Checks that an Availability refers to existing entities.
+    fn check_availability(&self, avail: &Availability) -> Result<()> {
+        if !self.teachers.contains_key(&avail.teacher_id) {
+            return Err(foreign_key_violation("availability_teacher_id_fkey"));
+        }
+
+        self.check_program_exists(avail.program_id, "availability_program_id_fkey")
+    }
+
+    fn check_program_exists(&self, program_id: Option<i64>, constraint: &str) -> Result<()> {
+        match program_id {
+            Some(program_id) if !self.programs.contains_key(&program_id) => {
+                Err(foreign_key_violation(constraint))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that a Session refers to existing entities, and that, if scheduled,
+    /// no other scheduled session claims the same occurrence of its availability.
+    fn check_session(&self, session: &Session) -> Result<()> {
+        if !self.teachers.contains_key(&session.teacher_id) {
+            return Err(foreign_key_violation("sessions_teacher_id_fkey"));
+        }
+        if !self.users.contains_key(&session.student_id.0) {
+            return Err(foreign_key_violation("sessions_student_id_fkey"));
+        }
+        if !self.availabilities.contains_key(&session.availability_id) {
+            return Err(foreign_key_violation("sessions_availability_id_fkey"));
+        }
+
+        if session.status == SessionStatus::Scheduled
+            && self.sessions.values().any(|other| {
+                other.id != session.id
+                    && other.status == SessionStatus::Scheduled
+                    && other.availability_id == session.availability_id
+                    && other.start_at == session.start_at
+            })
+        {
+            return Err(unique_violation("sessions_scheduled_occurrence_key"));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the students attending a Session, in the order they joined it.
+    fn students_of(&self, session_id: i64) -> impl Iterator<Item = &SessionStudent> {
+        self.session_students
+            .iter()
+            .filter(move |student| student.session_id == session_id)
+    }
+
+    /// Checks whether a Session has as many students as its availability's capacity.
+    fn session_is_full(&self, session: &Session) -> bool {
+        self.availabilities
+            .get(&session.availability_id)
+            .is_some_and(|avail| self.students_of(session.id).count() >= avail.capacity as usize)
+    }
+
+    /// Checks whether an availability is taken by a full scheduled Session
+    /// starting at or after the given moment.
+    fn is_taken_after(&self, availability_id: i64, utc: chrono::DateTime<chrono::Utc>) -> bool {
+        self.sessions.values().any(|session| {
+            session.availability_id == availability_id
+                && session.start_at >= utc
+                && session.status == SessionStatus::Scheduled
+                && self.session_is_full(session)
+        })
+    }
+
+    /// Returns the sessions matching the given predicate, in ascending 'start_at' order
+    /// (starting earlier first).
+    fn sessions_by_start(&self, predicate: impl Fn(&Session) -> bool) -> Vec<Session> {
+        let mut sessions = self
+            .sessions
+            .values()
+            .filter(|session| predicate(session))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        sessions.sort_by_key(|session| (session.start_at, session.id));
+        sessions
+    }
+
+    /// Adds a student to a Session, unless they are already attending it.
+    /// Returns the inserted count.
+    fn add_student(&mut self, session_id: i64, student_id: DiscordId) -> Result<usize> {
+        if !self.sessions.contains_key(&session_id) {
+            return Err(foreign_key_violation("session_students_session_id_fkey"));
+        }
+        if !self.users.contains_key(&student_id.0) {
+            return Err(foreign_key_violation("session_students_student_id_fkey"));
+        }
+        if self
+            .students_of(session_id)
+            .any(|student| student.student_id == student_id)
+        {
+            return Ok(0);
+        }
+
+        self.session_students.push(SessionStudent {
+            session_id,
+            student_id,
+            joined_at: chrono::Utc::now(),
+        });
+        Ok(1)
+    }
+}
+
+/// Creates the error the database would give if a unique constraint was violated.
+fn unique_violation(constraint: &str) -> Error {
+    database_error(
+        DatabaseErrorKind::UniqueViolation,
+        format!("duplicate key value violates unique constraint \"{constraint}\""),
+    )
+}
+
+/// Creates the error the database would give if a foreign key constraint was violated.
+fn foreign_key_violation(constraint: &str) -> Error {
+    database_error(
+        DatabaseErrorKind::ForeignKeyViolation,
+        format!("violates foreign key constraint \"{constraint}\""),
+    )
+}
+
+/// Creates the error the database would give if a check constraint was violated.
+fn check_violation(constraint: &str) -> Error {
+    database_error(
+        DatabaseErrorKind::CheckViolation,
+        format!("new row violates check constraint \"{constraint}\""),
+    )
+}
+
+fn database_error(kind: DatabaseErrorKind, message: String) -> Error {
+    Error::Diesel(DieselError::DatabaseError(kind, Box::new(message)))
+}
+
+/// Checks whether an error was caused by a violated unique constraint.
+fn is_unique_violation(err: &Error) -> bool {
+    matches!(
+        err,
+        Error::Diesel(DieselError::DatabaseError(
+            DatabaseErrorKind::UniqueViolation,
+            _
+        ))
+    )
+}
+
+/// Creates the error the database would give when updating a non-existing entity.
+fn not_found() -> Error {
+    Error::Diesel(DieselError::NotFound)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        db::DatabaseManager,
+        error::{Error, Result},
+        model::{DiscordId, NewTeacher, NewUser},
+    };
+
+    #[tokio::test]
+    async fn test_memory_constraints_and_transactions() -> Result<()> {
+        let db = DatabaseManager::in_memory();
+        let new_user = NewUser {
+            discord_id: DiscordId(1),
+            name: "Student".to_string(),
+            email: "student@student.com".to_string(),
+            bio: None,
+            timezone: Default::default(),
+            locale: None,
+        };
+        let user = db.user_repository().insert(&new_user).await?;
+
+        // unique constraints are respected
+        assert!(db.user_repository().insert(&new_user).await.is_err());
+        assert_eq!(
+            0,
+            db.user_repository()
+                .insert_if_not_exists(&NewUser {
+                    discord_id: DiscordId(2),
+                    ..new_user.clone()
+                })
+                .await?
+        );
+
+        let result: Result<()> = db
+            .transaction(|db| async move {
+                db.teacher_repository()
+                    .insert(&NewTeacher {
+                        name: "John Doe".to_string(),
+                        email: "memory@mentor.com".to_string(),
+                        specialty: "Math".to_string(),
+                        applied_at: None,
+                        company: None,
+                        company_role: None,
+                        bio: None,
+                        course_info: None,
+                        whatsapp: None,
+                        linkedin: None,
+                        comment_general: None,
+                        comment_experience: None,
+                        discord_id: None,
+                        // foreign keys are respected
+                        program_id: Some(1),
+                        timezone: Default::default(),
+                        max_sessions_per_week: None,
+                        locale: None,
+                    })
+                    .await?;
+
+                Ok(())
+            })
+            .await;
+        assert!(matches!(result, Err(Error::Diesel(_))));
+
+        let removed_user = user.clone();
+        let result: Result<()> = db
+            .transaction(|db| async move {
+                db.user_repository().remove(&removed_user).await?;
+                Err(Error::Other("failure"))
+            })
+            .await;
+        assert!(matches!(result, Err(Error::Other("failure"))));
+
+        // the removal was rolled back
+        assert_eq!(vec![user], db.user_repository().find_all().await?);
+        assert!(db.connection_source().is_none());
+
+        Ok(())
+    }
+}
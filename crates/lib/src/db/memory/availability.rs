@@ -0,0 +1,619 @@
+use async_trait::async_trait;
+use chrono::{Datelike, TimeZone};
+
+use super::{
+    super::store::AvailabilityStore, foreign_key_violation, not_found, MemoryDatabase, MemoryState,
+};
+use crate::{
+    error::{Error, Result},
+    model::{
+        Availability, NewAvailability, PartialAvailability, Program, ProgramStatus, Session,
+        Teacher, Weekday,
+    },
+    util::time::datetime_as_utc,
+};
+
+/// Manages Availability instances kept in memory.
+#[derive(Clone)]
+pub struct MemoryAvailabilityRepository {
+    database: MemoryDatabase,
+}
+
+impl MemoryAvailabilityRepository {
+    /// Creates a new MemoryAvailabilityRepository operating on the given in-memory database.
+    pub fn new(database: &MemoryDatabase) -> Self {
+        Self {
+            database: database.clone(),
+        }
+    }
+}
+
+/// Builds an Availability with the given ID and the new availability's fields.
+fn availability_with_id(id: i64, avail: &NewAvailability) -> Availability {
+    let avail = avail.clone();
+    Availability {
+        id,
+        teacher_id: avail.teacher_id,
+        weekday: avail.weekday,
+        time_start: avail.time_start,
+        expired: avail.expired,
+        duration: avail.duration,
+        valid_from: avail.valid_from,
+        valid_until: avail.valid_until,
+        program_id: avail.program_id,
+        capacity: avail.capacity,
+    }
+}
+
+impl MemoryState {
+    /// Returns the non-expired availabilities, along with their programs, which may be
+    /// scheduled at some point between the given dates, and which aren't taken by a full
+    /// scheduled session starting at or after the given moment.
+    fn find_nontaken_between(
+        &self,
+        weekdays: &[Weekday],
+        first_date: chrono::NaiveDate,
+        last_date: chrono::NaiveDate,
+        utc: chrono::DateTime<chrono::Utc>,
+    ) -> Vec<(Availability, Program)> {
+        self.availabilities
+            .values()
+            .filter(|avail| weekdays.contains(&avail.weekday))
+            .filter(|avail| !avail.expired)
+            .filter(|avail| avail.valid_from.is_none_or(|from| from <= last_date))
+            .filter(|avail| avail.valid_until.is_none_or(|until| until >= first_date))
+            .filter_map(|avail| {
+                avail
+                    .program_id
+                    .and_then(|program_id| self.programs.get(&program_id))
+                    .map(|program| (avail, program))
+            })
+            .filter(|(_, program)| {
+                program.status == ProgramStatus::Active
+                    && program.enrollment_open
+                    && program.start_date <= last_date
+                    && program.end_date >= first_date
+            })
+            .filter(|(avail, _)| !self.is_taken_after(avail.id, utc))
+            .map(|(avail, program)| (avail.clone(), program.clone()))
+            .collect()
+    }
+
+    /// Checks whether an availability can still be scheduled at its first start after
+    /// the given datetime, according to its mentor's weekly cap
+    /// (see [`Availability::is_within_weekly_cap`]).
+    fn is_within_weekly_cap(
+        &self,
+        avail: &Availability,
+        datetime: &chrono::DateTime<chrono::FixedOffset>,
+    ) -> bool {
+        let Some(teacher) = self.teachers.get(&avail.teacher_id) else {
+            return true;
+        };
+        let sessions = self
+            .sessions
+            .values()
+            .filter(|session| session.teacher_id == teacher.id)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let start_at = avail.first_start_after(teacher.timezone, datetime);
+        avail.is_within_weekly_cap(teacher, &sessions, &start_at)
+    }
+}
+
+#[async_trait]
+impl AvailabilityStore for MemoryAvailabilityRepository {
+    async fn get(&self, id: i64) -> Result<Option<Availability>> {
+        Ok(self.database.lock().availabilities.get(&id).cloned())
+    }
+
+    async fn insert(&self, avail: &NewAvailability) -> Result<Availability> {
+        let mut state = self.database.lock();
+        let avail = availability_with_id(state.last_ids.availability + 1, avail);
+        state.check_availability(&avail)?;
+
+        state.last_ids.availability = avail.id;
+        state.availabilities.insert(avail.id, avail.clone());
+        Ok(avail)
+    }
+
+    async fn remove(&self, avail: &Availability) -> Result<usize> {
+        let mut state = self.database.lock();
+        if state
+            .sessions
+            .values()
+            .any(|session| session.availability_id == avail.id)
+        {
+            return Err(foreign_key_violation("sessions_availability_id_fkey"));
+        }
+
+        for entry in state.waitlist_entries.values_mut() {
+            if entry.offered_availability_id == Some(avail.id) {
+                entry.offered_availability_id = None;
+            }
+        }
+        Ok(state.availabilities.remove(&avail.id).map_or(0, |_| 1))
+    }
+
+    async fn find_all(&self) -> Result<Vec<Availability>> {
+        Ok(self
+            .database
+            .lock()
+            .availabilities
+            .values()
+            .cloned()
+            .collect())
+    }
+
+    async fn upsert(&self, avail: &NewAvailability) -> Result<Availability> {
+        // new availabilities have no ID yet, so they never conflict with existing ones
+        self.insert(avail).await
+    }
+
+    async fn update(
+        &self,
+        old_avail: &Availability,
+        new_avail: PartialAvailability,
+    ) -> Result<Availability> {
+        let mut state = self.database.lock();
+        let mut avail = state
+            .availabilities
+            .get(&old_avail.id)
+            .cloned()
+            .ok_or_else(not_found)?;
+
+        let PartialAvailability {
+            id,
+            teacher_id,
+            weekday,
+            time_start,
+            expired,
+            duration,
+            valid_from,
+            valid_until,
+            program_id,
+            capacity,
+        } = new_avail;
+        avail.id = id.unwrap_or(avail.id);
+        avail.teacher_id = teacher_id.unwrap_or(avail.teacher_id);
+        avail.weekday = weekday.unwrap_or(avail.weekday);
+        avail.time_start = time_start.unwrap_or(avail.time_start);
+        avail.expired = expired.unwrap_or(avail.expired);
+        avail.duration = duration.unwrap_or(avail.duration);
+        avail.valid_from = valid_from.unwrap_or(avail.valid_from);
+        avail.valid_until = valid_until.unwrap_or(avail.valid_until);
+        avail.program_id = program_id.unwrap_or(avail.program_id);
+        avail.capacity = capacity.unwrap_or(avail.capacity);
+
+        if avail.id != old_avail.id {
+            return Err(Error::Other(
+                "Changing an availability's ID is not supported in memory",
+            ));
+        }
+        state.check_availability(&avail)?;
+
+        state.availabilities.insert(avail.id, avail.clone());
+        Ok(avail)
+    }
+
+    async fn find_by_teacher(&self, teacher: &Teacher) -> Result<Vec<Availability>> {
+        Ok(self
+            .database
+            .lock()
+            .availabilities
+            .values()
+            .filter(|avail| avail.teacher_id == teacher.id)
+            .cloned()
+            .collect())
+    }
+
+    async fn expire_by_teacher_and_ids(&self, teacher_id: i64, ids: &[i64]) -> Result<usize> {
+        let mut state = self.database.lock();
+        let mut updated = 0;
+        for avail in state.availabilities.values_mut() {
+            if avail.teacher_id == teacher_id && ids.contains(&avail.id) {
+                avail.expired = true;
+                updated += 1;
+            }
+        }
+
+        Ok(updated)
+    }
+
+    async fn set_validity_window(
+        &self,
+        teacher_id: Option<i64>,
+        valid_from: Option<chrono::NaiveDate>,
+        valid_until: Option<chrono::NaiveDate>,
+    ) -> Result<usize> {
+        let mut state = self.database.lock();
+        let mut updated = 0;
+        for avail in state.availabilities.values_mut() {
+            if !avail.expired && teacher_id.is_none_or(|id| avail.teacher_id == id) {
+                avail.valid_from = valid_from;
+                avail.valid_until = valid_until;
+                updated += 1;
+            }
+        }
+
+        Ok(updated)
+    }
+
+    async fn find_by_session(&self, session: &Session) -> Result<Option<Availability>> {
+        let state = self.database.lock();
+
+        Ok(state
+            .sessions
+            .get(&session.id)
+            .and_then(|session| state.availabilities.get(&session.availability_id))
+            .cloned())
+    }
+
+    async fn check_is_taken_at(
+        &self,
+        id: i64,
+        datetime: &chrono::DateTime<chrono::FixedOffset>,
+    ) -> Result<bool> {
+        let state = self.database.lock();
+
+        Ok(state.availabilities.contains_key(&id)
+            && state.is_taken_after(id, datetime_as_utc(datetime)))
+    }
+
+    async fn find_nontaken_within_a_week_of_date(
+        &self,
+        datetime: chrono::DateTime<chrono::FixedOffset>,
+    ) -> Result<Vec<Availability>> {
+        let utc = chrono::Utc.from_utc_datetime(&datetime.naive_utc());
+        let weekday: Weekday = datetime.naive_local().weekday().into();
+        // each mentor's date may be one day apart from the given datetime's
+        let first_date = datetime.date_naive() - chrono::Duration::days(1);
+        let last_date = first_date + chrono::Duration::days(8);
+
+        let state = self.database.lock();
+        let availabilities =
+            state.find_nontaken_between(&weekday.next_7_days(), first_date, last_date, utc);
+
+        // ensure each availability is valid at the specific date it would occur
+        // (in its mentor's timezone)
+        Ok(availabilities
+            .into_iter()
+            .filter(|(avail, program)| {
+                let timezone = state
+                    .teachers
+                    .get(&avail.teacher_id)
+                    .map(|teacher| teacher.timezone)
+                    .unwrap_or_default();
+                let date = avail
+                    .first_possible_date_after(timezone, &datetime)
+                    .date_naive();
+                avail.is_valid_at(date) && program.includes_date(date)
+            })
+            .filter(|(avail, _)| state.is_within_weekly_cap(avail, &datetime))
+            .map(|(avail, _)| avail)
+            .collect())
+    }
+
+    async fn find_nontaken_at_date(
+        &self,
+        datetime: chrono::DateTime<chrono::FixedOffset>,
+    ) -> Result<Vec<Availability>> {
+        let utc = chrono::Utc.from_utc_datetime(&datetime.naive_utc());
+        let weekday: Weekday = datetime.naive_local().weekday().into();
+        let date = datetime.date_naive();
+
+        let state = self.database.lock();
+        Ok(state
+            .find_nontaken_between(&[weekday], date, date, utc)
+            .into_iter()
+            .filter(|(avail, _)| state.is_within_weekly_cap(avail, &datetime))
+            .map(|(avail, _)| avail)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use crate::{
+        db::DatabaseManager,
+        error::{Error, Result},
+        model::{
+            BookingPolicy, DiscordId, NewAvailability, NewProgram, NewSession, NewTeacher, NewUser,
+            ProgramStatus, SessionStatus, Weekday,
+        },
+        util::BRAZIL_TIMEZONE,
+    };
+
+    #[tokio::test]
+    async fn test_memory_availability_find_nontaken() -> Result<()> {
+        let db = DatabaseManager::in_memory();
+        let availability_repo = db.availability_repository();
+
+        let program = db
+            .program_repository()
+            .insert(&NewProgram {
+                name: "Memory Program".to_string(),
+                start_date: chrono::NaiveDate::from_ymd_opt(2023, 9, 1).unwrap(),
+                end_date: chrono::NaiveDate::from_ymd_opt(2023, 9, 30).unwrap(),
+                status: ProgramStatus::Active,
+                enrollment_open: true,
+            })
+            .await?;
+        let teacher = db
+            .teacher_repository()
+            .insert(&NewTeacher {
+                name: "John Doe".to_string(),
+                email: "memory@mentor.com".to_string(),
+                specialty: "Math".to_string(),
+                applied_at: None,
+                company: None,
+                company_role: None,
+                bio: None,
+                course_info: None,
+                whatsapp: None,
+                linkedin: None,
+                comment_general: None,
+                comment_experience: None,
+                discord_id: None,
+                program_id: Some(program.id),
+                timezone: Default::default(),
+                max_sessions_per_week: None,
+                locale: None,
+            })
+            .await?;
+
+        let new_availability = NewAvailability {
+            teacher_id: teacher.id,
+            weekday: Weekday::Wednesday,
+            time_start: chrono::NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+            expired: false,
+            duration: 1,
+            valid_from: None,
+            valid_until: None,
+            program_id: Some(program.id),
+            capacity: 1,
+        };
+        let availability = availability_repo.insert(&new_availability).await?;
+        // not valid at the date it would occur
+        availability_repo
+            .insert(&NewAvailability {
+                valid_until: chrono::NaiveDate::from_ymd_opt(2023, 9, 12),
+                ..new_availability.clone()
+            })
+            .await?;
+        // without a program
+        availability_repo
+            .insert(&NewAvailability {
+                program_id: None,
+                ..new_availability.clone()
+            })
+            .await?;
+        // expired
+        availability_repo
+            .insert(&NewAvailability {
+                expired: true,
+                ..new_availability
+            })
+            .await?;
+
+        // monday, 11/09/2023; availability on wednesday, 13/09/2023
+        let monday = BRAZIL_TIMEZONE
+            .with_ymd_and_hms(2023, 9, 11, 0, 0, 0)
+            .unwrap();
+        let wednesday = BRAZIL_TIMEZONE
+            .with_ymd_and_hms(2023, 9, 13, 8, 0, 0)
+            .unwrap();
+        assert_eq!(
+            vec![availability.clone()],
+            availability_repo
+                .find_nontaken_within_a_week_of_date(monday)
+                .await?
+        );
+        assert_eq!(
+            vec![availability.clone()],
+            availability_repo.find_nontaken_at_date(wednesday).await?
+        );
+
+        let student = db
+            .user_repository()
+            .insert(&NewUser {
+                discord_id: DiscordId(1),
+                name: "Student".to_string(),
+                email: "student@student.com".to_string(),
+                bio: None,
+                timezone: Default::default(),
+                locale: None,
+            })
+            .await?;
+        let start_at = chrono::Utc.with_ymd_and_hms(2023, 9, 13, 13, 0, 0).unwrap();
+        db.session_repository()
+            .book(
+                &NewSession {
+                    teacher_id: teacher.id,
+                    student_id: student.discord_id,
+                    availability_id: availability.id,
+                    summary: None,
+                    notified: false,
+                    meet_id: None,
+                    calendar_event_id: None,
+                    start_at,
+                    end_at: start_at + chrono::Duration::minutes(40),
+                    status: SessionStatus::Scheduled,
+                    cancelled_at: None,
+                    cancel_reason: None,
+                },
+                &BookingPolicy::UNLIMITED,
+            )
+            .await?;
+
+        // no seats left
+        assert!(
+            availability_repo
+                .check_is_taken_at(availability.id, &monday)
+                .await?
+        );
+        assert!(availability_repo
+            .find_nontaken_within_a_week_of_date(monday)
+            .await?
+            .is_empty());
+
+        // closing the program's enrollment hides its availabilities as well
+        let program = db
+            .program_repository()
+            .set_enrollment_open(&program, false)
+            .await?;
+        assert!(!program.is_open());
+        assert!(availability_repo
+            .find_nontaken_at_date(wednesday)
+            .await?
+            .is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_memory_availability_hidden_once_teacher_reaches_weekly_cap() -> Result<()> {
+        let db = DatabaseManager::in_memory();
+        let availability_repo = db.availability_repository();
+        let session_repo = db.session_repository();
+
+        let teacher = db
+            .teacher_repository()
+            .insert(&NewTeacher {
+                name: "John Doe".to_string(),
+                email: "weeklycap@mentor.com".to_string(),
+                specialty: "Math".to_string(),
+                applied_at: None,
+                company: None,
+                company_role: None,
+                bio: None,
+                course_info: None,
+                whatsapp: None,
+                linkedin: None,
+                comment_general: None,
+                comment_experience: None,
+                discord_id: None,
+                program_id: None,
+                timezone: Default::default(),
+                max_sessions_per_week: Some(1),
+                locale: None,
+            })
+            .await?;
+        let program = db
+            .program_repository()
+            .insert(&NewProgram {
+                name: "Weekly Cap Program".to_string(),
+                start_date: chrono::NaiveDate::from_ymd_opt(2023, 9, 1).unwrap(),
+                end_date: chrono::NaiveDate::from_ymd_opt(2023, 9, 30).unwrap(),
+                status: ProgramStatus::Active,
+                enrollment_open: true,
+            })
+            .await?;
+        let new_availability = NewAvailability {
+            teacher_id: teacher.id,
+            weekday: Weekday::Wednesday,
+            time_start: chrono::NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+            expired: false,
+            duration: 1,
+            valid_from: None,
+            valid_until: None,
+            program_id: Some(program.id),
+            capacity: 2,
+        };
+        let wednesday_avail = availability_repo.insert(&new_availability).await?;
+        let thursday_avail = availability_repo
+            .insert(&NewAvailability {
+                weekday: Weekday::Thursday,
+                capacity: 1,
+                ..new_availability
+            })
+            .await?;
+
+        let student = db
+            .user_repository()
+            .insert(&NewUser {
+                discord_id: DiscordId(1),
+                name: "Student".to_string(),
+                email: "weeklycap@student.com".to_string(),
+                bio: None,
+                timezone: Default::default(),
+                locale: None,
+            })
+            .await?;
+
+        // monday, 11/09/2023; sessions on wednesday (13/09) and thursday (14/09)
+        let monday = BRAZIL_TIMEZONE
+            .with_ymd_and_hms(2023, 9, 11, 0, 0, 0)
+            .unwrap();
+        let thursday = BRAZIL_TIMEZONE
+            .with_ymd_and_hms(2023, 9, 14, 0, 0, 0)
+            .unwrap();
+        let mut availabilities = availability_repo
+            .find_nontaken_within_a_week_of_date(monday)
+            .await?;
+        availabilities.sort_by_key(|avail| avail.id);
+        assert_eq!(
+            vec![wednesday_avail.clone(), thursday_avail.clone()],
+            availabilities
+        );
+
+        let start_at = chrono::Utc.with_ymd_and_hms(2023, 9, 13, 13, 0, 0).unwrap();
+        let new_session = NewSession {
+            teacher_id: teacher.id,
+            student_id: student.discord_id,
+            availability_id: wednesday_avail.id,
+            summary: None,
+            notified: false,
+            meet_id: None,
+            calendar_event_id: None,
+            start_at,
+            end_at: start_at + chrono::Duration::minutes(40),
+            status: SessionStatus::Scheduled,
+            cancelled_at: None,
+            cancel_reason: None,
+        };
+        session_repo
+            .book(&new_session, &BookingPolicy::UNLIMITED)
+            .await?;
+
+        // the cap was reached in this week, but the group session still has seats left
+        assert_eq!(
+            vec![wednesday_avail.clone()],
+            availability_repo
+                .find_nontaken_within_a_week_of_date(monday)
+                .await?
+        );
+        assert!(availability_repo
+            .find_nontaken_at_date(thursday)
+            .await?
+            .is_empty());
+
+        let thursday_start_at = start_at + chrono::Duration::days(1);
+        assert!(matches!(
+            session_repo
+                .book(
+                    &NewSession {
+                        availability_id: thursday_avail.id,
+                        start_at: thursday_start_at,
+                        end_at: thursday_start_at + chrono::Duration::minutes(40),
+                        ..new_session
+                    },
+                    &BookingPolicy::UNLIMITED
+                )
+                .await,
+            Err(Error::SessionTimeTaken)
+        ));
+
+        // the next week isn't affected
+        let mut availabilities = availability_repo
+            .find_nontaken_within_a_week_of_date(monday + chrono::Duration::days(7))
+            .await?;
+        availabilities.sort_by_key(|avail| avail.id);
+        assert_eq!(vec![wednesday_avail, thursday_avail], availabilities);
+
+        Ok(())
+    }
+}
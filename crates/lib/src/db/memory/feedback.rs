@@ -0,0 +1,104 @@
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+
+use super::{super::store::FeedbackStore, check_violation, foreign_key_violation, MemoryDatabase};
+use crate::{
+    error::Result,
+    model::{DiscordId, NewSessionFeedback, SessionFeedback, Teacher, TeacherRating},
+};
+
+/// Manages SessionFeedback instances kept in memory.
+#[derive(Clone)]
+pub struct MemoryFeedbackRepository {
+    database: MemoryDatabase,
+}
+
+impl MemoryFeedbackRepository {
+    /// Creates a new MemoryFeedbackRepository operating on the given in-memory database.
+    pub fn new(database: &MemoryDatabase) -> Self {
+        Self {
+            database: database.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl FeedbackStore for MemoryFeedbackRepository {
+    async fn get(&self, session_id: i64, author_id: DiscordId) -> Result<Option<SessionFeedback>> {
+        Ok(self
+            .database
+            .lock()
+            .session_feedback
+            .get(&(session_id, author_id.0))
+            .cloned())
+    }
+
+    async fn submit(&self, feedback: &NewSessionFeedback) -> Result<SessionFeedback> {
+        let mut state = self.database.lock();
+        if !state.sessions.contains_key(&feedback.session_id) {
+            return Err(foreign_key_violation("session_feedback_session_id_fkey"));
+        }
+        if !feedback.has_valid_rating() {
+            return Err(check_violation("session_feedback_rating_check"));
+        }
+
+        let feedback = feedback.clone();
+        let feedback = SessionFeedback {
+            session_id: feedback.session_id,
+            author_id: feedback.author_id,
+            from_mentor: feedback.from_mentor,
+            rating: feedback.rating,
+            comment: feedback.comment,
+            submitted_at: chrono::Utc::now(),
+        };
+        state.session_feedback.insert(
+            (feedback.session_id, feedback.author_id.0),
+            feedback.clone(),
+        );
+
+        Ok(feedback)
+    }
+
+    async fn find_by_session(&self, session_id: i64) -> Result<Vec<SessionFeedback>> {
+        let mut feedback = self
+            .database
+            .lock()
+            .session_feedback
+            .values()
+            .filter(|feedback| feedback.session_id == session_id)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        feedback.sort_by_key(|feedback| feedback.submitted_at);
+        Ok(feedback)
+    }
+
+    async fn find_teacher_ratings(&self) -> Result<Vec<(Teacher, TeacherRating)>> {
+        let state = self.database.lock();
+
+        let mut ratings_by_teacher = BTreeMap::<i64, Vec<i16>>::new();
+        for feedback in state.session_feedback.values() {
+            let Some(session) = state.sessions.get(&feedback.session_id) else {
+                continue;
+            };
+            if !feedback.from_mentor {
+                ratings_by_teacher
+                    .entry(session.teacher_id)
+                    .or_default()
+                    .push(feedback.rating);
+            }
+        }
+
+        let mut teacher_ratings = ratings_by_teacher
+            .into_iter()
+            .filter_map(|(teacher_id, ratings)| {
+                let teacher = state.teachers.get(&teacher_id)?.clone();
+                TeacherRating::aggregate(teacher_id, ratings).map(|rating| (teacher, rating))
+            })
+            .collect::<Vec<_>>();
+
+        teacher_ratings.sort_by(|(a, _), (b, _)| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+        Ok(teacher_ratings)
+    }
+}
@@ -0,0 +1,167 @@
+use async_trait::async_trait;
+
+use super::{
+    super::store::ProgramStore, foreign_key_violation, not_found, unique_violation, MemoryDatabase,
+    MemoryState,
+};
+use crate::{
+    error::{Error, Result},
+    model::{NewProgram, PartialProgram, Program, Teacher},
+};
+
+/// Manages Program instances kept in memory.
+#[derive(Clone)]
+pub struct MemoryProgramRepository {
+    database: MemoryDatabase,
+}
+
+impl MemoryProgramRepository {
+    /// Creates a new MemoryProgramRepository operating on the given in-memory database.
+    pub fn new(database: &MemoryDatabase) -> Self {
+        Self {
+            database: database.clone(),
+        }
+    }
+}
+
+impl MemoryState {
+    /// Checks that no other program has the same name.
+    fn check_program(&self, program: &Program) -> Result<()> {
+        if self
+            .programs
+            .values()
+            .any(|other| other.id != program.id && other.name == program.name)
+        {
+            Err(unique_violation("programs_name_key"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[async_trait]
+impl ProgramStore for MemoryProgramRepository {
+    async fn get(&self, id: i64) -> Result<Option<Program>> {
+        Ok(self.database.lock().programs.get(&id).cloned())
+    }
+
+    async fn insert(&self, program: &NewProgram) -> Result<Program> {
+        let mut state = self.database.lock();
+        let program = program.clone();
+        let program = Program {
+            id: state.last_ids.program + 1,
+            name: program.name,
+            start_date: program.start_date,
+            end_date: program.end_date,
+            status: program.status,
+            enrollment_open: program.enrollment_open,
+        };
+        state.check_program(&program)?;
+
+        state.last_ids.program = program.id;
+        state.programs.insert(program.id, program.clone());
+        Ok(program)
+    }
+
+    async fn remove(&self, program: &Program) -> Result<usize> {
+        let mut state = self.database.lock();
+        let removed = state.programs.remove(&program.id).map_or(0, |_| 1);
+
+        // program references are set to null when it is removed
+        for teacher in state.teachers.values_mut() {
+            if teacher.program_id == Some(program.id) {
+                teacher.program_id = None;
+            }
+        }
+        for avail in state.availabilities.values_mut() {
+            if avail.program_id == Some(program.id) {
+                avail.program_id = None;
+            }
+        }
+
+        Ok(removed)
+    }
+
+    async fn find_all(&self) -> Result<Vec<Program>> {
+        let mut programs = self
+            .database
+            .lock()
+            .programs
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        programs.sort_by_key(|program| std::cmp::Reverse(program.start_date));
+        Ok(programs)
+    }
+
+    async fn upsert(&self, program: &NewProgram) -> Result<Program> {
+        // new programs have no ID yet, so they never conflict with existing ones
+        self.insert(program).await
+    }
+
+    async fn update(&self, old_program: &Program, new_program: PartialProgram) -> Result<Program> {
+        let mut state = self.database.lock();
+        let mut program = state
+            .programs
+            .get(&old_program.id)
+            .cloned()
+            .ok_or_else(not_found)?;
+
+        let PartialProgram {
+            id,
+            name,
+            start_date,
+            end_date,
+            status,
+            enrollment_open,
+        } = new_program;
+        program.id = id.unwrap_or(program.id);
+        program.name = name.unwrap_or(program.name);
+        program.start_date = start_date.unwrap_or(program.start_date);
+        program.end_date = end_date.unwrap_or(program.end_date);
+        program.status = status.unwrap_or(program.status);
+        program.enrollment_open = enrollment_open.unwrap_or(program.enrollment_open);
+
+        if program.id != old_program.id {
+            return Err(Error::Other(
+                "Changing a program's ID is not supported in memory",
+            ));
+        }
+        state.check_program(&program)?;
+
+        state.programs.insert(program.id, program.clone());
+        Ok(program)
+    }
+
+    async fn find_by_name(&self, name: &str) -> Result<Option<Program>> {
+        Ok(self
+            .database
+            .lock()
+            .programs
+            .values()
+            .find(|program| program.name == name)
+            .cloned())
+    }
+
+    async fn attach_teacher(&self, program: &Program, teacher: &Teacher) -> Result<usize> {
+        let mut state = self.database.lock();
+        if !state.programs.contains_key(&program.id) {
+            return Err(foreign_key_violation("teachers_program_id_fkey"));
+        }
+
+        if let Some(teacher) = state.teachers.get_mut(&teacher.id) {
+            teacher.program_id = Some(program.id);
+        }
+
+        let mut updated = 0;
+        for avail in state.availabilities.values_mut() {
+            if avail.teacher_id == teacher.id && !avail.expired {
+                avail.program_id = Some(program.id);
+                updated += 1;
+            }
+        }
+
+        Ok(updated)
+    }
+}
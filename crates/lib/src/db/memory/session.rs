@@ -0,0 +1,1123 @@
+use async_trait::async_trait;
+
+use super::{
+    super::{
+        pagination::{Page, PageRequest, SortOrder},
+        repository::{SessionCursor, SessionFilter},
+        store::SessionStore,
+    },
+    foreign_key_violation, is_unique_violation, not_found, MemoryDatabase, MemoryState,
+};
+use crate::{
+    error::{Error, Result},
+    model::{
+        BookedSession, BookingPolicy, BookingRefusal, DiscordId, NewSession, PartialSession,
+        Session, SessionStatus, Teacher, User, WaitlistStatus,
+    },
+};
+
+/// Manages Session instances kept in memory.
+#[derive(Clone)]
+pub struct MemorySessionRepository {
+    database: MemoryDatabase,
+}
+
+impl MemorySessionRepository {
+    /// Creates a new MemorySessionRepository operating on the given in-memory database.
+    pub fn new(database: &MemoryDatabase) -> Self {
+        Self {
+            database: database.clone(),
+        }
+    }
+}
+
+impl MemoryState {
+    /// Inserts a Session, registering its student as attending it.
+    fn insert_session(&mut self, session: &NewSession) -> Result<Session> {
+        let session = session.clone();
+        let session = Session {
+            id: self.last_ids.session + 1,
+            teacher_id: session.teacher_id,
+            student_id: session.student_id,
+            availability_id: session.availability_id,
+            summary: session.summary,
+            notified: session.notified,
+            meet_id: session.meet_id,
+            calendar_event_id: session.calendar_event_id,
+            start_at: session.start_at,
+            end_at: session.end_at,
+            status: session.status,
+            cancelled_at: session.cancelled_at,
+            cancel_reason: session.cancel_reason,
+            feedback_requested: false,
+        };
+        self.check_session(&session)?;
+
+        self.last_ids.session = session.id;
+        self.sessions.insert(session.id, session.clone());
+        self.add_student(session.id, session.student_id)?;
+
+        Ok(session)
+    }
+
+    /// Returns a Session along with its teacher and student, if they all exist.
+    fn with_participants(&self, session: &Session) -> Option<(Session, Teacher, User)> {
+        let teacher = self.teachers.get(&session.teacher_id)?;
+        let student = self.users.get(&session.student_id.0)?;
+
+        Some((session.clone(), teacher.clone(), student.clone()))
+    }
+
+    /// Checks whether a student attends a Session.
+    fn is_attended_by(&self, session_id: i64, student_id: DiscordId) -> bool {
+        self.students_of(session_id)
+            .any(|student| student.student_id == student_id)
+    }
+
+    /// Checks the policy's limits for a student booking a session with the given teacher and
+    /// availability, starting at the given moment, given the other sessions they attend
+    /// (except for the excluded one, if any). Fails with [`Error::BookingRefused`] if a
+    /// limit is exceeded.
+    fn check_booking_policy(
+        &self,
+        policy: &BookingPolicy,
+        student_id: DiscordId,
+        teacher_id: i64,
+        availability_id: i64,
+        start_at: chrono::DateTime<chrono::Utc>,
+        excluded_session_id: Option<i64>,
+    ) -> Result<()> {
+        let program_of = |availability_id: i64| {
+            self.availabilities
+                .get(&availability_id)
+                .and_then(|avail| avail.program_id)
+        };
+        let booked_sessions = self
+            .sessions
+            .values()
+            .filter(|session| {
+                Some(session.id) != excluded_session_id
+                    && self.is_attended_by(session.id, student_id)
+            })
+            .map(|session| BookedSession {
+                session,
+                program_id: program_of(session.availability_id),
+            })
+            .collect::<Vec<_>>();
+
+        policy
+            .check(
+                &booked_sessions,
+                teacher_id,
+                program_of(availability_id),
+                start_at,
+                chrono::Utc::now(),
+            )
+            .map_err(Error::BookingRefused)
+    }
+
+    /// Fails with [`Error::BookingRefused`] if the given occurrence of an available time was
+    /// offered to a student in the waitlist other than the given one, whose offer didn't expire.
+    fn check_not_held_for_waitlist(
+        &self,
+        student_id: DiscordId,
+        availability_id: i64,
+        start_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<()> {
+        let now = chrono::Utc::now();
+        let held = self.waitlist_entries.values().any(|entry| {
+            entry.status == WaitlistStatus::Offered
+                && entry.offered_availability_id == Some(availability_id)
+                && entry.offered_start_at == Some(start_at)
+                && entry
+                    .offer_expires_at
+                    .is_some_and(|expires_at| expires_at > now)
+                && entry.student_id != student_id
+        });
+
+        if held {
+            Err(Error::BookingRefused(BookingRefusal::HeldForWaitlist))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[async_trait]
+impl SessionStore for MemorySessionRepository {
+    async fn get(&self, id: i64) -> Result<Option<Session>> {
+        Ok(self.database.lock().sessions.get(&id).cloned())
+    }
+
+    async fn insert(&self, session: &NewSession) -> Result<Session> {
+        self.database.lock().insert_session(session)
+    }
+
+    async fn remove(&self, session: &Session) -> Result<usize> {
+        let mut state = self.database.lock();
+        let removed = state.sessions.remove(&session.id).map_or(0, |_| 1);
+
+        // students, reminders and feedback are removed along with their session
+        state
+            .session_students
+            .retain(|student| student.session_id != session.id);
+        state
+            .session_reminders
+            .retain(|(session_id, _)| *session_id != session.id);
+        state
+            .session_feedback
+            .retain(|(session_id, _), _| *session_id != session.id);
+
+        Ok(removed)
+    }
+
+    async fn find_all(&self) -> Result<Vec<Session>> {
+        Ok(self.database.lock().sessions_by_start(|_| true))
+    }
+
+    async fn upsert(&self, session: &NewSession) -> Result<Session> {
+        // new sessions have no ID yet, so they never conflict with existing ones
+        self.insert(session).await
+    }
+
+    async fn update(&self, old_session: &Session, new_session: PartialSession) -> Result<Session> {
+        let mut state = self.database.lock();
+        let mut session = state
+            .sessions
+            .get(&old_session.id)
+            .cloned()
+            .ok_or_else(not_found)?;
+
+        let PartialSession {
+            id,
+            teacher_id,
+            student_id,
+            availability_id,
+            summary,
+            notified,
+            meet_id,
+            calendar_event_id,
+            start_at,
+            end_at,
+            status,
+            cancelled_at,
+            cancel_reason,
+            feedback_requested,
+        } = new_session;
+        session.id = id.unwrap_or(session.id);
+        session.teacher_id = teacher_id.unwrap_or(session.teacher_id);
+        session.student_id = student_id.unwrap_or(session.student_id);
+        session.availability_id = availability_id.unwrap_or(session.availability_id);
+        session.summary = summary.unwrap_or(session.summary);
+        session.notified = notified.unwrap_or(session.notified);
+        session.meet_id = meet_id.unwrap_or(session.meet_id);
+        session.calendar_event_id = calendar_event_id.unwrap_or(session.calendar_event_id);
+        session.start_at = start_at.unwrap_or(session.start_at);
+        session.end_at = end_at.unwrap_or(session.end_at);
+        session.status = status.unwrap_or(session.status);
+        session.cancelled_at = cancelled_at.unwrap_or(session.cancelled_at);
+        session.cancel_reason = cancel_reason.unwrap_or(session.cancel_reason);
+        session.feedback_requested = feedback_requested.unwrap_or(session.feedback_requested);
+
+        if session.id != old_session.id {
+            return Err(Error::Other(
+                "Changing a session's ID is not supported in memory",
+            ));
+        }
+        state.check_session(&session)?;
+
+        state.sessions.insert(session.id, session.clone());
+        Ok(session)
+    }
+
+    async fn get_with_teacher(&self, session_id: i64) -> Result<Option<(Session, Teacher)>> {
+        let state = self.database.lock();
+
+        Ok(state.sessions.get(&session_id).and_then(|session| {
+            state
+                .teachers
+                .get(&session.teacher_id)
+                .map(|teacher| (session.clone(), teacher.clone()))
+        }))
+    }
+
+    async fn get_with_participants(
+        &self,
+        session_id: i64,
+    ) -> Result<Option<(Session, Teacher, User)>> {
+        let state = self.database.lock();
+
+        Ok(state
+            .sessions
+            .get(&session_id)
+            .and_then(|session| state.with_participants(session)))
+    }
+
+    async fn find_by_teacher(&self, teacher_id: i64) -> Result<Vec<Session>> {
+        Ok(self
+            .database
+            .lock()
+            .sessions_by_start(|session| session.teacher_id == teacher_id))
+    }
+
+    async fn find_upcoming_by_teacher_with_students(
+        &self,
+        teacher_id: i64,
+    ) -> Result<Vec<(Session, User)>> {
+        let now = chrono::Utc::now();
+        let state = self.database.lock();
+
+        Ok(state
+            .sessions_by_start(|session| {
+                session.teacher_id == teacher_id
+                    && session.status == SessionStatus::Scheduled
+                    && session.start_at >= now
+            })
+            .into_iter()
+            .filter_map(|session| {
+                let student = state.users.get(&session.student_id.0)?.clone();
+                Some((session, student))
+            })
+            .collect())
+    }
+
+    async fn find_scheduled_by_teacher_starting_between(
+        &self,
+        teacher_id: i64,
+        starts_from: chrono::DateTime<chrono::Utc>,
+        starts_until: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<Session>> {
+        Ok(self.database.lock().sessions_by_start(|session| {
+            session.teacher_id == teacher_id
+                && session.status == SessionStatus::Scheduled
+                && starts_from <= session.start_at
+                && session.start_at <= starts_until
+        }))
+    }
+
+    async fn find_page(
+        &self,
+        filter: &SessionFilter,
+        request: &PageRequest<SessionCursor>,
+    ) -> Result<Page<Session, SessionCursor>> {
+        let state = self.database.lock();
+        let mut sessions = state.sessions_by_start(|session| {
+            filter
+                .teacher_id
+                .is_none_or(|teacher_id| session.teacher_id == teacher_id)
+                && filter
+                    .student_id
+                    .is_none_or(|student_id| state.is_attended_by(session.id, student_id))
+                && filter
+                    .starts_from
+                    .is_none_or(|starts_from| session.start_at >= starts_from)
+                && filter
+                    .starts_until
+                    .is_none_or(|starts_until| session.start_at <= starts_until)
+                && filter.status.is_none_or(|status| session.status == status)
+                && request.after.is_none_or(|after| {
+                    let position = (session.start_at, session.id);
+                    match request.order {
+                        SortOrder::Ascending => position > (after.start_at, after.id),
+                        SortOrder::Descending => position < (after.start_at, after.id),
+                    }
+                })
+        });
+
+        if request.order == SortOrder::Descending {
+            sessions.reverse();
+        }
+        sessions.truncate(request.query_limit() as usize);
+
+        Ok(Page::from_queried(
+            sessions,
+            request,
+            |session: &Session| SessionCursor {
+                start_at: session.start_at,
+                id: session.id,
+            },
+        ))
+    }
+
+    async fn find_by_program_with_participants(
+        &self,
+        program_id: i64,
+    ) -> Result<Vec<(Session, Teacher, User)>> {
+        let state = self.database.lock();
+
+        Ok(state
+            .sessions_by_start(|session| {
+                state
+                    .availabilities
+                    .get(&session.availability_id)
+                    .is_some_and(|avail| avail.program_id == Some(program_id))
+            })
+            .iter()
+            .filter_map(|session| state.with_participants(session))
+            .collect())
+    }
+
+    async fn find_by_availability_starting_at(
+        &self,
+        availability_id: i64,
+        start_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Option<Session>> {
+        Ok(self
+            .database
+            .lock()
+            .sessions
+            .values()
+            .find(|session| {
+                session.availability_id == availability_id
+                    && session.start_at == start_at
+                    && session.status == SessionStatus::Scheduled
+            })
+            .cloned())
+    }
+
+    async fn reschedule(
+        &self,
+        session: &Session,
+        policy: &BookingPolicy,
+        teacher_id: i64,
+        availability_id: i64,
+        start_at: chrono::DateTime<chrono::Utc>,
+        end_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Option<Session>> {
+        let mut state = self.database.lock();
+        let Some(mut rescheduled) = state
+            .sessions
+            .get(&session.id)
+            .filter(|session| session.status == SessionStatus::Scheduled)
+            .cloned()
+        else {
+            return Ok(None);
+        };
+
+        let Some(teacher) = state.teachers.get(&teacher_id) else {
+            return Err(not_found());
+        };
+        // the session itself doesn't count towards the new time's week or overlap with it
+        let other_sessions = || {
+            state
+                .sessions
+                .values()
+                .filter(|other| other.id != session.id)
+        };
+        if teacher.is_at_weekly_cap(other_sessions(), &start_at)
+            || other_sessions().any(|other| {
+                other.teacher_id == teacher_id
+                    && other.status == SessionStatus::Scheduled
+                    && other.start_at < end_at
+                    && other.end_at > start_at
+            })
+        {
+            return Ok(None);
+        }
+
+        // moving a session neither adds to the student's active sessions
+        // nor is prevented by a no-show cooldown
+        let policy = BookingPolicy {
+            max_active_sessions: None,
+            no_show_cooldown_days: None,
+            ..*policy
+        };
+        state.check_booking_policy(
+            &policy,
+            session.student_id,
+            teacher_id,
+            availability_id,
+            start_at,
+            Some(session.id),
+        )?;
+
+        rescheduled.teacher_id = teacher_id;
+        rescheduled.availability_id = availability_id;
+        rescheduled.start_at = start_at;
+        rescheduled.end_at = end_at;
+        rescheduled.notified = false;
+        rescheduled.feedback_requested = false;
+
+        // the new time must not be claimed by another scheduled session
+        match state.check_session(&rescheduled) {
+            Err(err) if is_unique_violation(&err) => return Ok(None),
+            result => result?,
+        }
+
+        state.sessions.insert(rescheduled.id, rescheduled.clone());
+        state
+            .session_reminders
+            .retain(|(session_id, _)| *session_id != session.id);
+
+        Ok(Some(rescheduled))
+    }
+
+    async fn book(&self, session: &NewSession, policy: &BookingPolicy) -> Result<Session> {
+        let mut state = self.database.lock();
+        let Some(teacher) = state.teachers.get(&session.teacher_id) else {
+            return Err(not_found());
+        };
+        if teacher.is_at_weekly_cap(state.sessions.values(), &session.start_at) {
+            return Err(Error::SessionTimeTaken);
+        }
+
+        let time_taken = state.sessions.values().any(|other| {
+            other.teacher_id == session.teacher_id
+                && other.status == SessionStatus::Scheduled
+                && other.start_at < session.end_at
+                && other.end_at > session.start_at
+        });
+        if time_taken {
+            return Err(Error::SessionTimeTaken);
+        }
+
+        state.check_booking_policy(
+            policy,
+            session.student_id,
+            session.teacher_id,
+            session.availability_id,
+            session.start_at,
+            None,
+        )?;
+        state.check_not_held_for_waitlist(
+            session.student_id,
+            session.availability_id,
+            session.start_at,
+        )?;
+
+        state.insert_session(session).map_err(|err| {
+            if is_unique_violation(&err) {
+                // another session claims the same occurrence of the availability
+                Error::SessionTimeTaken
+            } else {
+                err
+            }
+        })
+    }
+
+    async fn add_student(&self, session_id: i64, student_id: DiscordId) -> Result<usize> {
+        self.database.lock().add_student(session_id, student_id)
+    }
+
+    async fn add_student_if_seats_left(
+        &self,
+        session_id: i64,
+        student_id: DiscordId,
+        capacity: i16,
+        policy: &BookingPolicy,
+    ) -> Result<bool> {
+        let mut state = self.database.lock();
+        let Some(session) = state.sessions.get(&session_id) else {
+            return Err(not_found());
+        };
+        if state.students_of(session_id).count() >= capacity as usize {
+            return Ok(false);
+        }
+
+        state.check_booking_policy(
+            policy,
+            student_id,
+            session.teacher_id,
+            session.availability_id,
+            session.start_at,
+            Some(session_id),
+        )?;
+
+        state
+            .add_student(session_id, student_id)
+            .map(|inserted| inserted > 0)
+    }
+
+    async fn remove_student(&self, session_id: i64, student_id: DiscordId) -> Result<usize> {
+        let mut state = self.database.lock();
+        let student_count = state.session_students.len();
+        state
+            .session_students
+            .retain(|student| student.session_id != session_id || student.student_id != student_id);
+
+        Ok(student_count - state.session_students.len())
+    }
+
+    async fn find_students(&self, session_id: i64) -> Result<Vec<User>> {
+        let state = self.database.lock();
+
+        Ok(state
+            .students_of(session_id)
+            .filter_map(|student| state.users.get(&student.student_id.0))
+            .cloned()
+            .collect())
+    }
+
+    async fn find_needing_reminder(
+        &self,
+        offset_minutes: i32,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<(Session, Teacher)>> {
+        let until = now + chrono::Duration::minutes(offset_minutes as i64);
+        let state = self.database.lock();
+
+        Ok(state
+            .sessions_by_start(|session| {
+                session.status == SessionStatus::Scheduled
+                    && !session.notified
+                    && now < session.start_at
+                    && session.start_at <= until
+                    && !state.session_reminders.iter().any(|(session_id, offset)| {
+                        *session_id == session.id && *offset <= offset_minutes
+                    })
+            })
+            .into_iter()
+            .filter_map(|session| {
+                let teacher = state.teachers.get(&session.teacher_id)?.clone();
+                Some((session, teacher))
+            })
+            .collect())
+    }
+
+    async fn register_reminder(&self, session_id: i64, offset_minutes: i32) -> Result<usize> {
+        let mut state = self.database.lock();
+        if !state.sessions.contains_key(&session_id) {
+            return Err(foreign_key_violation("session_reminders_session_id_fkey"));
+        }
+
+        Ok(state
+            .session_reminders
+            .insert((session_id, offset_minutes))
+            .into())
+    }
+
+    async fn find_needing_feedback_request(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<(Session, Teacher)>> {
+        let state = self.database.lock();
+
+        Ok(state
+            .sessions_by_start(|session| {
+                matches!(
+                    session.status,
+                    SessionStatus::Scheduled | SessionStatus::Completed
+                ) && !session.feedback_requested
+                    && session.end_at <= now
+            })
+            .into_iter()
+            .filter_map(|session| {
+                let teacher = state.teachers.get(&session.teacher_id)?.clone();
+                Some((session, teacher))
+            })
+            .collect())
+    }
+
+    async fn register_feedback_request(&self, session_id: i64) -> Result<usize> {
+        let mut state = self.database.lock();
+
+        Ok(match state.sessions.get_mut(&session_id) {
+            Some(session) if !session.feedback_requested => {
+                session.feedback_requested = true;
+                1
+            }
+            _ => 0,
+        })
+    }
+
+    async fn find_by_student(&self, student_id: DiscordId) -> Result<Vec<Session>> {
+        let state = self.database.lock();
+
+        Ok(state.sessions_by_start(|session| state.is_attended_by(session.id, student_id)))
+    }
+
+    async fn find_student_autocomplete(
+        &self,
+        student_id: DiscordId,
+        partial_id: &str,
+        only_active: bool,
+    ) -> Result<Vec<Session>> {
+        if !partial_id.is_empty() && partial_id.parse::<u32>().is_err() {
+            // invalid integer => no results
+            return Ok(Vec::new());
+        }
+
+        let now = chrono::Utc::now();
+        let state = self.database.lock();
+
+        Ok(state.sessions_by_start(|session| {
+            state.is_attended_by(session.id, student_id)
+                && session.id.to_string().starts_with(partial_id)
+                && (!only_active
+                    || session.start_at >= now && session.status == SessionStatus::Scheduled)
+        }))
+    }
+
+    async fn find_starts_after(
+        &self,
+        starts_after: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<Session>> {
+        Ok(self
+            .database
+            .lock()
+            .sessions_by_start(|session| session.start_at >= starts_after))
+    }
+
+    async fn find_starts_before(
+        &self,
+        starts_before: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<Session>> {
+        Ok(self
+            .database
+            .lock()
+            .sessions_by_start(|session| session.start_at <= starts_before))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use crate::{
+        db::DatabaseManager,
+        error::{Error, Result},
+        model::{
+            BookingPolicy, BookingRefusal, DiscordId, NewAvailability, NewSession,
+            NewSessionFeedback, NewTeacher, NewUser, NewWaitlistEntry, PartialTeacher,
+            SessionStatus, Weekday,
+        },
+    };
+
+    #[tokio::test]
+    async fn test_memory_session_book_reschedule_and_join() -> Result<()> {
+        let db = DatabaseManager::in_memory();
+        let session_repo = db.session_repository();
+
+        let teacher = db
+            .teacher_repository()
+            .insert(&NewTeacher {
+                name: "John Doe".to_string(),
+                email: "memory@mentor.com".to_string(),
+                specialty: "Math".to_string(),
+                applied_at: None,
+                company: None,
+                company_role: None,
+                bio: None,
+                course_info: None,
+                whatsapp: None,
+                linkedin: None,
+                comment_general: None,
+                comment_experience: None,
+                discord_id: None,
+                program_id: None,
+                timezone: Default::default(),
+                max_sessions_per_week: None,
+                locale: None,
+            })
+            .await?;
+
+        let new_availability = NewAvailability {
+            teacher_id: teacher.id,
+            weekday: Weekday::Monday,
+            time_start: chrono::NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+            expired: false,
+            duration: 1,
+            valid_from: None,
+            valid_until: None,
+            program_id: None,
+            capacity: 2,
+        };
+        let availability = db
+            .availability_repository()
+            .insert(&new_availability)
+            .await?;
+        let other_availability = db
+            .availability_repository()
+            .insert(&NewAvailability {
+                time_start: chrono::NaiveTime::from_hms_opt(11, 0, 0).unwrap(),
+                ..new_availability
+            })
+            .await?;
+
+        let new_user = NewUser {
+            discord_id: DiscordId(1),
+            name: "First Student".to_string(),
+            email: "first@student.com".to_string(),
+            bio: None,
+            timezone: Default::default(),
+            locale: None,
+        };
+        let first = db.user_repository().insert(&new_user).await?;
+        let second = db
+            .user_repository()
+            .insert(&NewUser {
+                discord_id: DiscordId(2),
+                email: "second@student.com".to_string(),
+                ..new_user.clone()
+            })
+            .await?;
+        let third = db
+            .user_repository()
+            .insert(&NewUser {
+                discord_id: DiscordId(3),
+                email: "third@student.com".to_string(),
+                ..new_user
+            })
+            .await?;
+
+        let start_at = chrono::Utc.with_ymd_and_hms(2023, 9, 11, 13, 0, 0).unwrap();
+        let new_session = NewSession {
+            teacher_id: teacher.id,
+            student_id: first.discord_id,
+            availability_id: availability.id,
+            summary: None,
+            notified: false,
+            meet_id: None,
+            calendar_event_id: None,
+            start_at,
+            end_at: start_at + chrono::Duration::minutes(40),
+            status: SessionStatus::Scheduled,
+            cancelled_at: None,
+            cancel_reason: None,
+        };
+        let session = session_repo
+            .book(&new_session, &BookingPolicy::UNLIMITED)
+            .await?;
+        assert_eq!(Some(session.clone()), session_repo.get(session.id).await?);
+        assert_eq!(
+            vec![first.clone()],
+            session_repo.find_students(session.id).await?
+        );
+
+        // an overlapping session of the same teacher can't be booked
+        assert!(matches!(
+            session_repo
+                .book(
+                    &NewSession {
+                        student_id: second.discord_id,
+                        start_at: start_at + chrono::Duration::minutes(20),
+                        end_at: start_at + chrono::Duration::minutes(60),
+                        ..new_session.clone()
+                    },
+                    &BookingPolicy::UNLIMITED
+                )
+                .await,
+            Err(Error::SessionTimeTaken)
+        ));
+
+        // times offered to another student in the waitlist are held for them
+        let held_start_at = chrono::Utc.with_ymd_and_hms(2023, 9, 18, 14, 0, 0).unwrap();
+        let entry = db
+            .waitlist_repository()
+            .join(&NewWaitlistEntry {
+                student_id: third.discord_id,
+                teacher_id: None,
+                specialty: None,
+            })
+            .await?
+            .unwrap();
+        db.waitlist_repository()
+            .offer(
+                entry.id,
+                other_availability.id,
+                held_start_at,
+                chrono::Utc::now() + chrono::Duration::hours(1),
+            )
+            .await?;
+        assert!(matches!(
+            session_repo
+                .book(
+                    &NewSession {
+                        student_id: second.discord_id,
+                        availability_id: other_availability.id,
+                        start_at: held_start_at,
+                        end_at: held_start_at + chrono::Duration::minutes(40),
+                        ..new_session.clone()
+                    },
+                    &BookingPolicy::UNLIMITED
+                )
+                .await,
+            Err(Error::BookingRefused(BookingRefusal::HeldForWaitlist))
+        ));
+
+        // nor another session of the same student, if the booking limits forbid it
+        let later_start_at = start_at + chrono::Duration::hours(2);
+        assert!(matches!(
+            session_repo
+                .book(
+                    &NewSession {
+                        start_at: later_start_at,
+                        end_at: later_start_at + chrono::Duration::minutes(40),
+                        ..new_session.clone()
+                    },
+                    &BookingPolicy {
+                        max_sessions_per_mentor_per_week: Some(1),
+                        ..BookingPolicy::UNLIMITED
+                    }
+                )
+                .await,
+            Err(Error::BookingRefused(_))
+        ));
+
+        // other students may join it until it is full
+        assert!(
+            session_repo
+                .add_student_if_seats_left(
+                    session.id,
+                    second.discord_id,
+                    availability.capacity,
+                    &BookingPolicy::UNLIMITED
+                )
+                .await?
+        );
+        assert!(
+            !session_repo
+                .add_student_if_seats_left(
+                    session.id,
+                    third.discord_id,
+                    availability.capacity,
+                    &BookingPolicy::UNLIMITED
+                )
+                .await?
+        );
+        assert_eq!(
+            vec![session.clone()],
+            session_repo.find_by_student(second.discord_id).await?
+        );
+
+        // reminders are reset when rescheduling
+        assert_eq!(1, session_repo.register_reminder(session.id, 60).await?);
+        assert_eq!(0, session_repo.register_reminder(session.id, 60).await?);
+
+        let new_start_at = start_at + chrono::Duration::hours(1);
+        let rescheduled = session_repo
+            .reschedule(
+                &session,
+                &BookingPolicy::UNLIMITED,
+                teacher.id,
+                other_availability.id,
+                new_start_at,
+                new_start_at + chrono::Duration::minutes(40),
+            )
+            .await?
+            .unwrap();
+        assert_eq!(other_availability.id, rescheduled.availability_id);
+        assert_eq!(
+            vec![(rescheduled.clone(), teacher.clone())],
+            session_repo
+                .find_needing_reminder(60, new_start_at - chrono::Duration::minutes(30))
+                .await?
+        );
+
+        // the old time is free again, while the new one is taken
+        let other_session = session_repo
+            .book(
+                &NewSession {
+                    student_id: third.discord_id,
+                    ..new_session
+                },
+                &BookingPolicy::UNLIMITED,
+            )
+            .await?;
+        assert_eq!(
+            None,
+            session_repo
+                .reschedule(
+                    &other_session,
+                    &BookingPolicy::UNLIMITED,
+                    teacher.id,
+                    other_availability.id,
+                    new_start_at,
+                    new_start_at + chrono::Duration::minutes(40),
+                )
+                .await?
+        );
+
+        // nor can it be moved to overlap with the other one
+        let overlapping_start_at = new_start_at + chrono::Duration::minutes(20);
+        assert_eq!(
+            None,
+            session_repo
+                .reschedule(
+                    &other_session,
+                    &BookingPolicy::UNLIMITED,
+                    teacher.id,
+                    availability.id,
+                    overlapping_start_at,
+                    overlapping_start_at + chrono::Duration::minutes(40),
+                )
+                .await?
+        );
+
+        // nor within a week in which the teacher reached their weekly cap
+        // (to which the session being moved doesn't count)
+        let teacher = db
+            .teacher_repository()
+            .update(
+                &teacher,
+                PartialTeacher {
+                    max_sessions_per_week: Some(Some(2)),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        let moved = session_repo
+            .reschedule(
+                &other_session,
+                &BookingPolicy::UNLIMITED,
+                teacher.id,
+                availability.id,
+                later_start_at,
+                later_start_at + chrono::Duration::minutes(40),
+            )
+            .await?
+            .unwrap();
+        db.teacher_repository()
+            .update(
+                &teacher,
+                PartialTeacher {
+                    max_sessions_per_week: Some(Some(1)),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        assert_eq!(
+            None,
+            session_repo
+                .reschedule(
+                    &moved,
+                    &BookingPolicy::UNLIMITED,
+                    teacher.id,
+                    availability.id,
+                    start_at,
+                    start_at + chrono::Duration::minutes(40),
+                )
+                .await?
+        );
+
+        // removing a session also removes its students
+        assert_eq!(1, session_repo.remove(&rescheduled).await?);
+        assert!(session_repo
+            .find_by_student(second.discord_id)
+            .await?
+            .is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_memory_session_feedback_requests_and_ratings() -> Result<()> {
+        let db = DatabaseManager::in_memory();
+        let session_repo = db.session_repository();
+        let feedback_repo = db.feedback_repository();
+
+        let teacher = db
+            .teacher_repository()
+            .insert(&NewTeacher {
+                name: "John Doe".to_string(),
+                email: "feedback@mentor.com".to_string(),
+                specialty: "Math".to_string(),
+                applied_at: None,
+                company: None,
+                company_role: None,
+                bio: None,
+                course_info: None,
+                whatsapp: None,
+                linkedin: None,
+                comment_general: None,
+                comment_experience: None,
+                discord_id: None,
+                program_id: None,
+                timezone: Default::default(),
+                max_sessions_per_week: None,
+                locale: None,
+            })
+            .await?;
+
+        let availability = db
+            .availability_repository()
+            .insert(&NewAvailability {
+                teacher_id: teacher.id,
+                weekday: Weekday::Monday,
+                time_start: chrono::NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+                expired: false,
+                duration: 1,
+                valid_from: None,
+                valid_until: None,
+                program_id: None,
+                capacity: 1,
+            })
+            .await?;
+
+        let student = db
+            .user_repository()
+            .insert(&NewUser {
+                discord_id: DiscordId(1),
+                name: "Student".to_string(),
+                email: "student@student.com".to_string(),
+                bio: None,
+                timezone: Default::default(),
+                locale: None,
+            })
+            .await?;
+
+        let start_at = chrono::Utc.with_ymd_and_hms(2023, 9, 11, 13, 0, 0).unwrap();
+        let end_at = start_at + chrono::Duration::minutes(40);
+        let session = session_repo
+            .insert(&NewSession {
+                teacher_id: teacher.id,
+                student_id: student.discord_id,
+                availability_id: availability.id,
+                summary: None,
+                notified: true,
+                meet_id: None,
+                calendar_event_id: None,
+                start_at,
+                end_at,
+                status: SessionStatus::Scheduled,
+                cancelled_at: None,
+                cancel_reason: None,
+            })
+            .await?;
+        assert!(!session.feedback_requested);
+
+        let needing_feedback_request = |now| async move {
+            session_repo
+                .find_needing_feedback_request(now)
+                .await
+                .map(|found| found.into_iter().map(|(s, _)| s.id).collect::<Vec<_>>())
+        };
+
+        // feedback is only requested once the session ends, and only once
+        assert!(needing_feedback_request(start_at).await?.is_empty());
+        assert_eq!(vec![session.id], needing_feedback_request(end_at).await?);
+        assert_eq!(1, session_repo.register_feedback_request(session.id).await?);
+        assert_eq!(0, session_repo.register_feedback_request(session.id).await?);
+        assert!(needing_feedback_request(end_at).await?.is_empty());
+
+        // ratings must be within range
+        let new_feedback = NewSessionFeedback {
+            session_id: session.id,
+            author_id: student.discord_id,
+            from_mentor: false,
+            rating: 6,
+            comment: None,
+        };
+        assert!(matches!(
+            feedback_repo.submit(&new_feedback).await,
+            Err(Error::Diesel(_))
+        ));
+
+        feedback_repo
+            .submit(&NewSessionFeedback {
+                rating: 4,
+                ..new_feedback
+            })
+            .await?;
+        let ratings = feedback_repo.find_teacher_ratings().await?;
+        assert_eq!(1, ratings.len());
+        assert_eq!(teacher, ratings[0].0);
+        assert_eq!(4.0, ratings[0].1.average_rating);
+
+        // feedback is removed along with its session
+        session_repo.remove(&session).await?;
+        assert!(feedback_repo.find_teacher_ratings().await?.is_empty());
+
+        Ok(())
+    }
+}
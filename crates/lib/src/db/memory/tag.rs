@@ -0,0 +1,98 @@
+use async_trait::async_trait;
+
+use super::{super::store::TagStore, foreign_key_violation, MemoryDatabase};
+use crate::{error::Result, model::Tag};
+
+/// Manages Tag instances kept in memory, as well as the tags of each Teacher.
+#[derive(Clone)]
+pub struct MemoryTagRepository {
+    database: MemoryDatabase,
+}
+
+impl MemoryTagRepository {
+    /// Creates a new MemoryTagRepository operating on the given in-memory database.
+    pub fn new(database: &MemoryDatabase) -> Self {
+        Self {
+            database: database.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl TagStore for MemoryTagRepository {
+    async fn find_all(&self) -> Result<Vec<Tag>> {
+        let mut tags = self
+            .database
+            .lock()
+            .tags
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        tags.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(tags)
+    }
+
+    async fn find_by_teacher(&self, teacher_id: i64) -> Result<Vec<Tag>> {
+        Ok(self
+            .find_by_teachers(&[teacher_id])
+            .await?
+            .into_iter()
+            .map(|(_, tag)| tag)
+            .collect())
+    }
+
+    async fn find_by_teachers(&self, teacher_ids: &[i64]) -> Result<Vec<(i64, Tag)>> {
+        let state = self.database.lock();
+        let mut teacher_tags = state
+            .teacher_tags
+            .iter()
+            .filter(|(teacher_id, _)| teacher_ids.contains(teacher_id))
+            .filter_map(|&(teacher_id, tag_id)| {
+                Some((teacher_id, state.tags.get(&tag_id)?.clone()))
+            })
+            .collect::<Vec<_>>();
+
+        teacher_tags.sort_by(|(a_teacher, a), (b_teacher, b)| {
+            a.name.cmp(&b.name).then(a_teacher.cmp(b_teacher))
+        });
+        Ok(teacher_tags)
+    }
+
+    async fn set_teacher_tags(&self, teacher_id: i64, names: &[String]) -> Result<Vec<Tag>> {
+        let mut state = self.database.lock();
+        if !state.teachers.contains_key(&teacher_id) {
+            return Err(foreign_key_violation("teacher_tags_teacher_id_fkey"));
+        }
+
+        let mut tags = Vec::with_capacity(names.len());
+        for name in names {
+            let existing = state.tags.values().find(|tag| tag.name == *name).cloned();
+            let tag = if let Some(tag) = existing {
+                tag
+            } else {
+                let tag = Tag {
+                    id: state.last_ids.tag + 1,
+                    name: name.clone(),
+                };
+                state.last_ids.tag = tag.id;
+                state.tags.insert(tag.id, tag.clone());
+                tag
+            };
+
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        tags.sort_by(|a, b| a.name.cmp(&b.name));
+
+        state
+            .teacher_tags
+            .retain(|(tagged_teacher_id, _)| *tagged_teacher_id != teacher_id);
+        state
+            .teacher_tags
+            .extend(tags.iter().map(|tag| (teacher_id, tag.id)));
+
+        Ok(tags)
+    }
+}
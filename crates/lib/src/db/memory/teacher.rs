@@ -0,0 +1,289 @@
+use async_trait::async_trait;
+
+use super::{
+    super::{
+        pagination::{Page, PageRequest, SortOrder},
+        repository::TeacherFilter,
+        store::TeacherStore,
+    },
+    foreign_key_violation, is_unique_violation, not_found, MemoryDatabase,
+};
+use crate::{
+    error::{Error, Result},
+    model::{Availability, DiscordId, NewTeacher, PartialTeacher, Teacher},
+};
+
+/// Manages Teacher instances kept in memory.
+#[derive(Clone)]
+pub struct MemoryTeacherRepository {
+    database: MemoryDatabase,
+}
+
+impl MemoryTeacherRepository {
+    /// Creates a new MemoryTeacherRepository operating on the given in-memory database.
+    pub fn new(database: &MemoryDatabase) -> Self {
+        Self {
+            database: database.clone(),
+        }
+    }
+}
+
+/// Builds a Teacher with the given ID and the new teacher's fields.
+fn teacher_with_id(id: i64, teacher: &NewTeacher) -> Teacher {
+    let teacher = teacher.clone();
+    Teacher {
+        id,
+        name: teacher.name,
+        email: teacher.email,
+        specialty: teacher.specialty,
+        applied_at: teacher.applied_at,
+        bio: teacher.bio,
+        course_info: teacher.course_info,
+        company: teacher.company,
+        company_role: teacher.company_role,
+        whatsapp: teacher.whatsapp,
+        linkedin: teacher.linkedin,
+        comment_general: teacher.comment_general,
+        comment_experience: teacher.comment_experience,
+        discord_id: teacher.discord_id,
+        program_id: teacher.program_id,
+        timezone: teacher.timezone,
+        max_sessions_per_week: teacher.max_sessions_per_week,
+        locale: teacher.locale,
+    }
+}
+
+#[async_trait]
+impl TeacherStore for MemoryTeacherRepository {
+    async fn get(&self, id: i64) -> Result<Option<Teacher>> {
+        Ok(self.database.lock().teachers.get(&id).cloned())
+    }
+
+    async fn insert(&self, teacher: &NewTeacher) -> Result<Teacher> {
+        let mut state = self.database.lock();
+        let teacher = teacher_with_id(state.last_ids.teacher + 1, teacher);
+        state.check_teacher(&teacher)?;
+
+        state.last_ids.teacher = teacher.id;
+        state.teachers.insert(teacher.id, teacher.clone());
+        Ok(teacher)
+    }
+
+    async fn remove(&self, teacher: &Teacher) -> Result<usize> {
+        let mut state = self.database.lock();
+        if state
+            .availabilities
+            .values()
+            .any(|avail| avail.teacher_id == teacher.id)
+        {
+            return Err(foreign_key_violation("availability_teacher_id_fkey"));
+        }
+        if state
+            .sessions
+            .values()
+            .any(|session| session.teacher_id == teacher.id)
+        {
+            return Err(foreign_key_violation("sessions_teacher_id_fkey"));
+        }
+
+        state
+            .waitlist_entries
+            .retain(|_, entry| entry.teacher_id != Some(teacher.id));
+        state
+            .teacher_tags
+            .retain(|(teacher_id, _)| *teacher_id != teacher.id);
+        Ok(state.teachers.remove(&teacher.id).map_or(0, |_| 1))
+    }
+
+    async fn find_all(&self) -> Result<Vec<Teacher>> {
+        Ok(self.database.lock().teachers.values().cloned().collect())
+    }
+
+    async fn upsert(&self, teacher: &NewTeacher) -> Result<Teacher> {
+        // new teachers have no ID yet, so they never conflict with existing ones
+        self.insert(teacher).await
+    }
+
+    async fn update(&self, old_teacher: &Teacher, new_teacher: PartialTeacher) -> Result<Teacher> {
+        let mut state = self.database.lock();
+        let mut teacher = state
+            .teachers
+            .get(&old_teacher.id)
+            .cloned()
+            .ok_or_else(not_found)?;
+
+        let PartialTeacher {
+            id,
+            name,
+            email,
+            specialty,
+            applied_at,
+            bio,
+            course_info,
+            company,
+            company_role,
+            whatsapp,
+            linkedin,
+            comment_general,
+            comment_experience,
+            discord_id,
+            program_id,
+            timezone,
+            max_sessions_per_week,
+            locale,
+        } = new_teacher;
+        teacher.id = id.unwrap_or(teacher.id);
+        teacher.name = name.unwrap_or(teacher.name);
+        teacher.email = email.unwrap_or(teacher.email);
+        teacher.specialty = specialty.unwrap_or(teacher.specialty);
+        teacher.applied_at = applied_at.unwrap_or(teacher.applied_at);
+        teacher.bio = bio.unwrap_or(teacher.bio);
+        teacher.course_info = course_info.unwrap_or(teacher.course_info);
+        teacher.company = company.unwrap_or(teacher.company);
+        teacher.company_role = company_role.unwrap_or(teacher.company_role);
+        teacher.whatsapp = whatsapp.unwrap_or(teacher.whatsapp);
+        teacher.linkedin = linkedin.unwrap_or(teacher.linkedin);
+        teacher.comment_general = comment_general.unwrap_or(teacher.comment_general);
+        teacher.comment_experience = comment_experience.unwrap_or(teacher.comment_experience);
+        teacher.discord_id = discord_id.unwrap_or(teacher.discord_id);
+        teacher.program_id = program_id.unwrap_or(teacher.program_id);
+        teacher.timezone = timezone.unwrap_or(teacher.timezone);
+        teacher.max_sessions_per_week =
+            max_sessions_per_week.unwrap_or(teacher.max_sessions_per_week);
+        teacher.locale = locale.unwrap_or(teacher.locale);
+
+        if teacher.id != old_teacher.id {
+            return Err(Error::Other(
+                "Changing a teacher's ID is not supported in memory",
+            ));
+        }
+        state.check_teacher(&teacher)?;
+
+        state.teachers.insert(teacher.id, teacher.clone());
+        Ok(teacher)
+    }
+
+    async fn find_by_email(&self, email: &str) -> Result<Option<Teacher>> {
+        Ok(self
+            .database
+            .lock()
+            .teachers
+            .values()
+            .find(|teacher| teacher.email == email)
+            .cloned())
+    }
+
+    async fn find_page(
+        &self,
+        filter: &TeacherFilter,
+        request: &PageRequest<i64>,
+    ) -> Result<Page<Teacher, i64>> {
+        let search = filter
+            .search
+            .as_deref()
+            .map(|search| search.trim().to_lowercase());
+
+        let matches = |teacher: &&Teacher| {
+            filter
+                .program_id
+                .is_none_or(|program_id| teacher.program_id == Some(program_id))
+                && search.as_deref().is_none_or(|search| {
+                    teacher.name.to_lowercase().contains(search)
+                        || teacher.email.to_lowercase().contains(search)
+                })
+                && match (request.order, request.after) {
+                    (SortOrder::Ascending, Some(after)) => teacher.id > after,
+                    (SortOrder::Descending, Some(after)) => teacher.id < after,
+                    (_, None) => true,
+                }
+        };
+
+        let state = self.database.lock();
+        let limit = request.query_limit() as usize;
+        let teachers = match request.order {
+            SortOrder::Ascending => state
+                .teachers
+                .values()
+                .filter(matches)
+                .take(limit)
+                .cloned()
+                .collect(),
+            SortOrder::Descending => state
+                .teachers
+                .values()
+                .rev()
+                .filter(matches)
+                .take(limit)
+                .cloned()
+                .collect(),
+        };
+
+        Ok(Page::from_queried(
+            teachers,
+            request,
+            |teacher: &Teacher| teacher.id,
+        ))
+    }
+
+    async fn find_by_discord_id(&self, discord_id: DiscordId) -> Result<Option<Teacher>> {
+        Ok(self
+            .database
+            .lock()
+            .teachers
+            .values()
+            .find(|teacher| teacher.discord_id == Some(discord_id))
+            .cloned())
+    }
+
+    async fn insert_if_not_exists(&self, teacher: &NewTeacher) -> Result<usize> {
+        let mut state = self.database.lock();
+        let teacher = teacher_with_id(state.last_ids.teacher + 1, teacher);
+        match state.check_teacher(&teacher) {
+            // already registered
+            Err(err) if is_unique_violation(&err) => return Ok(0),
+            result => result?,
+        }
+
+        state.last_ids.teacher = teacher.id;
+        state.teachers.insert(teacher.id, teacher);
+        Ok(1)
+    }
+
+    async fn find_by_availabilities(
+        &self,
+        availabilities: &[Availability],
+    ) -> Result<Vec<(Teacher, Availability)>> {
+        let ids = availabilities
+            .iter()
+            .map(|avail| avail.id)
+            .collect::<Vec<_>>();
+        let teacher_ids = availabilities
+            .iter()
+            .map(|avail| avail.teacher_id)
+            .collect::<Vec<_>>();
+        let state = self.database.lock();
+
+        Ok(state
+            .availabilities
+            .values()
+            .filter(|avail| ids.contains(&avail.id) && teacher_ids.contains(&avail.teacher_id))
+            .filter_map(|avail| {
+                state
+                    .teachers
+                    .get(&avail.teacher_id)
+                    .map(|teacher| (teacher.clone(), avail.clone()))
+            })
+            .collect())
+    }
+
+    async fn find_by_ids(&self, ids: &[i64]) -> Result<Vec<Teacher>> {
+        Ok(self
+            .database
+            .lock()
+            .teachers
+            .values()
+            .filter(|teacher| ids.contains(&teacher.id))
+            .cloned()
+            .collect())
+    }
+}
@@ -0,0 +1,197 @@
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+
+use super::{
+    super::store::UserStore, foreign_key_violation, not_found, unique_violation, MemoryDatabase,
+};
+use crate::{
+    error::Result,
+    model::{DiscordId, NewUser, PartialUser, User},
+};
+
+/// Manages User instances kept in memory.
+#[derive(Clone)]
+pub struct MemoryUserRepository {
+    database: MemoryDatabase,
+}
+
+impl MemoryUserRepository {
+    /// Creates a new MemoryUserRepository operating on the given in-memory database.
+    pub fn new(database: &MemoryDatabase) -> Self {
+        Self {
+            database: database.clone(),
+        }
+    }
+}
+
+/// Checks that no user, other than the one with the given Discord ID, has the given email.
+fn check_email_available(
+    users: &BTreeMap<u64, User>,
+    email: &str,
+    except: DiscordId,
+) -> Result<()> {
+    if users
+        .values()
+        .any(|other| other.discord_id != except && other.email == email)
+    {
+        Err(unique_violation("users_email_key"))
+    } else {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl UserStore for MemoryUserRepository {
+    async fn get(&self, discord_id: DiscordId) -> Result<Option<User>> {
+        Ok(self.database.lock().users.get(&discord_id.0).cloned())
+    }
+
+    async fn insert(&self, user: &NewUser) -> Result<User> {
+        let mut state = self.database.lock();
+        if state.users.contains_key(&user.discord_id.0) {
+            return Err(unique_violation("users_pkey"));
+        }
+        check_email_available(&state.users, &user.email, user.discord_id)?;
+
+        state.users.insert(user.discord_id.0, user.clone());
+        Ok(user.clone())
+    }
+
+    async fn remove(&self, user: &User) -> Result<usize> {
+        let mut state = self.database.lock();
+        let id = user.discord_id;
+        if state
+            .sessions
+            .values()
+            .any(|session| session.student_id == id)
+            || state
+                .session_students
+                .iter()
+                .any(|student| student.student_id == id)
+        {
+            return Err(foreign_key_violation("sessions_student_id_fkey"));
+        }
+
+        state
+            .waitlist_entries
+            .retain(|_, entry| entry.student_id != id);
+        state.booking_overrides.remove(&id.0);
+        Ok(state.users.remove(&id.0).map_or(0, |_| 1))
+    }
+
+    async fn find_all(&self) -> Result<Vec<User>> {
+        Ok(self.database.lock().users.values().cloned().collect())
+    }
+
+    async fn upsert(&self, user: &NewUser) -> Result<User> {
+        let mut state = self.database.lock();
+        check_email_available(&state.users, &user.email, user.discord_id)?;
+
+        state.users.insert(user.discord_id.0, user.clone());
+        Ok(user.clone())
+    }
+
+    async fn update(&self, old_user: &User, new_user: PartialUser) -> Result<User> {
+        let mut state = self.database.lock();
+        let mut user = state
+            .users
+            .get(&old_user.discord_id.0)
+            .cloned()
+            .ok_or_else(not_found)?;
+
+        let PartialUser {
+            discord_id,
+            name,
+            email,
+            bio,
+            timezone,
+            locale,
+        } = new_user;
+        if let Some(discord_id) = discord_id {
+            user.discord_id = discord_id;
+        }
+        if let Some(name) = name {
+            user.name = name;
+        }
+        if let Some(email) = email {
+            user.email = email;
+        }
+        if let Some(bio) = bio {
+            user.bio = bio;
+        }
+        if let Some(timezone) = timezone {
+            user.timezone = timezone;
+        }
+        if let Some(locale) = locale {
+            user.locale = locale;
+        }
+
+        if user.discord_id != old_user.discord_id && state.users.contains_key(&user.discord_id.0) {
+            return Err(unique_violation("users_pkey"));
+        }
+        check_email_available(&state.users, &user.email, old_user.discord_id)?;
+
+        state.users.remove(&old_user.discord_id.0);
+        state.users.insert(user.discord_id.0, user.clone());
+        Ok(user)
+    }
+
+    async fn insert_if_not_exists(&self, user: &NewUser) -> Result<usize> {
+        let mut state = self.database.lock();
+        if state.users.contains_key(&user.discord_id.0)
+            || state.users.values().any(|other| other.email == user.email)
+        {
+            return Ok(0);
+        }
+
+        state.users.insert(user.discord_id.0, user.clone());
+        Ok(1)
+    }
+
+    async fn grant_booking_override(
+        &self,
+        student_id: DiscordId,
+        granted_by: DiscordId,
+    ) -> Result<usize> {
+        let mut state = self.database.lock();
+        if !state.users.contains_key(&student_id.0) {
+            return Err(foreign_key_violation("booking_overrides_student_id_fkey"));
+        }
+
+        state.booking_overrides.insert(student_id.0, granted_by.0);
+        Ok(1)
+    }
+
+    async fn has_booking_override(&self, student_id: DiscordId) -> Result<bool> {
+        Ok(self
+            .database
+            .lock()
+            .booking_overrides
+            .contains_key(&student_id.0))
+    }
+
+    async fn use_booking_override(&self, student_id: DiscordId) -> Result<usize> {
+        Ok(self
+            .database
+            .lock()
+            .booking_overrides
+            .remove(&student_id.0)
+            .map_or(0, |_| 1))
+    }
+
+    async fn set_dm_opt_out(&self, discord_id: DiscordId, opted_out: bool) -> Result<usize> {
+        let mut state = self.database.lock();
+        let changed = if opted_out {
+            state.dm_opt_outs.insert(discord_id.0)
+        } else {
+            state.dm_opt_outs.remove(&discord_id.0)
+        };
+
+        Ok(usize::from(changed))
+    }
+
+    async fn has_dm_opt_out(&self, discord_id: DiscordId) -> Result<bool> {
+        Ok(self.database.lock().dm_opt_outs.contains(&discord_id.0))
+    }
+}
@@ -0,0 +1,180 @@
+use async_trait::async_trait;
+
+use super::{super::store::WaitlistStore, foreign_key_violation, MemoryDatabase, MemoryState};
+use crate::{
+    error::Result,
+    model::{DiscordId, NewWaitlistEntry, WaitlistEntry, WaitlistStatus},
+};
+
+/// Manages WaitlistEntry instances kept in memory.
+#[derive(Clone)]
+pub struct MemoryWaitlistRepository {
+    database: MemoryDatabase,
+}
+
+impl MemoryWaitlistRepository {
+    /// Creates a new MemoryWaitlistRepository operating on the given in-memory database.
+    pub fn new(database: &MemoryDatabase) -> Self {
+        Self {
+            database: database.clone(),
+        }
+    }
+
+    /// Changes the status of a student's active entry, if any.
+    fn set_active_status(&self, student_id: DiscordId, status: WaitlistStatus) -> usize {
+        let mut state = self.database.lock();
+        let mut count = 0;
+        for entry in state.waitlist_entries.values_mut() {
+            if entry.student_id == student_id && entry.status.is_active() {
+                entry.status = status;
+                count += 1;
+            }
+        }
+
+        count
+    }
+}
+
+impl MemoryState {
+    /// Returns the waitlist entries matching the given predicate, in the order
+    /// they joined the waitlist.
+    fn waitlist_entries_by_join(
+        &self,
+        predicate: impl Fn(&WaitlistEntry) -> bool,
+    ) -> Vec<WaitlistEntry> {
+        let mut entries = self
+            .waitlist_entries
+            .values()
+            .filter(|entry| predicate(entry))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        entries.sort_by_key(|entry| (entry.joined_at, entry.id));
+        entries
+    }
+}
+
+#[async_trait]
+impl WaitlistStore for MemoryWaitlistRepository {
+    async fn get(&self, id: i64) -> Result<Option<WaitlistEntry>> {
+        Ok(self.database.lock().waitlist_entries.get(&id).cloned())
+    }
+
+    async fn join(&self, entry: &NewWaitlistEntry) -> Result<Option<WaitlistEntry>> {
+        let mut state = self.database.lock();
+        if !state.users.contains_key(&entry.student_id.0) {
+            return Err(foreign_key_violation("waitlist_entries_student_id_fkey"));
+        }
+        if entry
+            .teacher_id
+            .is_some_and(|teacher_id| !state.teachers.contains_key(&teacher_id))
+        {
+            return Err(foreign_key_violation("waitlist_entries_teacher_id_fkey"));
+        }
+        if state
+            .waitlist_entries
+            .values()
+            .any(|other| other.student_id == entry.student_id && other.status.is_active())
+        {
+            return Ok(None);
+        }
+
+        let entry = entry.clone();
+        let entry = WaitlistEntry {
+            id: state.last_ids.waitlist_entry + 1,
+            student_id: entry.student_id,
+            teacher_id: entry.teacher_id,
+            specialty: entry.specialty,
+            status: WaitlistStatus::Waiting,
+            joined_at: chrono::Utc::now(),
+            offered_availability_id: None,
+            offered_start_at: None,
+            offer_expires_at: None,
+        };
+
+        state.last_ids.waitlist_entry = entry.id;
+        state.waitlist_entries.insert(entry.id, entry.clone());
+        Ok(Some(entry))
+    }
+
+    async fn find_active_by_student(&self, student_id: DiscordId) -> Result<Option<WaitlistEntry>> {
+        Ok(self
+            .database
+            .lock()
+            .waitlist_entries
+            .values()
+            .find(|entry| entry.student_id == student_id && entry.status.is_active())
+            .cloned())
+    }
+
+    async fn leave(&self, student_id: DiscordId) -> Result<usize> {
+        Ok(self.set_active_status(student_id, WaitlistStatus::Left))
+    }
+
+    async fn fulfill(&self, student_id: DiscordId) -> Result<usize> {
+        Ok(self.set_active_status(student_id, WaitlistStatus::Fulfilled))
+    }
+
+    async fn find_waiting(&self) -> Result<Vec<WaitlistEntry>> {
+        Ok(self
+            .database
+            .lock()
+            .waitlist_entries_by_join(|entry| entry.status == WaitlistStatus::Waiting))
+    }
+
+    async fn find_offered(&self) -> Result<Vec<WaitlistEntry>> {
+        Ok(self
+            .database
+            .lock()
+            .waitlist_entries_by_join(|entry| entry.status == WaitlistStatus::Offered))
+    }
+
+    async fn offer(
+        &self,
+        entry_id: i64,
+        availability_id: i64,
+        start_at: chrono::DateTime<chrono::Utc>,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Option<WaitlistEntry>> {
+        let mut state = self.database.lock();
+        if !state.availabilities.contains_key(&availability_id) {
+            return Err(foreign_key_violation(
+                "waitlist_entries_offered_availability_id_fkey",
+            ));
+        }
+
+        let Some(entry) = state
+            .waitlist_entries
+            .get_mut(&entry_id)
+            .filter(|entry| entry.status == WaitlistStatus::Waiting)
+        else {
+            return Ok(None);
+        };
+
+        entry.status = WaitlistStatus::Offered;
+        entry.offered_availability_id = Some(availability_id);
+        entry.offered_start_at = Some(start_at);
+        entry.offer_expires_at = Some(expires_at);
+        Ok(Some(entry.clone()))
+    }
+
+    async fn expire_offers(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<WaitlistEntry>> {
+        let mut state = self.database.lock();
+        let mut expired = Vec::new();
+        for entry in state.waitlist_entries.values_mut() {
+            if entry.status == WaitlistStatus::Offered
+                && entry
+                    .offer_expires_at
+                    .is_some_and(|expires_at| expires_at <= now)
+            {
+                entry.status = WaitlistStatus::Expired;
+                expired.push(entry.clone());
+            }
+        }
+
+        Ok(expired)
+    }
+}
@@ -0,0 +1,146 @@
+//! Contains the database migrations embedded into the binary (from the
+//! `migrations/` folder) and functions to apply them at startup.
+use diesel::{
+    migration::{MigrationSource, MigrationVersion},
+    pg::Pg,
+    Connection, PgConnection,
+};
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
+
+use crate::error::{Error, Result};
+
+/// All migrations known to this binary.
+pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("../../migrations");
+
+/// Fails with [`Error::SchemaAhead`] if the given database has migrations applied
+/// which this binary doesn't know of, whether or not any migrations will be applied.
+pub async fn verify_schema_not_ahead(database_url: &str) -> Result<()> {
+    with_connection(database_url, check_schema_not_ahead).await
+}
+
+/// Lists the names of the migrations not yet applied to the given database,
+/// without applying them (a dry run), in the order they would be applied.
+///
+/// Fails with [`Error::SchemaAhead`] if the database has migrations applied
+/// which this binary doesn't know of.
+pub async fn pending_migrations(database_url: &str) -> Result<Vec<String>> {
+    with_connection(database_url, |connection| {
+        check_schema_not_ahead(connection)?;
+        pending_migration_names(connection)
+    })
+    .await
+}
+
+/// Applies all migrations not yet applied to the given database, returning
+/// their names, in the order they were applied.
+///
+/// Fails with [`Error::SchemaAhead`] (applying nothing) if the database has
+/// migrations applied which this binary doesn't know of.
+pub async fn run_pending_migrations(database_url: &str) -> Result<Vec<String>> {
+    with_connection(database_url, |connection| {
+        check_schema_not_ahead(connection)?;
+        run_pending(connection)
+    })
+    .await
+}
+
+/// Runs a blocking operation on a new synchronous connection to the given database,
+/// without blocking the async runtime.
+async fn with_connection<T, F>(database_url: &str, operation: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&mut PgConnection) -> Result<T> + Send + 'static,
+{
+    let database_url = database_url.to_owned();
+
+    tokio::task::spawn_blocking(move || {
+        let mut connection = PgConnection::establish(&database_url)?;
+        operation(&mut connection)
+    })
+    .await
+    .map_err(|err| Error::Generic(Box::new(err)))?
+}
+
+/// Fails with [`Error::SchemaAhead`] if the database has migrations applied
+/// which are not among the embedded ones.
+pub(crate) fn check_schema_not_ahead(connection: &mut PgConnection) -> Result<()> {
+    let known_versions = MigrationSource::<Pg>::migrations(&MIGRATIONS)
+        .map_err(Error::Migration)?
+        .iter()
+        .map(|migration| migration.name().version().as_owned())
+        .collect::<Vec<MigrationVersion<'static>>>();
+
+    let unknown_versions = connection
+        .applied_migrations()
+        .map_err(Error::Migration)?
+        .into_iter()
+        .filter(|version| !known_versions.contains(version))
+        .map(|version| version.to_string())
+        .collect::<Vec<_>>();
+
+    if unknown_versions.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::SchemaAhead(unknown_versions))
+    }
+}
+
+fn pending_migration_names(connection: &mut PgConnection) -> Result<Vec<String>> {
+    Ok(connection
+        .pending_migrations(MIGRATIONS)
+        .map_err(Error::Migration)?
+        .iter()
+        .map(|migration| migration.name().to_string())
+        .collect())
+}
+
+/// Applies each pending migration (each in its own transaction), returning their names.
+pub(crate) fn run_pending(connection: &mut PgConnection) -> Result<Vec<String>> {
+    let pending = connection
+        .pending_migrations(MIGRATIONS)
+        .map_err(Error::Migration)?;
+
+    let mut applied = Vec::with_capacity(pending.len());
+    for migration in pending {
+        connection
+            .run_migration(&*migration)
+            .map_err(Error::Migration)?;
+        applied.push(migration.name().to_string());
+    }
+
+    Ok(applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use diesel::{sql_query, Connection, PgConnection, RunQueryDsl};
+
+    use super::{check_schema_not_ahead, pending_migration_names, run_pending};
+    use crate::error::{Error, Result};
+
+    #[test]
+    fn test_migrations_detect_schema_ahead_of_binary() -> Result<()> {
+        dotenvy::dotenv().unwrap();
+        let test_db_url =
+            std::env::var("DATABASE_TEST_URL").expect("Failed to get 'DATABASE_TEST_URL' env var.");
+        let mut connection = PgConnection::establish(&test_db_url)?;
+
+        run_pending(&mut connection)?;
+        assert_eq!(
+            Vec::<String>::new(),
+            pending_migration_names(&mut connection)?
+        );
+        check_schema_not_ahead(&mut connection)?;
+
+        connection.begin_test_transaction()?;
+        sql_query("INSERT INTO __diesel_schema_migrations (version) VALUES ('29991231000000')")
+            .execute(&mut connection)?;
+
+        assert!(matches!(
+            check_schema_not_ahead(&mut connection),
+            Err(Error::SchemaAhead(versions)) if versions == vec!["29991231000000".to_string()]
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,105 @@
+//! Contains types for paginated queries on repositories.
+//! Pagination is cursor-based (keyset): each page is requested after the last
+//! entity of the previous one, so pages stay consistent as entities are added.
+
+/// The order in which paginated entities are sorted.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SortOrder {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+/// Requests a page of at most 'size' entities, starting right after the entity
+/// identified by the 'after' cursor (or from the very first one, if 'None').
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageRequest<C> {
+    pub size: usize,
+    pub after: Option<C>,
+    pub order: SortOrder,
+}
+
+/// A page of entities, along with the cursor to request the next one
+/// ('None' if this is the last page).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page<T, C> {
+    pub items: Vec<T>,
+    pub next: Option<C>,
+}
+
+impl<C> PageRequest<C> {
+    /// Requests the first page with the given size, in ascending order.
+    pub fn first(size: usize) -> Self {
+        Self {
+            size,
+            after: None,
+            order: SortOrder::Ascending,
+        }
+    }
+
+    /// Changes the order in which entities are sorted.
+    pub fn ordered(self, order: SortOrder) -> Self {
+        Self { order, ..self }
+    }
+
+    /// The maximum amount of entities to query, including an extra one
+    /// to determine whether there is a next page.
+    pub(crate) fn query_limit(&self) -> i64 {
+        self.size as i64 + 1
+    }
+}
+
+impl<T, C: Clone> Page<T, C> {
+    /// Creates a page from entities queried with [`PageRequest::query_limit`],
+    /// dropping the extra entity (if any) and setting the next cursor to the
+    /// last entity kept, through the given function.
+    pub(crate) fn from_queried(
+        mut items: Vec<T>,
+        request: &PageRequest<C>,
+        cursor_of: impl Fn(&T) -> C,
+    ) -> Self {
+        let has_next = items.len() > request.size;
+        items.truncate(request.size);
+
+        let next = if has_next {
+            items.last().map(cursor_of)
+        } else {
+            None
+        };
+
+        Self { items, next }
+    }
+
+    /// Returns the request for the page after this one, with the same size and order
+    /// as the given request for this page, or 'None' if this is the last page.
+    pub fn next_request(&self, request: &PageRequest<C>) -> Option<PageRequest<C>> {
+        self.next.clone().map(|after| PageRequest {
+            after: Some(after),
+            ..request.clone()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_from_queried_drops_extra_item_and_sets_cursor() {
+        let request = PageRequest::<i32>::first(2);
+        assert_eq!(3, request.query_limit());
+
+        let page = Page::from_queried(vec![1, 2, 3], &request, |item| *item);
+        assert_eq!(vec![1, 2], page.items);
+        assert_eq!(Some(2), page.next);
+
+        let next_request = page.next_request(&request).unwrap();
+        assert_eq!(Some(2), next_request.after);
+        assert_eq!(request.size, next_request.size);
+
+        let last_page = Page::from_queried(vec![3], &next_request, |item| *item);
+        assert_eq!(vec![3], last_page.items);
+        assert_eq!(None, last_page.next);
+        assert_eq!(None, last_page.next_request(&next_request));
+    }
+}
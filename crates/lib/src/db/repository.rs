@@ -1,24 +1,34 @@
 //! Contains structs implementing [`Repository`], acting as CRUD interfaces
 //! with the database.
-use std::sync::Arc;
-
 use async_trait::async_trait;
 use diesel::query_builder::AsQuery;
-use diesel_async::{pooled_connection::deadpool, AsyncPgConnection};
 
+use super::connection::{ConnectionSource, DbConnection};
 use crate::error::Result;
 
 mod user;
 pub use user::UserRepository;
 
 mod session;
-pub use session::SessionRepository;
+pub use session::{SessionCursor, SessionFilter, SessionRepository};
 
 mod availability;
 pub use availability::AvailabilityRepository;
 
 mod teacher;
-pub use teacher::TeacherRepository;
+pub use teacher::{TeacherFilter, TeacherRepository};
+
+mod program;
+pub use program::ProgramRepository;
+
+mod feedback;
+pub use feedback::FeedbackRepository;
+
+mod tag;
+pub use tag::TagRepository;
+
+mod waitlist;
+pub use waitlist::WaitlistRepository;
 
 pub mod macros;
 
@@ -47,12 +57,15 @@ pub trait Repository {
 
     const TABLE: Self::Table;
 
-    /// Returns the active connection pool.
-    fn get_connection_pool(&self) -> Arc<deadpool::Pool<AsyncPgConnection>>;
+    /// Returns where this repository obtains its connections from
+    /// (a connection pool, or an ongoing transaction).
+    fn get_connection_source(&self) -> &ConnectionSource;
 
     /// Locks the connection for own usage.
-    async fn lock_connection(&self) -> Result<deadpool::Object<AsyncPgConnection>> {
-        self.get_connection_pool().get().await.map_err(From::from)
+    /// Within a transaction, the connection is shared by all repositories, so
+    /// it must be released before any other repository method is called.
+    async fn lock_connection(&self) -> Result<DbConnection> {
+        self.get_connection_source().get().await
     }
 
     /// Gets an entity by their Primary Key.
@@ -90,9 +103,12 @@ pub trait UpdatableRepository: Repository {
 #[cfg(test)]
 mod tests {
     use diesel::Connection;
-    use diesel_migrations::MigrationHarness;
 
-    use super::super::connection::DatabaseManager;
+    use super::super::{connection::DatabaseManager, migrations};
+    use crate::{
+        error::{Error, Result},
+        model::{DiscordId, NewUser},
+    };
 
     /// Initializes the database for testing.
     pub(super) fn init_db() -> DatabaseManager {
@@ -105,11 +121,102 @@ mod tests {
         DatabaseManager::test(&test_db_url).unwrap()
     }
 
-    /// Migrates the test database based on the migrations directory.
+    /// Migrates the test database with the embedded migrations.
     fn migrate_test_db(db_url: &str) {
         let mut connection = diesel::PgConnection::establish(db_url).unwrap();
-        let migrations_dir =
-            diesel_migrations::FileBasedMigrations::find_migrations_directory().unwrap();
-        connection.run_pending_migrations(migrations_dir).unwrap();
+        migrations::run_pending(&mut connection).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_transaction_commits_on_success() -> Result<()> {
+        let db = init_db();
+
+        let user = db
+            .transaction(|db| async move {
+                db.user_repository()
+                    .insert(&NewUser {
+                        discord_id: DiscordId(161),
+                        name: "Committed".to_string(),
+                        email: "committed@student.com".to_string(),
+                        bio: None,
+                        timezone: Default::default(),
+                        locale: None,
+                    })
+                    .await
+            })
+            .await?;
+
+        assert_eq!(Some(user), db.user_repository().get(DiscordId(161)).await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_transaction_rolls_back_on_error() -> Result<()> {
+        let db = init_db();
+
+        let result: Result<()> = db
+            .transaction(|db| async move {
+                db.user_repository()
+                    .insert(&NewUser {
+                        discord_id: DiscordId(162),
+                        name: "Rolled Back".to_string(),
+                        email: "rolledback@student.com".to_string(),
+                        bio: None,
+                        timezone: Default::default(),
+                        locale: None,
+                    })
+                    .await?;
+
+                // nested transactions are rolled back independently
+                let nested: Result<()> = db
+                    .transaction(|_| async { Err(Error::Other("nested failure")) })
+                    .await;
+                assert!(nested.is_err());
+                assert!(db.user_repository().get(DiscordId(162)).await?.is_some());
+
+                Err(Error::Other("failure"))
+            })
+            .await;
+
+        assert!(matches!(result, Err(Error::Other("failure"))));
+        assert_eq!(None, db.user_repository().get(DiscordId(162)).await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_transaction_rolls_back_when_dropped() -> Result<()> {
+        let db = init_db();
+
+        db.transaction(|db| async move {
+            // the nested transaction is dropped while still ongoing
+            let timed_out = tokio::time::timeout(
+                std::time::Duration::from_millis(200),
+                db.transaction(|db| async move {
+                    db.user_repository()
+                        .insert(&NewUser {
+                            discord_id: DiscordId(163),
+                            name: "Dropped".to_string(),
+                            email: "dropped@student.com".to_string(),
+                            bio: None,
+                            timezone: Default::default(),
+                            locale: None,
+                        })
+                        .await?;
+
+                    std::future::pending::<Result<()>>().await
+                }),
+            )
+            .await;
+            assert!(timed_out.is_err());
+
+            // let the rollback start before using the connection again
+            tokio::task::yield_now().await;
+            assert_eq!(None, db.user_repository().get(DiscordId(163)).await?);
+
+            Ok(())
+        })
+        .await
     }
 }
@@ -1,49 +1,119 @@
-use std::sync::Arc;
-
 use async_trait::async_trait;
 use chrono::{Datelike, TimeZone};
 use diesel::{
-    dsl::{count, exists, not},
+    dsl::{count, exists, not, sql},
+    expression::SqlLiteral,
+    sql_types::Bool,
     BelongingToDsl, BoolExpressionMethods, ExpressionMethods, OptionalExtension, QueryDsl,
 };
-use diesel_async::{pooled_connection::deadpool::Pool, AsyncPgConnection, RunQueryDsl};
+use diesel_async::RunQueryDsl;
 
 use super::{
-    super::schema::{availability, sessions},
+    super::{
+        connection::ConnectionSource,
+        schema::{availability, programs, sessions, teachers},
+        store::AvailabilityStore,
+    },
     repo_find_all, repo_get, repo_insert, repo_remove, repo_update, repo_upsert, Repository,
     UpdatableRepository,
 };
 use crate::{
     error::Result,
-    model::{Availability, NewAvailability, PartialAvailability, Session, Teacher, Weekday},
+    model::{
+        Availability, NewAvailability, PartialAvailability, Program, ProgramStatus, Session,
+        SessionStatus, Teacher, Weekday,
+    },
     util::time::datetime_as_utc,
 };
 
 /// Manages Availability instances.
 #[derive(Clone)]
 pub struct AvailabilityRepository {
-    pool: Arc<Pool<AsyncPgConnection>>,
+    connection: ConnectionSource,
 }
 
 impl AvailabilityRepository {
     /// Creates a new AvailabilityRepository operating with the given
-    /// connection pool.
-    pub fn new(pool: &Arc<Pool<AsyncPgConnection>>) -> Self {
+    /// connection source (a connection pool or a transaction).
+    pub fn new(connection: &ConnectionSource) -> Self {
         Self {
-            pool: Arc::clone(pool),
+            connection: connection.clone(),
         }
     }
+}
+
+#[async_trait]
+impl AvailabilityStore for AvailabilityRepository {
+    async fn get(&self, id: i64) -> Result<Option<Availability>> {
+        Repository::get(self, id).await
+    }
+
+    async fn insert(&self, avail: &NewAvailability) -> Result<Availability> {
+        Repository::insert(self, avail).await
+    }
+
+    async fn remove(&self, avail: &Availability) -> Result<usize> {
+        Repository::remove(self, avail).await
+    }
+
+    async fn find_all(&self) -> Result<Vec<Availability>> {
+        Repository::find_all(self).await
+    }
+
+    async fn upsert(&self, avail: &NewAvailability) -> Result<Availability> {
+        UpdatableRepository::upsert(self, avail).await
+    }
+
+    async fn update(
+        &self,
+        old_avail: &Availability,
+        new_avail: PartialAvailability,
+    ) -> Result<Availability> {
+        UpdatableRepository::update(self, old_avail, new_avail).await
+    }
 
-    /// Get all Availability instances attached to a certain teacher.
-    pub async fn find_by_teacher(&self, teacher: &Teacher) -> Result<Vec<Availability>> {
+    async fn find_by_teacher(&self, teacher: &Teacher) -> Result<Vec<Availability>> {
         Availability::belonging_to(&teacher)
             .get_results(&mut self.lock_connection().await?)
             .await
             .map_err(From::from)
     }
 
-    /// Get the Availability a Session is attached to.
-    pub async fn find_by_session(&self, session: &Session) -> Result<Option<Availability>> {
+    async fn expire_by_teacher_and_ids(&self, teacher_id: i64, ids: &[i64]) -> Result<usize> {
+        diesel::update(availability::table)
+            .filter(availability::teacher_id.eq(teacher_id))
+            .filter(availability::id.eq_any(ids))
+            .set(availability::expired.eq(true))
+            .execute(&mut self.lock_connection().await?)
+            .await
+            .map_err(From::from)
+    }
+
+    async fn set_validity_window(
+        &self,
+        teacher_id: Option<i64>,
+        valid_from: Option<chrono::NaiveDate>,
+        valid_until: Option<chrono::NaiveDate>,
+    ) -> Result<usize> {
+        let mut query = diesel::update(availability::table)
+            .filter(availability::expired.eq(false))
+            .into_boxed();
+
+        if let Some(teacher_id) = teacher_id {
+            query = query.filter(availability::teacher_id.eq(teacher_id));
+        }
+
+        query
+            .set((
+                availability::valid_from.eq(valid_from),
+                availability::valid_until.eq(valid_until),
+            ))
+            .execute(&mut self.lock_connection().await?)
+            .await
+            .map_err(From::from)
+    }
+
+    async fn find_by_session(&self, session: &Session) -> Result<Option<Availability>> {
         availability::table
             .inner_join(sessions::table)
             .filter(sessions::id.eq(session.id))
@@ -54,9 +124,7 @@ impl AvailabilityRepository {
             .map_err(From::from)
     }
 
-    /// Checks if the given availability exists and is taken by a Session
-    /// which starts after the given date.
-    pub async fn check_is_taken_at(
+    async fn check_is_taken_at(
         &self,
         id: i64,
         datetime: &chrono::DateTime<chrono::FixedOffset>,
@@ -68,11 +136,14 @@ impl AvailabilityRepository {
                 .select(count(availability::id))
                 .filter(availability::id.eq(id))
                 .filter(exists(
-                    sessions::table.filter(
-                        sessions::availability_id
-                            .eq(availability::id)
-                            .and(sessions::start_at.ge(utc)),
-                    ),
+                    sessions::table
+                        .filter(
+                            sessions::availability_id
+                                .eq(availability::id)
+                                .and(sessions::start_at.ge(utc)),
+                        )
+                        .filter(sessions::status.eq(SessionStatus::Scheduled))
+                        .filter(session_is_full()),
                 )),
             &mut self.lock_connection().await?,
         )
@@ -81,61 +152,173 @@ impl AvailabilityRepository {
         .map_err(From::from)
     }
 
-    /// Finds all non-taken availabilities within a week of the given datetime.
-    /// It is assumed that availability times stored
-    /// in the DB are in UTC-3.
-    pub async fn find_nontaken_within_a_week_of_date(
+    async fn find_nontaken_within_a_week_of_date(
         &self,
         datetime: chrono::DateTime<chrono::FixedOffset>,
     ) -> Result<Vec<Availability>> {
         let utc = chrono::Utc.from_utc_datetime(&datetime.naive_utc());
         let weekday: Weekday = datetime.naive_local().weekday().into();
+        // each mentor's date may be one day apart from the given datetime's
+        let first_date = datetime.date_naive() - chrono::Duration::days(1);
+        let last_date = first_date + chrono::Duration::days(8);
 
-        // get all 'Availability' which occur later today (same weekday)
-        // except for those linked to sessions
-        availability::table
-            .select(availability::all_columns)
+        // get all 'Availability' which may occur in the next 7 days
+        // except for those linked to full scheduled sessions, or whose validity window
+        // (or program) doesn't intersect with those days
+        let availabilities: Vec<(Availability, Program, Teacher)> = availability::table
+            .inner_join(programs::table)
+            .inner_join(teachers::table)
+            .select((
+                availability::all_columns,
+                programs::all_columns,
+                teachers::all_columns,
+            ))
             .filter(availability::weekday.eq_any(weekday.next_7_days()))
+            .filter(availability::expired.eq(false))
+            .filter(
+                availability::valid_from
+                    .is_null()
+                    .or(availability::valid_from.le(last_date)),
+            )
+            .filter(
+                availability::valid_until
+                    .is_null()
+                    .or(availability::valid_until.ge(first_date)),
+            )
+            .filter(programs::status.eq(ProgramStatus::Active))
+            .filter(programs::enrollment_open.eq(true))
+            .filter(programs::start_date.le(last_date))
+            .filter(programs::end_date.ge(first_date))
             .filter(not(exists(
-                sessions::table.filter(
-                    sessions::availability_id
-                        .eq(availability::id)
-                        .and(sessions::start_at.ge(utc)),
-                ),
+                sessions::table
+                    .filter(
+                        sessions::availability_id
+                            .eq(availability::id)
+                            .and(sessions::start_at.ge(utc)),
+                    )
+                    .filter(sessions::status.eq(SessionStatus::Scheduled))
+                    .filter(session_is_full()),
             )))
             .get_results(&mut self.lock_connection().await?)
+            .await?;
+
+        // ensure each availability is valid at the specific date it would occur
+        // (in its mentor's timezone)
+        let availabilities = availabilities
+            .into_iter()
+            .filter(|(avail, program, teacher)| {
+                let date = avail
+                    .first_possible_date_after(teacher.timezone, &datetime)
+                    .date_naive();
+                avail.is_valid_at(date) && program.includes_date(date)
+            })
+            .map(|(avail, _, teacher)| (avail, teacher))
+            .collect();
+
+        self.retain_within_weekly_caps(availabilities, &datetime)
             .await
-            .map_err(From::from)
     }
 
-    /// Finds all non-taken availabilities at the given datetime.
-    /// It is assumed that availability times stored
-    /// in the DB are in UTC-3.
-    pub async fn find_nontaken_at_date(
+    async fn find_nontaken_at_date(
         &self,
         datetime: chrono::DateTime<chrono::FixedOffset>,
     ) -> Result<Vec<Availability>> {
         let utc = chrono::Utc.from_utc_datetime(&datetime.naive_utc());
         let weekday: Weekday = datetime.naive_local().weekday().into();
+        let date = datetime.date_naive();
 
         // get all 'Availability' which occur later today (same weekday)
-        // except for those linked to sessions
-        availability::table
-            .select(availability::all_columns)
+        // except for those linked to full scheduled sessions or not valid at this date
+        let availabilities: Vec<(Availability, Teacher)> = availability::table
+            .inner_join(programs::table)
+            .inner_join(teachers::table)
+            .select((availability::all_columns, teachers::all_columns))
             .filter(availability::weekday.eq(weekday))
+            .filter(availability::expired.eq(false))
+            .filter(
+                availability::valid_from
+                    .is_null()
+                    .or(availability::valid_from.le(date)),
+            )
+            .filter(
+                availability::valid_until
+                    .is_null()
+                    .or(availability::valid_until.ge(date)),
+            )
+            .filter(programs::status.eq(ProgramStatus::Active))
+            .filter(programs::enrollment_open.eq(true))
+            .filter(programs::start_date.le(date))
+            .filter(programs::end_date.ge(date))
             .filter(not(exists(
-                sessions::table.filter(
-                    sessions::availability_id
-                        .eq(availability::id)
-                        .and(sessions::start_at.ge(utc)),
-                ),
+                sessions::table
+                    .filter(
+                        sessions::availability_id
+                            .eq(availability::id)
+                            .and(sessions::start_at.ge(utc)),
+                    )
+                    .filter(sessions::status.eq(SessionStatus::Scheduled))
+                    .filter(session_is_full()),
             )))
             .get_results(&mut self.lock_connection().await?)
+            .await?;
+
+        self.retain_within_weekly_caps(availabilities, &datetime)
             .await
-            .map_err(From::from)
     }
 }
 
+impl AvailabilityRepository {
+    /// Keeps only the availabilities which can still be scheduled at their first start
+    /// after the given datetime, according to their mentors' weekly caps
+    /// (see [`Availability::is_within_weekly_cap`]).
+    async fn retain_within_weekly_caps(
+        &self,
+        availabilities: Vec<(Availability, Teacher)>,
+        datetime: &chrono::DateTime<chrono::FixedOffset>,
+    ) -> Result<Vec<Availability>> {
+        let mut capped_teacher_ids = availabilities
+            .iter()
+            .filter(|(_, teacher)| teacher.max_sessions_per_week.is_some())
+            .map(|(_, teacher)| teacher.id)
+            .collect::<Vec<_>>();
+        capped_teacher_ids.sort_unstable();
+        capped_teacher_ids.dedup();
+
+        let capped_sessions: Vec<Session> = if capped_teacher_ids.is_empty() {
+            Vec::new()
+        } else {
+            // the weeks of the next 7 days, in any timezone, are within this range
+            let utc = datetime_as_utc(datetime);
+            sessions::table
+                .filter(sessions::teacher_id.eq_any(capped_teacher_ids))
+                .filter(sessions::status.ne(SessionStatus::CancelledByStudent))
+                .filter(sessions::status.ne(SessionStatus::CancelledByMentor))
+                .filter(sessions::start_at.ge(utc - chrono::Duration::days(9)))
+                .filter(sessions::start_at.le(utc + chrono::Duration::days(17)))
+                .get_results(&mut self.lock_connection().await?)
+                .await?
+        };
+
+        Ok(availabilities
+            .into_iter()
+            .filter(|(avail, teacher)| {
+                let start_at = avail.first_start_after(teacher.timezone, datetime);
+                avail.is_within_weekly_cap(teacher, &capped_sessions, &start_at)
+            })
+            .map(|(avail, _)| avail)
+            .collect())
+    }
+}
+
+/// SQL condition which holds when the session being queried has as many students
+/// as its availability's capacity (no seats left).
+fn session_is_full() -> SqlLiteral<Bool> {
+    sql::<Bool>(
+        "(SELECT COUNT(*) FROM session_students \
+        WHERE session_students.session_id = sessions.id) >= availability.capacity",
+    )
+}
+
 #[async_trait]
 impl Repository for AvailabilityRepository {
     type Table = availability::table;
@@ -148,8 +331,8 @@ impl Repository for AvailabilityRepository {
 
     const TABLE: Self::Table = availability::table;
 
-    fn get_connection_pool(&self) -> Arc<Pool<AsyncPgConnection>> {
-        Arc::clone(&self.pool)
+    fn get_connection_source(&self) -> &ConnectionSource {
+        &self.connection
     }
 
     /// Gets an Availability by its ID.
@@ -186,3 +369,548 @@ impl UpdatableRepository for AvailabilityRepository {
         repo_update!(self; old_avail => new_avail)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::super::tests::init_db;
+    use crate::{
+        error::{Error, Result},
+        model::{
+            BookingPolicy, DiscordId, NewAvailability, NewProgram, NewSession, NewTeacher, NewUser,
+            ProgramStatus, SessionStatus, Weekday,
+        },
+        util::BRAZIL_TIMEZONE,
+    };
+
+    #[tokio::test]
+    async fn test_availability_expire_by_teacher_and_ids() -> Result<()> {
+        let db = init_db();
+        let teacher_repo = db.teacher_repository();
+        let availability_repo = db.availability_repository();
+
+        let teacher = teacher_repo
+            .insert(&NewTeacher {
+                name: "John Doe".to_string(),
+                email: "avail@mentor.com".to_string(),
+                specialty: "Math".to_string(),
+                applied_at: None,
+                company: None,
+                company_role: None,
+                bio: None,
+                course_info: None,
+                whatsapp: None,
+                linkedin: None,
+                comment_general: None,
+                comment_experience: None,
+                discord_id: None,
+                program_id: None,
+                timezone: Default::default(),
+                max_sessions_per_week: None,
+                locale: None,
+            })
+            .await?;
+
+        let new_availability = NewAvailability {
+            teacher_id: teacher.id,
+            weekday: Weekday::Monday,
+            time_start: chrono::NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+            expired: false,
+            duration: 1,
+            valid_from: None,
+            valid_until: None,
+            program_id: None,
+            capacity: 1,
+        };
+        let first = availability_repo.insert(&new_availability).await?;
+        let second = availability_repo
+            .insert(&NewAvailability {
+                weekday: Weekday::Tuesday,
+                ..new_availability
+            })
+            .await?;
+
+        // availabilities of other teachers are left untouched
+        assert_eq!(
+            0,
+            availability_repo
+                .expire_by_teacher_and_ids(teacher.id + 1, &[first.id])
+                .await?
+        );
+        assert_eq!(
+            1,
+            availability_repo
+                .expire_by_teacher_and_ids(teacher.id, &[first.id])
+                .await?
+        );
+
+        let availabilities = availability_repo.find_by_teacher(&teacher).await?;
+        assert_eq!(2, availabilities.len());
+        for availability in availabilities {
+            assert_eq!(availability.id == first.id, availability.expired);
+            assert!(availability.id == first.id || availability.id == second.id);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_availability_find_nontaken_respects_validity_windows() -> Result<()> {
+        let db = init_db();
+        let teacher_repo = db.teacher_repository();
+        let availability_repo = db.availability_repository();
+        let program = db
+            .program_repository()
+            .insert(&NewProgram {
+                name: "Window Program".to_string(),
+                start_date: chrono::NaiveDate::from_ymd_opt(2023, 9, 1).unwrap(),
+                end_date: chrono::NaiveDate::from_ymd_opt(2023, 9, 30).unwrap(),
+                status: ProgramStatus::Active,
+                enrollment_open: true,
+            })
+            .await?;
+
+        let teacher = teacher_repo
+            .insert(&NewTeacher {
+                name: "John Doe".to_string(),
+                email: "window@mentor.com".to_string(),
+                specialty: "Math".to_string(),
+                applied_at: None,
+                company: None,
+                company_role: None,
+                bio: None,
+                course_info: None,
+                whatsapp: None,
+                linkedin: None,
+                comment_general: None,
+                comment_experience: None,
+                discord_id: None,
+                program_id: None,
+                timezone: Default::default(),
+                max_sessions_per_week: None,
+                locale: None,
+            })
+            .await?;
+
+        let date = |day| chrono::NaiveDate::from_ymd_opt(2023, 9, day);
+        let new_availability = NewAvailability {
+            teacher_id: teacher.id,
+            weekday: Weekday::Wednesday,
+            time_start: chrono::NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+            expired: false,
+            duration: 1,
+            valid_from: None,
+            valid_until: None,
+            program_id: Some(program.id),
+            capacity: 1,
+        };
+
+        // no window => always valid
+        let unbounded = availability_repo.insert(&new_availability).await?;
+        // occurs on 13/09 => valid
+        let within = availability_repo
+            .insert(&NewAvailability {
+                valid_from: date(11),
+                valid_until: date(16),
+                ..new_availability.clone()
+            })
+            .await?;
+        // window intersects with the week, but not on wednesday (13/09)
+        availability_repo
+            .insert(&NewAvailability {
+                valid_from: date(14),
+                valid_until: date(30),
+                ..new_availability.clone()
+            })
+            .await?;
+        // window ended before the week
+        availability_repo
+            .insert(&NewAvailability {
+                valid_until: date(10),
+                ..new_availability
+            })
+            .await?;
+
+        // monday, 11/09/2023
+        let monday = BRAZIL_TIMEZONE
+            .with_ymd_and_hms(2023, 9, 11, 0, 0, 0)
+            .unwrap();
+        let mut found_ids = availability_repo
+            .find_nontaken_within_a_week_of_date(monday)
+            .await?
+            .into_iter()
+            .map(|avail| avail.id)
+            .collect::<Vec<_>>();
+        found_ids.sort_unstable();
+
+        assert_eq!(vec![unbounded.id, within.id], found_ids);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_availability_find_nontaken_only_includes_open_programs() -> Result<()> {
+        let db = init_db();
+        let teacher_repo = db.teacher_repository();
+        let availability_repo = db.availability_repository();
+        let program_repo = db.program_repository();
+
+        let teacher = teacher_repo
+            .insert(&NewTeacher {
+                name: "John Doe".to_string(),
+                email: "programs@mentor.com".to_string(),
+                specialty: "Math".to_string(),
+                applied_at: None,
+                company: None,
+                company_role: None,
+                bio: None,
+                course_info: None,
+                whatsapp: None,
+                linkedin: None,
+                comment_general: None,
+                comment_experience: None,
+                discord_id: None,
+                program_id: None,
+                timezone: Default::default(),
+                max_sessions_per_week: None,
+                locale: None,
+            })
+            .await?;
+
+        let date = |month, day| chrono::NaiveDate::from_ymd_opt(2023, month, day).unwrap();
+        let new_program = NewProgram {
+            name: "Open Program".to_string(),
+            start_date: date(9, 1),
+            end_date: date(9, 30),
+            status: ProgramStatus::Active,
+            enrollment_open: true,
+        };
+        let open = program_repo.insert(&new_program).await?;
+        let closed = program_repo
+            .insert(&NewProgram {
+                name: "Closed Program".to_string(),
+                enrollment_open: false,
+                ..new_program.clone()
+            })
+            .await?;
+        let archived = program_repo
+            .insert(&NewProgram {
+                name: "Archived Program".to_string(),
+                status: ProgramStatus::Archived,
+                ..new_program.clone()
+            })
+            .await?;
+        // ends on tuesday, 12/09, before the availability's wednesday
+        let ending = program_repo
+            .insert(&NewProgram {
+                name: "Ending Program".to_string(),
+                end_date: date(9, 12),
+                ..new_program
+            })
+            .await?;
+
+        let new_availability = NewAvailability {
+            teacher_id: teacher.id,
+            weekday: Weekday::Wednesday,
+            time_start: chrono::NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+            expired: false,
+            duration: 1,
+            valid_from: None,
+            valid_until: None,
+            program_id: Some(open.id),
+            capacity: 1,
+        };
+        let in_open_program = availability_repo.insert(&new_availability).await?;
+        for program_id in [None, Some(closed.id), Some(archived.id), Some(ending.id)] {
+            availability_repo
+                .insert(&NewAvailability {
+                    program_id,
+                    ..new_availability.clone()
+                })
+                .await?;
+        }
+
+        // monday, 11/09/2023
+        let monday = BRAZIL_TIMEZONE
+            .with_ymd_and_hms(2023, 9, 11, 0, 0, 0)
+            .unwrap();
+        let found_ids = availability_repo
+            .find_nontaken_within_a_week_of_date(monday)
+            .await?
+            .into_iter()
+            .map(|avail| avail.id)
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec![in_open_program.id], found_ids);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_availability_with_seats_left_is_not_taken() -> Result<()> {
+        let db = init_db();
+        let availability_repo = db.availability_repository();
+        let session_repo = db.session_repository();
+
+        let teacher = db
+            .teacher_repository()
+            .insert(&NewTeacher {
+                name: "John Doe".to_string(),
+                email: "capacity@mentor.com".to_string(),
+                specialty: "Math".to_string(),
+                applied_at: None,
+                company: None,
+                company_role: None,
+                bio: None,
+                course_info: None,
+                whatsapp: None,
+                linkedin: None,
+                comment_general: None,
+                comment_experience: None,
+                discord_id: None,
+                program_id: None,
+                timezone: Default::default(),
+                max_sessions_per_week: None,
+                locale: None,
+            })
+            .await?;
+        let program = db
+            .program_repository()
+            .insert(&NewProgram {
+                name: "Capacity Program".to_string(),
+                start_date: chrono::NaiveDate::from_ymd_opt(2023, 9, 1).unwrap(),
+                end_date: chrono::NaiveDate::from_ymd_opt(2023, 9, 30).unwrap(),
+                status: ProgramStatus::Active,
+                enrollment_open: true,
+            })
+            .await?;
+        let availability = availability_repo
+            .insert(&NewAvailability {
+                teacher_id: teacher.id,
+                weekday: Weekday::Wednesday,
+                time_start: chrono::NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+                expired: false,
+                duration: 1,
+                valid_from: None,
+                valid_until: None,
+                program_id: Some(program.id),
+                capacity: 2,
+            })
+            .await?;
+
+        let new_user = NewUser {
+            discord_id: DiscordId(201),
+            name: "First Student".to_string(),
+            email: "first@student.com".to_string(),
+            bio: None,
+            timezone: Default::default(),
+            locale: None,
+        };
+        let first = db.user_repository().insert(&new_user).await?;
+        let second = db
+            .user_repository()
+            .insert(&NewUser {
+                discord_id: DiscordId(202),
+                name: "Second Student".to_string(),
+                email: "second@student.com".to_string(),
+                ..new_user
+            })
+            .await?;
+
+        // monday, 11/09/2023; session on wednesday, 13/09/2023
+        let monday = BRAZIL_TIMEZONE
+            .with_ymd_and_hms(2023, 9, 11, 0, 0, 0)
+            .unwrap();
+        let start_at = chrono::Utc.with_ymd_and_hms(2023, 9, 13, 13, 0, 0).unwrap();
+        let session = session_repo
+            .insert(&NewSession {
+                teacher_id: teacher.id,
+                student_id: first.discord_id,
+                availability_id: availability.id,
+                summary: None,
+                notified: false,
+                meet_id: None,
+                calendar_event_id: None,
+                start_at,
+                end_at: start_at + chrono::Duration::minutes(40),
+                status: SessionStatus::Scheduled,
+                cancelled_at: None,
+                cancel_reason: None,
+            })
+            .await?;
+
+        // one seat left
+        assert!(
+            !availability_repo
+                .check_is_taken_at(availability.id, &monday)
+                .await?
+        );
+        assert_eq!(
+            vec![availability.clone()],
+            availability_repo
+                .find_nontaken_within_a_week_of_date(monday)
+                .await?
+        );
+
+        session_repo
+            .add_student(session.id, second.discord_id)
+            .await?;
+
+        // full
+        assert!(
+            availability_repo
+                .check_is_taken_at(availability.id, &monday)
+                .await?
+        );
+        assert!(availability_repo
+            .find_nontaken_within_a_week_of_date(monday)
+            .await?
+            .is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_availability_hidden_once_teacher_reaches_weekly_cap() -> Result<()> {
+        let db = init_db();
+        let availability_repo = db.availability_repository();
+        let session_repo = db.session_repository();
+
+        let teacher = db
+            .teacher_repository()
+            .insert(&NewTeacher {
+                name: "John Doe".to_string(),
+                email: "weeklycap@mentor.com".to_string(),
+                specialty: "Math".to_string(),
+                applied_at: None,
+                company: None,
+                company_role: None,
+                bio: None,
+                course_info: None,
+                whatsapp: None,
+                linkedin: None,
+                comment_general: None,
+                comment_experience: None,
+                discord_id: None,
+                program_id: None,
+                timezone: Default::default(),
+                max_sessions_per_week: Some(1),
+                locale: None,
+            })
+            .await?;
+        let program = db
+            .program_repository()
+            .insert(&NewProgram {
+                name: "Weekly Cap Program".to_string(),
+                start_date: chrono::NaiveDate::from_ymd_opt(2023, 9, 1).unwrap(),
+                end_date: chrono::NaiveDate::from_ymd_opt(2023, 9, 30).unwrap(),
+                status: ProgramStatus::Active,
+                enrollment_open: true,
+            })
+            .await?;
+        let new_availability = NewAvailability {
+            teacher_id: teacher.id,
+            weekday: Weekday::Wednesday,
+            time_start: chrono::NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+            expired: false,
+            duration: 1,
+            valid_from: None,
+            valid_until: None,
+            program_id: Some(program.id),
+            capacity: 2,
+        };
+        let wednesday_avail = availability_repo.insert(&new_availability).await?;
+        let thursday_avail = availability_repo
+            .insert(&NewAvailability {
+                weekday: Weekday::Thursday,
+                capacity: 1,
+                ..new_availability
+            })
+            .await?;
+
+        let student = db
+            .user_repository()
+            .insert(&NewUser {
+                discord_id: DiscordId(203),
+                name: "Student".to_string(),
+                email: "weeklycap@student.com".to_string(),
+                bio: None,
+                timezone: Default::default(),
+                locale: None,
+            })
+            .await?;
+
+        // monday, 11/09/2023; sessions on wednesday (13/09) and thursday (14/09)
+        let monday = BRAZIL_TIMEZONE
+            .with_ymd_and_hms(2023, 9, 11, 0, 0, 0)
+            .unwrap();
+        let thursday = BRAZIL_TIMEZONE
+            .with_ymd_and_hms(2023, 9, 14, 0, 0, 0)
+            .unwrap();
+        let mut availabilities = availability_repo
+            .find_nontaken_within_a_week_of_date(monday)
+            .await?;
+        availabilities.sort_by_key(|avail| avail.id);
+        assert_eq!(
+            vec![wednesday_avail.clone(), thursday_avail.clone()],
+            availabilities
+        );
+
+        let start_at = chrono::Utc.with_ymd_and_hms(2023, 9, 13, 13, 0, 0).unwrap();
+        let new_session = NewSession {
+            teacher_id: teacher.id,
+            student_id: student.discord_id,
+            availability_id: wednesday_avail.id,
+            summary: None,
+            notified: false,
+            meet_id: None,
+            calendar_event_id: None,
+            start_at,
+            end_at: start_at + chrono::Duration::minutes(40),
+            status: SessionStatus::Scheduled,
+            cancelled_at: None,
+            cancel_reason: None,
+        };
+        session_repo
+            .book(&new_session, &BookingPolicy::UNLIMITED)
+            .await?;
+
+        // the cap was reached in this week, but the group session still has seats left
+        assert_eq!(
+            vec![wednesday_avail.clone()],
+            availability_repo
+                .find_nontaken_within_a_week_of_date(monday)
+                .await?
+        );
+        assert!(availability_repo
+            .find_nontaken_at_date(thursday)
+            .await?
+            .is_empty());
+
+        let thursday_start_at = start_at + chrono::Duration::days(1);
+        assert!(matches!(
+            session_repo
+                .book(
+                    &NewSession {
+                        availability_id: thursday_avail.id,
+                        start_at: thursday_start_at,
+                        end_at: thursday_start_at + chrono::Duration::minutes(40),
+                        ..new_session
+                    },
+                    &BookingPolicy::UNLIMITED
+                )
+                .await,
+            Err(Error::SessionTimeTaken)
+        ));
+
+        // the next week isn't affected
+        let mut availabilities = availability_repo
+            .find_nontaken_within_a_week_of_date(monday + chrono::Duration::days(7))
+            .await?;
+        availabilities.sort_by_key(|avail| avail.id);
+        assert_eq!(vec![wednesday_avail, thursday_avail], availabilities);
+
+        Ok(())
+    }
+}
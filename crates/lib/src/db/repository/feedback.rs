@@ -0,0 +1,270 @@
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+use super::{
+    super::{
+        connection::ConnectionSource,
+        schema::{session_feedback, sessions, teachers},
+        store::FeedbackStore,
+    },
+    repo_find_all, repo_get, repo_insert, repo_remove, Repository,
+};
+use crate::{
+    error::Result,
+    model::{DiscordId, NewSessionFeedback, SessionFeedback, Teacher, TeacherRating},
+};
+
+/// Manages SessionFeedback instances.
+#[derive(Clone)]
+pub struct FeedbackRepository {
+    connection: ConnectionSource,
+}
+
+impl FeedbackRepository {
+    /// Creates a new FeedbackRepository operating with the given
+    /// connection source (a connection pool or a transaction).
+    pub fn new(connection: &ConnectionSource) -> Self {
+        Self {
+            connection: connection.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl FeedbackStore for FeedbackRepository {
+    async fn get(&self, session_id: i64, author_id: DiscordId) -> Result<Option<SessionFeedback>> {
+        Repository::get(self, (session_id, author_id)).await
+    }
+
+    async fn submit(&self, feedback: &NewSessionFeedback) -> Result<SessionFeedback> {
+        diesel::insert_into(session_feedback::table)
+            .values(feedback)
+            .on_conflict((session_feedback::session_id, session_feedback::author_id))
+            .do_update()
+            .set((
+                feedback,
+                session_feedback::submitted_at.eq(chrono::Utc::now()),
+            ))
+            .get_result(&mut self.lock_connection().await?)
+            .await
+            .map_err(From::from)
+    }
+
+    async fn find_by_session(&self, session_id: i64) -> Result<Vec<SessionFeedback>> {
+        session_feedback::table
+            .filter(session_feedback::session_id.eq(session_id))
+            .order_by(session_feedback::submitted_at.asc())
+            .get_results(&mut self.lock_connection().await?)
+            .await
+            .map_err(From::from)
+    }
+
+    async fn find_teacher_ratings(&self) -> Result<Vec<(Teacher, TeacherRating)>> {
+        let rated_teachers: Vec<(Teacher, i16)> = session_feedback::table
+            .inner_join(sessions::table.inner_join(teachers::table))
+            .filter(session_feedback::from_mentor.eq(false))
+            .select((teachers::all_columns, session_feedback::rating))
+            .get_results(&mut self.lock_connection().await?)
+            .await?;
+
+        let mut ratings_by_teacher = BTreeMap::<i64, (Teacher, Vec<i16>)>::new();
+        for (teacher, rating) in rated_teachers {
+            ratings_by_teacher
+                .entry(teacher.id)
+                .or_insert_with(|| (teacher, Vec::new()))
+                .1
+                .push(rating);
+        }
+
+        let mut teacher_ratings = ratings_by_teacher
+            .into_values()
+            .filter_map(|(teacher, ratings)| {
+                TeacherRating::aggregate(teacher.id, ratings).map(|rating| (teacher, rating))
+            })
+            .collect::<Vec<_>>();
+
+        teacher_ratings.sort_by(|(a, _), (b, _)| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+        Ok(teacher_ratings)
+    }
+}
+
+#[async_trait]
+impl Repository for FeedbackRepository {
+    type Table = session_feedback::table;
+
+    type Entity = SessionFeedback;
+
+    type NewEntity = NewSessionFeedback;
+
+    type PrimaryKey = (i64, DiscordId);
+
+    const TABLE: Self::Table = session_feedback::table;
+
+    fn get_connection_source(&self) -> &ConnectionSource {
+        &self.connection
+    }
+
+    /// Gets a SessionFeedback by its session's ID and its author's Discord ID.
+    async fn get(
+        &self,
+        (session_id, author_id): (i64, DiscordId),
+    ) -> Result<Option<SessionFeedback>> {
+        repo_get!(self, session_feedback::table; (session_id, author_id))
+    }
+
+    async fn insert(&self, feedback: &NewSessionFeedback) -> Result<SessionFeedback> {
+        repo_insert!(self, session_feedback::table; feedback)
+    }
+
+    async fn remove(&self, feedback: &SessionFeedback) -> Result<usize> {
+        repo_remove!(self; feedback)
+    }
+
+    async fn find_all(&self) -> Result<Vec<SessionFeedback>> {
+        repo_find_all!(self, session_feedback::table, session_feedback::table)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::init_db;
+    use crate::{
+        error::Result,
+        model::{
+            DiscordId, NewAvailability, NewSession, NewSessionFeedback, NewTeacher, NewUser,
+            SessionStatus, Weekday,
+        },
+    };
+
+    #[tokio::test]
+    async fn test_feedback_submit_and_teacher_ratings() -> Result<()> {
+        let db = init_db();
+        let feedback_repo = db.feedback_repository();
+
+        let teacher = db
+            .teacher_repository()
+            .insert(&NewTeacher {
+                name: "John Doe".to_string(),
+                email: "feedback@mentor.com".to_string(),
+                specialty: "Math".to_string(),
+                applied_at: None,
+                company: None,
+                company_role: None,
+                bio: None,
+                course_info: None,
+                whatsapp: None,
+                linkedin: None,
+                comment_general: None,
+                comment_experience: None,
+                discord_id: Some(DiscordId(131)),
+                program_id: None,
+                timezone: Default::default(),
+                max_sessions_per_week: None,
+                locale: None,
+            })
+            .await?;
+
+        let availability = db
+            .availability_repository()
+            .insert(&NewAvailability {
+                teacher_id: teacher.id,
+                weekday: Weekday::Monday,
+                time_start: chrono::NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+                expired: false,
+                duration: 1,
+                valid_from: None,
+                valid_until: None,
+                program_id: None,
+                capacity: 1,
+            })
+            .await?;
+
+        let student = db
+            .user_repository()
+            .insert(&NewUser {
+                discord_id: DiscordId(132),
+                name: "Rating Student".to_string(),
+                email: "rating@student.com".to_string(),
+                bio: None,
+                timezone: Default::default(),
+                locale: None,
+            })
+            .await?;
+
+        let start_at = chrono::Utc::now() - chrono::Duration::days(1);
+        let session = db
+            .session_repository()
+            .insert(&NewSession {
+                teacher_id: teacher.id,
+                student_id: student.discord_id,
+                availability_id: availability.id,
+                summary: None,
+                notified: true,
+                meet_id: None,
+                calendar_event_id: None,
+                start_at,
+                end_at: start_at + chrono::Duration::minutes(40),
+                status: SessionStatus::Scheduled,
+                cancelled_at: None,
+                cancel_reason: None,
+            })
+            .await?;
+
+        let new_feedback = NewSessionFeedback {
+            session_id: session.id,
+            author_id: student.discord_id,
+            from_mentor: false,
+            rating: 3,
+            comment: None,
+        };
+        feedback_repo.submit(&new_feedback).await?;
+
+        // submitting again replaces the previous feedback
+        let feedback = feedback_repo
+            .submit(&NewSessionFeedback {
+                rating: 5,
+                comment: Some("Great session".to_string()),
+                ..new_feedback
+            })
+            .await?;
+        assert_eq!(5, feedback.rating);
+        assert_eq!(
+            Some(&feedback),
+            feedback_repo
+                .get(session.id, student.discord_id)
+                .await?
+                .as_ref()
+        );
+
+        // the mentor's feedback doesn't count towards their own rating
+        feedback_repo
+            .submit(&NewSessionFeedback {
+                session_id: session.id,
+                author_id: DiscordId(131),
+                from_mentor: true,
+                rating: 1,
+                comment: None,
+            })
+            .await?;
+        assert_eq!(2, feedback_repo.find_by_session(session.id).await?.len());
+
+        let (rated_teacher, rating) = feedback_repo
+            .find_teacher_ratings()
+            .await?
+            .into_iter()
+            .find(|(rated_teacher, _)| rated_teacher.id == teacher.id)
+            .unwrap();
+        assert_eq!(teacher, rated_teacher);
+        assert_eq!(1, rating.rating_count);
+        assert_eq!(5.0, rating.average_rating);
+
+        // feedback is removed along with its session
+        db.session_repository().remove(&session).await?;
+        assert!(feedback_repo.find_by_session(session.id).await?.is_empty());
+
+        Ok(())
+    }
+}
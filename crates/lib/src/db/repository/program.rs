@@ -0,0 +1,264 @@
+use async_trait::async_trait;
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+use super::{
+    super::{
+        connection::ConnectionSource,
+        schema::{availability, programs, teachers},
+        store::ProgramStore,
+    },
+    repo_find_all, repo_find_by_first, repo_get, repo_insert, repo_remove, repo_update,
+    repo_upsert, Repository, UpdatableRepository,
+};
+use crate::{
+    error::Result,
+    model::{NewProgram, PartialProgram, Program, Teacher},
+};
+
+/// Manages Program instances.
+#[derive(Clone)]
+pub struct ProgramRepository {
+    connection: ConnectionSource,
+}
+
+impl ProgramRepository {
+    /// Creates a new ProgramRepository operating with the given
+    /// connection source (a connection pool or a transaction).
+    pub fn new(connection: &ConnectionSource) -> Self {
+        Self {
+            connection: connection.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl ProgramStore for ProgramRepository {
+    async fn get(&self, id: i64) -> Result<Option<Program>> {
+        Repository::get(self, id).await
+    }
+
+    async fn insert(&self, program: &NewProgram) -> Result<Program> {
+        Repository::insert(self, program).await
+    }
+
+    async fn remove(&self, program: &Program) -> Result<usize> {
+        Repository::remove(self, program).await
+    }
+
+    async fn find_all(&self) -> Result<Vec<Program>> {
+        Repository::find_all(self).await
+    }
+
+    async fn upsert(&self, program: &NewProgram) -> Result<Program> {
+        UpdatableRepository::upsert(self, program).await
+    }
+
+    async fn update(&self, old_program: &Program, new_program: PartialProgram) -> Result<Program> {
+        UpdatableRepository::update(self, old_program, new_program).await
+    }
+
+    async fn find_by_name(&self, name: &str) -> Result<Option<Program>> {
+        repo_find_by_first!(
+            self, programs::table;
+
+            programs::name.eq(name)
+        )
+    }
+
+    async fn attach_teacher(&self, program: &Program, teacher: &Teacher) -> Result<usize> {
+        let mut connection = self.lock_connection().await?;
+
+        diesel::update(teacher)
+            .set(teachers::program_id.eq(program.id))
+            .execute(&mut connection)
+            .await?;
+
+        diesel::update(availability::table)
+            .filter(availability::teacher_id.eq(teacher.id))
+            .filter(availability::expired.eq(false))
+            .set(availability::program_id.eq(program.id))
+            .execute(&mut connection)
+            .await
+            .map_err(From::from)
+    }
+}
+
+#[async_trait]
+impl Repository for ProgramRepository {
+    type Table = programs::table;
+
+    type Entity = Program;
+
+    type NewEntity = NewProgram;
+
+    type PrimaryKey = i64;
+
+    const TABLE: Self::Table = programs::table;
+
+    fn get_connection_source(&self) -> &ConnectionSource {
+        &self.connection
+    }
+
+    /// Gets a Program by its ID.
+    async fn get(&self, id: i64) -> Result<Option<Program>> {
+        repo_get!(self, programs::table; id)
+    }
+
+    async fn insert(&self, program: &NewProgram) -> Result<Program> {
+        repo_insert!(self, programs::table; program)
+    }
+
+    async fn remove(&self, program: &Program) -> Result<usize> {
+        repo_remove!(self; program)
+    }
+
+    async fn find_all(&self) -> Result<Vec<Program>> {
+        repo_find_all!(self, programs::table, programs::table; @order_by: programs::start_date.desc())
+    }
+}
+
+#[async_trait]
+impl UpdatableRepository for ProgramRepository {
+    type PartialEntity = PartialProgram;
+
+    async fn upsert(&self, program: &NewProgram) -> Result<Program> {
+        repo_upsert!(self, programs::table; /*conflict_columns=*/programs::id; program)
+    }
+
+    async fn update(&self, old_program: &Program, new_program: PartialProgram) -> Result<Program> {
+        repo_update!(self; old_program => new_program)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::init_db;
+    use crate::{
+        error::Result,
+        model::{NewAvailability, NewProgram, NewTeacher, PartialProgram, ProgramStatus, Weekday},
+    };
+
+    #[tokio::test]
+    async fn test_program_open_close_and_archive() -> Result<()> {
+        let db = init_db();
+        let program_repo = db.program_repository();
+
+        let program = program_repo
+            .insert(&NewProgram {
+                name: "Lifecycle Program".to_string(),
+                start_date: chrono::NaiveDate::from_ymd_opt(2023, 8, 1).unwrap(),
+                end_date: chrono::NaiveDate::from_ymd_opt(2023, 11, 30).unwrap(),
+                status: ProgramStatus::Active,
+                enrollment_open: false,
+            })
+            .await?;
+        assert!(!program.is_open());
+        assert_eq!(
+            Some(&program),
+            program_repo
+                .find_by_name("Lifecycle Program")
+                .await?
+                .as_ref()
+        );
+
+        let program = program_repo.set_enrollment_open(&program, true).await?;
+        assert!(program.is_open());
+
+        let program = program_repo.archive(&program).await?;
+        assert_eq!(ProgramStatus::Archived, program.status);
+        assert!(!program.enrollment_open);
+        assert_eq!(
+            Some(program),
+            program_repo.find_by_name("Lifecycle Program").await?
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_program_attach_teacher_updates_non_expired_availabilities() -> Result<()> {
+        let db = init_db();
+        let program_repo = db.program_repository();
+        let teacher_repo = db.teacher_repository();
+        let availability_repo = db.availability_repository();
+
+        let program = program_repo
+            .insert(&NewProgram {
+                name: "Attach Program".to_string(),
+                start_date: chrono::NaiveDate::from_ymd_opt(2023, 8, 1).unwrap(),
+                end_date: chrono::NaiveDate::from_ymd_opt(2023, 11, 30).unwrap(),
+                status: ProgramStatus::Active,
+                enrollment_open: true,
+            })
+            .await?;
+
+        let teacher = teacher_repo
+            .insert(&NewTeacher {
+                name: "John Doe".to_string(),
+                email: "program@mentor.com".to_string(),
+                specialty: "Math".to_string(),
+                applied_at: None,
+                company: None,
+                company_role: None,
+                bio: None,
+                course_info: None,
+                whatsapp: None,
+                linkedin: None,
+                comment_general: None,
+                comment_experience: None,
+                discord_id: None,
+                program_id: None,
+                timezone: Default::default(),
+                max_sessions_per_week: None,
+                locale: None,
+            })
+            .await?;
+
+        let new_availability = NewAvailability {
+            teacher_id: teacher.id,
+            weekday: Weekday::Monday,
+            time_start: chrono::NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+            expired: false,
+            duration: 1,
+            valid_from: None,
+            valid_until: None,
+            program_id: None,
+            capacity: 1,
+        };
+        let active = availability_repo.insert(&new_availability).await?;
+        let expired = availability_repo
+            .insert(&NewAvailability {
+                expired: true,
+                ..new_availability
+            })
+            .await?;
+
+        assert_eq!(1, program_repo.attach_teacher(&program, &teacher).await?);
+
+        let teacher = teacher_repo.get(teacher.id).await?.unwrap();
+        assert_eq!(Some(program.id), teacher.program_id);
+        assert_eq!(
+            Some(program.id),
+            availability_repo.get(active.id).await?.unwrap().program_id
+        );
+        assert_eq!(
+            None,
+            availability_repo.get(expired.id).await?.unwrap().program_id
+        );
+
+        // ensure 'update' also works for programs
+        let program = program_repo
+            .update(
+                &program,
+                PartialProgram {
+                    name: Some("Renamed Program".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        assert_eq!("Renamed Program", program.name);
+
+        Ok(())
+    }
+}
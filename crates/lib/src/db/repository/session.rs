@@ -1,36 +1,108 @@
-use std::sync::Arc;
-
 use async_trait::async_trait;
-use diesel::{dsl::sql, ExpressionMethods, OptionalExtension, QueryDsl};
-use diesel_async::{pooled_connection::deadpool::Pool, AsyncPgConnection, RunQueryDsl};
+use diesel::{
+    dsl::{count_star, exists, not, sql},
+    result::DatabaseErrorKind,
+    sql_types::{BigInt, Bool, SmallInt, Timestamptz},
+    BoolExpressionMethods, ExpressionMethods, OptionalExtension, QueryDsl,
+};
+use diesel_async::{AsyncConnection, AsyncPgConnection, RunQueryDsl};
+use scoped_futures::ScopedFutureExt;
 
 use super::{
-    super::schema::{sessions, teachers, users},
-    repo_find_all, repo_find_by, repo_get, repo_insert, repo_remove, repo_update, repo_upsert,
-    Repository, UpdatableRepository,
+    super::{
+        connection::ConnectionSource,
+        pagination::{Page, PageRequest, SortOrder},
+        schema::{
+            availability, session_reminders, session_students, sessions, teachers, users,
+            waitlist_entries,
+        },
+        store::SessionStore,
+    },
+    repo_find_all, repo_find_by, repo_find_by_first, repo_get, repo_remove, repo_update,
+    repo_upsert, Repository, UpdatableRepository,
 };
 use crate::{
-    error::Result,
-    model::{DiscordId, NewSession, PartialSession, Session, Teacher, User},
+    error::{Error, Result},
+    model::{
+        BookedSession, BookingPolicy, BookingRefusal, DiscordId, NewSession, NewSessionReminder,
+        NewSessionStudent, PartialSession, Session, SessionStatus, Teacher, User, WaitlistStatus,
+    },
 };
 
+/// Name of the unique index ensuring at most one scheduled session claims each
+/// occurrence of an available time.
+const SCHEDULED_OCCURRENCE_INDEX: &str = "sessions_scheduled_occurrence_key";
+
+/// Optional criteria which sessions must match in a [paginated query].
+///
+/// [paginated query]: SessionStore::find_page
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SessionFilter {
+    /// Only sessions with this teacher.
+    pub teacher_id: Option<i64>,
+    /// Only sessions attended by this student.
+    pub student_id: Option<DiscordId>,
+    /// Only sessions starting at or after this moment.
+    pub starts_from: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only sessions starting at or before this moment.
+    pub starts_until: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only sessions with this status.
+    pub status: Option<SessionStatus>,
+}
+
+/// Identifies the position of a session in a [paginated query],
+/// which sorts sessions by their start time (and then by ID).
+///
+/// [paginated query]: SessionStore::find_page
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SessionCursor {
+    pub start_at: chrono::DateTime<chrono::Utc>,
+    pub id: i64,
+}
+
 /// Manages Session instances.
 #[derive(Clone)]
 pub struct SessionRepository {
-    pool: Arc<Pool<AsyncPgConnection>>,
+    connection: ConnectionSource,
 }
 
 impl SessionRepository {
     /// Creates a new SessionRepository operating with the given
-    /// connection pool.
-    pub fn new(pool: &Arc<Pool<AsyncPgConnection>>) -> Self {
+    /// connection source (a connection pool or a transaction).
+    pub fn new(connection: &ConnectionSource) -> Self {
         Self {
-            pool: Arc::clone(pool),
+            connection: connection.clone(),
         }
     }
+}
+
+#[async_trait]
+impl SessionStore for SessionRepository {
+    async fn get(&self, id: i64) -> Result<Option<Session>> {
+        Repository::get(self, id).await
+    }
+
+    async fn insert(&self, session: &NewSession) -> Result<Session> {
+        Repository::insert(self, session).await
+    }
+
+    async fn remove(&self, session: &Session) -> Result<usize> {
+        Repository::remove(self, session).await
+    }
 
-    /// Finds a Session and retrieves the associated teacher's Teacher object.
-    pub async fn get_with_teacher(&self, session_id: i64) -> Result<Option<(Session, Teacher)>> {
+    async fn find_all(&self) -> Result<Vec<Session>> {
+        Repository::find_all(self).await
+    }
+
+    async fn upsert(&self, session: &NewSession) -> Result<Session> {
+        UpdatableRepository::upsert(self, session).await
+    }
+
+    async fn update(&self, old_session: &Session, new_session: PartialSession) -> Result<Session> {
+        UpdatableRepository::update(self, old_session, new_session).await
+    }
+
+    async fn get_with_teacher(&self, session_id: i64) -> Result<Option<(Session, Teacher)>> {
         sessions::table
             .filter(sessions::id.eq(session_id))
             .inner_join(teachers::table)
@@ -40,9 +112,7 @@ impl SessionRepository {
             .map_err(From::from)
     }
 
-    /// Finds a Session and retrieves both the associated teacher's Teacher object
-    /// and the student's User object.
-    pub async fn get_with_participants(
+    async fn get_with_participants(
         &self,
         session_id: i64,
     ) -> Result<Option<(Session, Teacher, User)>> {
@@ -56,9 +126,7 @@ impl SessionRepository {
             .map_err(From::from)
     }
 
-    /// Searches for Sessions by a particular teacher (with a particular Discord ID),
-    /// in ascending 'start_at' order (starting earlier first).
-    pub async fn find_by_teacher(&self, teacher_id: i64) -> Result<Vec<Session>> {
+    async fn find_by_teacher(&self, teacher_id: i64) -> Result<Vec<Session>> {
         repo_find_by!(
             self, sessions::table;
             sessions::teacher_id.eq(teacher_id);
@@ -66,22 +134,441 @@ impl SessionRepository {
         )
     }
 
-    /// Searches for Sessions by a particular student (with a particular Discord ID),
-    /// in ascending 'start_at' order (starting earlier first).
-    pub async fn find_by_student(&self, student_id: DiscordId) -> Result<Vec<Session>> {
+    async fn find_upcoming_by_teacher_with_students(
+        &self,
+        teacher_id: i64,
+    ) -> Result<Vec<(Session, User)>> {
+        sessions::table
+            .inner_join(users::table)
+            .filter(sessions::teacher_id.eq(teacher_id))
+            .filter(sessions::status.eq(SessionStatus::Scheduled))
+            .filter(sessions::start_at.ge(chrono::Utc::now()))
+            .order_by(sessions::start_at.asc())
+            .get_results(&mut self.lock_connection().await?)
+            .await
+            .map_err(From::from)
+    }
+
+    async fn find_scheduled_by_teacher_starting_between(
+        &self,
+        teacher_id: i64,
+        starts_from: chrono::DateTime<chrono::Utc>,
+        starts_until: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<Session>> {
+        sessions::table
+            .filter(sessions::teacher_id.eq(teacher_id))
+            .filter(sessions::status.eq(SessionStatus::Scheduled))
+            .filter(sessions::start_at.ge(starts_from))
+            .filter(sessions::start_at.le(starts_until))
+            .order_by(sessions::start_at.asc())
+            .get_results(&mut self.lock_connection().await?)
+            .await
+            .map_err(From::from)
+    }
+
+    async fn find_page(
+        &self,
+        filter: &SessionFilter,
+        request: &PageRequest<SessionCursor>,
+    ) -> Result<Page<Session, SessionCursor>> {
+        let mut query = sessions::table.into_boxed();
+
+        if let Some(teacher_id) = filter.teacher_id {
+            query = query.filter(sessions::teacher_id.eq(teacher_id));
+        }
+        if let Some(student_id) = filter.student_id {
+            query = query.filter(
+                sessions::id.eq_any(
+                    session_students::table
+                        .filter(session_students::student_id.eq(student_id))
+                        .select(session_students::session_id),
+                ),
+            );
+        }
+        if let Some(starts_from) = filter.starts_from {
+            query = query.filter(sessions::start_at.ge(starts_from));
+        }
+        if let Some(starts_until) = filter.starts_until {
+            query = query.filter(sessions::start_at.le(starts_until));
+        }
+        if let Some(status) = filter.status {
+            query = query.filter(sessions::status.eq(status));
+        }
+
+        query = match (request.order, request.after) {
+            (SortOrder::Ascending, Some(after)) => query.filter(
+                sessions::start_at.gt(after.start_at).or(sessions::start_at
+                    .eq(after.start_at)
+                    .and(sessions::id.gt(after.id))),
+            ),
+            (SortOrder::Descending, Some(after)) => query.filter(
+                sessions::start_at.lt(after.start_at).or(sessions::start_at
+                    .eq(after.start_at)
+                    .and(sessions::id.lt(after.id))),
+            ),
+            (_, None) => query,
+        };
+
+        query = match request.order {
+            SortOrder::Ascending => query.order_by((sessions::start_at.asc(), sessions::id.asc())),
+            SortOrder::Descending => {
+                query.order_by((sessions::start_at.desc(), sessions::id.desc()))
+            }
+        };
+
+        let sessions = query
+            .limit(request.query_limit())
+            .get_results(&mut self.lock_connection().await?)
+            .await?;
+
+        Ok(Page::from_queried(
+            sessions,
+            request,
+            |session: &Session| SessionCursor {
+                start_at: session.start_at,
+                id: session.id,
+            },
+        ))
+    }
+
+    async fn find_by_program_with_participants(
+        &self,
+        program_id: i64,
+    ) -> Result<Vec<(Session, Teacher, User)>> {
+        sessions::table
+            .inner_join(availability::table)
+            .inner_join(teachers::table)
+            .inner_join(users::table)
+            .filter(availability::program_id.eq(program_id))
+            .select((
+                sessions::all_columns,
+                teachers::all_columns,
+                users::all_columns,
+            ))
+            .order_by(sessions::start_at.asc())
+            .get_results(&mut self.lock_connection().await?)
+            .await
+            .map_err(From::from)
+    }
+
+    async fn find_by_availability_starting_at(
+        &self,
+        availability_id: i64,
+        start_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Option<Session>> {
+        repo_find_by_first!(
+            self, sessions::table;
+            sessions::availability_id.eq(availability_id)
+            sessions::start_at.eq(start_at)
+            sessions::status.eq(SessionStatus::Scheduled)
+        )
+    }
+
+    async fn reschedule(
+        &self,
+        session: &Session,
+        policy: &BookingPolicy,
+        teacher_id: i64,
+        availability_id: i64,
+        start_at: chrono::DateTime<chrono::Utc>,
+        end_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Option<Session>> {
+        // moving a session neither adds to the student's active sessions
+        // nor is prevented by a no-show cooldown
+        let policy = BookingPolicy {
+            max_active_sessions: None,
+            no_show_cooldown_days: None,
+            ..*policy
+        };
+        let rescheduled = self
+            .lock_connection()
+            .await?
+            .transaction::<_, Error, _>(|connection| {
+                async move {
+                    check_teacher_time_free(
+                        connection,
+                        teacher_id,
+                        start_at,
+                        end_at,
+                        Some(session.id),
+                    )
+                    .await?;
+
+                    check_booking_policy(
+                        connection,
+                        &policy,
+                        session.student_id,
+                        teacher_id,
+                        availability_id,
+                        start_at,
+                        Some(session.id),
+                    )
+                    .await?;
+
+                    let rescheduled: Option<Session> = diesel::update(sessions::table)
+                        .filter(sessions::id.eq(session.id))
+                        .filter(sessions::status.eq(SessionStatus::Scheduled))
+                        .filter(
+                            // the new time must not be claimed by another scheduled session
+                            sql::<Bool>(
+                                "NOT EXISTS (SELECT 1 FROM sessions AS other WHERE other.id <> \
+                                sessions.id AND other.status = ",
+                            )
+                            .bind::<SmallInt, _>(SessionStatus::Scheduled)
+                            .sql(" AND other.availability_id = ")
+                            .bind::<BigInt, _>(availability_id)
+                            .sql(" AND other.start_at = ")
+                            .bind::<Timestamptz, _>(start_at)
+                            .sql(")"),
+                        )
+                        .set((
+                            sessions::teacher_id.eq(teacher_id),
+                            sessions::availability_id.eq(availability_id),
+                            sessions::start_at.eq(start_at),
+                            sessions::end_at.eq(end_at),
+                            sessions::notified.eq(false),
+                            sessions::feedback_requested.eq(false),
+                        ))
+                        .get_result(connection)
+                        .await
+                        .optional()
+                        .map_err(map_occurrence_conflict)?;
+
+                    if rescheduled.is_some() {
+                        diesel::delete(
+                            session_reminders::table
+                                .filter(session_reminders::session_id.eq(session.id)),
+                        )
+                        .execute(connection)
+                        .await?;
+                    }
+
+                    Ok(rescheduled)
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        match rescheduled {
+            // the new time was taken (or the teacher reached their weekly cap) concurrently
+            Err(Error::SessionTimeTaken) => Ok(None),
+            rescheduled => rescheduled,
+        }
+    }
+
+    async fn book(&self, session: &NewSession, policy: &BookingPolicy) -> Result<Session> {
+        self.lock_connection()
+            .await?
+            .transaction::<_, Error, _>(|connection| {
+                async move {
+                    check_teacher_time_free(
+                        connection,
+                        session.teacher_id,
+                        session.start_at,
+                        session.end_at,
+                        None,
+                    )
+                    .await?;
+
+                    check_booking_policy(
+                        connection,
+                        policy,
+                        session.student_id,
+                        session.teacher_id,
+                        session.availability_id,
+                        session.start_at,
+                        None,
+                    )
+                    .await?;
+
+                    check_not_held_for_waitlist(
+                        connection,
+                        session.student_id,
+                        session.availability_id,
+                        session.start_at,
+                    )
+                    .await?;
+
+                    let session: Session = diesel::insert_into(sessions::table)
+                        .values(session)
+                        .get_result(connection)
+                        .await
+                        .map_err(map_occurrence_conflict)?;
+
+                    diesel::insert_into(session_students::table)
+                        .values(NewSessionStudent {
+                            session_id: session.id,
+                            student_id: session.student_id,
+                        })
+                        .execute(connection)
+                        .await?;
+
+                    Ok(session)
+                }
+                .scope_boxed()
+            })
+            .await
+    }
+
+    async fn add_student(&self, session_id: i64, student_id: DiscordId) -> Result<usize> {
+        diesel::insert_into(session_students::table)
+            .values(NewSessionStudent {
+                session_id,
+                student_id,
+            })
+            .on_conflict_do_nothing()
+            .execute(&mut self.lock_connection().await?)
+            .await
+            .map_err(From::from)
+    }
+
+    async fn find_needing_feedback_request(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<(Session, Teacher)>> {
+        sessions::table
+            .inner_join(teachers::table)
+            .filter(sessions::status.eq_any([SessionStatus::Scheduled, SessionStatus::Completed]))
+            .filter(sessions::feedback_requested.eq(false))
+            .filter(sessions::end_at.le(now))
+            .order_by(sessions::start_at.asc())
+            .get_results(&mut self.lock_connection().await?)
+            .await
+            .map_err(From::from)
+    }
+
+    async fn register_feedback_request(&self, session_id: i64) -> Result<usize> {
+        diesel::update(sessions::table.find(session_id))
+            .filter(sessions::feedback_requested.eq(false))
+            .set(sessions::feedback_requested.eq(true))
+            .execute(&mut self.lock_connection().await?)
+            .await
+            .map_err(From::from)
+    }
+
+    async fn add_student_if_seats_left(
+        &self,
+        session_id: i64,
+        student_id: DiscordId,
+        capacity: i16,
+        policy: &BookingPolicy,
+    ) -> Result<bool> {
+        self.lock_connection()
+            .await?
+            .transaction::<_, Error, _>(|connection| {
+                async move {
+                    // serializes joins to the same session
+                    let session = sessions::table
+                        .find(session_id)
+                        .for_update()
+                        .first::<Session>(connection)
+                        .await?;
+
+                    let student_count: i64 = session_students::table
+                        .filter(session_students::session_id.eq(session_id))
+                        .select(count_star())
+                        .get_result(connection)
+                        .await?;
+
+                    if student_count >= capacity as i64 {
+                        return Ok(false);
+                    }
+
+                    check_booking_policy(
+                        connection,
+                        policy,
+                        student_id,
+                        session.teacher_id,
+                        session.availability_id,
+                        session.start_at,
+                        Some(session.id),
+                    )
+                    .await?;
+
+                    diesel::insert_into(session_students::table)
+                        .values(NewSessionStudent {
+                            session_id,
+                            student_id,
+                        })
+                        .on_conflict_do_nothing()
+                        .execute(connection)
+                        .await
+                        .map(|inserted| inserted > 0)
+                        .map_err(From::from)
+                }
+                .scope_boxed()
+            })
+            .await
+    }
+
+    async fn remove_student(&self, session_id: i64, student_id: DiscordId) -> Result<usize> {
+        diesel::delete(session_students::table.find((session_id, student_id)))
+            .execute(&mut self.lock_connection().await?)
+            .await
+            .map_err(From::from)
+    }
+
+    async fn find_students(&self, session_id: i64) -> Result<Vec<User>> {
+        session_students::table
+            .inner_join(users::table)
+            .filter(session_students::session_id.eq(session_id))
+            .order_by((
+                session_students::joined_at.asc(),
+                session_students::student_id.asc(),
+            ))
+            .select(users::all_columns)
+            .get_results(&mut self.lock_connection().await?)
+            .await
+            .map_err(From::from)
+    }
+
+    async fn find_needing_reminder(
+        &self,
+        offset_minutes: i32,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<(Session, Teacher)>> {
+        sessions::table
+            .inner_join(teachers::table)
+            .filter(sessions::status.eq(SessionStatus::Scheduled))
+            .filter(sessions::notified.eq(false))
+            .filter(sessions::start_at.gt(now))
+            .filter(sessions::start_at.le(now + chrono::Duration::minutes(offset_minutes as i64)))
+            .filter(not(exists(
+                session_reminders::table
+                    .filter(session_reminders::session_id.eq(sessions::id))
+                    .filter(session_reminders::offset_minutes.le(offset_minutes)),
+            )))
+            .order_by(sessions::start_at.asc())
+            .get_results(&mut self.lock_connection().await?)
+            .await
+            .map_err(From::from)
+    }
+
+    async fn register_reminder(&self, session_id: i64, offset_minutes: i32) -> Result<usize> {
+        diesel::insert_into(session_reminders::table)
+            .values(NewSessionReminder {
+                session_id,
+                offset_minutes,
+            })
+            .on_conflict_do_nothing()
+            .execute(&mut self.lock_connection().await?)
+            .await
+            .map_err(From::from)
+    }
+
+    async fn find_by_student(&self, student_id: DiscordId) -> Result<Vec<Session>> {
         repo_find_by!(
             self, sessions::table;
-            sessions::student_id.eq(student_id);
+            exists(
+                session_students::table
+                    .filter(session_students::session_id.eq(sessions::id))
+                    .filter(session_students::student_id.eq(student_id))
+            );
             @order_by: sessions::start_at.asc()
         )
     }
 
-    /// Searches for Sessions by a particular student (with a particular Discord ID),
-    /// in ascending 'start_at' order (starting earlier first), based on a partial
-    /// session ID input.
-    /// If 'only_active' is given, only active sessions (which haven't already started)
-    /// are displayed.
-    pub async fn find_student_autocomplete(
+    async fn find_student_autocomplete(
         &self,
         student_id: DiscordId,
         partial_id: &str,
@@ -94,30 +581,31 @@ impl SessionRepository {
 
         repo_find_by!(
             self, sessions::table;
-            // belongs to this student
-            sessions::student_id.eq(student_id)
+            // is attended by this student
+            exists(
+                session_students::table
+                    .filter(session_students::session_id.eq(sessions::id))
+                    .filter(session_students::student_id.eq(student_id))
+            )
 
             // starts with the given id string
             sql::<diesel::sql_types::Bool>("starts_with(CAST(id AS TEXT), ")
                 .bind::<diesel::sql_types::Text, _>(partial_id)
                 .sql(")"),
 
-            // if only_active was given, only display sessions which haven't yet begun
-            @filter_if (only_active) => sessions::start_at.ge(chrono::Utc::now());
+            // if only_active was given, only display scheduled sessions which haven't yet begun
+            @filter_if (only_active) => sessions::start_at
+                .ge(chrono::Utc::now())
+                .and(sessions::status.eq(SessionStatus::Scheduled));
 
             @order_by: sessions::start_at.asc()
         )
     }
 
-    /// Searches for Sessions starting after a certain point in time,
-    /// in ascending order (starting earlier first).
-    pub async fn find_starts_after<T: chrono::TimeZone>(
+    async fn find_starts_after(
         &self,
-        starts_after: chrono::DateTime<T>,
-    ) -> Result<Vec<Session>>
-    where
-        <T as chrono::TimeZone>::Offset: Send + Sync,
-    {
+        starts_after: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<Session>> {
         repo_find_by!(
             self, sessions::table;
             sessions::start_at.ge(starts_after);
@@ -125,21 +613,10 @@ impl SessionRepository {
         )
     }
 
-    /// Searches for all Sessions starting after the current point in time,
-    /// in ascending order (starting earlier first).
-    pub async fn find_will_start(&self) -> Result<Vec<Session>> {
-        self.find_starts_after(chrono::Utc::now()).await
-    }
-
-    /// Searches for all Sessions starting before a certain point in time,
-    /// in ascending order (starting earlier first).
-    pub async fn find_starts_before<T: chrono::TimeZone>(
+    async fn find_starts_before(
         &self,
-        starts_before: chrono::DateTime<T>,
-    ) -> Result<Vec<Session>>
-    where
-        <T as chrono::TimeZone>::Offset: Send + Sync,
-    {
+        starts_before: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<Session>> {
         repo_find_by!(
             self, sessions::table;
             sessions::start_at.le(starts_before);
@@ -148,6 +625,161 @@ impl SessionRepository {
     }
 }
 
+/// Within a transaction, locks the given teacher (serializing bookings with them) and
+/// checks that none of their other scheduled sessions (except for the excluded one, if any)
+/// overlaps with the given time, and that they didn't reach their weekly cap
+/// (see [`Teacher::is_at_weekly_cap`]) in its week.
+/// Fails with [`Error::SessionTimeTaken`] otherwise.
+async fn check_teacher_time_free(
+    connection: &mut AsyncPgConnection,
+    teacher_id: i64,
+    start_at: chrono::DateTime<chrono::Utc>,
+    end_at: chrono::DateTime<chrono::Utc>,
+    excluded_session_id: Option<i64>,
+) -> Result<()> {
+    // session IDs start at 1, so 0 excludes nothing
+    let excluded_session_id = excluded_session_id.unwrap_or(0);
+    let teacher = teachers::table
+        .find(teacher_id)
+        .for_update()
+        .first::<Teacher>(connection)
+        .await?;
+
+    let time_taken = diesel::select(exists(
+        sessions::table
+            .filter(sessions::teacher_id.eq(teacher_id))
+            .filter(sessions::id.ne(excluded_session_id))
+            .filter(sessions::status.eq(SessionStatus::Scheduled))
+            .filter(sessions::start_at.lt(end_at))
+            .filter(sessions::end_at.gt(start_at)),
+    ))
+    .get_result::<bool>(connection)
+    .await?;
+
+    if time_taken {
+        return Err(Error::SessionTimeTaken);
+    }
+
+    if teacher.max_sessions_per_week.is_some() {
+        let week = chrono::Duration::days(8);
+        let nearby_sessions: Vec<Session> = sessions::table
+            .filter(sessions::teacher_id.eq(teacher.id))
+            .filter(sessions::id.ne(excluded_session_id))
+            .filter(sessions::start_at.gt(start_at - week))
+            .filter(sessions::start_at.lt(start_at + week))
+            .get_results(connection)
+            .await?;
+
+        if teacher.is_at_weekly_cap(&nearby_sessions, &start_at) {
+            return Err(Error::SessionTimeTaken);
+        }
+    }
+
+    Ok(())
+}
+
+/// Within a transaction, locks the given student (serializing their bookings) and checks the
+/// policy's limits for them booking a session with the given teacher and availability,
+/// starting at the given moment, given the other sessions they attend (except for the
+/// excluded one, if any). Fails with [`Error::BookingRefused`] if a limit is exceeded.
+async fn check_booking_policy(
+    connection: &mut AsyncPgConnection,
+    policy: &BookingPolicy,
+    student_id: DiscordId,
+    teacher_id: i64,
+    availability_id: i64,
+    start_at: chrono::DateTime<chrono::Utc>,
+    excluded_session_id: Option<i64>,
+) -> Result<()> {
+    if *policy == BookingPolicy::UNLIMITED {
+        return Ok(());
+    }
+
+    users::table
+        .find(student_id)
+        .select(users::discord_id)
+        .for_update()
+        .first::<DiscordId>(connection)
+        .await?;
+
+    // session IDs start at 1, so 0 excludes nothing
+    let excluded_session_id = excluded_session_id.unwrap_or(0);
+    let student_sessions: Vec<(Session, Option<i64>)> = sessions::table
+        .inner_join(availability::table)
+        .filter(exists(
+            session_students::table
+                .filter(session_students::session_id.eq(sessions::id))
+                .filter(session_students::student_id.eq(student_id)),
+        ))
+        .filter(sessions::id.ne(excluded_session_id))
+        .select((sessions::all_columns, availability::program_id))
+        .get_results(connection)
+        .await?;
+
+    let program_id: Option<i64> = availability::table
+        .find(availability_id)
+        .select(availability::program_id)
+        .first(connection)
+        .await?;
+
+    let booked_sessions = student_sessions
+        .iter()
+        .map(|(session, program_id)| BookedSession {
+            session,
+            program_id: *program_id,
+        })
+        .collect::<Vec<_>>();
+
+    policy
+        .check(
+            &booked_sessions,
+            teacher_id,
+            program_id,
+            start_at,
+            chrono::Utc::now(),
+        )
+        .map_err(Error::BookingRefused)
+}
+
+/// Fails with [`Error::BookingRefused`] if the given occurrence of an available time was
+/// offered to a student in the waitlist other than the given one, whose offer didn't expire.
+async fn check_not_held_for_waitlist(
+    connection: &mut AsyncPgConnection,
+    student_id: DiscordId,
+    availability_id: i64,
+    start_at: chrono::DateTime<chrono::Utc>,
+) -> Result<()> {
+    let held = diesel::select(exists(
+        waitlist_entries::table
+            .filter(waitlist_entries::status.eq(WaitlistStatus::Offered))
+            .filter(waitlist_entries::offered_availability_id.eq(availability_id))
+            .filter(waitlist_entries::offered_start_at.eq(start_at))
+            .filter(waitlist_entries::offer_expires_at.gt(chrono::Utc::now()))
+            .filter(waitlist_entries::student_id.ne(student_id)),
+    ))
+    .get_result::<bool>(connection)
+    .await?;
+
+    if held {
+        Err(Error::BookingRefused(BookingRefusal::HeldForWaitlist))
+    } else {
+        Ok(())
+    }
+}
+
+/// Converts a Diesel error into [`Error::SessionTimeTaken`] if it was caused by two
+/// scheduled sessions claiming the same occurrence of an available time.
+fn map_occurrence_conflict(err: diesel::result::Error) -> Error {
+    match &err {
+        diesel::result::Error::DatabaseError(DatabaseErrorKind::UniqueViolation, info)
+            if info.constraint_name() == Some(SCHEDULED_OCCURRENCE_INDEX) =>
+        {
+            Error::SessionTimeTaken
+        }
+        _ => err.into(),
+    }
+}
+
 #[async_trait]
 impl Repository for SessionRepository {
     type Table = sessions::table;
@@ -160,8 +792,8 @@ impl Repository for SessionRepository {
 
     const TABLE: Self::Table = sessions::table;
 
-    fn get_connection_pool(&self) -> Arc<Pool<AsyncPgConnection>> {
-        Arc::clone(&self.pool)
+    fn get_connection_source(&self) -> &ConnectionSource {
+        &self.connection
     }
 
     /// Gets a Session by its ID.
@@ -169,8 +801,31 @@ impl Repository for SessionRepository {
         repo_get!(self, sessions::table; id)
     }
 
+    /// Inserts a Session, registering its student as attending it.
     async fn insert(&self, session: &NewSession) -> Result<Session> {
-        repo_insert!(self, sessions::table; session)
+        // the session is never left without its student, who is also in 'session_students'
+        self.lock_connection()
+            .await?
+            .transaction::<_, Error, _>(|connection| {
+                async move {
+                    let session: Session = diesel::insert_into(sessions::table)
+                        .values(session)
+                        .get_result(connection)
+                        .await?;
+
+                    diesel::insert_into(session_students::table)
+                        .values(NewSessionStudent {
+                            session_id: session.id,
+                            student_id: session.student_id,
+                        })
+                        .execute(connection)
+                        .await?;
+
+                    Ok(session)
+                }
+                .scope_boxed()
+            })
+            .await
     }
 
     async fn remove(&self, session: &Session) -> Result<usize> {
@@ -194,3 +849,1375 @@ impl UpdatableRepository for SessionRepository {
         repo_update!(self; old_session => new_session)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::super::tests::init_db;
+    use crate::{
+        db::{PageRequest, SessionFilter, SortOrder},
+        error::{Error, Result},
+        model::{
+            BookingPolicy, BookingRefusal, DiscordId, NewAvailability, NewSession, NewTeacher,
+            NewUser, NewWaitlistEntry, SessionStatus, Weekday,
+        },
+    };
+
+    #[tokio::test]
+    async fn test_session_add_and_remove_students() -> Result<()> {
+        let db = init_db();
+        let session_repo = db.session_repository();
+
+        let teacher = db
+            .teacher_repository()
+            .insert(&NewTeacher {
+                name: "John Doe".to_string(),
+                email: "group@mentor.com".to_string(),
+                specialty: "Math".to_string(),
+                applied_at: None,
+                company: None,
+                company_role: None,
+                bio: None,
+                course_info: None,
+                whatsapp: None,
+                linkedin: None,
+                comment_general: None,
+                comment_experience: None,
+                discord_id: None,
+                program_id: None,
+                timezone: Default::default(),
+                max_sessions_per_week: None,
+                locale: None,
+            })
+            .await?;
+
+        let availability = db
+            .availability_repository()
+            .insert(&NewAvailability {
+                teacher_id: teacher.id,
+                weekday: Weekday::Monday,
+                time_start: chrono::NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+                expired: false,
+                duration: 1,
+                valid_from: None,
+                valid_until: None,
+                program_id: None,
+                capacity: 3,
+            })
+            .await?;
+
+        let new_user = NewUser {
+            discord_id: DiscordId(101),
+            name: "First Student".to_string(),
+            email: "first@student.com".to_string(),
+            bio: None,
+            timezone: Default::default(),
+            locale: None,
+        };
+        let first = db.user_repository().insert(&new_user).await?;
+        let second = db
+            .user_repository()
+            .insert(&NewUser {
+                discord_id: DiscordId(102),
+                name: "Second Student".to_string(),
+                email: "second@student.com".to_string(),
+                ..new_user
+            })
+            .await?;
+
+        let start_at = chrono::Utc.with_ymd_and_hms(2023, 9, 11, 13, 0, 0).unwrap();
+        let session = session_repo
+            .insert(&NewSession {
+                teacher_id: teacher.id,
+                student_id: first.discord_id,
+                availability_id: availability.id,
+                summary: None,
+                notified: false,
+                meet_id: None,
+                calendar_event_id: None,
+                start_at,
+                end_at: start_at + chrono::Duration::minutes(40),
+                status: SessionStatus::Scheduled,
+                cancelled_at: None,
+                cancel_reason: None,
+            })
+            .await?;
+
+        // the student who booked the session attends it
+        assert_eq!(
+            vec![first.clone()],
+            session_repo.find_students(session.id).await?
+        );
+        assert_eq!(
+            Some(&session),
+            session_repo
+                .find_by_availability_starting_at(availability.id, start_at)
+                .await?
+                .as_ref()
+        );
+
+        assert_eq!(
+            1,
+            session_repo
+                .add_student(session.id, second.discord_id)
+                .await?
+        );
+        // adding twice does nothing
+        assert_eq!(
+            0,
+            session_repo
+                .add_student(session.id, second.discord_id)
+                .await?
+        );
+        assert_eq!(
+            vec![first.clone(), second.clone()],
+            session_repo.find_students(session.id).await?
+        );
+        assert_eq!(
+            vec![session.clone()],
+            session_repo.find_by_student(second.discord_id).await?
+        );
+
+        assert_eq!(
+            1,
+            session_repo
+                .remove_student(session.id, first.discord_id)
+                .await?
+        );
+        assert_eq!(vec![second], session_repo.find_students(session.id).await?);
+        assert!(session_repo
+            .find_by_student(first.discord_id)
+            .await?
+            .is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_session_cancel_and_set_status() -> Result<()> {
+        let db = init_db();
+        let session_repo = db.session_repository();
+
+        let teacher = db
+            .teacher_repository()
+            .insert(&NewTeacher {
+                name: "John Doe".to_string(),
+                email: "cancel@mentor.com".to_string(),
+                specialty: "Math".to_string(),
+                applied_at: None,
+                company: None,
+                company_role: None,
+                bio: None,
+                course_info: None,
+                whatsapp: None,
+                linkedin: None,
+                comment_general: None,
+                comment_experience: None,
+                discord_id: None,
+                program_id: None,
+                timezone: Default::default(),
+                max_sessions_per_week: None,
+                locale: None,
+            })
+            .await?;
+
+        let availability = db
+            .availability_repository()
+            .insert(&NewAvailability {
+                teacher_id: teacher.id,
+                weekday: Weekday::Monday,
+                time_start: chrono::NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+                expired: false,
+                duration: 1,
+                valid_from: None,
+                valid_until: None,
+                program_id: None,
+                capacity: 1,
+            })
+            .await?;
+
+        let student = db
+            .user_repository()
+            .insert(&NewUser {
+                discord_id: DiscordId(111),
+                name: "Cancelling Student".to_string(),
+                email: "cancelling@student.com".to_string(),
+                bio: None,
+                timezone: Default::default(),
+                locale: None,
+            })
+            .await?;
+
+        let start_at = chrono::Utc.with_ymd_and_hms(2023, 9, 11, 13, 0, 0).unwrap();
+        let new_session = NewSession {
+            teacher_id: teacher.id,
+            student_id: student.discord_id,
+            availability_id: availability.id,
+            summary: None,
+            notified: false,
+            meet_id: None,
+            calendar_event_id: None,
+            start_at,
+            end_at: start_at + chrono::Duration::minutes(40),
+            status: SessionStatus::Scheduled,
+            cancelled_at: None,
+            cancel_reason: None,
+        };
+        let session = session_repo.insert(&new_session).await?;
+
+        // cancelling requires a cancellation status
+        assert!(session_repo
+            .cancel(&session, SessionStatus::Completed, None)
+            .await
+            .is_err());
+        assert!(session_repo
+            .set_status(&session, SessionStatus::CancelledByMentor)
+            .await
+            .is_err());
+
+        let cancelled = session_repo
+            .cancel(
+                &session,
+                SessionStatus::CancelledByStudent,
+                Some("Got sick".to_string()),
+            )
+            .await?;
+        assert_eq!(SessionStatus::CancelledByStudent, cancelled.status);
+        assert!(cancelled.cancelled_at.is_some());
+        assert_eq!(Some("Got sick"), cancelled.cancel_reason.as_deref());
+
+        // the cancelled session is kept, but no longer claims its time
+        assert_eq!(
+            vec![cancelled.clone()],
+            session_repo.find_by_student(student.discord_id).await?
+        );
+        assert_eq!(
+            None,
+            session_repo
+                .find_by_availability_starting_at(availability.id, start_at)
+                .await?
+        );
+
+        // the time can be booked again
+        let rebooked = session_repo.insert(&new_session).await?;
+        assert_eq!(
+            Some(&rebooked),
+            session_repo
+                .find_by_availability_starting_at(availability.id, start_at)
+                .await?
+                .as_ref()
+        );
+
+        let completed = session_repo
+            .set_status(&rebooked, SessionStatus::Completed)
+            .await?;
+        assert_eq!(SessionStatus::Completed, completed.status);
+        assert_eq!(None, completed.cancelled_at);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_session_find_needing_reminder() -> Result<()> {
+        let db = init_db();
+        let session_repo = db.session_repository();
+
+        let teacher = db
+            .teacher_repository()
+            .insert(&NewTeacher {
+                name: "John Doe".to_string(),
+                email: "reminder@mentor.com".to_string(),
+                specialty: "Math".to_string(),
+                applied_at: None,
+                company: None,
+                company_role: None,
+                bio: None,
+                course_info: None,
+                whatsapp: None,
+                linkedin: None,
+                comment_general: None,
+                comment_experience: None,
+                discord_id: None,
+                program_id: None,
+                timezone: Default::default(),
+                max_sessions_per_week: None,
+                locale: None,
+            })
+            .await?;
+
+        let availability = db
+            .availability_repository()
+            .insert(&NewAvailability {
+                teacher_id: teacher.id,
+                weekday: Weekday::Monday,
+                time_start: chrono::NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+                expired: false,
+                duration: 1,
+                valid_from: None,
+                valid_until: None,
+                program_id: None,
+                capacity: 1,
+            })
+            .await?;
+
+        let student = db
+            .user_repository()
+            .insert(&NewUser {
+                discord_id: DiscordId(121),
+                name: "Reminded Student".to_string(),
+                email: "reminded@student.com".to_string(),
+                bio: None,
+                timezone: Default::default(),
+                locale: None,
+            })
+            .await?;
+
+        let now = chrono::Utc.with_ymd_and_hms(2023, 9, 11, 12, 0, 0).unwrap();
+        let start_at = now + chrono::Duration::hours(20);
+        let session = session_repo
+            .insert(&NewSession {
+                teacher_id: teacher.id,
+                student_id: student.discord_id,
+                availability_id: availability.id,
+                summary: None,
+                notified: false,
+                meet_id: None,
+                calendar_event_id: None,
+                start_at,
+                end_at: start_at + chrono::Duration::minutes(40),
+                status: SessionStatus::Scheduled,
+                cancelled_at: None,
+                cancel_reason: None,
+            })
+            .await?;
+
+        let repo = &session_repo;
+        let needing_reminder = |offset_minutes, now| async move {
+            repo.find_needing_reminder(offset_minutes, now)
+                .await
+                .map(|found| found.into_iter().map(|(s, _)| s.id).collect::<Vec<_>>())
+        };
+
+        // only the 24h reminder is due
+        assert!(needing_reminder(60, now).await?.is_empty());
+        assert_eq!(vec![session.id], needing_reminder(24 * 60, now).await?);
+
+        assert_eq!(
+            1,
+            session_repo.register_reminder(session.id, 24 * 60).await?
+        );
+        assert_eq!(
+            0,
+            session_repo.register_reminder(session.id, 24 * 60).await?
+        );
+        assert!(needing_reminder(24 * 60, now).await?.is_empty());
+
+        // 1h before the session, the 1h reminder is due
+        let later = start_at - chrono::Duration::minutes(50);
+        assert_eq!(vec![session.id], needing_reminder(60, later).await?);
+        assert_eq!(1, session_repo.register_reminder(session.id, 60).await?);
+
+        // a reminder closer to the session's start covers the farther ones
+        assert!(needing_reminder(60, later).await?.is_empty());
+        assert!(needing_reminder(24 * 60, later).await?.is_empty());
+
+        // notified sessions need no more reminders
+        assert_eq!(vec![session.id], needing_reminder(55, later).await?);
+        let session = session_repo.set_notified(&session).await?;
+        assert!(session.notified);
+        assert!(needing_reminder(55, later).await?.is_empty());
+
+        // started sessions need no reminders
+        assert!(needing_reminder(24 * 60, start_at).await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_session_reschedule() -> Result<()> {
+        let db = init_db();
+        let session_repo = db.session_repository();
+
+        let teacher = db
+            .teacher_repository()
+            .insert(&NewTeacher {
+                name: "John Doe".to_string(),
+                email: "reschedule@mentor.com".to_string(),
+                specialty: "Math".to_string(),
+                applied_at: None,
+                company: None,
+                company_role: None,
+                bio: None,
+                course_info: None,
+                whatsapp: None,
+                linkedin: None,
+                comment_general: None,
+                comment_experience: None,
+                discord_id: None,
+                program_id: None,
+                timezone: Default::default(),
+                max_sessions_per_week: None,
+                locale: None,
+            })
+            .await?;
+
+        let new_availability = NewAvailability {
+            teacher_id: teacher.id,
+            weekday: Weekday::Monday,
+            time_start: chrono::NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+            expired: false,
+            duration: 1,
+            valid_from: None,
+            valid_until: None,
+            program_id: None,
+            capacity: 1,
+        };
+        let monday = db
+            .availability_repository()
+            .insert(&new_availability)
+            .await?;
+        let tuesday = db
+            .availability_repository()
+            .insert(&NewAvailability {
+                weekday: Weekday::Tuesday,
+                ..new_availability
+            })
+            .await?;
+
+        let new_user = NewUser {
+            discord_id: DiscordId(131),
+            name: "First Student".to_string(),
+            email: "first.reschedule@student.com".to_string(),
+            bio: None,
+            timezone: Default::default(),
+            locale: None,
+        };
+        let first = db.user_repository().insert(&new_user).await?;
+        let second = db
+            .user_repository()
+            .insert(&NewUser {
+                discord_id: DiscordId(132),
+                name: "Second Student".to_string(),
+                email: "second.reschedule@student.com".to_string(),
+                ..new_user
+            })
+            .await?;
+
+        let monday_start = chrono::Utc.with_ymd_and_hms(2023, 9, 11, 13, 0, 0).unwrap();
+        let tuesday_start = monday_start + chrono::Duration::days(1);
+        let new_session = NewSession {
+            teacher_id: teacher.id,
+            student_id: first.discord_id,
+            availability_id: monday.id,
+            summary: None,
+            notified: true,
+            meet_id: None,
+            calendar_event_id: None,
+            start_at: monday_start,
+            end_at: monday_start + chrono::Duration::minutes(40),
+            status: SessionStatus::Scheduled,
+            cancelled_at: None,
+            cancel_reason: None,
+        };
+        let session = session_repo.insert(&new_session).await?;
+        session_repo.register_reminder(session.id, 60).await?;
+
+        let other_session = session_repo
+            .insert(&NewSession {
+                student_id: second.discord_id,
+                availability_id: tuesday.id,
+                start_at: tuesday_start,
+                end_at: tuesday_start + chrono::Duration::minutes(40),
+                ..new_session.clone()
+            })
+            .await?;
+
+        // can't move to a time claimed by another scheduled session
+        assert_eq!(
+            None,
+            session_repo
+                .reschedule(
+                    &session,
+                    &BookingPolicy::UNLIMITED,
+                    teacher.id,
+                    tuesday.id,
+                    tuesday_start,
+                    other_session.end_at,
+                )
+                .await?
+        );
+
+        session_repo
+            .cancel(&other_session, SessionStatus::CancelledByStudent, None)
+            .await?;
+
+        let rescheduled = session_repo
+            .reschedule(
+                &session,
+                &BookingPolicy::UNLIMITED,
+                teacher.id,
+                tuesday.id,
+                tuesday_start,
+                other_session.end_at,
+            )
+            .await?
+            .expect("the time should be free after the other session was cancelled");
+        assert_eq!(session.id, rescheduled.id);
+        assert_eq!(tuesday.id, rescheduled.availability_id);
+        assert_eq!(tuesday_start, rescheduled.start_at);
+        assert_eq!(other_session.end_at, rescheduled.end_at);
+        // reminders are sent again for the new time
+        assert!(!rescheduled.notified);
+        assert_eq!(1, session_repo.register_reminder(session.id, 60).await?);
+
+        // the old time is free again
+        assert_eq!(
+            None,
+            session_repo
+                .find_by_availability_starting_at(monday.id, monday_start)
+                .await?
+        );
+
+        // cancelled sessions can't be rescheduled
+        let cancelled = session_repo
+            .cancel(&rescheduled, SessionStatus::CancelledByMentor, None)
+            .await?;
+        assert_eq!(
+            None,
+            session_repo
+                .reschedule(
+                    &cancelled,
+                    &BookingPolicy::UNLIMITED,
+                    teacher.id,
+                    monday.id,
+                    monday_start,
+                    session.end_at,
+                )
+                .await?
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_session_reschedule_respects_overlaps_and_weekly_caps() -> Result<()> {
+        let db = init_db();
+        let session_repo = db.session_repository();
+
+        let new_teacher = NewTeacher {
+            name: "John Doe".to_string(),
+            email: "reschedule.overlap@mentor.com".to_string(),
+            specialty: "Math".to_string(),
+            applied_at: None,
+            company: None,
+            company_role: None,
+            bio: None,
+            course_info: None,
+            whatsapp: None,
+            linkedin: None,
+            comment_general: None,
+            comment_experience: None,
+            discord_id: None,
+            program_id: None,
+            timezone: Default::default(),
+            max_sessions_per_week: None,
+            locale: None,
+        };
+        let teacher = db.teacher_repository().insert(&new_teacher).await?;
+        let capped_teacher = db
+            .teacher_repository()
+            .insert(&NewTeacher {
+                email: "reschedule.cap@mentor.com".to_string(),
+                max_sessions_per_week: Some(1),
+                ..new_teacher
+            })
+            .await?;
+
+        let new_availability = NewAvailability {
+            teacher_id: teacher.id,
+            weekday: Weekday::Monday,
+            time_start: chrono::NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+            expired: false,
+            duration: 1,
+            valid_from: None,
+            valid_until: None,
+            program_id: None,
+            capacity: 1,
+        };
+        let monday = db
+            .availability_repository()
+            .insert(&new_availability)
+            .await?;
+        let capped_monday = db
+            .availability_repository()
+            .insert(&NewAvailability {
+                teacher_id: capped_teacher.id,
+                ..new_availability.clone()
+            })
+            .await?;
+        let capped_tuesday = db
+            .availability_repository()
+            .insert(&NewAvailability {
+                teacher_id: capped_teacher.id,
+                weekday: Weekday::Tuesday,
+                ..new_availability
+            })
+            .await?;
+
+        let student = db
+            .user_repository()
+            .insert(&NewUser {
+                discord_id: DiscordId(133),
+                name: "Student".to_string(),
+                email: "overlap.reschedule@student.com".to_string(),
+                bio: None,
+                timezone: Default::default(),
+                locale: None,
+            })
+            .await?;
+
+        let monday_start = chrono::Utc.with_ymd_and_hms(2023, 9, 11, 13, 0, 0).unwrap();
+        let tuesday_start = monday_start + chrono::Duration::days(1);
+        let next_monday_start = monday_start + chrono::Duration::weeks(1);
+        let duration = chrono::Duration::minutes(40);
+        let new_session = NewSession {
+            teacher_id: teacher.id,
+            student_id: student.discord_id,
+            availability_id: monday.id,
+            summary: None,
+            notified: false,
+            meet_id: None,
+            calendar_event_id: None,
+            start_at: monday_start,
+            end_at: monday_start + duration,
+            status: SessionStatus::Scheduled,
+            cancelled_at: None,
+            cancel_reason: None,
+        };
+        let session = session_repo.insert(&new_session).await?;
+        let next_session = session_repo
+            .insert(&NewSession {
+                start_at: next_monday_start,
+                end_at: next_monday_start + duration,
+                ..new_session.clone()
+            })
+            .await?;
+        let capped_session = session_repo
+            .insert(&NewSession {
+                teacher_id: capped_teacher.id,
+                availability_id: capped_monday.id,
+                ..new_session
+            })
+            .await?;
+
+        // can't move to a time overlapping with another of the teacher's sessions
+        let overlapping_start = next_session.start_at + chrono::Duration::minutes(20);
+        assert_eq!(
+            None,
+            session_repo
+                .reschedule(
+                    &session,
+                    &BookingPolicy::UNLIMITED,
+                    teacher.id,
+                    monday.id,
+                    overlapping_start,
+                    overlapping_start + duration,
+                )
+                .await?
+        );
+
+        // nor to a week in which the new teacher reached their weekly cap
+        assert_eq!(
+            None,
+            session_repo
+                .reschedule(
+                    &session,
+                    &BookingPolicy::UNLIMITED,
+                    capped_teacher.id,
+                    capped_tuesday.id,
+                    tuesday_start,
+                    tuesday_start + duration,
+                )
+                .await?
+        );
+
+        // the session being moved doesn't count towards its own week
+        let rescheduled = session_repo
+            .reschedule(
+                &capped_session,
+                &BookingPolicy::UNLIMITED,
+                capped_teacher.id,
+                capped_tuesday.id,
+                tuesday_start,
+                tuesday_start + duration,
+            )
+            .await?;
+        assert_eq!(
+            Some(tuesday_start),
+            rescheduled.map(|session| session.start_at)
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_session_find_scheduled_by_teacher_starting_between() -> Result<()> {
+        let db = init_db();
+        let session_repo = db.session_repository();
+
+        let teacher = db
+            .teacher_repository()
+            .insert(&NewTeacher {
+                name: "John Doe".to_string(),
+                email: "cancelrange@mentor.com".to_string(),
+                specialty: "Math".to_string(),
+                applied_at: None,
+                company: None,
+                company_role: None,
+                bio: None,
+                course_info: None,
+                whatsapp: None,
+                linkedin: None,
+                comment_general: None,
+                comment_experience: None,
+                discord_id: None,
+                program_id: None,
+                timezone: Default::default(),
+                max_sessions_per_week: None,
+                locale: None,
+            })
+            .await?;
+
+        let availability = db
+            .availability_repository()
+            .insert(&NewAvailability {
+                teacher_id: teacher.id,
+                weekday: Weekday::Monday,
+                time_start: chrono::NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+                expired: false,
+                duration: 1,
+                valid_from: None,
+                valid_until: None,
+                program_id: None,
+                capacity: 1,
+            })
+            .await?;
+
+        let user = db
+            .user_repository()
+            .insert(&NewUser {
+                discord_id: DiscordId(141),
+                name: "Range Student".to_string(),
+                email: "cancelrange@student.com".to_string(),
+                bio: None,
+                timezone: Default::default(),
+                locale: None,
+            })
+            .await?;
+
+        let first_start = chrono::Utc.with_ymd_and_hms(2023, 9, 11, 13, 0, 0).unwrap();
+        let new_session = NewSession {
+            teacher_id: teacher.id,
+            student_id: user.discord_id,
+            availability_id: availability.id,
+            summary: None,
+            notified: false,
+            meet_id: None,
+            calendar_event_id: None,
+            start_at: first_start,
+            end_at: first_start + chrono::Duration::minutes(40),
+            status: SessionStatus::Scheduled,
+            cancelled_at: None,
+            cancel_reason: None,
+        };
+        let week_start = |weeks| first_start + chrono::Duration::weeks(weeks);
+        let first = session_repo.insert(&new_session).await?;
+        let second = session_repo
+            .insert(&NewSession {
+                start_at: week_start(1),
+                end_at: week_start(1) + chrono::Duration::minutes(40),
+                ..new_session.clone()
+            })
+            .await?;
+        let cancelled = session_repo
+            .insert(&NewSession {
+                start_at: week_start(2),
+                end_at: week_start(2) + chrono::Duration::minutes(40),
+                ..new_session.clone()
+            })
+            .await?;
+        session_repo
+            .cancel(&cancelled, SessionStatus::CancelledByMentor, None)
+            .await?;
+        session_repo
+            .insert(&NewSession {
+                start_at: week_start(3),
+                end_at: week_start(3) + chrono::Duration::minutes(40),
+                ..new_session
+            })
+            .await?;
+
+        assert_eq!(
+            vec![first, second],
+            session_repo
+                .find_scheduled_by_teacher_starting_between(teacher.id, first_start, week_start(2))
+                .await?
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_session_book_enforces_booking_policy() -> Result<()> {
+        let db = init_db();
+        let session_repo = db.session_repository();
+
+        let teacher = db
+            .teacher_repository()
+            .insert(&NewTeacher {
+                name: "John Doe".to_string(),
+                email: "policy@mentor.com".to_string(),
+                specialty: "Math".to_string(),
+                applied_at: None,
+                company: None,
+                company_role: None,
+                bio: None,
+                course_info: None,
+                whatsapp: None,
+                linkedin: None,
+                comment_general: None,
+                comment_experience: None,
+                discord_id: None,
+                program_id: None,
+                timezone: Default::default(),
+                max_sessions_per_week: None,
+                locale: None,
+            })
+            .await?;
+
+        let new_availability = NewAvailability {
+            teacher_id: teacher.id,
+            weekday: Weekday::Monday,
+            time_start: chrono::NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+            expired: false,
+            duration: 1,
+            valid_from: None,
+            valid_until: None,
+            program_id: None,
+            capacity: 2,
+        };
+        let monday = db
+            .availability_repository()
+            .insert(&new_availability)
+            .await?;
+        let tuesday = db
+            .availability_repository()
+            .insert(&NewAvailability {
+                weekday: Weekday::Tuesday,
+                ..new_availability
+            })
+            .await?;
+
+        let new_user = NewUser {
+            discord_id: DiscordId(134),
+            name: "First Student".to_string(),
+            email: "first.policy@student.com".to_string(),
+            bio: None,
+            timezone: Default::default(),
+            locale: None,
+        };
+        let first = db.user_repository().insert(&new_user).await?;
+        let second = db
+            .user_repository()
+            .insert(&NewUser {
+                discord_id: DiscordId(135),
+                email: "second.policy@student.com".to_string(),
+                ..new_user
+            })
+            .await?;
+
+        // sessions in the future count as active
+        let monday_start = chrono::Utc.with_ymd_and_hms(2100, 9, 13, 13, 0, 0).unwrap();
+        let tuesday_start = monday_start + chrono::Duration::days(1);
+        let new_session = NewSession {
+            teacher_id: teacher.id,
+            student_id: first.discord_id,
+            availability_id: monday.id,
+            summary: None,
+            notified: false,
+            meet_id: None,
+            calendar_event_id: None,
+            start_at: monday_start,
+            end_at: monday_start + chrono::Duration::minutes(40),
+            status: SessionStatus::Scheduled,
+            cancelled_at: None,
+            cancel_reason: None,
+        };
+        let policy = BookingPolicy {
+            max_active_sessions: Some(1),
+            max_sessions_per_mentor_per_week: Some(1),
+            ..BookingPolicy::UNLIMITED
+        };
+
+        let session = session_repo.book(&new_session, &policy).await?;
+        let tuesday_session = NewSession {
+            availability_id: tuesday.id,
+            start_at: tuesday_start,
+            end_at: tuesday_start + chrono::Duration::minutes(40),
+            ..new_session.clone()
+        };
+        assert!(matches!(
+            session_repo.book(&tuesday_session, &policy).await,
+            Err(Error::BookingRefused(
+                BookingRefusal::TooManyActiveSessions { max: 1 }
+            ))
+        ));
+
+        // the session being moved doesn't count towards the limits
+        let rescheduled = session_repo
+            .reschedule(
+                &session,
+                &policy,
+                teacher.id,
+                tuesday.id,
+                tuesday_start,
+                tuesday_session.end_at,
+            )
+            .await?;
+        assert!(rescheduled.is_some());
+
+        // joining a session counts as booking it
+        let other_session = session_repo
+            .book(
+                &NewSession {
+                    student_id: second.discord_id,
+                    ..new_session
+                },
+                &policy,
+            )
+            .await?;
+        assert!(matches!(
+            session_repo
+                .add_student_if_seats_left(
+                    other_session.id,
+                    first.discord_id,
+                    monday.capacity,
+                    &BookingPolicy {
+                        max_active_sessions: None,
+                        ..policy
+                    },
+                )
+                .await,
+            Err(Error::BookingRefused(
+                BookingRefusal::TooManyWithMentorPerWeek { max: 1 }
+            ))
+        ));
+        assert!(
+            session_repo
+                .add_student_if_seats_left(
+                    other_session.id,
+                    first.discord_id,
+                    monday.capacity,
+                    &BookingPolicy::UNLIMITED,
+                )
+                .await?
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_session_book_refuses_times_held_for_waitlist() -> Result<()> {
+        let db = init_db();
+        let session_repo = db.session_repository();
+
+        let teacher = db
+            .teacher_repository()
+            .insert(&NewTeacher {
+                name: "John Doe".to_string(),
+                email: "held@mentor.com".to_string(),
+                specialty: "Math".to_string(),
+                applied_at: None,
+                company: None,
+                company_role: None,
+                bio: None,
+                course_info: None,
+                whatsapp: None,
+                linkedin: None,
+                comment_general: None,
+                comment_experience: None,
+                discord_id: None,
+                program_id: None,
+                timezone: Default::default(),
+                max_sessions_per_week: None,
+                locale: None,
+            })
+            .await?;
+
+        let availability = db
+            .availability_repository()
+            .insert(&NewAvailability {
+                teacher_id: teacher.id,
+                weekday: Weekday::Monday,
+                time_start: chrono::NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+                expired: false,
+                duration: 1,
+                valid_from: None,
+                valid_until: None,
+                program_id: None,
+                capacity: 1,
+            })
+            .await?;
+
+        let new_user = NewUser {
+            discord_id: DiscordId(204),
+            name: "Waiting Student".to_string(),
+            email: "waiting.held@student.com".to_string(),
+            bio: None,
+            timezone: Default::default(),
+            locale: None,
+        };
+        let waiting = db.user_repository().insert(&new_user).await?;
+        let other = db
+            .user_repository()
+            .insert(&NewUser {
+                discord_id: DiscordId(205),
+                email: "other.held@student.com".to_string(),
+                ..new_user
+            })
+            .await?;
+
+        let start_at = chrono::Utc.with_ymd_and_hms(2100, 9, 13, 13, 0, 0).unwrap();
+        let entry = db
+            .waitlist_repository()
+            .join(&NewWaitlistEntry {
+                student_id: waiting.discord_id,
+                teacher_id: None,
+                specialty: None,
+            })
+            .await?
+            .unwrap();
+        db.waitlist_repository()
+            .offer(
+                entry.id,
+                availability.id,
+                start_at,
+                chrono::Utc::now() + chrono::Duration::hours(1),
+            )
+            .await?;
+
+        let new_session = NewSession {
+            teacher_id: teacher.id,
+            student_id: other.discord_id,
+            availability_id: availability.id,
+            summary: None,
+            notified: false,
+            meet_id: None,
+            calendar_event_id: None,
+            start_at,
+            end_at: start_at + chrono::Duration::minutes(40),
+            status: SessionStatus::Scheduled,
+            cancelled_at: None,
+            cancel_reason: None,
+        };
+        assert!(matches!(
+            session_repo
+                .book(&new_session, &BookingPolicy::UNLIMITED)
+                .await,
+            Err(Error::BookingRefused(BookingRefusal::HeldForWaitlist))
+        ));
+
+        // the time is still offered to the student it is held for
+        session_repo
+            .book(
+                &NewSession {
+                    student_id: waiting.discord_id,
+                    ..new_session
+                },
+                &BookingPolicy::UNLIMITED,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_session_book_refuses_taken_times() -> Result<()> {
+        let db = init_db();
+        let session_repo = db.session_repository();
+
+        let teacher = db
+            .teacher_repository()
+            .insert(&NewTeacher {
+                name: "John Doe".to_string(),
+                email: "book@mentor.com".to_string(),
+                specialty: "Math".to_string(),
+                applied_at: None,
+                company: None,
+                company_role: None,
+                bio: None,
+                course_info: None,
+                whatsapp: None,
+                linkedin: None,
+                comment_general: None,
+                comment_experience: None,
+                discord_id: None,
+                program_id: None,
+                timezone: Default::default(),
+                max_sessions_per_week: None,
+                locale: None,
+            })
+            .await?;
+
+        let availability = db
+            .availability_repository()
+            .insert(&NewAvailability {
+                teacher_id: teacher.id,
+                weekday: Weekday::Monday,
+                time_start: chrono::NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+                expired: false,
+                duration: 1,
+                valid_from: None,
+                valid_until: None,
+                program_id: None,
+                capacity: 2,
+            })
+            .await?;
+
+        let new_user = NewUser {
+            discord_id: DiscordId(151),
+            name: "First Student".to_string(),
+            email: "first.book@student.com".to_string(),
+            bio: None,
+            timezone: Default::default(),
+            locale: None,
+        };
+        let first = db.user_repository().insert(&new_user).await?;
+        let second = db
+            .user_repository()
+            .insert(&NewUser {
+                discord_id: DiscordId(152),
+                email: "second.book@student.com".to_string(),
+                ..new_user.clone()
+            })
+            .await?;
+        let third = db
+            .user_repository()
+            .insert(&NewUser {
+                discord_id: DiscordId(153),
+                email: "third.book@student.com".to_string(),
+                ..new_user
+            })
+            .await?;
+
+        let start_at = chrono::Utc.with_ymd_and_hms(2023, 9, 11, 13, 0, 0).unwrap();
+        let new_session = NewSession {
+            teacher_id: teacher.id,
+            student_id: first.discord_id,
+            availability_id: availability.id,
+            summary: None,
+            notified: false,
+            meet_id: None,
+            calendar_event_id: None,
+            start_at,
+            end_at: start_at + chrono::Duration::minutes(40),
+            status: SessionStatus::Scheduled,
+            cancelled_at: None,
+            cancel_reason: None,
+        };
+        let session = session_repo
+            .book(&new_session, &BookingPolicy::UNLIMITED)
+            .await?;
+        assert_eq!(
+            vec![first.clone()],
+            session_repo.find_students(session.id).await?
+        );
+
+        // an overlapping session of the same teacher can't be booked
+        assert!(matches!(
+            session_repo
+                .book(
+                    &NewSession {
+                        student_id: second.discord_id,
+                        start_at: start_at + chrono::Duration::minutes(20),
+                        end_at: start_at + chrono::Duration::minutes(60),
+                        ..new_session.clone()
+                    },
+                    &BookingPolicy::UNLIMITED
+                )
+                .await,
+            Err(Error::SessionTimeTaken)
+        ));
+
+        // other students may join it until it is full
+        assert!(
+            session_repo
+                .add_student_if_seats_left(
+                    session.id,
+                    second.discord_id,
+                    availability.capacity,
+                    &BookingPolicy::UNLIMITED
+                )
+                .await?
+        );
+        assert!(
+            !session_repo
+                .add_student_if_seats_left(
+                    session.id,
+                    third.discord_id,
+                    availability.capacity,
+                    &BookingPolicy::UNLIMITED
+                )
+                .await?
+        );
+        assert_eq!(2, session_repo.find_students(session.id).await?.len());
+
+        // once cancelled, the time is free again
+        session_repo
+            .cancel(&session, SessionStatus::CancelledByStudent, None)
+            .await?;
+        let rebooked = session_repo
+            .book(
+                &NewSession {
+                    student_id: third.discord_id,
+                    ..new_session
+                },
+                &BookingPolicy::UNLIMITED,
+            )
+            .await?;
+        assert_eq!(vec![third], session_repo.find_students(rebooked.id).await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_session_find_page() -> Result<()> {
+        let db = init_db();
+        let session_repo = db.session_repository();
+
+        let teacher = db
+            .teacher_repository()
+            .insert(&NewTeacher {
+                name: "John Doe".to_string(),
+                email: "page@mentor.com".to_string(),
+                specialty: "Math".to_string(),
+                applied_at: None,
+                company: None,
+                company_role: None,
+                bio: None,
+                course_info: None,
+                whatsapp: None,
+                linkedin: None,
+                comment_general: None,
+                comment_experience: None,
+                discord_id: None,
+                program_id: None,
+                timezone: Default::default(),
+                max_sessions_per_week: None,
+                locale: None,
+            })
+            .await?;
+
+        let availability = db
+            .availability_repository()
+            .insert(&NewAvailability {
+                teacher_id: teacher.id,
+                weekday: Weekday::Monday,
+                time_start: chrono::NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+                expired: false,
+                duration: 1,
+                valid_from: None,
+                valid_until: None,
+                program_id: None,
+                capacity: 1,
+            })
+            .await?;
+
+        let new_user = NewUser {
+            discord_id: DiscordId(171),
+            name: "Paged Student".to_string(),
+            email: "first.page@student.com".to_string(),
+            bio: None,
+            timezone: Default::default(),
+            locale: None,
+        };
+        let user = db.user_repository().insert(&new_user).await?;
+        let other_user = db
+            .user_repository()
+            .insert(&NewUser {
+                discord_id: DiscordId(172),
+                email: "second.page@student.com".to_string(),
+                ..new_user
+            })
+            .await?;
+
+        let first_start = chrono::Utc.with_ymd_and_hms(2023, 9, 11, 13, 0, 0).unwrap();
+        let new_session = NewSession {
+            teacher_id: teacher.id,
+            student_id: user.discord_id,
+            availability_id: availability.id,
+            summary: None,
+            notified: false,
+            meet_id: None,
+            calendar_event_id: None,
+            start_at: first_start,
+            end_at: first_start + chrono::Duration::minutes(40),
+            status: SessionStatus::Scheduled,
+            cancelled_at: None,
+            cancel_reason: None,
+        };
+        let mut sessions = Vec::new();
+        for week in 0..3 {
+            let start_at = first_start + chrono::Duration::weeks(week);
+            sessions.push(
+                session_repo
+                    .insert(&NewSession {
+                        start_at,
+                        end_at: start_at + chrono::Duration::minutes(40),
+                        ..new_session.clone()
+                    })
+                    .await?,
+            );
+        }
+        // same start time as the first session, so it comes right after it (by ID)
+        let other_student_session = session_repo
+            .insert(&NewSession {
+                student_id: other_user.discord_id,
+                status: SessionStatus::Completed,
+                ..new_session
+            })
+            .await?;
+
+        let filter = SessionFilter {
+            teacher_id: Some(teacher.id),
+            ..Default::default()
+        };
+        let request = PageRequest::first(2);
+        let page = session_repo.find_page(&filter, &request).await?;
+        assert_eq!(
+            vec![sessions[0].clone(), other_student_session.clone()],
+            page.items
+        );
+
+        let request = page.next_request(&request).unwrap();
+        let page = session_repo.find_page(&filter, &request).await?;
+        assert_eq!(sessions[1..].to_vec(), page.items);
+        assert_eq!(None, page.next_request(&request));
+
+        let filter = SessionFilter {
+            student_id: Some(user.discord_id),
+            starts_from: Some(first_start + chrono::Duration::days(1)),
+            ..filter
+        };
+        let page = session_repo
+            .find_page(
+                &filter,
+                &PageRequest::first(5).ordered(SortOrder::Descending),
+            )
+            .await?;
+        assert_eq!(vec![sessions[2].clone(), sessions[1].clone()], page.items);
+
+        Ok(())
+    }
+}
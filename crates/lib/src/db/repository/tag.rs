@@ -0,0 +1,209 @@
+use async_trait::async_trait;
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::{AsyncConnection, RunQueryDsl};
+use scoped_futures::ScopedFutureExt;
+
+use super::{
+    super::{
+        connection::ConnectionSource,
+        schema::{tags, teacher_tags},
+        store::TagStore,
+    },
+    repo_find_all, repo_get, repo_insert, repo_remove, Repository,
+};
+use crate::{
+    error::{Error, Result},
+    model::{NewTag, Tag, TeacherTag},
+};
+
+/// Manages Tag instances, as well as the tags of each Teacher.
+#[derive(Clone)]
+pub struct TagRepository {
+    connection: ConnectionSource,
+}
+
+impl TagRepository {
+    /// Creates a new TagRepository operating with the given
+    /// connection source (a connection pool or a transaction).
+    pub fn new(connection: &ConnectionSource) -> Self {
+        Self {
+            connection: connection.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl TagStore for TagRepository {
+    async fn find_all(&self) -> Result<Vec<Tag>> {
+        Repository::find_all(self).await
+    }
+
+    async fn find_by_teacher(&self, teacher_id: i64) -> Result<Vec<Tag>> {
+        Ok(self
+            .find_by_teachers(&[teacher_id])
+            .await?
+            .into_iter()
+            .map(|(_, tag)| tag)
+            .collect())
+    }
+
+    async fn find_by_teachers(&self, teacher_ids: &[i64]) -> Result<Vec<(i64, Tag)>> {
+        teacher_tags::table
+            .inner_join(tags::table)
+            .filter(teacher_tags::teacher_id.eq_any(teacher_ids))
+            .select((teacher_tags::teacher_id, tags::all_columns))
+            .order_by((tags::name.asc(), teacher_tags::teacher_id.asc()))
+            .get_results(&mut self.lock_connection().await?)
+            .await
+            .map_err(From::from)
+    }
+
+    async fn set_teacher_tags(&self, teacher_id: i64, names: &[String]) -> Result<Vec<Tag>> {
+        self.lock_connection()
+            .await?
+            .transaction::<_, Error, _>(|connection| {
+                async move {
+                    let new_tags = names
+                        .iter()
+                        .map(|name| NewTag { name: name.clone() })
+                        .collect::<Vec<_>>();
+
+                    diesel::insert_into(tags::table)
+                        .values(&new_tags)
+                        .on_conflict_do_nothing()
+                        .execute(connection)
+                        .await?;
+
+                    let mut tags: Vec<Tag> = tags::table
+                        .filter(tags::name.eq_any(names))
+                        .get_results(connection)
+                        .await?;
+                    tags.sort_by(|a, b| a.name.cmp(&b.name));
+
+                    diesel::delete(
+                        teacher_tags::table.filter(teacher_tags::teacher_id.eq(teacher_id)),
+                    )
+                    .execute(connection)
+                    .await?;
+
+                    diesel::insert_into(teacher_tags::table)
+                        .values(
+                            tags.iter()
+                                .map(|tag| TeacherTag {
+                                    teacher_id,
+                                    tag_id: tag.id,
+                                })
+                                .collect::<Vec<_>>(),
+                        )
+                        .execute(connection)
+                        .await?;
+
+                    Ok(tags)
+                }
+                .scope_boxed()
+            })
+            .await
+    }
+}
+
+#[async_trait]
+impl Repository for TagRepository {
+    type Table = tags::table;
+
+    type Entity = Tag;
+
+    type NewEntity = NewTag;
+
+    type PrimaryKey = i64;
+
+    const TABLE: Self::Table = tags::table;
+
+    fn get_connection_source(&self) -> &ConnectionSource {
+        &self.connection
+    }
+
+    /// Gets a Tag by its ID.
+    async fn get(&self, id: i64) -> Result<Option<Tag>> {
+        repo_get!(self, tags::table; id)
+    }
+
+    async fn insert(&self, tag: &NewTag) -> Result<Tag> {
+        repo_insert!(self, tags::table; tag)
+    }
+
+    async fn remove(&self, tag: &Tag) -> Result<usize> {
+        repo_remove!(self; tag)
+    }
+
+    async fn find_all(&self) -> Result<Vec<Tag>> {
+        repo_find_all!(self, tags::table, tags::table; @order_by: tags::name.asc())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::init_db;
+    use crate::{error::Result, model::NewTeacher};
+
+    #[tokio::test]
+    async fn test_tag_teachers_from_specialty() -> Result<()> {
+        let db = init_db();
+        let tag_repo = db.tag_repository();
+
+        let new_teacher = |email: &str, specialty: &str| NewTeacher {
+            name: "John Doe".to_string(),
+            email: email.to_string(),
+            specialty: specialty.to_string(),
+            applied_at: None,
+            company: None,
+            company_role: None,
+            bio: None,
+            course_info: None,
+            whatsapp: None,
+            linkedin: None,
+            comment_general: None,
+            comment_experience: None,
+            discord_id: None,
+            program_id: None,
+            timezone: Default::default(),
+            max_sessions_per_week: None,
+            locale: None,
+        };
+        let first = db
+            .teacher_repository()
+            .insert(&new_teacher("first@tags.com", "Tag Physics, Tag Math"))
+            .await?;
+        let second = db
+            .teacher_repository()
+            .insert(&new_teacher("second@tags.com", "tag math"))
+            .await?;
+
+        let first_tags = tag_repo.tag_from_specialty(&first).await?;
+        assert_eq!(
+            vec!["tag math", "tag physics"],
+            first_tags.iter().map(|tag| &*tag.name).collect::<Vec<_>>()
+        );
+
+        // existing tags are reused
+        let second_tags = tag_repo.tag_from_specialty(&second).await?;
+        assert_eq!(vec![first_tags[0].clone()], second_tags);
+
+        let teacher_tags = tag_repo.find_by_teachers(&[first.id, second.id]).await?;
+        assert_eq!(
+            vec![
+                (first.id, first_tags[0].clone()),
+                (second.id, first_tags[0].clone()),
+                (first.id, first_tags[1].clone()),
+            ],
+            teacher_tags
+        );
+
+        // tags are replaced, and removed along with their teacher
+        tag_repo.set_teacher_tags(first.id, &[]).await?;
+        assert!(tag_repo.find_by_teacher(first.id).await?.is_empty());
+        db.teacher_repository().remove(&second).await?;
+        assert!(tag_repo.find_by_teacher(second.id).await?.is_empty());
+
+        Ok(())
+    }
+}
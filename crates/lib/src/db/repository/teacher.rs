@@ -1,37 +1,79 @@
-use std::sync::Arc;
-
 use async_trait::async_trait;
-use diesel::{ExpressionMethods, OptionalExtension, QueryDsl};
-use diesel_async::{pooled_connection::deadpool::Pool, AsyncPgConnection, RunQueryDsl};
+use diesel::{
+    BoolExpressionMethods, ExpressionMethods, OptionalExtension, PgTextExpressionMethods, QueryDsl,
+};
+use diesel_async::RunQueryDsl;
 
 use super::{
-    super::schema::{self, teachers},
+    super::{
+        connection::ConnectionSource,
+        pagination::{Page, PageRequest, SortOrder},
+        schema::{self, teachers},
+        store::TeacherStore,
+    },
     repo_find_all, repo_find_by, repo_get, repo_insert, repo_remove, repo_update, repo_upsert,
     Repository, UpdatableRepository,
 };
 use crate::{
     db::repository::repo_find_by_first,
     error::Result,
-    model::{Availability, NewTeacher, PartialTeacher, Session, Teacher},
+    model::{Availability, DiscordId, NewTeacher, PartialTeacher, Teacher},
 };
 
+/// Optional criteria which teachers must match in a [paginated query].
+///
+/// [paginated query]: TeacherStore::find_page
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TeacherFilter {
+    /// Only teachers attached to this program.
+    pub program_id: Option<i64>,
+    /// Only teachers whose name or email contain this text (case-insensitive).
+    pub search: Option<String>,
+}
+
 /// Manages Teacher instances.
 #[derive(Clone)]
 pub struct TeacherRepository {
-    pool: Arc<Pool<AsyncPgConnection>>,
+    connection: ConnectionSource,
 }
 
 impl TeacherRepository {
     /// Creates a new TeacherRepository operating with the given
-    /// connection pool.
-    pub fn new(pool: &Arc<Pool<AsyncPgConnection>>) -> Self {
+    /// connection source (a connection pool or a transaction).
+    pub fn new(connection: &ConnectionSource) -> Self {
         Self {
-            pool: Arc::clone(pool),
+            connection: connection.clone(),
         }
     }
+}
+
+#[async_trait]
+impl TeacherStore for TeacherRepository {
+    async fn get(&self, id: i64) -> Result<Option<Teacher>> {
+        Repository::get(self, id).await
+    }
+
+    async fn insert(&self, teacher: &NewTeacher) -> Result<Teacher> {
+        Repository::insert(self, teacher).await
+    }
+
+    async fn remove(&self, teacher: &Teacher) -> Result<usize> {
+        Repository::remove(self, teacher).await
+    }
+
+    async fn find_all(&self) -> Result<Vec<Teacher>> {
+        Repository::find_all(self).await
+    }
+
+    async fn upsert(&self, teacher: &NewTeacher) -> Result<Teacher> {
+        UpdatableRepository::upsert(self, teacher).await
+    }
 
-    /// Returns the teacher with the given email, if any.
-    pub async fn find_by_email(&self, email: &str) -> Result<Option<Teacher>> {
+    async fn update(&self, old_teacher: &Teacher, new_teacher: PartialTeacher) -> Result<Teacher> {
+        UpdatableRepository::update(self, old_teacher, new_teacher).await
+    }
+
+    async fn find_by_email(&self, email: &str) -> Result<Option<Teacher>> {
         repo_find_by_first!(
             self, teachers::table;
 
@@ -39,10 +81,64 @@ impl TeacherRepository {
         )
     }
 
-    /// Attempts to insert a Teacher; does nothing if such a Teacher (with the same e-mail
-    /// or something) is already registered.
-    /// Returns the inserted row count (1 if a new Teacher was inserted or 0 otherwise).
-    pub async fn insert_if_not_exists(&self, teacher: &NewTeacher) -> Result<usize> {
+    async fn find_page(
+        &self,
+        filter: &TeacherFilter,
+        request: &PageRequest<i64>,
+    ) -> Result<Page<Teacher, i64>> {
+        let mut query = teachers::table.into_boxed();
+
+        if let Some(program_id) = filter.program_id {
+            query = query.filter(teachers::program_id.eq(program_id));
+        }
+        if let Some(search) = filter.search.as_deref() {
+            let pattern = format!(
+                "%{}%",
+                search
+                    .trim()
+                    .replace('\\', "\\\\")
+                    .replace('%', "\\%")
+                    .replace('_', "\\_")
+            );
+            query = query.filter(
+                teachers::name
+                    .ilike(pattern.clone())
+                    .or(teachers::email.ilike(pattern)),
+            );
+        }
+
+        query = match (request.order, request.after) {
+            (SortOrder::Ascending, Some(after)) => query.filter(teachers::id.gt(after)),
+            (SortOrder::Descending, Some(after)) => query.filter(teachers::id.lt(after)),
+            (_, None) => query,
+        };
+
+        query = match request.order {
+            SortOrder::Ascending => query.order_by(teachers::id.asc()),
+            SortOrder::Descending => query.order_by(teachers::id.desc()),
+        };
+
+        let teachers = query
+            .limit(request.query_limit())
+            .get_results(&mut self.lock_connection().await?)
+            .await?;
+
+        Ok(Page::from_queried(
+            teachers,
+            request,
+            |teacher: &Teacher| teacher.id,
+        ))
+    }
+
+    async fn find_by_discord_id(&self, discord_id: DiscordId) -> Result<Option<Teacher>> {
+        repo_find_by_first!(
+            self, teachers::table;
+
+            teachers::discord_id.eq(discord_id)
+        )
+    }
+
+    async fn insert_if_not_exists(&self, teacher: &NewTeacher) -> Result<usize> {
         diesel::insert_into(teachers::table)
             .values(teacher)
             .on_conflict_do_nothing()
@@ -51,13 +147,7 @@ impl TeacherRepository {
             .map_err(From::from)
     }
 
-    /// Gets a Session's teacher.
-    pub async fn find_by_session(&self, session: &Session) -> Result<Option<Teacher>> {
-        self.get(session.teacher_id).await
-    }
-
-    /// Gets all teachers linked to certain availabilities.
-    pub async fn find_by_availabilities(
+    async fn find_by_availabilities(
         &self,
         availabilities: &[Availability],
     ) -> Result<Vec<(Teacher, Availability)>> {
@@ -84,8 +174,7 @@ impl TeacherRepository {
             .map_err(From::from)
     }
 
-    /// Fetches teachers with an ID among a certain group of IDs.
-    pub async fn find_by_ids(&self, ids: &[i64]) -> Result<Vec<Teacher>> {
+    async fn find_by_ids(&self, ids: &[i64]) -> Result<Vec<Teacher>> {
         repo_find_by!(
             self, teachers::table;
 
@@ -106,8 +195,8 @@ impl Repository for TeacherRepository {
 
     const TABLE: Self::Table = teachers::table;
 
-    fn get_connection_pool(&self) -> Arc<Pool<AsyncPgConnection>> {
-        Arc::clone(&self.pool)
+    fn get_connection_source(&self) -> &ConnectionSource {
+        &self.connection
     }
 
     /// Gets a Teacher by their Discord ID.
@@ -147,9 +236,9 @@ mod tests {
 
     use super::super::tests::init_db;
     use crate::{
-        db::{Repository, UpdatableRepository},
+        db::{PageRequest, SortOrder, TeacherFilter},
         error::Result,
-        model::NewTeacher,
+        model::{DiscordId, NewTeacher},
     };
 
     #[tokio::test]
@@ -170,6 +259,11 @@ mod tests {
             linkedin: Some("https://linkedin.com/????????".to_string()),
             comment_general: None,
             comment_experience: Some("All the experience".to_string()),
+            discord_id: None,
+            program_id: None,
+            timezone: Default::default(),
+            max_sessions_per_week: None,
+            locale: None,
         };
 
         let inserted_teacher = teacher_repo.insert(&new_teacher).await?;
@@ -216,6 +310,11 @@ mod tests {
             linkedin: Some("https://linkedin.com/amongus".to_string()),
             comment_general: None,
             comment_experience: Some("No comments".to_string()),
+            discord_id: None,
+            program_id: None,
+            timezone: Default::default(),
+            max_sessions_per_week: None,
+            locale: None,
         };
         let other_teacher = NewTeacher {
             company: None,
@@ -256,4 +355,116 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_teacher_link_and_find_by_discord_id() -> Result<()> {
+        let db = init_db();
+        let teacher_repo = db.teacher_repository();
+
+        let new_teacher = NewTeacher {
+            name: "Linked Mentor".to_string(),
+            email: "linked@mentor.com".to_string(),
+            specialty: "Databases".to_string(),
+            applied_at: None,
+            company: None,
+            company_role: None,
+            bio: None,
+            course_info: None,
+            whatsapp: None,
+            linkedin: None,
+            comment_general: None,
+            comment_experience: None,
+            discord_id: None,
+            program_id: None,
+            timezone: Default::default(),
+            max_sessions_per_week: None,
+            locale: None,
+        };
+        let discord_id = DiscordId(12345);
+
+        let inserted_teacher = teacher_repo.insert(&new_teacher).await?;
+
+        assert_eq!(None, teacher_repo.find_by_discord_id(discord_id).await?);
+
+        let linked_teacher = teacher_repo
+            .link_discord_id(&inserted_teacher, discord_id)
+            .await?;
+
+        assert_eq!(Some(discord_id), linked_teacher.discord_id);
+        assert_eq!(
+            Some(linked_teacher),
+            teacher_repo.find_by_discord_id(discord_id).await?
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_teacher_find_page() -> Result<()> {
+        let db = init_db();
+        let teacher_repo = db.teacher_repository();
+
+        let new_teacher = NewTeacher {
+            name: "Paged Mentor".to_string(),
+            email: "first.paged@mentor.com".to_string(),
+            specialty: "Math".to_string(),
+            applied_at: None,
+            company: None,
+            company_role: None,
+            bio: None,
+            course_info: None,
+            whatsapp: None,
+            linkedin: None,
+            comment_general: None,
+            comment_experience: None,
+            discord_id: None,
+            program_id: None,
+            timezone: Default::default(),
+            max_sessions_per_week: None,
+            locale: None,
+        };
+        let first = teacher_repo.insert(&new_teacher).await?;
+        let second = teacher_repo
+            .insert(&NewTeacher {
+                email: "second.paged@mentor.com".to_string(),
+                ..new_teacher.clone()
+            })
+            .await?;
+        let third = teacher_repo
+            .insert(&NewTeacher {
+                email: "third.paged@mentor.com".to_string(),
+                ..new_teacher.clone()
+            })
+            .await?;
+        teacher_repo
+            .insert(&NewTeacher {
+                name: "Someone Else".to_string(),
+                email: "unrelated@mentor.com".to_string(),
+                ..new_teacher
+            })
+            .await?;
+
+        let filter = TeacherFilter {
+            search: Some("PAGED".to_string()),
+            ..Default::default()
+        };
+        let request = PageRequest::first(2);
+        let page = teacher_repo.find_page(&filter, &request).await?;
+        assert_eq!(vec![first.clone(), second.clone()], page.items);
+
+        let request = page.next_request(&request).unwrap();
+        let page = teacher_repo.find_page(&filter, &request).await?;
+        assert_eq!(vec![third.clone()], page.items);
+        assert_eq!(None, page.next_request(&request));
+
+        let page = teacher_repo
+            .find_page(
+                &filter,
+                &PageRequest::first(5).ordered(SortOrder::Descending),
+            )
+            .await?;
+        assert_eq!(vec![third, second, first], page.items);
+
+        Ok(())
+    }
 }
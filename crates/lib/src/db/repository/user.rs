@@ -1,46 +1,64 @@
-use std::sync::Arc;
-
 use async_trait::async_trait;
-use diesel::{OptionalExtension, QueryDsl};
-use diesel_async::{pooled_connection::deadpool::Pool, AsyncPgConnection, RunQueryDsl};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::RunQueryDsl;
 
 use super::{
-    super::schema::users, repo_find_all, repo_get, repo_insert, repo_remove, repo_update,
-    repo_upsert, Repository, UpdatableRepository,
+    super::{
+        connection::ConnectionSource,
+        schema::{booking_overrides, dm_opt_outs, users},
+        store::UserStore,
+    },
+    repo_find_all, repo_get, repo_insert, repo_remove, repo_update, repo_upsert, Repository,
+    UpdatableRepository,
 };
 use crate::{
-    error::{Error, Result},
-    model::{DiscordId, NewUser, PartialUser, Session, User},
+    error::Result,
+    model::{DiscordId, NewUser, PartialUser, User},
 };
 
 /// Manages User instances.
 #[derive(Clone)]
 pub struct UserRepository {
-    pool: Arc<Pool<AsyncPgConnection>>,
+    connection: ConnectionSource,
 }
 
 impl UserRepository {
     /// Creates a new UserRepository operating with the given
-    /// connection pool.
-    pub fn new(pool: &Arc<Pool<AsyncPgConnection>>) -> Self {
+    /// connection source (a connection pool or a transaction).
+    pub fn new(connection: &ConnectionSource) -> Self {
         Self {
-            pool: Arc::clone(pool),
+            connection: connection.clone(),
         }
     }
+}
 
-    /// Gets a User from the database by their Discord ID,
-    /// or inserts them instead.
-    pub async fn get_or_insert(&self, user: &NewUser) -> Result<User> {
-        if let Some(found_user) = self.get(user.discord_id).await? {
-            Ok(found_user)
-        } else {
-            self.insert(user).await
-        }
+#[async_trait]
+impl UserStore for UserRepository {
+    async fn get(&self, discord_id: DiscordId) -> Result<Option<User>> {
+        Repository::get(self, discord_id).await
+    }
+
+    async fn insert(&self, user: &NewUser) -> Result<User> {
+        Repository::insert(self, user).await
+    }
+
+    async fn remove(&self, user: &User) -> Result<usize> {
+        Repository::remove(self, user).await
+    }
+
+    async fn find_all(&self) -> Result<Vec<User>> {
+        Repository::find_all(self).await
+    }
+
+    async fn upsert(&self, user: &NewUser) -> Result<User> {
+        UpdatableRepository::upsert(self, user).await
     }
 
-    /// Attempts to insert a User; does nothing if such a User is already registered.
-    /// Returns the inserted row count (1 if a new User was inserted or 0 otherwise).
-    pub async fn insert_if_not_exists(&self, user: &NewUser) -> Result<usize> {
+    async fn update(&self, old_user: &User, new_user: PartialUser) -> Result<User> {
+        UpdatableRepository::update(self, old_user, new_user).await
+    }
+
+    async fn insert_if_not_exists(&self, user: &NewUser) -> Result<usize> {
         diesel::insert_into(users::table)
             .values(user)
             .on_conflict_do_nothing()
@@ -49,11 +67,69 @@ impl UserRepository {
             .map_err(From::from)
     }
 
-    /// Gets a Session's student User.
-    pub async fn find_student_of_session(&self, session: &Session) -> Result<User> {
-        self.get(session.student_id)
-            .await?
-            .ok_or_else(|| Error::Other("Could not find User that is student of a session!"))
+    async fn grant_booking_override(
+        &self,
+        student_id: DiscordId,
+        granted_by: DiscordId,
+    ) -> Result<usize> {
+        diesel::insert_into(booking_overrides::table)
+            .values((
+                booking_overrides::student_id.eq(student_id),
+                booking_overrides::granted_by.eq(granted_by),
+            ))
+            .on_conflict(booking_overrides::student_id)
+            .do_update()
+            .set((
+                booking_overrides::granted_by.eq(granted_by),
+                booking_overrides::granted_at.eq(diesel::dsl::now),
+            ))
+            .execute(&mut self.lock_connection().await?)
+            .await
+            .map_err(From::from)
+    }
+
+    async fn has_booking_override(&self, student_id: DiscordId) -> Result<bool> {
+        let count: i64 = booking_overrides::table
+            .find(student_id)
+            .count()
+            .get_result(&mut self.lock_connection().await?)
+            .await?;
+
+        Ok(count > 0)
+    }
+
+    async fn use_booking_override(&self, student_id: DiscordId) -> Result<usize> {
+        diesel::delete(booking_overrides::table.find(student_id))
+            .execute(&mut self.lock_connection().await?)
+            .await
+            .map_err(From::from)
+    }
+
+    async fn set_dm_opt_out(&self, discord_id: DiscordId, opted_out: bool) -> Result<usize> {
+        let mut conn = self.lock_connection().await?;
+
+        if opted_out {
+            diesel::insert_into(dm_opt_outs::table)
+                .values(dm_opt_outs::discord_id.eq(discord_id))
+                .on_conflict_do_nothing()
+                .execute(&mut conn)
+                .await
+        } else {
+            diesel::delete(dm_opt_outs::table.find(discord_id))
+                .execute(&mut conn)
+                .await
+        }
+        .map_err(From::from)
+    }
+
+    async fn has_dm_opt_out(&self, discord_id: DiscordId) -> Result<bool> {
+        let count: i64 = dm_opt_outs::table
+            .find(discord_id)
+            .count()
+            .get_result(&mut self.lock_connection().await?)
+            .await?;
+
+        Ok(count > 0)
     }
 }
 
@@ -69,8 +145,8 @@ impl Repository for UserRepository {
 
     const TABLE: Self::Table = users::table;
 
-    fn get_connection_pool(&self) -> Arc<Pool<AsyncPgConnection>> {
-        Arc::clone(&self.pool)
+    fn get_connection_source(&self) -> &ConnectionSource {
+        &self.connection
     }
 
     /// Gets a User by their Discord ID.
@@ -108,9 +184,8 @@ impl UpdatableRepository for UserRepository {
 mod tests {
     use super::super::tests::init_db;
     use crate::{
-        db::{Repository, UpdatableRepository},
         error::Result,
-        model::{DiscordId, NewUser},
+        model::{DiscordId, NewUser, PartialUser, Timezone},
     };
 
     #[tokio::test]
@@ -124,6 +199,8 @@ mod tests {
             name: "Joseph".to_string(),
             email: "joseph@gmail.com".to_string(),
             bio: Some("I am myself".to_string()),
+            timezone: Default::default(),
+            locale: None,
         };
 
         assert_eq!(None, repo.get(id).await?);
@@ -150,6 +227,8 @@ mod tests {
             name: "Joseph".to_string(),
             email: "joseph@gmail.com".to_string(),
             bio: Some("I am myself".to_string()),
+            timezone: Default::default(),
+            locale: None,
         };
         let other_user = NewUser {
             bio: None,
@@ -189,12 +268,16 @@ mod tests {
             name: "Robson".to_string(),
             email: "robson@gmail.com".to_string(),
             bio: Some("My bio".to_string()),
+            timezone: Default::default(),
+            locale: None,
         };
         let other_user_with_same_id = NewUser {
             discord_id: id,
             name: "Jefferson".to_string(),
             email: "jefferson@gmail.com".to_string(),
             bio: Some("I have the same ID, but I won't be inserted!".to_string()),
+            timezone: Default::default(),
+            locale: None,
         };
 
         assert_eq!(None, repo.get(id).await?);
@@ -206,4 +289,87 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_user_timezone_defaults_and_updates() -> Result<()> {
+        let db = init_db();
+        let repo = db.user_repository();
+
+        let id = DiscordId(4);
+        assert_eq!(Timezone::DEFAULT, repo.get_timezone(id).await?);
+
+        let user = repo
+            .insert(&NewUser {
+                discord_id: id,
+                name: "Maria".to_string(),
+                email: "maria@gmail.com".to_string(),
+                bio: None,
+                timezone: "Europe/Lisbon".parse().unwrap(),
+                locale: None,
+            })
+            .await?;
+        assert_eq!("Europe/Lisbon", repo.get_timezone(id).await?.name());
+
+        let manaus: Timezone = "America/Manaus".parse().unwrap();
+        let user = repo
+            .update(
+                &user,
+                PartialUser {
+                    timezone: Some(manaus),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        assert_eq!(manaus, user.timezone);
+        assert_eq!(Some(user), repo.get(id).await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_user_booking_override_grant_and_use() -> Result<()> {
+        let db = init_db();
+        let repo = db.user_repository();
+
+        let id = DiscordId(5);
+        let admin_id = DiscordId(6);
+        repo.insert(&NewUser {
+            discord_id: id,
+            name: "Clara".to_string(),
+            email: "clara@gmail.com".to_string(),
+            bio: None,
+            timezone: Default::default(),
+            locale: None,
+        })
+        .await?;
+
+        assert!(!repo.has_booking_override(id).await?);
+        assert_eq!(1, repo.grant_booking_override(id, admin_id).await?);
+        assert_eq!(1, repo.grant_booking_override(id, admin_id).await?);
+        assert!(repo.has_booking_override(id).await?);
+        assert_eq!(1, repo.use_booking_override(id).await?);
+        assert!(!repo.has_booking_override(id).await?);
+        assert_eq!(0, repo.use_booking_override(id).await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_user_dm_opt_out_set_and_unset() -> Result<()> {
+        let db = init_db();
+        let repo = db.user_repository();
+
+        // mentors may opt out without being registered as students
+        let id = DiscordId(7);
+
+        assert!(!repo.has_dm_opt_out(id).await?);
+        assert_eq!(1, repo.set_dm_opt_out(id, true).await?);
+        assert_eq!(0, repo.set_dm_opt_out(id, true).await?);
+        assert!(repo.has_dm_opt_out(id).await?);
+        assert_eq!(1, repo.set_dm_opt_out(id, false).await?);
+        assert!(!repo.has_dm_opt_out(id).await?);
+        assert_eq!(0, repo.set_dm_opt_out(id, false).await?);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,328 @@
+use async_trait::async_trait;
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+use super::{
+    super::{connection::ConnectionSource, schema::waitlist_entries, store::WaitlistStore},
+    repo_find_all, repo_find_by, repo_find_by_first, repo_get, repo_insert, repo_remove,
+    Repository,
+};
+use crate::{
+    error::Result,
+    model::{DiscordId, NewWaitlistEntry, WaitlistEntry, WaitlistStatus},
+};
+
+/// The statuses of entries which are still in the waitlist.
+const ACTIVE_STATUSES: [WaitlistStatus; 2] = [WaitlistStatus::Waiting, WaitlistStatus::Offered];
+
+/// Manages WaitlistEntry instances.
+#[derive(Clone)]
+pub struct WaitlistRepository {
+    connection: ConnectionSource,
+}
+
+impl WaitlistRepository {
+    /// Creates a new WaitlistRepository operating with the given
+    /// connection source (a connection pool or a transaction).
+    pub fn new(connection: &ConnectionSource) -> Self {
+        Self {
+            connection: connection.clone(),
+        }
+    }
+
+    /// Changes the status of a student's active entry, if any.
+    async fn set_active_status(
+        &self,
+        student_id: DiscordId,
+        status: WaitlistStatus,
+    ) -> Result<usize> {
+        diesel::update(waitlist_entries::table)
+            .filter(waitlist_entries::student_id.eq(student_id))
+            .filter(waitlist_entries::status.eq_any(ACTIVE_STATUSES))
+            .set(waitlist_entries::status.eq(status))
+            .execute(&mut self.lock_connection().await?)
+            .await
+            .map_err(From::from)
+    }
+}
+
+#[async_trait]
+impl WaitlistStore for WaitlistRepository {
+    async fn get(&self, id: i64) -> Result<Option<WaitlistEntry>> {
+        Repository::get(self, id).await
+    }
+
+    async fn join(&self, entry: &NewWaitlistEntry) -> Result<Option<WaitlistEntry>> {
+        // conflicts with the student's active entry, if any
+        diesel::insert_into(waitlist_entries::table)
+            .values(entry)
+            .on_conflict_do_nothing()
+            .get_result(&mut self.lock_connection().await?)
+            .await
+            .optional()
+            .map_err(From::from)
+    }
+
+    async fn find_active_by_student(&self, student_id: DiscordId) -> Result<Option<WaitlistEntry>> {
+        repo_find_by_first!(
+            self, waitlist_entries::table;
+
+            waitlist_entries::student_id.eq(student_id)
+            waitlist_entries::status.eq_any(ACTIVE_STATUSES)
+        )
+    }
+
+    async fn leave(&self, student_id: DiscordId) -> Result<usize> {
+        self.set_active_status(student_id, WaitlistStatus::Left)
+            .await
+    }
+
+    async fn fulfill(&self, student_id: DiscordId) -> Result<usize> {
+        self.set_active_status(student_id, WaitlistStatus::Fulfilled)
+            .await
+    }
+
+    async fn find_waiting(&self) -> Result<Vec<WaitlistEntry>> {
+        waitlist_entries::table
+            .filter(waitlist_entries::status.eq(WaitlistStatus::Waiting))
+            .order_by((
+                waitlist_entries::joined_at.asc(),
+                waitlist_entries::id.asc(),
+            ))
+            .get_results(&mut self.lock_connection().await?)
+            .await
+            .map_err(From::from)
+    }
+
+    async fn find_offered(&self) -> Result<Vec<WaitlistEntry>> {
+        repo_find_by!(
+            self, waitlist_entries::table;
+            waitlist_entries::status.eq(WaitlistStatus::Offered);
+            @order_by: waitlist_entries::joined_at.asc()
+        )
+    }
+
+    async fn offer(
+        &self,
+        entry_id: i64,
+        availability_id: i64,
+        start_at: chrono::DateTime<chrono::Utc>,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Option<WaitlistEntry>> {
+        diesel::update(waitlist_entries::table.find(entry_id))
+            .filter(waitlist_entries::status.eq(WaitlistStatus::Waiting))
+            .set((
+                waitlist_entries::status.eq(WaitlistStatus::Offered),
+                waitlist_entries::offered_availability_id.eq(availability_id),
+                waitlist_entries::offered_start_at.eq(start_at),
+                waitlist_entries::offer_expires_at.eq(expires_at),
+            ))
+            .get_result(&mut self.lock_connection().await?)
+            .await
+            .optional()
+            .map_err(From::from)
+    }
+
+    async fn expire_offers(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<WaitlistEntry>> {
+        diesel::update(waitlist_entries::table)
+            .filter(waitlist_entries::status.eq(WaitlistStatus::Offered))
+            .filter(waitlist_entries::offer_expires_at.le(now))
+            .set(waitlist_entries::status.eq(WaitlistStatus::Expired))
+            .get_results(&mut self.lock_connection().await?)
+            .await
+            .map_err(From::from)
+    }
+}
+
+#[async_trait]
+impl Repository for WaitlistRepository {
+    type Table = waitlist_entries::table;
+
+    type Entity = WaitlistEntry;
+
+    type NewEntity = NewWaitlistEntry;
+
+    type PrimaryKey = i64;
+
+    const TABLE: Self::Table = waitlist_entries::table;
+
+    fn get_connection_source(&self) -> &ConnectionSource {
+        &self.connection
+    }
+
+    /// Gets a WaitlistEntry by its ID.
+    async fn get(&self, id: i64) -> Result<Option<WaitlistEntry>> {
+        repo_get!(self, waitlist_entries::table; id)
+    }
+
+    async fn insert(&self, entry: &NewWaitlistEntry) -> Result<WaitlistEntry> {
+        repo_insert!(self, waitlist_entries::table; entry)
+    }
+
+    async fn remove(&self, entry: &WaitlistEntry) -> Result<usize> {
+        repo_remove!(self; entry)
+    }
+
+    async fn find_all(&self) -> Result<Vec<WaitlistEntry>> {
+        repo_find_all!(self, waitlist_entries::table, waitlist_entries::table; @order_by: waitlist_entries::joined_at.asc())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::init_db;
+    use crate::{
+        error::Result,
+        model::{
+            DiscordId, NewAvailability, NewTeacher, NewUser, NewWaitlistEntry, WaitlistStatus,
+            Weekday,
+        },
+    };
+
+    #[tokio::test]
+    async fn test_waitlist_join_offer_and_expire() -> Result<()> {
+        let db = init_db();
+        let waitlist_repo = db.waitlist_repository();
+
+        let mut students = Vec::new();
+        for id in [141, 142] {
+            students.push(
+                db.user_repository()
+                    .insert(&NewUser {
+                        discord_id: DiscordId(id),
+                        name: format!("Waiting Student {id}"),
+                        email: format!("waiting{id}@student.com"),
+                        bio: None,
+                        timezone: Default::default(),
+                        locale: None,
+                    })
+                    .await?,
+            );
+        }
+
+        let new_entry = |student_id| NewWaitlistEntry {
+            student_id,
+            teacher_id: None,
+            specialty: Some("Math".to_string()),
+        };
+        let first = waitlist_repo
+            .join(&new_entry(students[0].discord_id))
+            .await?
+            .unwrap();
+        let second = waitlist_repo
+            .join(&new_entry(students[1].discord_id))
+            .await?
+            .unwrap();
+        assert_eq!(WaitlistStatus::Waiting, first.status);
+
+        // students can't join twice
+        assert_eq!(
+            None,
+            waitlist_repo
+                .join(&new_entry(students[0].discord_id))
+                .await?
+        );
+
+        let waiting = waitlist_repo.find_waiting().await?;
+        let position = |id| waiting.iter().position(|entry| entry.id == id);
+        assert!(position(first.id) < position(second.id));
+
+        let teacher = db
+            .teacher_repository()
+            .insert(&NewTeacher {
+                name: "John Doe".to_string(),
+                email: "waitlist@mentor.com".to_string(),
+                specialty: "Math".to_string(),
+                applied_at: None,
+                company: None,
+                company_role: None,
+                bio: None,
+                course_info: None,
+                whatsapp: None,
+                linkedin: None,
+                comment_general: None,
+                comment_experience: None,
+                discord_id: None,
+                program_id: None,
+                timezone: Default::default(),
+                max_sessions_per_week: None,
+                locale: None,
+            })
+            .await?;
+        let availability = db
+            .availability_repository()
+            .insert(&NewAvailability {
+                teacher_id: teacher.id,
+                weekday: Weekday::Monday,
+                time_start: chrono::NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+                expired: false,
+                duration: 1,
+                valid_from: None,
+                valid_until: None,
+                program_id: None,
+                capacity: 1,
+            })
+            .await?;
+
+        // the first student is offered a time, until it expires
+        let now = chrono::Utc::now();
+        let expires_at = now + chrono::Duration::minutes(30);
+        let offered = waitlist_repo
+            .offer(first.id, availability.id, now, expires_at)
+            .await?
+            .unwrap();
+        assert_eq!(WaitlistStatus::Offered, offered.status);
+        assert_eq!(Some(availability.id), offered.offered_availability_id);
+        assert_eq!(
+            None,
+            waitlist_repo
+                .offer(first.id, availability.id, now, expires_at)
+                .await?
+        );
+        assert!(waitlist_repo
+            .find_offered()
+            .await?
+            .iter()
+            .any(|entry| entry.id == first.id));
+
+        assert!(waitlist_repo.expire_offers(now).await?.is_empty());
+        let expired = waitlist_repo.expire_offers(expires_at).await?;
+        assert!(expired.iter().any(|entry| entry.id == first.id));
+        assert_eq!(
+            None,
+            waitlist_repo
+                .find_active_by_student(students[0].discord_id)
+                .await?
+        );
+
+        // leaving and fulfilling only affect active entries
+        assert_eq!(1, waitlist_repo.leave(students[1].discord_id).await?);
+        assert_eq!(0, waitlist_repo.leave(students[1].discord_id).await?);
+        assert_eq!(
+            None,
+            waitlist_repo
+                .find_active_by_student(students[1].discord_id)
+                .await?
+        );
+        assert_eq!(
+            WaitlistStatus::Left,
+            waitlist_repo.get(second.id).await?.unwrap().status
+        );
+
+        // a student who left may join again
+        let rejoined = waitlist_repo
+            .join(&new_entry(students[1].discord_id))
+            .await?
+            .unwrap();
+        assert_eq!(1, waitlist_repo.fulfill(students[1].discord_id).await?);
+        assert_eq!(
+            WaitlistStatus::Fulfilled,
+            waitlist_repo.get(rejoined.id).await?.unwrap().status
+        );
+
+        Ok(())
+    }
+}
@@ -8,6 +8,63 @@ diesel::table! {
         time_start -> Time,
         expired -> Bool,
         duration -> Int2,
+        valid_from -> Nullable<Date>,
+        valid_until -> Nullable<Date>,
+        program_id -> Nullable<Int8>,
+        capacity -> Int2,
+    }
+}
+
+diesel::table! {
+    booking_overrides (student_id) {
+        student_id -> Varchar,
+        granted_by -> Varchar,
+        granted_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    dm_opt_outs (discord_id) {
+        discord_id -> Varchar,
+        opted_out_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    programs (id) {
+        id -> Int8,
+        name -> Varchar,
+        start_date -> Date,
+        end_date -> Date,
+        status -> Int2,
+        enrollment_open -> Bool,
+    }
+}
+
+diesel::table! {
+    session_feedback (session_id, author_id) {
+        session_id -> Int8,
+        author_id -> Varchar,
+        from_mentor -> Bool,
+        rating -> Int2,
+        comment -> Nullable<Text>,
+        submitted_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    session_reminders (session_id, offset_minutes) {
+        session_id -> Int8,
+        offset_minutes -> Int4,
+        sent_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    session_students (session_id, student_id) {
+        session_id -> Int8,
+        student_id -> Varchar,
+        joined_at -> Timestamptz,
     }
 }
 
@@ -23,6 +80,24 @@ diesel::table! {
         calendar_event_id -> Nullable<Varchar>,
         start_at -> Timestamptz,
         end_at -> Timestamptz,
+        status -> Int2,
+        cancelled_at -> Nullable<Timestamptz>,
+        cancel_reason -> Nullable<Text>,
+        feedback_requested -> Bool,
+    }
+}
+
+diesel::table! {
+    tags (id) {
+        id -> Int8,
+        name -> Varchar,
+    }
+}
+
+diesel::table! {
+    teacher_tags (teacher_id, tag_id) {
+        teacher_id -> Int8,
+        tag_id -> Int8,
     }
 }
 
@@ -41,6 +116,11 @@ diesel::table! {
         linkedin -> Nullable<Varchar>,
         comment_general -> Nullable<Varchar>,
         comment_experience -> Nullable<Varchar>,
+        discord_id -> Nullable<Varchar>,
+        program_id -> Nullable<Int8>,
+        timezone -> Varchar,
+        max_sessions_per_week -> Nullable<Int2>,
+        locale -> Nullable<Varchar>,
     }
 }
 
@@ -50,12 +130,54 @@ diesel::table! {
         name -> Varchar,
         email -> Varchar,
         bio -> Nullable<Text>,
+        timezone -> Varchar,
+        locale -> Nullable<Varchar>,
+    }
+}
+
+diesel::table! {
+    waitlist_entries (id) {
+        id -> Int8,
+        student_id -> Varchar,
+        teacher_id -> Nullable<Int8>,
+        specialty -> Nullable<Varchar>,
+        status -> Int2,
+        joined_at -> Timestamptz,
+        offered_availability_id -> Nullable<Int8>,
+        offered_start_at -> Nullable<Timestamptz>,
+        offer_expires_at -> Nullable<Timestamptz>,
     }
 }
 
+diesel::joinable!(availability -> programs (program_id));
 diesel::joinable!(availability -> teachers (teacher_id));
+diesel::joinable!(booking_overrides -> users (student_id));
+diesel::joinable!(session_feedback -> sessions (session_id));
+diesel::joinable!(session_reminders -> sessions (session_id));
+diesel::joinable!(session_students -> sessions (session_id));
+diesel::joinable!(session_students -> users (student_id));
 diesel::joinable!(sessions -> availability (availability_id));
 diesel::joinable!(sessions -> teachers (teacher_id));
 diesel::joinable!(sessions -> users (student_id));
+diesel::joinable!(teacher_tags -> tags (tag_id));
+diesel::joinable!(teacher_tags -> teachers (teacher_id));
+diesel::joinable!(teachers -> programs (program_id));
+diesel::joinable!(waitlist_entries -> availability (offered_availability_id));
+diesel::joinable!(waitlist_entries -> teachers (teacher_id));
+diesel::joinable!(waitlist_entries -> users (student_id));
 
-diesel::allow_tables_to_appear_in_same_query!(availability, sessions, teachers, users,);
+diesel::allow_tables_to_appear_in_same_query!(
+    availability,
+    booking_overrides,
+    dm_opt_outs,
+    programs,
+    session_feedback,
+    session_reminders,
+    session_students,
+    sessions,
+    tags,
+    teacher_tags,
+    teachers,
+    users,
+    waitlist_entries,
+);
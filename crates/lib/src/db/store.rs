@@ -0,0 +1,634 @@
+//! Contains the operations supported by each repository, regardless of where
+//! its entities are stored. They are implemented both by the database repositories
+//! (see [`UserRepository`]) and by the in-memory ones (see [`MemoryUserRepository`]),
+//! which are used to test without a database.
+//!
+//! [`UserRepository`]: super::UserRepository
+//! [`MemoryUserRepository`]: super::memory::MemoryUserRepository
+use async_trait::async_trait;
+
+use super::{
+    pagination::{Page, PageRequest},
+    repository::{SessionCursor, SessionFilter, TeacherFilter},
+};
+use crate::{
+    error::{Error, Result},
+    model::{
+        Availability, BookingPolicy, DiscordId, NewAvailability, NewProgram, NewSession,
+        NewSessionFeedback, NewTeacher, NewUser, NewWaitlistEntry, PartialAvailability,
+        PartialProgram, PartialSession, PartialTeacher, PartialUser, Program, ProgramStatus,
+        Session, SessionFeedback, SessionStatus, Tag, Teacher, TeacherRating, Timezone, User,
+        WaitlistEntry,
+    },
+};
+
+/// Operations on stored User instances.
+#[async_trait]
+pub trait UserStore: Send + Sync {
+    /// Gets a User by their Discord ID.
+    async fn get(&self, discord_id: DiscordId) -> Result<Option<User>>;
+
+    /// Inserts a new User.
+    async fn insert(&self, user: &NewUser) -> Result<User>;
+
+    /// Removes a User, returning the removed count.
+    async fn remove(&self, user: &User) -> Result<usize>;
+
+    /// Finds all stored Users.
+    async fn find_all(&self) -> Result<Vec<User>>;
+
+    /// Inserts a new User, or updates them if they already exist.
+    async fn upsert(&self, user: &NewUser) -> Result<User>;
+
+    /// Updates an existing User with new data.
+    async fn update(&self, old_user: &User, new_user: PartialUser) -> Result<User>;
+
+    /// Attempts to insert a User; does nothing if such a User is already registered.
+    /// Returns the inserted row count (1 if a new User was inserted or 0 otherwise).
+    async fn insert_if_not_exists(&self, user: &NewUser) -> Result<usize>;
+
+    /// Allows a student to book their next session regardless of the booking limits
+    /// (replacing any previous override). Returns the inserted or updated row count.
+    async fn grant_booking_override(
+        &self,
+        student_id: DiscordId,
+        granted_by: DiscordId,
+    ) -> Result<usize>;
+
+    /// Checks whether a student was granted a booking override which wasn't used yet.
+    async fn has_booking_override(&self, student_id: DiscordId) -> Result<bool>;
+
+    /// Uses up (removes) a student's booking override, returning the removed count.
+    async fn use_booking_override(&self, student_id: DiscordId) -> Result<usize>;
+
+    /// Sets whether the Discord user with the given ID (a student or a linked mentor,
+    /// registered or not) opted out of being notified of their sessions through DMs.
+    /// Returns the inserted or removed row count.
+    async fn set_dm_opt_out(&self, discord_id: DiscordId, opted_out: bool) -> Result<usize>;
+
+    /// Checks whether the Discord user with the given ID opted out of DM notifications.
+    async fn has_dm_opt_out(&self, discord_id: DiscordId) -> Result<bool>;
+
+    /// Gets a User by their Discord ID, or inserts them instead.
+    async fn get_or_insert(&self, user: &NewUser) -> Result<User> {
+        if let Some(found_user) = self.get(user.discord_id).await? {
+            Ok(found_user)
+        } else {
+            self.insert(user).await
+        }
+    }
+
+    /// Gets a Session's student User.
+    async fn find_student_of_session(&self, session: &Session) -> Result<User> {
+        self.get(session.student_id)
+            .await?
+            .ok_or_else(|| Error::Other("Could not find User that is student of a session!"))
+    }
+
+    /// Gets the timezone of the User with the given Discord ID, or the default
+    /// timezone if they aren't registered.
+    async fn get_timezone(&self, discord_id: DiscordId) -> Result<Timezone> {
+        Ok(self
+            .get(discord_id)
+            .await?
+            .map(|user| user.timezone)
+            .unwrap_or_default())
+    }
+}
+
+/// Operations on stored Teacher instances.
+#[async_trait]
+pub trait TeacherStore: Send + Sync {
+    /// Gets a Teacher by their ID.
+    async fn get(&self, id: i64) -> Result<Option<Teacher>>;
+
+    /// Inserts a new Teacher.
+    async fn insert(&self, teacher: &NewTeacher) -> Result<Teacher>;
+
+    /// Removes a Teacher, returning the removed count.
+    async fn remove(&self, teacher: &Teacher) -> Result<usize>;
+
+    /// Finds all stored Teachers.
+    async fn find_all(&self) -> Result<Vec<Teacher>>;
+
+    /// Inserts a new Teacher, or updates them if they already exist.
+    async fn upsert(&self, teacher: &NewTeacher) -> Result<Teacher>;
+
+    /// Updates an existing Teacher with new data.
+    async fn update(&self, old_teacher: &Teacher, new_teacher: PartialTeacher) -> Result<Teacher>;
+
+    /// Returns the teacher with the given email, if any.
+    async fn find_by_email(&self, email: &str) -> Result<Option<Teacher>>;
+
+    /// Finds a page of Teachers matching the given filter, sorted by their IDs
+    /// (which are also the page cursors) in the requested order.
+    async fn find_page(
+        &self,
+        filter: &TeacherFilter,
+        request: &PageRequest<i64>,
+    ) -> Result<Page<Teacher, i64>>;
+
+    /// Returns the teacher linked to the given Discord user, if any.
+    async fn find_by_discord_id(&self, discord_id: DiscordId) -> Result<Option<Teacher>>;
+
+    /// Attempts to insert a Teacher; does nothing if such a Teacher (with the same e-mail
+    /// or something) is already registered.
+    /// Returns the inserted row count (1 if a new Teacher was inserted or 0 otherwise).
+    async fn insert_if_not_exists(&self, teacher: &NewTeacher) -> Result<usize>;
+
+    /// Gets all teachers linked to certain availabilities.
+    async fn find_by_availabilities(
+        &self,
+        availabilities: &[Availability],
+    ) -> Result<Vec<(Teacher, Availability)>>;
+
+    /// Fetches teachers with an ID among a certain group of IDs.
+    async fn find_by_ids(&self, ids: &[i64]) -> Result<Vec<Teacher>>;
+
+    /// Links a teacher to a Discord user, so that they may interact
+    /// with the bot as that teacher.
+    async fn link_discord_id(&self, teacher: &Teacher, discord_id: DiscordId) -> Result<Teacher> {
+        self.update(
+            teacher,
+            PartialTeacher {
+                discord_id: Some(Some(discord_id)),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    /// Gets a Session's teacher.
+    async fn find_by_session(&self, session: &Session) -> Result<Option<Teacher>> {
+        self.get(session.teacher_id).await
+    }
+}
+
+/// Operations on stored Availability instances.
+#[async_trait]
+pub trait AvailabilityStore: Send + Sync {
+    /// Gets an Availability by its ID.
+    async fn get(&self, id: i64) -> Result<Option<Availability>>;
+
+    /// Inserts a new Availability.
+    async fn insert(&self, avail: &NewAvailability) -> Result<Availability>;
+
+    /// Removes an Availability, returning the removed count.
+    async fn remove(&self, avail: &Availability) -> Result<usize>;
+
+    /// Finds all stored Availabilities.
+    async fn find_all(&self) -> Result<Vec<Availability>>;
+
+    /// Inserts a new Availability, or updates it if it already exists.
+    async fn upsert(&self, avail: &NewAvailability) -> Result<Availability>;
+
+    /// Updates an existing Availability with new data.
+    async fn update(
+        &self,
+        old_avail: &Availability,
+        new_avail: PartialAvailability,
+    ) -> Result<Availability>;
+
+    /// Get all Availability instances attached to a certain teacher.
+    async fn find_by_teacher(&self, teacher: &Teacher) -> Result<Vec<Availability>>;
+
+    /// Marks the availabilities with the given IDs, attached to a certain teacher,
+    /// as expired, such that they can no longer be picked for new sessions.
+    /// Returns the amount of availabilities which were updated.
+    async fn expire_by_teacher_and_ids(&self, teacher_id: i64, ids: &[i64]) -> Result<usize>;
+
+    /// Sets the validity window (the first and last dates in which they can be scheduled)
+    /// of all non-expired availabilities, or only those of a certain teacher, if given.
+    /// Returns the amount of availabilities which were updated.
+    async fn set_validity_window(
+        &self,
+        teacher_id: Option<i64>,
+        valid_from: Option<chrono::NaiveDate>,
+        valid_until: Option<chrono::NaiveDate>,
+    ) -> Result<usize>;
+
+    /// Get the Availability a Session is attached to.
+    async fn find_by_session(&self, session: &Session) -> Result<Option<Availability>>;
+
+    /// Checks if the given availability exists and is taken by a scheduled Session
+    /// which starts after the given date and has no seats left.
+    async fn check_is_taken_at(
+        &self,
+        id: i64,
+        datetime: &chrono::DateTime<chrono::FixedOffset>,
+    ) -> Result<bool>;
+
+    /// Finds all non-taken (or with seats left), non-expired availabilities within a week
+    /// of the given datetime, which are valid at the date they would occur, and which
+    /// belong to a program with open enrollment running at that date.
+    /// Each availability's date is taken in its mentor's timezone.
+    async fn find_nontaken_within_a_week_of_date(
+        &self,
+        datetime: chrono::DateTime<chrono::FixedOffset>,
+    ) -> Result<Vec<Availability>>;
+
+    /// Finds all non-taken (or with seats left), non-expired availabilities which are
+    /// valid at the given datetime, and which belong to a program with open enrollment
+    /// running at that date.
+    /// The date (and its weekday) is taken in the given datetime's offset.
+    async fn find_nontaken_at_date(
+        &self,
+        datetime: chrono::DateTime<chrono::FixedOffset>,
+    ) -> Result<Vec<Availability>>;
+}
+
+/// Operations on stored Program instances.
+#[async_trait]
+pub trait ProgramStore: Send + Sync {
+    /// Gets a Program by its ID.
+    async fn get(&self, id: i64) -> Result<Option<Program>>;
+
+    /// Inserts a new Program.
+    async fn insert(&self, program: &NewProgram) -> Result<Program>;
+
+    /// Removes a Program, returning the removed count.
+    async fn remove(&self, program: &Program) -> Result<usize>;
+
+    /// Finds all stored Programs, starting later first.
+    async fn find_all(&self) -> Result<Vec<Program>>;
+
+    /// Inserts a new Program, or updates it if it already exists.
+    async fn upsert(&self, program: &NewProgram) -> Result<Program>;
+
+    /// Updates an existing Program with new data.
+    async fn update(&self, old_program: &Program, new_program: PartialProgram) -> Result<Program>;
+
+    /// Returns the program with the given name, if any.
+    async fn find_by_name(&self, name: &str) -> Result<Option<Program>>;
+
+    /// Attaches a teacher, as well as all of their non-expired availabilities,
+    /// to a program. Returns the amount of availabilities which were updated.
+    async fn attach_teacher(&self, program: &Program, teacher: &Teacher) -> Result<usize>;
+
+    /// Opens or closes a program's enrollment, determining whether or not students can
+    /// schedule sessions with its mentors.
+    async fn set_enrollment_open(&self, program: &Program, open: bool) -> Result<Program> {
+        self.update(
+            program,
+            PartialProgram {
+                enrollment_open: Some(open),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    /// Archives a program, closing its enrollment. Its sessions are kept for
+    /// historical purposes.
+    async fn archive(&self, program: &Program) -> Result<Program> {
+        self.update(
+            program,
+            PartialProgram {
+                status: Some(ProgramStatus::Archived),
+                enrollment_open: Some(false),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+}
+
+/// Operations on stored Session instances.
+#[async_trait]
+pub trait SessionStore: Send + Sync {
+    /// Gets a Session by its ID.
+    async fn get(&self, id: i64) -> Result<Option<Session>>;
+
+    /// Inserts a Session, registering its student as attending it.
+    async fn insert(&self, session: &NewSession) -> Result<Session>;
+
+    /// Removes a Session (along with its students and reminders), returning the removed count.
+    async fn remove(&self, session: &Session) -> Result<usize>;
+
+    /// Finds all stored Sessions, in ascending 'start_at' order (starting earlier first).
+    async fn find_all(&self) -> Result<Vec<Session>>;
+
+    /// Inserts a new Session, or updates it if it already exists.
+    async fn upsert(&self, session: &NewSession) -> Result<Session>;
+
+    /// Updates an existing Session with new data.
+    async fn update(&self, old_session: &Session, new_session: PartialSession) -> Result<Session>;
+
+    /// Finds a Session and retrieves the associated teacher's Teacher object.
+    async fn get_with_teacher(&self, session_id: i64) -> Result<Option<(Session, Teacher)>>;
+
+    /// Finds a Session and retrieves both the associated teacher's Teacher object
+    /// and the student's User object.
+    async fn get_with_participants(
+        &self,
+        session_id: i64,
+    ) -> Result<Option<(Session, Teacher, User)>>;
+
+    /// Searches for Sessions by a particular teacher,
+    /// in ascending 'start_at' order (starting earlier first).
+    async fn find_by_teacher(&self, teacher_id: i64) -> Result<Vec<Session>>;
+
+    /// Searches for scheduled Sessions by a particular teacher which haven't started yet,
+    /// along with each session's student, in ascending 'start_at' order
+    /// (starting earlier first).
+    async fn find_upcoming_by_teacher_with_students(
+        &self,
+        teacher_id: i64,
+    ) -> Result<Vec<(Session, User)>>;
+
+    /// Searches for scheduled Sessions by a particular teacher starting within the given
+    /// (inclusive) period, in ascending 'start_at' order (starting earlier first).
+    async fn find_scheduled_by_teacher_starting_between(
+        &self,
+        teacher_id: i64,
+        starts_from: chrono::DateTime<chrono::Utc>,
+        starts_until: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<Session>>;
+
+    /// Finds a page of Sessions matching the given filter, sorted by their start time
+    /// (and then by ID) in the requested order.
+    async fn find_page(
+        &self,
+        filter: &SessionFilter,
+        request: &PageRequest<SessionCursor>,
+    ) -> Result<Page<Session, SessionCursor>>;
+
+    /// Searches for Sessions held within a particular program (through their availabilities),
+    /// along with each session's teacher and student, in ascending 'start_at' order
+    /// (starting earlier first).
+    async fn find_by_program_with_participants(
+        &self,
+        program_id: i64,
+    ) -> Result<Vec<(Session, Teacher, User)>>;
+
+    /// Finds the scheduled Session claiming a certain availability at a certain time, if any.
+    async fn find_by_availability_starting_at(
+        &self,
+        availability_id: i64,
+        start_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Option<Session>>;
+
+    /// Moves a scheduled Session to another availability (possibly of another teacher) and
+    /// time, atomically, as long as no other scheduled Session of that teacher overlaps with
+    /// the new time, and the teacher didn't reach their weekly cap in its week (as in
+    /// [`SessionStore::book`]). Its reminders are reset, as they refer to the old time.
+    /// Its student's limits on sessions with the same mentor
+    /// (see [`BookingPolicy::check_mentor`]) are checked within the same transaction, not
+    /// counting the session itself, failing with [`Error::BookingRefused`] if exceeded.
+    /// Returns the updated Session, or 'None' if it was no longer scheduled or if the
+    /// new time was taken in the meantime.
+    async fn reschedule(
+        &self,
+        session: &Session,
+        policy: &BookingPolicy,
+        teacher_id: i64,
+        availability_id: i64,
+        start_at: chrono::DateTime<chrono::Utc>,
+        end_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Option<Session>>;
+
+    /// Books a new Session (adding its student to it), as long as no other scheduled session
+    /// of its teacher overlaps with it, and its teacher didn't reach their weekly cap
+    /// (see [`Teacher::is_at_weekly_cap`]) in its week. The checks and the insertion happen
+    /// atomically, so concurrent bookings can't both succeed.
+    /// Fails with [`Error::SessionTimeTaken`] if the session's time was already taken
+    /// (or its teacher has no more sessions to give in that week).
+    /// The given policy's limits for its student are also checked atomically (with the
+    /// student's other bookings), failing with [`Error::BookingRefused`] if exceeded, or if
+    /// the time is held for another student in the waitlist, who was offered it.
+    async fn book(&self, session: &NewSession, policy: &BookingPolicy) -> Result<Session>;
+
+    /// Adds a student to a (group) Session.
+    /// Returns the inserted row count (0 if the student was already attending it).
+    async fn add_student(&self, session_id: i64, student_id: DiscordId) -> Result<usize>;
+
+    /// Adds a student to a (group) Session, as long as it has less than 'capacity' students,
+    /// such that concurrent joins can't exceed it. The given policy's limits for the student
+    /// are also checked atomically, failing with [`Error::BookingRefused`] if exceeded.
+    /// Returns whether the student was added (false if the session was full or if the student
+    /// was already attending it).
+    async fn add_student_if_seats_left(
+        &self,
+        session_id: i64,
+        student_id: DiscordId,
+        capacity: i16,
+        policy: &BookingPolicy,
+    ) -> Result<bool>;
+
+    /// Removes a student from a (group) Session.
+    /// Returns the removed row count (0 if the student wasn't attending it).
+    async fn remove_student(&self, session_id: i64, student_id: DiscordId) -> Result<usize>;
+
+    /// Gets all students attending a Session, in the order they joined it.
+    async fn find_students(&self, session_id: i64) -> Result<Vec<User>>;
+
+    /// Searches for scheduled Sessions, along with each session's teacher, which start
+    /// within the given amount of minutes after 'now' (but haven't started yet) and whose
+    /// students weren't reminded of them yet, at that offset or closer to their start.
+    /// Sessions are returned in ascending 'start_at' order (starting earlier first).
+    async fn find_needing_reminder(
+        &self,
+        offset_minutes: i32,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<(Session, Teacher)>>;
+
+    /// Registers that a reminder was sent for a Session at the given offset (in minutes
+    /// before its start). Returns the inserted row count (0 if it was already registered,
+    /// in which case the reminder shouldn't be sent again).
+    async fn register_reminder(&self, session_id: i64, offset_minutes: i32) -> Result<usize>;
+
+    /// Searches for scheduled or completed Sessions, along with each session's teacher,
+    /// which ended at or before 'now' and whose participants weren't asked for feedback
+    /// yet. Sessions are returned in ascending 'start_at' order (starting earlier first).
+    async fn find_needing_feedback_request(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<(Session, Teacher)>>;
+
+    /// Registers that a Session's participants were asked for feedback. Returns the
+    /// updated row count (0 if it was already registered, in which case they shouldn't
+    /// be asked again).
+    async fn register_feedback_request(&self, session_id: i64) -> Result<usize>;
+
+    /// Searches for Sessions attended by a particular student (with a particular Discord ID),
+    /// in ascending 'start_at' order (starting earlier first).
+    async fn find_by_student(&self, student_id: DiscordId) -> Result<Vec<Session>>;
+
+    /// Searches for Sessions by a particular student (with a particular Discord ID),
+    /// in ascending 'start_at' order (starting earlier first), based on a partial
+    /// session ID input.
+    /// If 'only_active' is given, only active sessions (scheduled ones which haven't
+    /// already started) are displayed.
+    async fn find_student_autocomplete(
+        &self,
+        student_id: DiscordId,
+        partial_id: &str,
+        only_active: bool,
+    ) -> Result<Vec<Session>>;
+
+    /// Searches for Sessions starting after a certain point in time,
+    /// in ascending order (starting earlier first).
+    async fn find_starts_after(
+        &self,
+        starts_after: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<Session>>;
+
+    /// Searches for all Sessions starting before a certain point in time,
+    /// in ascending order (starting earlier first).
+    async fn find_starts_before(
+        &self,
+        starts_before: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<Session>>;
+
+    /// Searches for all Sessions starting after the current point in time,
+    /// in ascending order (starting earlier first).
+    async fn find_will_start(&self) -> Result<Vec<Session>> {
+        self.find_starts_after(chrono::Utc::now()).await
+    }
+
+    /// Cancels a Session with the given (cancellation) status, registering when it was
+    /// cancelled and why. The session is kept for future reference.
+    async fn cancel(
+        &self,
+        session: &Session,
+        status: SessionStatus,
+        reason: Option<String>,
+    ) -> Result<Session> {
+        if !status.is_cancelled() {
+            return Err(Error::Other(
+                "Attempted to cancel a session with a non-cancellation status",
+            ));
+        }
+
+        self.update(
+            session,
+            PartialSession {
+                status: Some(status),
+                cancelled_at: Some(Some(chrono::Utc::now())),
+                cancel_reason: Some(reason),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    /// Changes the status of a Session, e.g. to mark it as completed.
+    /// For cancellations, use [`SessionStore::cancel`] instead.
+    async fn set_status(&self, session: &Session, status: SessionStatus) -> Result<Session> {
+        if status.is_cancelled() {
+            return Err(Error::Other(
+                "Attempted to set a cancellation status without cancelling the session",
+            ));
+        }
+
+        self.update(
+            session,
+            PartialSession {
+                status: Some(status),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    /// Marks a Session as notified, meaning that all of its reminders were sent.
+    async fn set_notified(&self, session: &Session) -> Result<Session> {
+        self.update(
+            session,
+            PartialSession {
+                notified: Some(true),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+}
+
+/// Operations on stored SessionFeedback instances.
+#[async_trait]
+pub trait FeedbackStore: Send + Sync {
+    /// Gets the feedback given on a Session by a particular person, if any.
+    async fn get(&self, session_id: i64, author_id: DiscordId) -> Result<Option<SessionFeedback>>;
+
+    /// Registers feedback given on a Session, replacing any feedback previously given
+    /// on it by the same person.
+    async fn submit(&self, feedback: &NewSessionFeedback) -> Result<SessionFeedback>;
+
+    /// Gets all feedback given on a Session, in the order it was submitted.
+    async fn find_by_session(&self, session_id: i64) -> Result<Vec<SessionFeedback>>;
+
+    /// Aggregates the ratings given by students to each mentor's sessions.
+    /// Mentors without ratings are omitted. Results are sorted by mentor name.
+    async fn find_teacher_ratings(&self) -> Result<Vec<(Teacher, TeacherRating)>>;
+}
+
+/// Operations on stored WaitlistEntry instances.
+#[async_trait]
+pub trait WaitlistStore: Send + Sync {
+    /// Gets a WaitlistEntry by its ID.
+    async fn get(&self, id: i64) -> Result<Option<WaitlistEntry>>;
+
+    /// Adds a student to the end of the waitlist. Returns 'None' (adding nothing)
+    /// if they are already in it.
+    async fn join(&self, entry: &NewWaitlistEntry) -> Result<Option<WaitlistEntry>>;
+
+    /// Gets the entry with which a student is in the waitlist (waiting or with an offer),
+    /// if any.
+    async fn find_active_by_student(&self, student_id: DiscordId) -> Result<Option<WaitlistEntry>>;
+
+    /// Removes a student from the waitlist, keeping their entry with the 'Left' status.
+    /// Returns the updated count (0 if they weren't in it).
+    async fn leave(&self, student_id: DiscordId) -> Result<usize>;
+
+    /// Removes a student from the waitlist after they scheduled a session, keeping their
+    /// entry with the 'Fulfilled' status. Returns the updated count (0 if they weren't in it).
+    async fn fulfill(&self, student_id: DiscordId) -> Result<usize>;
+
+    /// Searches for the entries waiting for a time to be offered, in the order
+    /// they joined the waitlist.
+    async fn find_waiting(&self) -> Result<Vec<WaitlistEntry>>;
+
+    /// Searches for the entries which were offered a time they haven't claimed yet.
+    async fn find_offered(&self) -> Result<Vec<WaitlistEntry>>;
+
+    /// Offers the given occurrence of an availability to a waiting entry's student,
+    /// holding it for them until the given moment. Returns the updated entry, or 'None'
+    /// if it is no longer waiting.
+    async fn offer(
+        &self,
+        entry_id: i64,
+        availability_id: i64,
+        start_at: chrono::DateTime<chrono::Utc>,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Option<WaitlistEntry>>;
+
+    /// Expires all offers which weren't claimed until 'now', removing their students
+    /// from the waitlist. Returns the expired entries.
+    async fn expire_offers(&self, now: chrono::DateTime<chrono::Utc>)
+        -> Result<Vec<WaitlistEntry>>;
+}
+
+/// Operations on stored Tag instances, and on the tags of each Teacher.
+#[async_trait]
+pub trait TagStore: Send + Sync {
+    /// Finds all stored Tags, sorted by name.
+    async fn find_all(&self) -> Result<Vec<Tag>>;
+
+    /// Finds the tags of a Teacher, sorted by name.
+    async fn find_by_teacher(&self, teacher_id: i64) -> Result<Vec<Tag>>;
+
+    /// Finds the tags of each of the Teachers with the given IDs, as pairs of
+    /// teacher IDs and tags, sorted by tag name.
+    async fn find_by_teachers(&self, teacher_ids: &[i64]) -> Result<Vec<(i64, Tag)>>;
+
+    /// Replaces the tags of a Teacher with the tags with the given (already normalized)
+    /// names, creating the ones which don't exist yet. Returns the Teacher's new tags.
+    async fn set_teacher_tags(&self, teacher_id: i64, names: &[String]) -> Result<Vec<Tag>>;
+
+    /// Replaces the tags of a Teacher with the ones extracted from their specialty
+    /// (see [`Tag::extract_names`]). Returns the Teacher's new tags.
+    async fn tag_from_specialty(&self, teacher: &Teacher) -> Result<Vec<Tag>> {
+        self.set_teacher_tags(teacher.id, &Tag::extract_names(&teacher.specialty))
+            .await
+    }
+}
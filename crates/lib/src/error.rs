@@ -31,6 +31,13 @@ pub enum Error {
     /// [`ConnectionError`]: diesel::result::ConnectionError
     DieselConnection(diesel::result::ConnectionError),
 
+    /// A database migration could not be listed or applied.
+    Migration(Box<dyn std::error::Error + Send + Sync>),
+
+    /// Indicates the database has migrations applied (with the given versions)
+    /// which this binary doesn't know of, i.e. its schema is ahead of the binary.
+    SchemaAhead(Vec<String>),
+
     /// A Deadpool [`BuildError`] occurred.
     ///
     /// [`BuildError`]: deadpool::BuildError
@@ -78,6 +85,15 @@ pub enum Error {
     /// [lettre `Error`]: lettre::error::Error
     Lettre(lettre::error::Error),
 
+    /// Indicates sending an e-mail through an SMTP server failed.
+    /// Holds a [lettre SMTP `Error`].
+    ///
+    /// [lettre SMTP `Error`]: lettre::transport::smtp::Error
+    Smtp(lettre::transport::smtp::Error),
+
+    /// Indicates the bot's configuration is invalid, for the given reason.
+    InvalidConfig(&'static str),
+
     /// Holds a [regex `Error`].
     ///
     /// [regex `Error`]: regex::Error
@@ -91,6 +107,16 @@ pub enum Error {
     /// Holds one or more validation errors.
     Validations(validator::ValidationErrors),
 
+    /// Indicates a session could not be booked (or moved) at a certain time,
+    /// as another scheduled session has already claimed it.
+    SessionTimeTaken,
+
+    /// Indicates a student may not book (or move) a session, according to the
+    /// [`BookingPolicy`], for the given reason.
+    ///
+    /// [`BookingPolicy`]: crate::model::BookingPolicy
+    BookingRefused(crate::model::BookingRefusal),
+
     #[allow(dead_code)]
     Generic(Box<dyn std::error::Error + Send + Sync>),
 
@@ -134,6 +160,7 @@ impl_from_error!(
     google_apis_common::oauth2::Error => Auth;
     lettre::address::AddressError => LettreAddress;
     lettre::error::Error => Lettre;
+    lettre::transport::smtp::Error => Smtp;
     regex::Error => Regex;
     csv::Error => Csv;
     validator::ValidationErrors => Validations;
@@ -148,6 +175,12 @@ impl Display for Error {
             Self::Serenity(inner) => Display::fmt(&inner, f),
             Self::Diesel(inner) => Display::fmt(&inner, f),
             Self::DieselConnection(inner) => Display::fmt(&inner, f),
+            Self::Migration(inner) => write!(f, "Failed to run database migrations: {inner}"),
+            Self::SchemaAhead(versions) => write!(
+                f,
+                "The database has migrations applied which are unknown to this binary: {}",
+                versions.join(", ")
+            ),
             Self::DeadpoolBuild(inner) => Display::fmt(&inner, f),
             Self::DeadpoolPool(inner) => Display::fmt(&inner, f),
             Self::GoogleApi(inner) => Display::fmt(&inner, f),
@@ -155,10 +188,18 @@ impl Display for Error {
             Self::Auth(inner) => Display::fmt(&inner, f),
             Self::LettreAddress(inner) => Display::fmt(&inner, f),
             Self::Lettre(inner) => Display::fmt(&inner, f),
+            Self::Smtp(inner) => Display::fmt(&inner, f),
+            Self::InvalidConfig(reason) => write!(f, "Invalid configuration: {reason}"),
             Self::Regex(inner) => Display::fmt(&inner, f),
             Self::Csv(inner) => Display::fmt(&inner, f),
             Self::Validations(inner) => Display::fmt(&inner, f),
             Self::DateTimeParse => write!(f, "Failed to parse the given date expression"),
+            Self::SessionTimeTaken => {
+                write!(f, "This time was already taken by another session")
+            }
+            Self::BookingRefused(refusal) => {
+                write!(f, "The booking limits refused this session: {refusal:?}")
+            }
             Self::CommandCheck(message) => write!(f, "{}", message),
             Self::Generic(inner) => Display::fmt(&inner, f),
             Self::Other(message) => write!(f, "{}", message),
@@ -183,6 +224,7 @@ impl std::error::Error for Error {
             Self::Validations(inner) => Some(inner),
             Self::Io(inner) => Some(inner),
             Self::Auth(inner) => Some(inner),
+            Self::Migration(inner) => Some(&**inner),
             Self::Generic(inner) => Some(&**inner),
             _ => None,
         }
@@ -6,6 +6,9 @@ pub mod model;
 pub mod notification;
 pub mod util;
 
+// translations used for e-mails (see `notification`)
+rust_i18n::i18n!("../../locales", fallback = "pt-BR");
+
 pub(crate) mod forms {
     pub(crate) use mentoriabot_forms::*;
 }
@@ -1,14 +1,36 @@
 //! Model structs used in the bot, usually interfacing with the DB.
 mod availability;
+mod booking_policy;
 mod discordid;
+mod program;
 mod session;
+mod session_feedback;
+mod session_reminder;
+mod session_status;
+mod session_student;
+mod tag;
 mod teacher;
+mod timezone;
 mod user;
+mod waitlist_entry;
+mod waitlist_status;
 mod weekday;
 
 pub use availability::{Availability, NewAvailability, PartialAvailability};
+pub use booking_policy::{BookedSession, BookingPolicy, BookingRefusal};
 pub use discordid::DiscordId;
-pub use session::{NewSession, PartialSession, Session};
+pub use program::{NewProgram, PartialProgram, Program, ProgramStatus};
+pub use session::{NewSession, PartialSession, Session, MINUTES_PER_DURATION_UNIT};
+pub use session_feedback::{
+    NewSessionFeedback, SessionFeedback, TeacherRating, MAX_RATING, MIN_RATING,
+};
+pub use session_reminder::{NewSessionReminder, SessionReminder};
+pub use session_status::SessionStatus;
+pub use session_student::{NewSessionStudent, SessionStudent};
+pub use tag::{NewTag, Tag, TeacherTag};
 pub use teacher::{NewTeacher, PartialTeacher, Teacher};
+pub use timezone::Timezone;
 pub use user::{NewUser, PartialUser, User};
+pub use waitlist_entry::{NewWaitlistEntry, WaitlistEntry};
+pub use waitlist_status::WaitlistStatus;
 pub use weekday::Weekday;
@@ -1,9 +1,18 @@
+use chrono::Timelike;
 use diesel::{AsChangeset, Associations, Identifiable, Insertable, Queryable, QueryableByName};
 
-use crate::{db::schema::*, model::Weekday};
+use crate::{
+    db::schema::*,
+    model::{Session, SessionStatus, Teacher, Timezone, Weekday, MINUTES_PER_DURATION_UNIT},
+    util::time::local_datetime_in,
+};
+
+const MINUTES_PER_WEEK: i64 = 7 * 24 * 60;
 
 /// Represents a certain time of the week when a Teacher can initiate a Session
 /// with students, which may "claim" one of them for the current week.
+/// Up to 'capacity' students may attend the same session, in which case
+/// further students join the session claimed by the first one.
 #[derive(
     Queryable,
     Identifiable,
@@ -17,6 +26,7 @@ use crate::{db::schema::*, model::Weekday};
     Eq,
 )]
 #[diesel(belongs_to(super::Teacher, foreign_key = teacher_id))]
+#[diesel(belongs_to(super::Program, foreign_key = program_id))]
 #[diesel(table_name = availability)]
 pub struct Availability {
     pub id: i64,
@@ -25,6 +35,10 @@ pub struct Availability {
     pub time_start: chrono::NaiveTime,
     pub expired: bool,
     pub duration: i16,
+    pub valid_from: Option<chrono::NaiveDate>,
+    pub valid_until: Option<chrono::NaiveDate>,
+    pub program_id: Option<i64>,
+    pub capacity: i16,
 }
 
 #[derive(Insertable, AsChangeset, Debug, Clone, PartialEq, Eq)]
@@ -35,6 +49,10 @@ pub struct NewAvailability {
     pub time_start: chrono::NaiveTime,
     pub expired: bool,
     pub duration: i16,
+    pub valid_from: Option<chrono::NaiveDate>,
+    pub valid_until: Option<chrono::NaiveDate>,
+    pub program_id: Option<i64>,
+    pub capacity: i16,
 }
 
 /// A Partial Availability, in order to specify certain fields to update.
@@ -47,17 +65,105 @@ pub struct PartialAvailability {
     pub time_start: Option<chrono::NaiveTime>,
     pub expired: Option<bool>,
     pub duration: Option<i16>,
+    pub valid_from: Option<Option<chrono::NaiveDate>>,
+    pub valid_until: Option<Option<chrono::NaiveDate>>,
+    pub program_id: Option<Option<i64>>,
+    pub capacity: Option<i16>,
 }
 
 impl Availability {
     /// Returns the first date this availability could correspond to
-    /// after the given date (first matching weekday).
-    pub fn first_possible_date_after(
+    /// after the given date (first matching weekday), in the given timezone of its
+    /// mentor (see [`Teacher::timezone`]).
+    ///
+    /// [`Teacher::timezone`]: super::Teacher::timezone
+    pub fn first_possible_date_after<Tz: chrono::TimeZone>(
+        &self,
+        timezone: Timezone,
+        initial_date: &chrono::DateTime<Tz>,
+    ) -> chrono::DateTime<chrono_tz::Tz> {
+        self.weekday
+            .next_day_with_this_weekday(&initial_date.with_timezone(&timezone.0))
+    }
+
+    /// Returns the moment this availability first starts after the given date
+    /// (on the first matching weekday, at its starting time in the given timezone of
+    /// its mentor), in UTC. Starting times skipped or repeated due to daylight saving
+    /// time are resolved as described in [`local_datetime_in`].
+    pub fn first_start_after<Tz: chrono::TimeZone>(
+        &self,
+        timezone: Timezone,
+        initial_date: &chrono::DateTime<Tz>,
+    ) -> chrono::DateTime<chrono::Utc> {
+        let date = self
+            .first_possible_date_after(timezone, initial_date)
+            .date_naive();
+
+        local_datetime_in(&timezone.0, &date.and_time(self.time_start)).with_timezone(&chrono::Utc)
+    }
+
+    /// Checks whether this availability can be scheduled at the given date,
+    /// according to its validity window (if any).
+    pub fn is_valid_at(&self, date: chrono::NaiveDate) -> bool {
+        let not_yet_valid = matches!(self.valid_from, Some(valid_from) if date < valid_from);
+        let no_longer_valid = matches!(self.valid_until, Some(valid_until) if valid_until < date);
+
+        !not_yet_valid && !no_longer_valid
+    }
+
+    /// Checks whether this availability can still be scheduled at the given moment
+    /// according to its mentor's weekly cap (see [`Teacher::is_at_weekly_cap`]), given
+    /// the mentor's sessions: either the cap wasn't reached in that week yet, or a session
+    /// scheduled at that moment (with seats left) is already claiming this availability.
+    pub fn is_within_weekly_cap(
         &self,
-        initial_date: &chrono::DateTime<chrono::FixedOffset>,
-    ) -> chrono::DateTime<chrono::FixedOffset> {
-        self.weekday.next_day_with_this_weekday(initial_date)
+        teacher: &Teacher,
+        sessions: &[Session],
+        start_at: &chrono::DateTime<chrono::Utc>,
+    ) -> bool {
+        !teacher.is_at_weekly_cap(sessions, start_at)
+            || sessions.iter().any(|session| {
+                session.availability_id == self.id
+                    && session.start_at == *start_at
+                    && session.status == SessionStatus::Scheduled
+            })
     }
+
+    /// Checks whether this availability's weekly time interval intersects with
+    /// the given new availability's (regardless of their teachers), as long as
+    /// their validity windows intersect as well.
+    /// Intervals wrapping around the end of the week (Saturday to Sunday) are
+    /// taken into account.
+    pub fn overlaps(&self, other: &NewAvailability) -> bool {
+        let windows_intersect = match (self.valid_from, other.valid_until) {
+            (Some(valid_from), Some(other_valid_until)) => valid_from <= other_valid_until,
+            _ => true,
+        } && match (other.valid_from, self.valid_until) {
+            (Some(other_valid_from), Some(valid_until)) => other_valid_from <= valid_until,
+            _ => true,
+        };
+
+        if !windows_intersect {
+            return false;
+        }
+
+        let (start, length) = minutes_into_week(self.weekday, self.time_start, self.duration);
+        let (other_start, other_length) =
+            minutes_into_week(other.weekday, other.time_start, other.duration);
+
+        (other_start - start).rem_euclid(MINUTES_PER_WEEK) < length
+            || (start - other_start).rem_euclid(MINUTES_PER_WEEK) < other_length
+    }
+}
+
+/// Returns the minute of the week (starting on Sunday) in which an availability
+/// starts, along with its length in minutes.
+fn minutes_into_week(weekday: Weekday, time_start: chrono::NaiveTime, duration: i16) -> (i64, i64) {
+    let start = i64::from(i16::from(weekday)) * 24 * 60
+        + i64::from(time_start.hour()) * 60
+        + i64::from(time_start.minute());
+
+    (start, i64::from(duration) * MINUTES_PER_DURATION_UNIT)
 }
 
 impl From<Availability> for PartialAvailability {
@@ -71,6 +177,10 @@ impl From<Availability> for PartialAvailability {
             time_start: Some(other.time_start),
             expired: Some(other.expired),
             duration: Some(other.duration),
+            valid_from: Some(other.valid_from),
+            valid_until: Some(other.valid_until),
+            program_id: Some(other.program_id),
+            capacity: Some(other.capacity),
         }
     }
 }
@@ -83,6 +193,10 @@ impl From<Availability> for NewAvailability {
             time_start,
             expired,
             duration,
+            valid_from,
+            valid_until,
+            program_id,
+            capacity,
             ..
         } = other;
 
@@ -92,6 +206,164 @@ impl From<Availability> for NewAvailability {
             time_start,
             expired,
             duration,
+            valid_from,
+            valid_until,
+            program_id,
+            capacity,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_availability(weekday: Weekday, hour: u32, minute: u32, duration: i16) -> Availability {
+        Availability {
+            id: 0,
+            teacher_id: 0,
+            weekday,
+            time_start: chrono::NaiveTime::from_hms_opt(hour, minute, 0).unwrap(),
+            expired: false,
+            duration,
+            valid_from: None,
+            valid_until: None,
+            program_id: None,
+            capacity: 1,
+        }
+    }
+
+    fn make_new_availability(
+        weekday: Weekday,
+        hour: u32,
+        minute: u32,
+        duration: i16,
+    ) -> NewAvailability {
+        make_availability(weekday, hour, minute, duration).into()
+    }
+
+    #[test]
+    fn test_availability_without_window_is_always_valid() {
+        let availability = make_availability(Weekday::Monday, 10, 0, 1);
+
+        assert!(availability.is_valid_at(chrono::NaiveDate::from_ymd_opt(2023, 9, 11).unwrap()));
+    }
+
+    #[test]
+    fn test_availability_is_only_valid_within_its_window() {
+        let availability = Availability {
+            valid_from: chrono::NaiveDate::from_ymd_opt(2023, 9, 11),
+            valid_until: chrono::NaiveDate::from_ymd_opt(2023, 9, 16),
+            ..make_availability(Weekday::Monday, 10, 0, 1)
+        };
+
+        assert!(!availability.is_valid_at(chrono::NaiveDate::from_ymd_opt(2023, 9, 10).unwrap()));
+        assert!(availability.is_valid_at(chrono::NaiveDate::from_ymd_opt(2023, 9, 11).unwrap()));
+        assert!(availability.is_valid_at(chrono::NaiveDate::from_ymd_opt(2023, 9, 16).unwrap()));
+        assert!(!availability.is_valid_at(chrono::NaiveDate::from_ymd_opt(2023, 9, 17).unwrap()));
+    }
+
+    #[test]
+    fn test_availabilities_at_the_same_time_overlap() {
+        let existing = make_availability(Weekday::Monday, 10, 0, 1);
+        let new = make_new_availability(Weekday::Monday, 10, 0, 1);
+
+        assert!(existing.overlaps(&new));
+    }
+
+    #[test]
+    fn test_availability_starting_during_another_overlaps() {
+        // 10:00 - 11:20 and 11:00 - 11:40
+        let longer = make_availability(Weekday::Monday, 10, 0, 2);
+        let shorter = make_availability(Weekday::Monday, 11, 0, 1);
+
+        assert!(longer.overlaps(&shorter.clone().into()));
+        assert!(shorter.overlaps(&longer.into()));
+    }
+
+    #[test]
+    fn test_consecutive_availabilities_do_not_overlap() {
+        // 10:00 - 10:40 and 10:40 - 11:20
+        let first = make_availability(Weekday::Monday, 10, 0, 1);
+        let second = make_availability(Weekday::Monday, 10, 40, 1);
+
+        assert!(!first.overlaps(&second.clone().into()));
+        assert!(!second.overlaps(&first.into()));
+    }
+
+    #[test]
+    fn test_availabilities_on_different_weekdays_do_not_overlap() {
+        let existing = make_availability(Weekday::Monday, 10, 0, 1);
+        let new = make_new_availability(Weekday::Tuesday, 10, 0, 1);
+
+        assert!(!existing.overlaps(&new));
+    }
+
+    #[test]
+    fn test_availabilities_with_disjoint_windows_do_not_overlap() {
+        let first = Availability {
+            valid_until: chrono::NaiveDate::from_ymd_opt(2023, 9, 16),
+            ..make_availability(Weekday::Monday, 10, 0, 1)
+        };
+        let second = Availability {
+            valid_from: chrono::NaiveDate::from_ymd_opt(2023, 9, 17),
+            ..make_availability(Weekday::Monday, 10, 0, 1)
+        };
+
+        assert!(!first.overlaps(&second.clone().into()));
+        assert!(!second.overlaps(&first.into()));
+    }
+
+    #[test]
+    fn test_availability_wrapping_around_the_week_overlaps() {
+        // Saturday 23:30 - Sunday 00:50 and Sunday 00:00 - 00:40
+        let saturday = make_availability(Weekday::Saturday, 23, 30, 2);
+        let sunday = make_availability(Weekday::Sunday, 0, 0, 1);
+
+        assert!(saturday.overlaps(&sunday.clone().into()));
+        assert!(sunday.overlaps(&saturday.into()));
+    }
+
+    #[test]
+    fn test_availability_starts_at_its_local_time_across_dst_changes() {
+        use chrono::TimeZone;
+
+        let timezone = Timezone(chrono_tz::America::New_York);
+        let availability = make_availability(Weekday::Monday, 10, 0, 1);
+
+        // before clocks are turned forward on 12/03/2023 (UTC-5), and after (UTC-4)
+        let before = chrono::Utc.with_ymd_and_hms(2023, 3, 1, 0, 0, 0).unwrap();
+        let after = chrono::Utc.with_ymd_and_hms(2023, 3, 10, 0, 0, 0).unwrap();
+
+        assert_eq!(
+            chrono::Utc.with_ymd_and_hms(2023, 3, 6, 15, 0, 0).unwrap(),
+            availability.first_start_after(timezone, &before)
+        );
+        assert_eq!(
+            chrono::Utc.with_ymd_and_hms(2023, 3, 13, 14, 0, 0).unwrap(),
+            availability.first_start_after(timezone, &after)
+        );
+    }
+
+    #[test]
+    fn test_availability_date_is_taken_in_its_mentors_timezone() {
+        use chrono::TimeZone;
+
+        let availability = make_availability(Weekday::Sunday, 10, 0, 1);
+        // Sunday 23:00 in UTC is still Sunday in São Paulo, but already Monday in Tokyo
+        let sunday_night = chrono::Utc.with_ymd_and_hms(2023, 9, 10, 23, 0, 0).unwrap();
+
+        assert_eq!(
+            chrono::NaiveDate::from_ymd_opt(2023, 9, 10).unwrap(),
+            availability
+                .first_possible_date_after(Timezone(chrono_tz::America::Sao_Paulo), &sunday_night)
+                .date_naive()
+        );
+        assert_eq!(
+            chrono::NaiveDate::from_ymd_opt(2023, 9, 17).unwrap(),
+            availability
+                .first_possible_date_after(Timezone(chrono_tz::Asia::Tokyo), &sunday_night)
+                .date_naive()
+        );
+    }
+}
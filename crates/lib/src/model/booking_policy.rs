@@ -0,0 +1,271 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Session, SessionStatus};
+
+/// Limits on how many sessions each student may book, so that no student takes up
+/// every available time of a popular mentor. Each limit is disabled if set to 'None'.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+pub struct BookingPolicy {
+    /// How many scheduled sessions which haven't ended yet a student may have at once.
+    pub max_active_sessions: Option<u32>,
+
+    /// How many sessions a student may have with the same mentor within a week
+    /// (7 days before or after the session being booked).
+    pub max_sessions_per_mentor_per_week: Option<u32>,
+
+    /// How many sessions a student may have with the same mentor within the same program.
+    pub max_sessions_per_mentor_per_program: Option<u32>,
+
+    /// For how many days after missing a session (a no-show) a student can't book sessions.
+    pub no_show_cooldown_days: Option<u32>,
+}
+
+/// Why a student may not book a session, according to a [`BookingPolicy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookingRefusal {
+    /// The student already has the maximum amount of scheduled sessions.
+    TooManyActiveSessions { max: u32 },
+
+    /// The student already has the maximum amount of sessions with the mentor within a week.
+    TooManyWithMentorPerWeek { max: u32 },
+
+    /// The student already has the maximum amount of sessions with the mentor in the program.
+    TooManyWithMentorPerProgram { max: u32 },
+
+    /// The student missed a session recently, and may only book again after the given moment.
+    NoShowCooldown {
+        until: chrono::DateTime<chrono::Utc>,
+    },
+
+    /// The time was offered to another student in the waitlist, and is held for them
+    /// until their offer expires.
+    HeldForWaitlist,
+}
+
+/// A session the student booked before, along with the program of its available time
+/// (if any), as considered by a [`BookingPolicy`].
+#[derive(Debug, Clone, Copy)]
+pub struct BookedSession<'a> {
+    pub session: &'a Session,
+    pub program_id: Option<i64>,
+}
+
+impl Default for BookingPolicy {
+    fn default() -> Self {
+        Self {
+            max_active_sessions: Some(2),
+            max_sessions_per_mentor_per_week: Some(1),
+            max_sessions_per_mentor_per_program: None,
+            no_show_cooldown_days: Some(14),
+        }
+    }
+}
+
+impl BookingPolicy {
+    /// A policy which doesn't limit bookings at all.
+    pub const UNLIMITED: Self = Self {
+        max_active_sessions: None,
+        max_sessions_per_mentor_per_week: None,
+        max_sessions_per_mentor_per_program: None,
+        no_show_cooldown_days: None,
+    };
+
+    /// Checks all limits (see [`BookingPolicy::check_student`] and
+    /// [`BookingPolicy::check_mentor`]) for a session with the given mentor (in the given
+    /// program, if any) starting at the given moment, given the sessions the student attends.
+    pub fn check(
+        &self,
+        sessions: &[BookedSession<'_>],
+        teacher_id: i64,
+        program_id: Option<i64>,
+        start_at: chrono::DateTime<chrono::Utc>,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), BookingRefusal> {
+        let student_sessions = sessions
+            .iter()
+            .map(|booked| booked.session.clone())
+            .collect::<Vec<_>>();
+
+        self.check_student(&student_sessions, now)?;
+        self.check_mentor(sessions, teacher_id, program_id, start_at)
+    }
+
+    /// Checks the limits which don't depend on the session being booked (active sessions
+    /// and the no-show cooldown), given the sessions the student attends.
+    pub fn check_student(
+        &self,
+        sessions: &[Session],
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), BookingRefusal> {
+        if let Some(max) = self.max_active_sessions {
+            let active = sessions
+                .iter()
+                .filter(|session| {
+                    session.status == SessionStatus::Scheduled && session.end_at > now
+                })
+                .count();
+            if active >= max as usize {
+                return Err(BookingRefusal::TooManyActiveSessions { max });
+            }
+        }
+
+        if let Some(days) = self.no_show_cooldown_days {
+            let until = sessions
+                .iter()
+                .filter(|session| session.status == SessionStatus::NoShow)
+                .map(|session| session.end_at + chrono::Duration::days(days.into()))
+                .max();
+            if let Some(until) = until.filter(|until| *until > now) {
+                return Err(BookingRefusal::NoShowCooldown { until });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks the limits on sessions with the same mentor, given the sessions the student
+    /// attends, for a session with the given mentor (in the given program, if any)
+    /// starting at the given moment. Cancelled sessions don't count.
+    pub fn check_mentor(
+        &self,
+        sessions: &[BookedSession<'_>],
+        teacher_id: i64,
+        program_id: Option<i64>,
+        start_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), BookingRefusal> {
+        let with_mentor = || {
+            sessions.iter().filter(move |booked| {
+                booked.session.teacher_id == teacher_id && !booked.session.status.is_cancelled()
+            })
+        };
+
+        if let Some(max) = self.max_sessions_per_mentor_per_week {
+            let week = chrono::Duration::days(7);
+            let in_week = with_mentor()
+                .filter(|booked| {
+                    booked.session.start_at > start_at - week
+                        && booked.session.start_at < start_at + week
+                })
+                .count();
+            if in_week >= max as usize {
+                return Err(BookingRefusal::TooManyWithMentorPerWeek { max });
+            }
+        }
+
+        if let (Some(max), Some(program_id)) =
+            (self.max_sessions_per_mentor_per_program, program_id)
+        {
+            let in_program = with_mentor()
+                .filter(|booked| booked.program_id == Some(program_id))
+                .count();
+            if in_program >= max as usize {
+                return Err(BookingRefusal::TooManyWithMentorPerProgram { max });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BookedSession, BookingPolicy, BookingRefusal};
+    use crate::model::{DiscordId, Session, SessionStatus};
+
+    fn session(
+        teacher_id: i64,
+        start_at: chrono::DateTime<chrono::Utc>,
+        status: SessionStatus,
+    ) -> Session {
+        Session {
+            id: 1,
+            summary: None,
+            notified: false,
+            teacher_id,
+            student_id: DiscordId(1),
+            availability_id: 1,
+            meet_id: None,
+            calendar_event_id: None,
+            start_at,
+            end_at: start_at + chrono::Duration::minutes(40),
+            status,
+            cancelled_at: None,
+            cancel_reason: None,
+            feedback_requested: false,
+        }
+    }
+
+    #[test]
+    fn test_booking_policy_limits() {
+        let now = chrono::Utc::now();
+        let day = chrono::Duration::days(1);
+        let policy = BookingPolicy {
+            max_active_sessions: Some(2),
+            max_sessions_per_mentor_per_week: Some(1),
+            max_sessions_per_mentor_per_program: Some(2),
+            no_show_cooldown_days: Some(7),
+        };
+
+        let mut sessions = vec![
+            session(1, now + day, SessionStatus::Scheduled),
+            session(2, now - day * 30, SessionStatus::Completed),
+            session(1, now + day * 2, SessionStatus::CancelledByStudent),
+        ];
+        assert_eq!(Ok(()), policy.check_student(&sessions, now));
+
+        sessions.push(session(2, now + day * 3, SessionStatus::Scheduled));
+        assert_eq!(
+            Err(BookingRefusal::TooManyActiveSessions { max: 2 }),
+            policy.check_student(&sessions, now)
+        );
+        assert_eq!(
+            Ok(()),
+            BookingPolicy::UNLIMITED.check_student(&sessions, now)
+        );
+
+        let missed = session(3, now - day * 2, SessionStatus::NoShow);
+        assert_eq!(
+            Err(BookingRefusal::NoShowCooldown {
+                until: missed.end_at + day * 7
+            }),
+            policy.check_student(&[missed], now)
+        );
+
+        let booked = sessions
+            .iter()
+            .map(|session| BookedSession {
+                session,
+                program_id: Some(1),
+            })
+            .collect::<Vec<_>>();
+
+        // the cancelled session doesn't count, but the scheduled one does
+        assert_eq!(
+            Err(BookingRefusal::TooManyWithMentorPerWeek { max: 1 }),
+            policy.check_mentor(&booked, 1, Some(1), now + day * 4)
+        );
+        assert_eq!(
+            Ok(()),
+            policy.check_mentor(&booked, 1, Some(1), now + day * 9)
+        );
+        assert_eq!(
+            Err(BookingRefusal::TooManyWithMentorPerProgram { max: 2 }),
+            policy.check_mentor(&booked, 2, Some(1), now + day * 20)
+        );
+        assert_eq!(
+            Ok(()),
+            policy.check_mentor(&booked, 2, Some(2), now + day * 20)
+        );
+
+        // both kinds of limits are checked together
+        assert_eq!(
+            Err(BookingRefusal::TooManyActiveSessions { max: 2 }),
+            policy.check(&booked, 3, Some(3), now + day * 20, now)
+        );
+        assert_eq!(
+            Ok(()),
+            BookingPolicy::UNLIMITED.check(&booked, 1, Some(1), now + day * 4, now)
+        );
+    }
+}
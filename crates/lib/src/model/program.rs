@@ -0,0 +1,216 @@
+use diesel::{
+    backend::RawValue,
+    deserialize::FromSql,
+    serialize::{Output, ToSql},
+    sql_types::SmallInt,
+    AsChangeset, AsExpression, FromSqlRow, Identifiable, Insertable, Queryable,
+};
+
+use crate::{db::schema::*, error::Error};
+
+/// Represents a mentoring program (or cohort), which runs between two dates,
+/// and to which teachers and their availabilities are attached.
+#[derive(Queryable, Identifiable, Insertable, AsChangeset, Debug, Clone, PartialEq, Eq)]
+pub struct Program {
+    pub id: i64,
+    pub name: String,
+    pub start_date: chrono::NaiveDate,
+    pub end_date: chrono::NaiveDate,
+    pub status: ProgramStatus,
+    pub enrollment_open: bool,
+}
+
+/// A New Program, to be inserted
+#[derive(Insertable, AsChangeset, Debug, Clone, PartialEq, Eq)]
+#[diesel(table_name = programs)]
+pub struct NewProgram {
+    pub name: String,
+    pub start_date: chrono::NaiveDate,
+    pub end_date: chrono::NaiveDate,
+    pub status: ProgramStatus,
+    pub enrollment_open: bool,
+}
+
+/// A Partial Program, in order to specify certain fields to update.
+#[derive(AsChangeset, Debug, Default, Clone, PartialEq, Eq)]
+#[diesel(table_name = programs)]
+pub struct PartialProgram {
+    pub id: Option<i64>,
+    pub name: Option<String>,
+    pub start_date: Option<chrono::NaiveDate>,
+    pub end_date: Option<chrono::NaiveDate>,
+    pub status: Option<ProgramStatus>,
+    pub enrollment_open: Option<bool>,
+}
+
+/// The lifecycle status of a [`Program`].
+#[derive(FromSqlRow, AsExpression, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[diesel(sql_type = SmallInt)]
+pub enum ProgramStatus {
+    /// The program is running (or will run); its enrollment may be open or closed.
+    Active = 0,
+    /// The program is over and kept only for historical purposes.
+    Archived = 1,
+}
+
+impl Program {
+    /// Checks whether students can currently schedule sessions within this program.
+    pub fn is_open(&self) -> bool {
+        self.status == ProgramStatus::Active && self.enrollment_open
+    }
+
+    /// Checks whether the given date is within the program's start and end dates (inclusive).
+    pub fn includes_date(&self, date: chrono::NaiveDate) -> bool {
+        self.start_date <= date && date <= self.end_date
+    }
+}
+
+impl ProgramStatus {
+    /// Converts this status to a localized string.
+    pub fn to_locale_string(&self, locale: &str) -> &'static str {
+        match locale {
+            "pt-BR" | "pt" => match self {
+                Self::Active => "Ativo",
+                Self::Archived => "Arquivado",
+            },
+            _ => match self {
+                Self::Active => "Active",
+                Self::Archived => "Archived",
+            },
+        }
+    }
+}
+
+impl TryFrom<i16> for ProgramStatus {
+    type Error = Error;
+
+    fn try_from(value: i16) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Active),
+            1 => Ok(Self::Archived),
+            _ => Err(Error::Other("Failed to convert from i16 to ProgramStatus")),
+        }
+    }
+}
+
+impl From<ProgramStatus> for i16 {
+    fn from(value: ProgramStatus) -> Self {
+        match value {
+            ProgramStatus::Active => 0,
+            ProgramStatus::Archived => 1,
+        }
+    }
+}
+
+impl ToSql<SmallInt, diesel::pg::Pg> for ProgramStatus
+where
+    i16: ToSql<SmallInt, diesel::pg::Pg>,
+{
+    /// Allows usage of ProgramStatus with diesel, with SmallInt fields.
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, diesel::pg::Pg>) -> diesel::serialize::Result {
+        let converted_self: i16 = (*self).into();
+        <i16 as ToSql<SmallInt, diesel::pg::Pg>>::to_sql(&converted_self, &mut out.reborrow())
+    }
+}
+
+impl<DB> FromSql<SmallInt, DB> for ProgramStatus
+where
+    DB: diesel::backend::Backend,
+    i16: FromSql<SmallInt, DB>,
+{
+    /// Allows usage of ProgramStatus with diesel, with SmallInt fields.
+    fn from_sql(bytes: RawValue<'_, DB>) -> diesel::deserialize::Result<Self> {
+        i16::from_sql(bytes).and_then(|v| Self::try_from(v).map_err(Into::into))
+    }
+}
+
+impl From<Program> for NewProgram {
+    /// Converts a [`Program`] into a [`NewProgram`]
+    /// by ignoring the 'id' attribute.
+    fn from(program: Program) -> Self {
+        Self {
+            name: program.name,
+            start_date: program.start_date,
+            end_date: program.end_date,
+            status: program.status,
+            enrollment_open: program.enrollment_open,
+        }
+    }
+}
+
+impl From<Program> for PartialProgram {
+    /// Converts a [`Program`] into a [`PartialProgram`]
+    /// by wrapping each Program field into a 'Some'.
+    fn from(program: Program) -> Self {
+        Self {
+            id: Some(program.id),
+            name: Some(program.name),
+            start_date: Some(program.start_date),
+            end_date: Some(program.end_date),
+            status: Some(program.status),
+            enrollment_open: Some(program.enrollment_open),
+        }
+    }
+}
+
+impl From<NewProgram> for PartialProgram {
+    /// Converts a [`NewProgram`] into a [`PartialProgram`]
+    /// by wrapping each Program field into a 'Some',
+    /// except for 'id' (None).
+    fn from(new_program: NewProgram) -> Self {
+        Self {
+            id: None,
+            name: Some(new_program.name),
+            start_date: Some(new_program.start_date),
+            end_date: Some(new_program.end_date),
+            status: Some(new_program.status),
+            enrollment_open: Some(new_program.enrollment_open),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_program(status: ProgramStatus, enrollment_open: bool) -> Program {
+        Program {
+            id: 0,
+            name: "2023.2".to_string(),
+            start_date: chrono::NaiveDate::from_ymd_opt(2023, 8, 1).unwrap(),
+            end_date: chrono::NaiveDate::from_ymd_opt(2023, 11, 30).unwrap(),
+            status,
+            enrollment_open,
+        }
+    }
+
+    #[test]
+    fn test_program_is_only_open_when_active_with_open_enrollment() {
+        assert!(make_program(ProgramStatus::Active, true).is_open());
+        assert!(!make_program(ProgramStatus::Active, false).is_open());
+        assert!(!make_program(ProgramStatus::Archived, true).is_open());
+        assert!(!make_program(ProgramStatus::Archived, false).is_open());
+    }
+
+    #[test]
+    fn test_program_includes_dates_between_start_and_end_inclusive() {
+        let program = make_program(ProgramStatus::Active, true);
+        let date = |month, day| chrono::NaiveDate::from_ymd_opt(2023, month, day).unwrap();
+
+        assert!(!program.includes_date(date(7, 31)));
+        assert!(program.includes_date(date(8, 1)));
+        assert!(program.includes_date(date(11, 30)));
+        assert!(!program.includes_date(date(12, 1)));
+    }
+
+    #[test]
+    fn test_program_status_roundtrips_through_i16() {
+        for status in [ProgramStatus::Active, ProgramStatus::Archived] {
+            assert_eq!(
+                Ok(status),
+                ProgramStatus::try_from(i16::from(status)).map_err(|_| ())
+            );
+        }
+        assert!(ProgramStatus::try_from(2).is_err());
+    }
+}
@@ -1,9 +1,16 @@
 use diesel::{AsChangeset, Associations, Identifiable, Insertable, Queryable};
 
-use super::DiscordId;
+use super::{DiscordId, SessionStatus};
 use crate::db::schema::*;
 
+/// How many minutes each unit of session (and availability) duration corresponds to.
+pub const MINUTES_PER_DURATION_UNIT: i64 = 40;
+
 /// Represents a session between a teacher and its student(s).
+/// 'student_id' refers to the student who booked the session, while all students
+/// attending it (including that one) are registered as [`SessionStudent`]s.
+///
+/// [`SessionStudent`]: super::SessionStudent
 #[derive(
     Queryable, Identifiable, Insertable, AsChangeset, Associations, Debug, Clone, PartialEq, Eq,
 )]
@@ -19,6 +26,11 @@ pub struct Session {
     pub calendar_event_id: Option<String>,
     pub start_at: chrono::DateTime<chrono::Utc>,
     pub end_at: chrono::DateTime<chrono::Utc>,
+    pub status: SessionStatus,
+    pub cancelled_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub cancel_reason: Option<String>,
+    /// Whether its participants were already asked for feedback after it ended.
+    pub feedback_requested: bool,
 }
 
 /// Auxiliary struct for inserting a Session.
@@ -34,6 +46,9 @@ pub struct NewSession {
     pub calendar_event_id: Option<String>,
     pub start_at: chrono::DateTime<chrono::Utc>,
     pub end_at: chrono::DateTime<chrono::Utc>,
+    pub status: SessionStatus,
+    pub cancelled_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub cancel_reason: Option<String>,
 }
 
 #[derive(AsChangeset, Debug, Default, Clone, PartialEq, Eq)]
@@ -49,6 +64,10 @@ pub struct PartialSession {
     pub calendar_event_id: Option<Option<String>>,
     pub start_at: Option<chrono::DateTime<chrono::Utc>>,
     pub end_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub status: Option<SessionStatus>,
+    pub cancelled_at: Option<Option<chrono::DateTime<chrono::Utc>>>,
+    pub cancel_reason: Option<Option<String>>,
+    pub feedback_requested: Option<bool>,
 }
 
 impl Session {
@@ -58,8 +77,6 @@ impl Session {
         start_at: chrono::DateTime<chrono::Utc>,
         duration_units: i64,
     ) -> chrono::DateTime<chrono::Utc> {
-        const MINUTES_PER_DURATION_UNIT: i64 = 40;
-
         start_at + chrono::Duration::minutes(duration_units * MINUTES_PER_DURATION_UNIT)
     }
 }
@@ -79,6 +96,10 @@ impl From<Session> for PartialSession {
             calendar_event_id: Some(session.calendar_event_id),
             start_at: Some(session.start_at),
             end_at: Some(session.end_at),
+            status: Some(session.status),
+            cancelled_at: Some(session.cancelled_at),
+            cancel_reason: Some(session.cancel_reason),
+            feedback_requested: Some(session.feedback_requested),
         }
     }
 }
@@ -97,6 +118,9 @@ impl From<Session> for NewSession {
             calendar_event_id,
             start_at,
             end_at,
+            status,
+            cancelled_at,
+            cancel_reason,
             ..
         } = session;
 
@@ -110,6 +134,9 @@ impl From<Session> for NewSession {
             calendar_event_id,
             start_at,
             end_at,
+            status,
+            cancelled_at,
+            cancel_reason,
         }
     }
 }
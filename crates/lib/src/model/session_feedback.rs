@@ -0,0 +1,94 @@
+use diesel::{AsChangeset, Associations, Identifiable, Insertable, Queryable};
+
+use super::DiscordId;
+use crate::db::schema::*;
+
+/// The lowest rating which can be given to a session.
+pub const MIN_RATING: i16 = 1;
+
+/// The highest rating which can be given to a session.
+pub const MAX_RATING: i16 = 5;
+
+/// Represents the feedback given on a [`Session`] by one of its students or by its mentor,
+/// after the session ended. Each person may give feedback on a session at most once.
+///
+/// [`Session`]: super::Session
+#[derive(Queryable, Identifiable, Insertable, Associations, Debug, Clone, PartialEq, Eq)]
+#[diesel(belongs_to(super::Session, foreign_key = session_id))]
+#[diesel(primary_key(session_id, author_id))]
+#[diesel(table_name = session_feedback)]
+pub struct SessionFeedback {
+    pub session_id: i64,
+    /// The Discord ID of whoever gave this feedback.
+    pub author_id: DiscordId,
+    /// Whether this was given by the session's mentor (otherwise, by a student).
+    pub from_mentor: bool,
+    /// A rating between [`MIN_RATING`] and [`MAX_RATING`].
+    pub rating: i16,
+    pub comment: Option<String>,
+    pub submitted_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Auxiliary struct for registering feedback given on a Session.
+#[derive(Insertable, AsChangeset, Debug, Clone, PartialEq, Eq)]
+#[diesel(table_name = session_feedback, treat_none_as_null = true)]
+pub struct NewSessionFeedback {
+    pub session_id: i64,
+    pub author_id: DiscordId,
+    pub from_mentor: bool,
+    pub rating: i16,
+    pub comment: Option<String>,
+}
+
+/// The ratings given by students to a mentor's sessions, aggregated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TeacherRating {
+    pub teacher_id: i64,
+    /// How many ratings were given.
+    pub rating_count: usize,
+    /// The average of all ratings given.
+    pub average_rating: f64,
+}
+
+impl TeacherRating {
+    /// Aggregates the given ratings of a mentor's sessions.
+    /// Returns `None` if there are no ratings.
+    pub fn aggregate(teacher_id: i64, ratings: impl IntoIterator<Item = i16>) -> Option<Self> {
+        let (rating_count, rating_sum) = ratings
+            .into_iter()
+            .fold((0usize, 0i64), |(count, sum), rating| {
+                (count + 1, sum + rating as i64)
+            });
+
+        (rating_count > 0).then(|| Self {
+            teacher_id,
+            rating_count,
+            average_rating: rating_sum as f64 / rating_count as f64,
+        })
+    }
+}
+
+impl NewSessionFeedback {
+    /// Checks whether the rating is within the accepted range.
+    pub fn has_valid_rating(&self) -> bool {
+        (MIN_RATING..=MAX_RATING).contains(&self.rating)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TeacherRating;
+
+    #[test]
+    fn test_teacher_rating_aggregate() {
+        assert_eq!(None, TeacherRating::aggregate(1, []));
+        assert_eq!(
+            Some(TeacherRating {
+                teacher_id: 1,
+                rating_count: 3,
+                average_rating: 4.0,
+            }),
+            TeacherRating::aggregate(1, [5, 4, 3])
+        );
+    }
+}
@@ -0,0 +1,24 @@
+use diesel::{Associations, Identifiable, Insertable, Queryable};
+
+use crate::db::schema::*;
+
+/// Represents a reminder which was sent for a [`Session`], a certain amount of minutes
+/// before it started, to its student(s) and mentor.
+///
+/// [`Session`]: super::Session
+#[derive(Queryable, Identifiable, Insertable, Associations, Debug, Clone, PartialEq, Eq)]
+#[diesel(belongs_to(super::Session, foreign_key = session_id))]
+#[diesel(primary_key(session_id, offset_minutes))]
+pub struct SessionReminder {
+    pub session_id: i64,
+    pub offset_minutes: i32,
+    pub sent_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Auxiliary struct for registering a reminder sent for a Session.
+#[derive(Insertable, Debug, Clone, PartialEq, Eq)]
+#[diesel(table_name = session_reminders)]
+pub struct NewSessionReminder {
+    pub session_id: i64,
+    pub offset_minutes: i32,
+}
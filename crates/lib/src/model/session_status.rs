@@ -0,0 +1,136 @@
+use diesel::{
+    backend::RawValue,
+    deserialize::FromSql,
+    serialize::{Output, ToSql},
+    sql_types::SmallInt,
+    AsExpression, FromSqlRow,
+};
+
+use crate::error::Error;
+
+/// The lifecycle status of a [`Session`]. Sessions are never deleted when cancelled;
+/// their status is changed instead, so that their history is kept.
+///
+/// [`Session`]: super::Session
+#[derive(FromSqlRow, AsExpression, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[diesel(sql_type = SmallInt)]
+pub enum SessionStatus {
+    /// The session is going to happen (or is happening).
+    Scheduled = 0,
+    /// The session was cancelled by the student(s).
+    CancelledByStudent = 1,
+    /// The session was cancelled by the mentor (or by an admin on their behalf).
+    CancelledByMentor = 2,
+    /// The session took place.
+    Completed = 3,
+    /// The session was scheduled, but the student did not show up.
+    NoShow = 4,
+}
+
+impl SessionStatus {
+    /// Checks whether this status corresponds to a cancelled session.
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self, Self::CancelledByStudent | Self::CancelledByMentor)
+    }
+
+    /// Converts this status to a localized string.
+    pub fn to_locale_string(&self, locale: &str) -> &'static str {
+        match locale {
+            "pt-BR" | "pt" => match self {
+                Self::Scheduled => "Marcada",
+                Self::CancelledByStudent => "Cancelada pelo aluno",
+                Self::CancelledByMentor => "Cancelada pelo mentor",
+                Self::Completed => "Concluída",
+                Self::NoShow => "Aluno não compareceu",
+            },
+            _ => match self {
+                Self::Scheduled => "Scheduled",
+                Self::CancelledByStudent => "Cancelled by student",
+                Self::CancelledByMentor => "Cancelled by mentor",
+                Self::Completed => "Completed",
+                Self::NoShow => "Student did not show up",
+            },
+        }
+    }
+}
+
+impl TryFrom<i16> for SessionStatus {
+    type Error = Error;
+
+    fn try_from(value: i16) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Scheduled),
+            1 => Ok(Self::CancelledByStudent),
+            2 => Ok(Self::CancelledByMentor),
+            3 => Ok(Self::Completed),
+            4 => Ok(Self::NoShow),
+            _ => Err(Error::Other("Failed to convert from i16 to SessionStatus")),
+        }
+    }
+}
+
+impl From<SessionStatus> for i16 {
+    fn from(value: SessionStatus) -> Self {
+        match value {
+            SessionStatus::Scheduled => 0,
+            SessionStatus::CancelledByStudent => 1,
+            SessionStatus::CancelledByMentor => 2,
+            SessionStatus::Completed => 3,
+            SessionStatus::NoShow => 4,
+        }
+    }
+}
+
+impl ToSql<SmallInt, diesel::pg::Pg> for SessionStatus
+where
+    i16: ToSql<SmallInt, diesel::pg::Pg>,
+{
+    /// Allows usage of SessionStatus with diesel, with SmallInt fields.
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, diesel::pg::Pg>) -> diesel::serialize::Result {
+        let converted_self: i16 = (*self).into();
+        <i16 as ToSql<SmallInt, diesel::pg::Pg>>::to_sql(&converted_self, &mut out.reborrow())
+    }
+}
+
+impl<DB> FromSql<SmallInt, DB> for SessionStatus
+where
+    DB: diesel::backend::Backend,
+    i16: FromSql<SmallInt, DB>,
+{
+    /// Allows usage of SessionStatus with diesel, with SmallInt fields.
+    fn from_sql(bytes: RawValue<'_, DB>) -> diesel::deserialize::Result<Self> {
+        i16::from_sql(bytes).and_then(|v| Self::try_from(v).map_err(Into::into))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_status_roundtrips_through_i16() {
+        for status in [
+            SessionStatus::Scheduled,
+            SessionStatus::CancelledByStudent,
+            SessionStatus::CancelledByMentor,
+            SessionStatus::Completed,
+            SessionStatus::NoShow,
+        ] {
+            assert_eq!(
+                Ok(status),
+                SessionStatus::try_from(i16::from(status)).map_err(|_| ())
+            );
+        }
+        assert!(SessionStatus::try_from(5).is_err());
+        assert!(SessionStatus::try_from(-1).is_err());
+    }
+
+    #[test]
+    fn test_session_status_is_cancelled() {
+        assert!(!SessionStatus::Scheduled.is_cancelled());
+        assert!(SessionStatus::CancelledByStudent.is_cancelled());
+        assert!(SessionStatus::CancelledByMentor.is_cancelled());
+        assert!(!SessionStatus::Completed.is_cancelled());
+        assert!(!SessionStatus::NoShow.is_cancelled());
+    }
+}
@@ -0,0 +1,26 @@
+use diesel::{Associations, Identifiable, Insertable, Queryable};
+
+use super::DiscordId;
+use crate::db::schema::*;
+
+/// Represents a student attending a [`Session`], which may have more than one
+/// student if it is a group session.
+///
+/// [`Session`]: super::Session
+#[derive(Queryable, Identifiable, Insertable, Associations, Debug, Clone, PartialEq, Eq)]
+#[diesel(belongs_to(super::Session, foreign_key = session_id))]
+#[diesel(belongs_to(super::User, foreign_key = student_id))]
+#[diesel(primary_key(session_id, student_id))]
+pub struct SessionStudent {
+    pub session_id: i64,
+    pub student_id: DiscordId,
+    pub joined_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Auxiliary struct for adding a student to a Session.
+#[derive(Insertable, Debug, Clone, PartialEq, Eq)]
+#[diesel(table_name = session_students)]
+pub struct NewSessionStudent {
+    pub session_id: i64,
+    pub student_id: DiscordId,
+}
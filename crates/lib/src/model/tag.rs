@@ -0,0 +1,71 @@
+use diesel::{Associations, Identifiable, Insertable, Queryable};
+use once_cell::sync::Lazy;
+
+use crate::db::schema::*;
+
+/// The separators between the areas listed in a mentor's specialty
+/// (must match the ones in the tags migration).
+static TAG_SEPARATOR_REGEX: Lazy<regex::Regex> =
+    Lazy::new(|| regex::Regex::new(r"[,;/|&\n]|\s(?:e|and)\s").unwrap());
+
+/// Represents a normalized area (e.g. "machine learning") extracted from the mentors'
+/// specialties, by which students can filter mentors.
+#[derive(Queryable, Identifiable, Insertable, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Tag {
+    pub id: i64,
+    /// The normalized name (see [`Tag::extract_names`]), unique among tags.
+    pub name: String,
+}
+
+/// Auxiliary struct for inserting a Tag.
+#[derive(Insertable, Debug, Clone, PartialEq, Eq)]
+#[diesel(table_name = tags)]
+pub struct NewTag {
+    pub name: String,
+}
+
+/// Represents a [`Tag`] given to a [`Teacher`].
+///
+/// [`Teacher`]: super::Teacher
+#[derive(Queryable, Identifiable, Insertable, Associations, Debug, Clone, PartialEq, Eq)]
+#[diesel(belongs_to(super::Teacher, foreign_key = teacher_id))]
+#[diesel(belongs_to(Tag, foreign_key = tag_id))]
+#[diesel(primary_key(teacher_id, tag_id))]
+#[diesel(table_name = teacher_tags)]
+pub struct TeacherTag {
+    pub teacher_id: i64,
+    pub tag_id: i64,
+}
+
+impl Tag {
+    /// Extracts the normalized names of the tags in a mentor's free-text specialty:
+    /// it is split at separators (commas, slashes, "e", "and"...), and each part
+    /// is lowercased, with repeated spaces collapsed. Duplicates are removed, keeping
+    /// the order in which the tags first appear.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mentoriabot_lib::model::Tag;
+    /// assert_eq!(
+    ///     vec!["álgebra linear", "cálculo", "machine learning"],
+    ///     Tag::extract_names("Álgebra  Linear, Cálculo e Machine Learning; cálculo.")
+    /// );
+    /// assert!(Tag::extract_names(" , ").is_empty());
+    /// ```
+    pub fn extract_names(specialty: &str) -> Vec<String> {
+        let specialty = specialty.to_lowercase();
+        let mut names = Vec::<String>::new();
+
+        for part in TAG_SEPARATOR_REGEX.split(&specialty) {
+            let name = part.split_whitespace().collect::<Vec<_>>().join(" ");
+            let name = name.trim_matches(|c| c == ' ' || c == '.');
+
+            if !name.is_empty() && !names.iter().any(|other| other == name) {
+                names.push(name.to_owned());
+            }
+        }
+
+        names
+    }
+}
@@ -1,6 +1,10 @@
 use diesel::{AsChangeset, Identifiable, Insertable, Queryable};
 
-use crate::db::schema::*;
+use super::{DiscordId, Session, Timezone};
+use crate::{
+    db::schema::*,
+    util::{self, time::week_start},
+};
 
 /// Represents a registered Teacher, which can create Lectures, and show their possible
 /// [`Availabilities`].
@@ -22,6 +26,16 @@ pub struct Teacher {
     pub linkedin: Option<String>,
     pub comment_general: Option<String>,
     pub comment_experience: Option<String>,
+    pub discord_id: Option<DiscordId>,
+    pub program_id: Option<i64>,
+    /// The timezone in which this teacher's availability times are defined.
+    pub timezone: Timezone,
+    /// The most sessions this teacher wants to deliver per week (Monday to Sunday, in
+    /// their timezone), or 'None' for no cap.
+    pub max_sessions_per_week: Option<i16>,
+    /// The locale in which this teacher gets e-mails (see [`Teacher::notification_locale`]),
+    /// taken from their Discord client once their profile is linked.
+    pub locale: Option<String>,
 }
 
 /// A New Teacher, to be inserted
@@ -40,6 +54,16 @@ pub struct NewTeacher {
     pub linkedin: Option<String>,
     pub comment_general: Option<String>,
     pub comment_experience: Option<String>,
+    pub discord_id: Option<DiscordId>,
+    pub program_id: Option<i64>,
+    /// The timezone in which this teacher's availability times are defined.
+    pub timezone: Timezone,
+    /// The most sessions this teacher wants to deliver per week (Monday to Sunday, in
+    /// their timezone), or 'None' for no cap.
+    pub max_sessions_per_week: Option<i16>,
+    /// The locale in which this teacher gets e-mails (see [`Teacher::notification_locale`]),
+    /// taken from their Discord client once their profile is linked.
+    pub locale: Option<String>,
 }
 
 /// A Partial Teacher, in order to specify certain fields to update.
@@ -59,6 +83,49 @@ pub struct PartialTeacher {
     pub linkedin: Option<Option<String>>,
     pub comment_general: Option<Option<String>>,
     pub comment_experience: Option<Option<String>>,
+    pub discord_id: Option<Option<DiscordId>>,
+    pub program_id: Option<Option<i64>>,
+    pub timezone: Option<Timezone>,
+    pub max_sessions_per_week: Option<Option<i16>>,
+    pub locale: Option<Option<String>>,
+}
+
+impl Teacher {
+    /// The locale in which this teacher gets e-mails: their Discord client's locale,
+    /// if known and supported, or the default one ("pt-BR").
+    pub fn notification_locale(&self) -> &'static str {
+        util::locale::default_optional_locale(self.locale.as_deref())
+    }
+
+    /// Checks whether this teacher already has as many sessions as their weekly cap
+    /// (see [`Teacher::max_sessions_per_week`]) in the week (Monday to Sunday, in their
+    /// timezone) of the given moment, given their sessions. Cancelled sessions don't count.
+    pub fn is_at_weekly_cap<'a>(
+        &self,
+        sessions: impl IntoIterator<Item = &'a Session>,
+        at: &chrono::DateTime<chrono::Utc>,
+    ) -> bool {
+        let Some(max) = self.max_sessions_per_week else {
+            return false;
+        };
+        let week = week_start(at.with_timezone(&self.timezone.0).date_naive());
+
+        let sessions_in_week = sessions
+            .into_iter()
+            .filter(|session| {
+                session.teacher_id == self.id
+                    && !session.status.is_cancelled()
+                    && week_start(
+                        session
+                            .start_at
+                            .with_timezone(&self.timezone.0)
+                            .date_naive(),
+                    ) == week
+            })
+            .count();
+
+        sessions_in_week >= max.max(0) as usize
+    }
 }
 
 impl From<Teacher> for NewTeacher {
@@ -78,6 +145,11 @@ impl From<Teacher> for NewTeacher {
             linkedin: teacher.linkedin,
             comment_general: teacher.comment_general,
             comment_experience: teacher.comment_experience,
+            discord_id: teacher.discord_id,
+            program_id: teacher.program_id,
+            timezone: teacher.timezone,
+            max_sessions_per_week: teacher.max_sessions_per_week,
+            locale: teacher.locale,
         }
     }
 }
@@ -100,6 +172,11 @@ impl From<Teacher> for PartialTeacher {
             linkedin: Some(teacher.linkedin),
             comment_general: Some(teacher.comment_general),
             comment_experience: Some(teacher.comment_experience),
+            discord_id: Some(teacher.discord_id),
+            program_id: Some(teacher.program_id),
+            timezone: Some(teacher.timezone),
+            max_sessions_per_week: Some(teacher.max_sessions_per_week),
+            locale: Some(teacher.locale),
         }
     }
 }
@@ -123,6 +200,11 @@ impl From<NewTeacher> for PartialTeacher {
             linkedin: Some(new_teacher.linkedin),
             comment_general: Some(new_teacher.comment_general),
             comment_experience: Some(new_teacher.comment_experience),
+            discord_id: Some(new_teacher.discord_id),
+            program_id: Some(new_teacher.program_id),
+            timezone: Some(new_teacher.timezone),
+            max_sessions_per_week: Some(new_teacher.max_sessions_per_week),
+            locale: Some(new_teacher.locale),
         }
     }
 }
@@ -0,0 +1,108 @@
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+use diesel::{
+    backend::RawValue,
+    deserialize::FromSql,
+    serialize,
+    serialize::{Output, ToSql},
+    sql_types::VarChar,
+    AsExpression, FromSqlRow,
+};
+
+/// An IANA timezone (e.g. "America/Sao_Paulo"), in which times are shown to
+/// and parsed from a user. Stored in the database by its name.
+#[derive(FromSqlRow, AsExpression, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[diesel(sql_type = VarChar)]
+pub struct Timezone(pub chrono_tz::Tz);
+
+impl Timezone {
+    /// The timezone assumed for users who haven't picked one (Brasília time).
+    pub const DEFAULT: Self = Self(chrono_tz::America::Sao_Paulo);
+
+    /// The IANA name of this timezone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mentoriabot_lib::model::Timezone;
+    /// let timezone: Timezone = "Europe/Lisbon".parse().unwrap();
+    ///
+    /// assert_eq!("Europe/Lisbon", timezone.name());
+    /// ```
+    pub fn name(self) -> &'static str {
+        self.0.name()
+    }
+
+    /// Returns the current time in this timezone.
+    pub fn now(self) -> chrono::DateTime<chrono_tz::Tz> {
+        chrono::Utc::now().with_timezone(&self.0)
+    }
+
+    /// Returns all known timezones whose names contain the given text (ignoring case),
+    /// sorted by name.
+    pub fn search(partial: &str) -> impl Iterator<Item = Self> + '_ {
+        let partial = partial.to_lowercase();
+
+        chrono_tz::TZ_VARIANTS
+            .iter()
+            .filter(move |tz| tz.name().to_lowercase().contains(&partial))
+            .map(|tz| Self(*tz))
+    }
+}
+
+impl Default for Timezone {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl FromStr for Timezone {
+    type Err = chrono_tz::ParseError;
+
+    /// Parses an IANA timezone name (e.g. "America/Manaus").
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.trim().parse().map(Self)
+    }
+}
+
+impl Display for Timezone {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl From<chrono_tz::Tz> for Timezone {
+    fn from(tz: chrono_tz::Tz) -> Self {
+        Self(tz)
+    }
+}
+
+impl From<Timezone> for chrono_tz::Tz {
+    fn from(value: Timezone) -> Self {
+        value.0
+    }
+}
+
+impl ToSql<VarChar, diesel::pg::Pg> for Timezone
+where
+    str: ToSql<VarChar, diesel::pg::Pg>,
+{
+    /// Allows usage of Timezone with diesel, with VarChar fields (holding its name).
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, diesel::pg::Pg>) -> serialize::Result {
+        <str as ToSql<VarChar, diesel::pg::Pg>>::to_sql(self.0.name(), out)
+    }
+}
+
+impl<DB> FromSql<VarChar, DB> for Timezone
+where
+    DB: diesel::backend::Backend,
+    String: FromSql<VarChar, DB>,
+{
+    /// Allows usage of Timezone with diesel, with VarChar fields (holding its name).
+    fn from_sql(bytes: RawValue<'_, DB>) -> diesel::deserialize::Result<Self> {
+        String::from_sql(bytes).and_then(|s| s.parse().map_err(Into::into))
+    }
+}
@@ -1,7 +1,7 @@
 use diesel::{AsChangeset, Identifiable, Insertable, Queryable};
 
-use super::DiscordId;
-use crate::db::schema::*;
+use super::{DiscordId, Timezone};
+use crate::{db::schema::*, util};
 
 /// Represents a registered User of our bot.
 #[derive(Queryable, Identifiable, Insertable, AsChangeset, Debug, Clone, PartialEq, Eq)]
@@ -11,6 +11,11 @@ pub struct User {
     pub name: String,
     pub email: String,
     pub bio: Option<String>,
+    /// The timezone in which times are shown to (and parsed from) this user.
+    pub timezone: Timezone,
+    /// The locale in which this user gets e-mails (see [`User::notification_locale`]),
+    /// taken from their Discord client.
+    pub locale: Option<String>,
 }
 
 pub type NewUser = User; // same fields
@@ -23,6 +28,8 @@ pub struct PartialUser {
     pub name: Option<String>,
     pub email: Option<String>,
     pub bio: Option<Option<String>>,
+    pub timezone: Option<Timezone>,
+    pub locale: Option<Option<String>>,
 }
 
 impl From<User> for PartialUser {
@@ -34,6 +41,16 @@ impl From<User> for PartialUser {
             name: Some(user.name),
             email: Some(user.email),
             bio: Some(user.bio),
+            timezone: Some(user.timezone),
+            locale: Some(user.locale),
         }
     }
 }
+
+impl User {
+    /// The locale in which this user gets e-mails: their Discord client's locale,
+    /// if known and supported, or the default one ("pt-BR").
+    pub fn notification_locale(&self) -> &'static str {
+        util::locale::default_optional_locale(self.locale.as_deref())
+    }
+}
@@ -0,0 +1,83 @@
+use diesel::{AsChangeset, Associations, Identifiable, Insertable, Queryable};
+
+use super::{DiscordId, Teacher, WaitlistStatus};
+use crate::db::schema::*;
+
+/// Represents a student waiting for a time to be available for scheduling, optionally
+/// only with a particular mentor or with a mentor of a particular specialty.
+/// Students are offered matching times in the order they joined the waitlist.
+#[derive(
+    Queryable, Identifiable, Insertable, AsChangeset, Associations, Debug, Clone, PartialEq, Eq,
+)]
+#[diesel(belongs_to(super::User, foreign_key = student_id))]
+#[diesel(table_name = waitlist_entries, treat_none_as_null = true)]
+pub struct WaitlistEntry {
+    pub id: i64,
+    pub student_id: DiscordId,
+    /// If set, only times of this mentor are offered.
+    pub teacher_id: Option<i64>,
+    /// If set, only times of mentors whose specialty contains this text are offered.
+    pub specialty: Option<String>,
+    pub status: WaitlistStatus,
+    pub joined_at: chrono::DateTime<chrono::Utc>,
+    /// The availability whose next occurrence was offered, if any.
+    pub offered_availability_id: Option<i64>,
+    /// When the offered time starts, if any.
+    pub offered_start_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Until when the offered time is held for this student, if any.
+    pub offer_expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Auxiliary struct for adding a student to the waitlist.
+#[derive(Insertable, Debug, Clone, PartialEq, Eq)]
+#[diesel(table_name = waitlist_entries)]
+pub struct NewWaitlistEntry {
+    pub student_id: DiscordId,
+    pub teacher_id: Option<i64>,
+    pub specialty: Option<String>,
+}
+
+impl WaitlistEntry {
+    /// Checks whether the given mentor's times may be offered to this entry's student.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mentoriabot_lib::model::{DiscordId, Teacher, WaitlistEntry, WaitlistStatus};
+    /// # let teacher = Teacher {
+    /// #     id: 1, name: "John".to_string(), email: "john@mentor.com".to_string(),
+    /// #     specialty: "Computer Science".to_string(), applied_at: None, bio: None,
+    /// #     course_info: None, company: None, company_role: None, whatsapp: None,
+    /// #     linkedin: None, comment_general: None, comment_experience: None,
+    /// #     discord_id: None, program_id: None, timezone: Default::default(),
+    /// #     max_sessions_per_week: None, locale: None,
+    /// # };
+    /// let entry = WaitlistEntry {
+    ///     id: 1,
+    ///     student_id: DiscordId(1),
+    ///     teacher_id: None,
+    ///     specialty: Some("computer".to_string()),
+    ///     status: WaitlistStatus::Waiting,
+    ///     joined_at: chrono::Utc::now(),
+    ///     offered_availability_id: None,
+    ///     offered_start_at: None,
+    ///     offer_expires_at: None,
+    /// };
+    ///
+    /// assert!(entry.matches(&teacher));
+    /// assert!(!WaitlistEntry {
+    ///     teacher_id: Some(2),
+    ///     ..entry
+    /// }
+    /// .matches(&teacher));
+    /// ```
+    pub fn matches(&self, teacher: &Teacher) -> bool {
+        self.teacher_id.is_none_or(|id| id == teacher.id)
+            && self.specialty.as_deref().is_none_or(|specialty| {
+                teacher
+                    .specialty
+                    .to_lowercase()
+                    .contains(&specialty.trim().to_lowercase())
+            })
+    }
+}
@@ -0,0 +1,128 @@
+use diesel::{
+    backend::RawValue,
+    deserialize::FromSql,
+    serialize::{Output, ToSql},
+    sql_types::SmallInt,
+    AsExpression, FromSqlRow,
+};
+
+use crate::error::Error;
+
+/// The status of a [`WaitlistEntry`]. Entries are kept after leaving the waitlist,
+/// with their status changed, so that its history is kept.
+///
+/// [`WaitlistEntry`]: super::WaitlistEntry
+#[derive(FromSqlRow, AsExpression, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[diesel(sql_type = SmallInt)]
+pub enum WaitlistStatus {
+    /// The student is waiting for a matching time to open up.
+    Waiting = 0,
+    /// A matching time was offered to (and is held for) the student, who may claim it
+    /// until the offer expires.
+    Offered = 1,
+    /// The student scheduled a session.
+    Fulfilled = 2,
+    /// The student didn't claim the offered time in time.
+    Expired = 3,
+    /// The student left the waitlist.
+    Left = 4,
+}
+
+impl WaitlistStatus {
+    /// Checks whether the student is still in the waitlist with this status
+    /// (waiting or with an offer).
+    pub fn is_active(&self) -> bool {
+        matches!(self, Self::Waiting | Self::Offered)
+    }
+
+    /// Converts this status to a localized string.
+    pub fn to_locale_string(&self, locale: &str) -> &'static str {
+        match locale {
+            "pt-BR" | "pt" => match self {
+                Self::Waiting => "Aguardando",
+                Self::Offered => "Horário oferecido",
+                Self::Fulfilled => "Atendido",
+                Self::Expired => "Oferta expirada",
+                Self::Left => "Saiu da lista",
+            },
+            _ => match self {
+                Self::Waiting => "Waiting",
+                Self::Offered => "Time offered",
+                Self::Fulfilled => "Fulfilled",
+                Self::Expired => "Offer expired",
+                Self::Left => "Left the waitlist",
+            },
+        }
+    }
+}
+
+impl TryFrom<i16> for WaitlistStatus {
+    type Error = Error;
+
+    fn try_from(value: i16) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Waiting),
+            1 => Ok(Self::Offered),
+            2 => Ok(Self::Fulfilled),
+            3 => Ok(Self::Expired),
+            4 => Ok(Self::Left),
+            _ => Err(Error::Other("Failed to convert from i16 to WaitlistStatus")),
+        }
+    }
+}
+
+impl From<WaitlistStatus> for i16 {
+    fn from(value: WaitlistStatus) -> Self {
+        match value {
+            WaitlistStatus::Waiting => 0,
+            WaitlistStatus::Offered => 1,
+            WaitlistStatus::Fulfilled => 2,
+            WaitlistStatus::Expired => 3,
+            WaitlistStatus::Left => 4,
+        }
+    }
+}
+
+impl ToSql<SmallInt, diesel::pg::Pg> for WaitlistStatus
+where
+    i16: ToSql<SmallInt, diesel::pg::Pg>,
+{
+    /// Allows usage of WaitlistStatus with diesel, with SmallInt fields.
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, diesel::pg::Pg>) -> diesel::serialize::Result {
+        let converted_self: i16 = (*self).into();
+        <i16 as ToSql<SmallInt, diesel::pg::Pg>>::to_sql(&converted_self, &mut out.reborrow())
+    }
+}
+
+impl<DB> FromSql<SmallInt, DB> for WaitlistStatus
+where
+    DB: diesel::backend::Backend,
+    i16: FromSql<SmallInt, DB>,
+{
+    /// Allows usage of WaitlistStatus with diesel, with SmallInt fields.
+    fn from_sql(bytes: RawValue<'_, DB>) -> diesel::deserialize::Result<Self> {
+        i16::from_sql(bytes).and_then(|v| Self::try_from(v).map_err(Into::into))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_waitlist_status_roundtrips_through_i16() {
+        for status in [
+            WaitlistStatus::Waiting,
+            WaitlistStatus::Offered,
+            WaitlistStatus::Fulfilled,
+            WaitlistStatus::Expired,
+            WaitlistStatus::Left,
+        ] {
+            assert_eq!(
+                Ok(status),
+                WaitlistStatus::try_from(i16::from(status)).map_err(|_| ())
+            );
+        }
+        assert!(WaitlistStatus::try_from(5).is_err());
+    }
+}
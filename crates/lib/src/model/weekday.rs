@@ -7,7 +7,7 @@ use diesel::{
     AsExpression, FromSqlRow,
 };
 
-use crate::error::Error;
+use crate::{error::Error, util::time::local_datetime_in};
 
 /// Represents a diesel-compatible weekday and provides several
 /// utilities for working with weekdays.
@@ -67,21 +67,37 @@ impl Weekday {
         }
     }
 
-    /// Given a datetime, returns a datetime with the closest future date that has
-    /// this weekday. The time component is kept the same.
-    /// (Simply clones the datetime if that weekday is the same as the given datetime's
-    /// weekday.)
-    pub fn next_day_with_this_weekday(
+    /// Given a date, returns the closest date (including itself) that has this weekday.
+    pub fn next_date_with_this_weekday(
         &self,
-        initial_day: &chrono::DateTime<chrono::FixedOffset>,
-    ) -> chrono::DateTime<chrono::FixedOffset> {
-        let initial_weekday = Self::from(initial_day.weekday());
+        initial_date: chrono::NaiveDate,
+    ) -> chrono::NaiveDate {
+        let initial_weekday = Self::from(initial_date.weekday());
         let next_weekdays = initial_weekday.next_7_days();
         // 0 <= delta <= 6
         // + all weekdays are always returned in ".next_7_days()", so shouldn't panic
         let delta = next_weekdays.iter().position(|w| w == self).unwrap();
 
-        *initial_day + chrono::Duration::days(delta.try_into().unwrap())
+        initial_date + chrono::Duration::days(delta.try_into().unwrap())
+    }
+
+    /// Given a datetime, returns a datetime with the closest future date that has
+    /// this weekday. The local time is kept the same, even if the timezone's offset
+    /// changes in between (e.g. due to daylight saving time), unless that time doesn't
+    /// exist or is ambiguous at the new date (see [`local_datetime_in`]).
+    /// (Simply clones the datetime if that weekday is the same as the given datetime's
+    /// weekday.)
+    pub fn next_day_with_this_weekday<Tz: chrono::TimeZone>(
+        &self,
+        initial_day: &chrono::DateTime<Tz>,
+    ) -> chrono::DateTime<Tz> {
+        let local = initial_day.naive_local();
+        let date = self.next_date_with_this_weekday(local.date());
+        if date == local.date() {
+            return initial_day.clone();
+        }
+
+        local_datetime_in(&initial_day.timezone(), &date.and_time(local.time()))
     }
 }
 
@@ -209,4 +225,22 @@ mod tests {
             Tuesday.next_day_with_this_weekday(&wed_10_05_2023)
         )
     }
+
+    #[test]
+    fn test_next_day_with_this_weekday_keeps_local_time_across_dst_changes() {
+        use chrono_tz::America::New_York;
+
+        // clocks are turned forward in New York on 12/03/2023
+        let fri_10_03_2023 = New_York.with_ymd_and_hms(2023, 3, 10, 10, 0, 0).unwrap();
+        let mon_13_03_2023 = New_York.with_ymd_and_hms(2023, 3, 13, 10, 0, 0).unwrap();
+
+        assert_eq!(
+            mon_13_03_2023,
+            Monday.next_day_with_this_weekday(&fri_10_03_2023)
+        );
+        assert_eq!(
+            chrono::Utc.with_ymd_and_hms(2023, 3, 13, 14, 0, 0).unwrap(),
+            Monday.next_day_with_this_weekday(&fri_10_03_2023)
+        );
+    }
 }
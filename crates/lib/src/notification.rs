@@ -2,8 +2,16 @@
 //! e.g., calendar or e-mail.
 mod calendar;
 mod email;
+mod gmail;
 mod google;
+mod google_calendar;
+mod local;
+mod smtp;
 
-pub use calendar::CalendarManager;
-pub use email::GmailManager;
+pub use calendar::{CalendarBackend, CalendarManager};
+pub use email::{EmailContent, EmailManager, EmailSender};
+pub use gmail::GmailManager;
 pub use google::GoogleApiManager;
+pub use google_calendar::GoogleCalendarManager;
+pub use local::{LocalCalendar, LocalEmailSender};
+pub use smtp::{SmtpConfig, SmtpEmailSender, SmtpTls};
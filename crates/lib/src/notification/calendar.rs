@@ -1,120 +1,137 @@
-//! Manages access to the Google Calendar API
+//! Manages the calendar events of sessions through some backend (see [`CalendarBackend`]).
+use std::sync::Arc;
 
-use google_calendar3::{
-    api::{
-        ConferenceData, ConferenceSolutionKey, CreateConferenceRequest, Event, EventAttendee,
-        EventDateTime,
-    },
-    hyper, hyper_rustls, CalendarHub,
-};
+use async_trait::async_trait;
+use google_calendar3::api::Event;
 
 use crate::{
     error::Result,
     model::{NewSession, Session, Teacher, User},
 };
 
-/// Manages Google Calendar operations.
+/// A calendar where each session gets an event with a video call, such as Google Calendar
+/// ([`GoogleCalendarManager`]) or a local directory for development ([`LocalCalendar`]).
+///
+/// [`GoogleCalendarManager`]: super::GoogleCalendarManager
+/// [`LocalCalendar`]: super::LocalCalendar
+#[async_trait]
+pub trait CalendarBackend: Send + Sync {
+    /// Creates an event for the given session, with a Google Meet call (whose ID is in the
+    /// event's conference data), inviting the student and the teacher.
+    async fn create_event_for_session(
+        &self,
+        student: &User,
+        teacher: &Teacher,
+        session: &NewSession,
+    ) -> Result<Event>;
+
+    /// Adds a student to the attendees of the event with the given ID, inviting them.
+    async fn add_attendee_to_event(&self, event_id: &str, student: &User) -> Result<()>;
+
+    /// Removes a student from the attendees of the event with the given ID.
+    async fn remove_attendee_from_event(&self, event_id: &str, student: &User) -> Result<()>;
+
+    /// Moves the event with the given ID to the session's new start and end times,
+    /// notifying its attendees. If the session's teacher changed, the previous teacher
+    /// is replaced by the new one in the event's attendees.
+    async fn reschedule_event(
+        &self,
+        event_id: &str,
+        session: &Session,
+        previous_teacher: &Teacher,
+        teacher: &Teacher,
+    ) -> Result<()>;
+
+    /// Cancels the event with the given ID.
+    async fn cancel_event(&self, event_id: &str) -> Result<()>;
+}
+
+/// Manages the calendar events of sessions through a [`CalendarBackend`].
 #[derive(Clone)]
 pub struct CalendarManager {
-    hub: CalendarHub<hyper_rustls::HttpsConnector<hyper::client::HttpConnector>>,
-    calendar_id: String,
+    backend: Arc<dyn CalendarBackend>,
 }
 
 impl CalendarManager {
-    /// Connects to the Google Calendar API, creating a new CalendarManager instance.
-    pub(super) async fn connect(
-        auth: impl google_apis_common::GetToken + 'static,
-        calendar_id: &str,
-    ) -> Result<Self> {
-        let hub = CalendarHub::new(
-            hyper::Client::builder().build(
-                hyper_rustls::HttpsConnectorBuilder::new()
-                    .with_native_roots()
-                    .https_or_http()
-                    .enable_http1()
-                    .enable_http2()
-                    .build(),
-            ),
-            auth,
-        );
-
-        Ok(Self {
-            hub,
-            calendar_id: calendar_id.into(),
-        })
+    /// Creates a CalendarManager managing events through the given backend.
+    pub fn new(backend: impl CalendarBackend + 'static) -> Self {
+        Self {
+            backend: Arc::new(backend),
+        }
     }
 
-    /// Creates a Google Calendar event, given a Session object.
+    /// Creates a calendar event (with a Google Meet call), given a Session object.
     pub async fn create_event_for_session(
         &self,
         student: &User,
         teacher: &Teacher,
         session: &NewSession,
     ) -> Result<Event> {
-        let event = Event {
-            summary: "Mentoria".to_string().into(),
-            description: "Mentoria".to_string().into(),
-            start: Some(EventDateTime {
-                date_time: Some(session.start_at),
-                ..Default::default()
-            }),
-            end: Some(EventDateTime {
-                date_time: Some(session.end_at),
-                ..Default::default()
-            }),
-            // create Google Meet conference
-            conference_data: Some(ConferenceData {
-                create_request: Some(CreateConferenceRequest {
-                    conference_solution_key: Some(ConferenceSolutionKey {
-                        type_: Some("hangoutsMeet".to_string()),
-                    }),
-                    // a random and unique request ID is necessary for some reason
-                    request_id: Some(
-                        crate::util::time::brazil_now()
-                            .timestamp_millis()
-                            .to_string(),
-                    ),
-                    status: None,
-                }),
-                ..Default::default()
-            }),
-            // invite the student and the teacher
-            attendees: Some(
-                [&student.email, &teacher.email]
-                    .iter()
-                    .map(|email| EventAttendee {
-                        email: Some((*email).clone()),
-                        response_status: Some("needsAction".to_string()),
-                        ..Default::default()
-                    })
-                    .collect(),
-            ),
-            ..Default::default()
-        };
-
-        self.hub
-            .events()
-            .insert(event, &self.calendar_id)
-            .conference_data_version(1) // enables creating conferences
-            .send_notifications(true)
-            .doit()
+        self.backend
+            .create_event_for_session(student, teacher, session)
             .await
-            .map(|(_, event)| event)
-            .map_err(From::from)
     }
 
-    /// Given a session, cancels its associated Google Calendar event, if any.
+    /// Adds a student joining a group session as an attendee of its associated
+    /// calendar event, if any, so they are invited to it.
+    pub async fn add_attendee_to_event_for_session(
+        &self,
+        session: &Session,
+        student: &User,
+    ) -> Result<()> {
+        match session.calendar_event_id.as_deref() {
+            Some(event_id) => self.backend.add_attendee_to_event(event_id, student).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Removes a student leaving a group session from the attendees of its
+    /// associated calendar event, if any.
+    pub async fn remove_attendee_from_event_for_session(
+        &self,
+        session: &Session,
+        student: &User,
+    ) -> Result<()> {
+        match session.calendar_event_id.as_deref() {
+            Some(event_id) => {
+                self.backend
+                    .remove_attendee_from_event(event_id, student)
+                    .await
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Moves the calendar event associated with a rescheduled session, if any,
+    /// to the session's new start and end times, notifying its attendees.
+    /// If the session's teacher changed, the previous teacher is replaced by the new one
+    /// in the event's attendees.
+    pub async fn reschedule_event_for_session(
+        &self,
+        session: &Session,
+        previous_teacher: &Teacher,
+        teacher: &Teacher,
+    ) -> Result<()> {
+        match session.calendar_event_id.as_deref() {
+            Some(event_id) => {
+                self.backend
+                    .reschedule_event(event_id, session, previous_teacher, teacher)
+                    .await
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Given a session, cancels its associated calendar event, if any.
     pub async fn cancel_event_for_session(&self, session: &Session) -> Result<()> {
-        if let Some(event_id) = session.calendar_event_id.as_deref() {
-            self.hub
-                .events()
-                .delete(&self.calendar_id, event_id)
-                .doit()
-                .await
-                .map(|_| ())
-                .map_err(From::from)
-        } else {
-            Ok(())
+        match session.calendar_event_id.as_deref() {
+            Some(event_id) => self.cancel_event(event_id).await,
+            None => Ok(()),
         }
     }
+
+    /// Cancels the calendar event with the given ID.
+    pub async fn cancel_event(&self, event_id: &str) -> Result<()> {
+        self.backend.cancel_event(event_id).await
+    }
 }
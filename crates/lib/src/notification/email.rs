@@ -1,139 +1,229 @@
-//! Manages access to the Google Gmail API
-use google_gmail1::{api as gmail, hyper, hyper_rustls, Gmail};
-use tokio::sync::OnceCell;
+//! Sends the bot's e-mails through some backend (see [`EmailSender`]).
+//! Each e-mail is rendered from templates (see [`template`]) in its recipient's locale.
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Datelike;
 
 use crate::{
-    error::{Error, Result},
-    model::{Session, Teacher, User},
-    util::{self, BRAZIL_TIMEZONE},
+    error::Result,
+    model::{Session, Teacher, User, Weekday},
+    util::{self, tr},
 };
 
+mod template;
+
+pub use template::EmailContent;
+use template::{format_datetime, format_duration, EmailTemplate};
+
+/// The name shown as the sender of the bot's e-mails.
+const SENDER_NAME: &str = "mentoria";
+
+/// A way of sending e-mails, such as the Gmail API ([`GmailManager`])
+/// or an SMTP server ([`SmtpEmailSender`]).
+///
+/// [`GmailManager`]: super::GmailManager
+/// [`SmtpEmailSender`]: super::SmtpEmailSender
+#[async_trait]
+pub trait EmailSender: Send + Sync {
+    /// Sends an e-mail with the given content (in plain text and HTML)
+    /// to the given recipients.
+    async fn send(&self, recipients: &[&str], content: &EmailContent) -> Result<()>;
+}
+
+/// Sends the bot's e-mails (such as those notifying students and mentors of their sessions)
+/// through an [`EmailSender`].
 #[derive(Clone)]
-pub struct GmailManager {
-    gmail: Gmail<hyper_rustls::HttpsConnector<hyper::client::HttpConnector>>,
-    user_id: String,
-    sender: OnceCell<String>,
+pub struct EmailManager {
+    sender: Arc<dyn EmailSender>,
 }
 
-/// Given a session, generates a string of the form '01/02/2023 às 23:59' indicating when it
-/// started / will start.
-fn generate_start_at_brazilian_string(session: &Session) -> String {
-    let start_at = session.start_at.with_timezone(&*BRAZIL_TIMEZONE);
-    let start_at_dm = util::time::day_month_display(&start_at.date_naive());
-    let start_at_hm = util::time::hour_minute_display(start_at.time());
+/// Builds an e-mail message from the given sender address to the given recipients,
+/// with its plain text and HTML bodies as alternatives.
+pub(super) fn build_message(
+    sender: &str,
+    recipients: &[&str],
+    content: &EmailContent,
+) -> Result<lettre::Message> {
+    let from = lettre::message::Mailbox::new(Some(SENDER_NAME.into()), sender.parse()?);
+    let mut to = lettre::message::Mailboxes::new();
+
+    for recipient in recipients {
+        to.push(lettre::message::Mailbox::new(None, recipient.parse()?))
+    }
+    let to: lettre::message::header::To = to.into();
 
-    format!("{start_at_dm} às {start_at_hm}")
+    Ok(lettre::Message::builder()
+        .subject(&content.subject)
+        .from(from)
+        .mailbox(to) // workaround to specify multiple recipients
+        .multipart(lettre::message::MultiPart::alternative_plain_html(
+            content.text.clone(),
+            content.html.clone(),
+        ))?)
 }
 
-impl GmailManager {
-    /// Connects to the Gmail API with the given authenticator.
-    pub(super) async fn connect(
-        auth: impl google_apis_common::GetToken + 'static,
-        user_id: &str,
-    ) -> Result<Self> {
-        let gmail = Gmail::new(
-            hyper::Client::builder().build(
-                hyper_rustls::HttpsConnectorBuilder::new()
-                    .with_native_roots()
-                    .https_or_http()
-                    .enable_http1()
-                    .enable_http2()
-                    .build(),
-            ),
-            auth,
-        );
-
-        Ok(Self {
-            gmail,
-            user_id: user_id.to_string(),
-            sender: Default::default(),
-        })
+impl EmailManager {
+    /// Creates an EmailManager sending e-mails through the given sender.
+    pub fn new(sender: impl EmailSender + 'static) -> Self {
+        Self {
+            sender: Arc::new(sender),
+        }
+    }
+
+    /// Send an e-mail with the given content to the given recipient.
+    async fn send_to(&self, recipient: &str, content: EmailContent) -> Result<()> {
+        self.sender.send(&[recipient], &content).await
     }
 
-    /// Send an e-mail with the given info.
-    async fn send_to(
+    /// Send an email to the teacher and to the student notifying that their session
+    /// was scheduled - if possible.
+    pub async fn send_emails_for_session(
         &self,
-        sender: &str,
-        recipients: impl IntoIterator<Item = &str>,
-        subject: &str,
-        content: &str,
+        teacher: &Teacher,
+        user: &User,
+        session: &Session,
+        meet_id: &str,
     ) -> Result<()> {
-        let from = lettre::message::Mailbox::new(Some("mentoria".into()), sender.parse()?);
-        let mut to = lettre::message::Mailboxes::new();
+        let session = Session {
+            meet_id: Some(meet_id.to_owned()),
+            ..session.clone()
+        };
 
-        for recipient in recipients {
-            to.push(lettre::message::Mailbox::new(None, recipient.parse()?))
+        let locale = teacher.notification_locale();
+        let mut mentor_email = EmailTemplate::new(
+            locale,
+            tr!("emails.scheduled.subject", locale = locale, "id" => session.id),
+        )
+        .paragraph(tr!(
+            "emails.scheduled.mentor_body",
+            locale = locale,
+            "student" => user.name,
+            "date" => format_datetime(&session.start_at, &teacher.timezone, locale),
+        ));
+        if let Some(bio) = &user.bio {
+            mentor_email = mentor_email.paragraph(tr!(
+                "emails.scheduled.student_bio",
+                locale = locale,
+                "bio" => bio,
+            ));
         }
-        let to: lettre::message::header::To = to.into();
-
-        let message = lettre::Message::builder()
-            .subject(subject)
-            .from(from)
-            .mailbox(to) // workaround to specify multiple recipients
-            .body(content.to_string())?;
 
-        let message = gmail::Message {
-            raw: Some(message.formatted()),
-            ..Default::default()
-        };
+        self.send_to(
+            &teacher.email,
+            mentor_email
+                .session_details(&session, &teacher.timezone)
+                .render(),
+        )
+        .await?;
 
-        let message_buffer =
-            tempfile::tempfile().map_err(|_| Error::Other("failed to create tempfile"))?;
-        const EMAIL_MIMETYPE: &str = "message/rfc822";
-
-        self.gmail
-            .users()
-            .messages_send(message, &self.user_id)
-            .upload(
-                message_buffer,
-                EMAIL_MIMETYPE
-                    .parse()
-                    .map_err(|_| Error::Other("failed to parse email mimetype"))?,
+        let locale = user.notification_locale();
+        self.send_to(
+            &user.email,
+            EmailTemplate::new(
+                locale,
+                tr!("emails.scheduled.subject", locale = locale, "id" => session.id),
             )
-            .await?;
-
-        Ok(())
+            .paragraph(tr!(
+                "emails.scheduled.student_body",
+                locale = locale,
+                "mentor" => teacher.name,
+                "date" => format_datetime(&session.start_at, &user.timezone, locale),
+            ))
+            .session_details(&session, &user.timezone)
+            .mentor_details(teacher)
+            .render(),
+        )
+        .await
     }
 
-    /// Send an email to the teacher and to the student notifying that their session
-    /// was scheduled - if possible.
-    pub async fn send_emails_for_session(
+    /// Send an email to the teacher and to a student joining a group session notifying
+    /// that the student will attend it - if possible.
+    pub async fn send_join_emails_for_session(
         &self,
         teacher: &Teacher,
         user: &User,
         session: &Session,
-        meet_id: &str,
     ) -> Result<()> {
-        let sender = self.resolve_sender().await?;
-        let start_at = generate_start_at_brazilian_string(session);
+        let locale = teacher.notification_locale();
+        self.send_to(
+            &teacher.email,
+            EmailTemplate::new(
+                locale,
+                tr!("emails.joined.mentor_subject", locale = locale, "id" => session.id),
+            )
+            .paragraph(tr!(
+                "emails.joined.mentor_body",
+                locale = locale,
+                "id" => session.id,
+                "student" => user.name,
+                "date" => format_datetime(&session.start_at, &teacher.timezone, locale),
+            ))
+            .render(),
+        )
+        .await?;
 
-        let student_bio = user
-            .bio
-            .as_ref()
-            .map(|bio| format!("\n\nO(a) aluno(a) definiu a seguinte bio: {bio}"))
-            .unwrap_or_else(String::default);
+        let locale = user.notification_locale();
+        self.send_to(
+            &user.email,
+            EmailTemplate::new(
+                locale,
+                tr!("emails.scheduled.subject", locale = locale, "id" => session.id),
+            )
+            .paragraph(tr!(
+                "emails.joined.student_body",
+                locale = locale,
+                "mentor" => teacher.name,
+                "date" => format_datetime(&session.start_at, &user.timezone, locale),
+            ))
+            .session_details(session, &user.timezone)
+            .mentor_details(teacher)
+            .render(),
+        )
+        .await
+    }
 
+    /// Send an email to the teacher and to a student leaving a group session notifying
+    /// that the student will no longer attend it - if possible.
+    pub async fn send_leave_emails_for_session(
+        &self,
+        teacher: &Teacher,
+        user: &User,
+        session: &Session,
+    ) -> Result<()> {
+        let locale = teacher.notification_locale();
         self.send_to(
-            sender,
-            [&*teacher.email],
-            &format!("Mentoria #{} Marcada", session.id),
-            &format!(
-                "Sua mentoria com o(a) aluno(a) {} foi agendada para {start_at}! O número dessa mentoria é #{}, que
-ocorrerá no Google Meet no seguinte link: https://meet.google.com/{meet_id}{}",
-                user.name, session.id, student_bio
-            ),
+            &teacher.email,
+            EmailTemplate::new(
+                locale,
+                tr!("emails.left.mentor_subject", locale = locale, "id" => session.id),
+            )
+            .paragraph(tr!(
+                "emails.left.mentor_body",
+                locale = locale,
+                "id" => session.id,
+                "student" => user.name,
+                "date" => format_datetime(&session.start_at, &teacher.timezone, locale),
+            ))
+            .render(),
         )
         .await?;
 
+        let locale = user.notification_locale();
         self.send_to(
-            sender,
-            [&*user.email],
-            &format!("Mentoria #{} Marcada", session.id),
-            &format!(
-                "Sua mentoria com o(a) mentor(a) {} foi agendada para {start_at}! O número dessa mentoria é #{}, que
-ocorrerá no Google Meet no seguinte link: https://meet.google.com/{meet_id}",
-                teacher.name, session.id
-            ),
+            &user.email,
+            EmailTemplate::new(
+                locale,
+                tr!("emails.cancelled.subject", locale = locale, "id" => session.id),
+            )
+            .paragraph(tr!(
+                "emails.left.student_body",
+                locale = locale,
+                "id" => session.id,
+                "mentor" => teacher.name,
+                "date" => format_datetime(&session.start_at, &user.timezone, locale),
+            ))
+            .render(),
         )
         .await
     }
@@ -146,43 +236,416 @@ ocorrerá no Google Meet no seguinte link: https://meet.google.com/{meet_id}",
         user: &User,
         session: &Session,
     ) -> Result<()> {
-        let sender = self.resolve_sender().await?;
-        let start_at = generate_start_at_brazilian_string(session);
+        let locale = teacher.notification_locale();
+        self.send_to(
+            &teacher.email,
+            EmailTemplate::new(
+                locale,
+                tr!("emails.cancelled.subject", locale = locale, "id" => session.id),
+            )
+            .paragraph(tr!(
+                "emails.cancelled.mentor_body",
+                locale = locale,
+                "id" => session.id,
+                "student" => user.name,
+                "date" => format_datetime(&session.start_at, &teacher.timezone, locale),
+            ))
+            .render(),
+        )
+        .await?;
 
+        let locale = user.notification_locale();
         self.send_to(
-            sender,
-            [&*teacher.email],
-            &format!("Mentoria #{} Desmarcada", session.id),
-            &format!(
-                "Sua mentoria com o(a) aluno(a) {}, de número #{} e anteriormente agendada para {start_at}, acaba de ser desmarcada.",
-                user.name, session.id
-            ),
+            &user.email,
+            EmailTemplate::new(
+                locale,
+                tr!("emails.cancelled.subject", locale = locale, "id" => session.id),
+            )
+            .paragraph(tr!(
+                "emails.cancelled.student_body",
+                locale = locale,
+                "id" => session.id,
+                "mentor" => teacher.name,
+                "date" => format_datetime(&session.start_at, &user.timezone, locale),
+            ))
+            .render(),
+        )
+        .await
+    }
+
+    /// Send an email to the students of a session cancelled by its teacher, explaining why
+    /// and, if any are given, listing alternative times they could schedule instead
+    /// - if possible. Each student is shown times in their own timezone.
+    pub async fn send_mentor_cancel_emails_for_session(
+        &self,
+        teacher: &Teacher,
+        students: &[User],
+        session: &Session,
+        reason: &str,
+        alternatives: &[chrono::DateTime<chrono::Utc>],
+    ) -> Result<()> {
+        for student in students {
+            let locale = student.notification_locale();
+            let mut email = EmailTemplate::new(
+                locale,
+                tr!("emails.mentor_cancelled.subject", locale = locale, "id" => session.id),
+            )
+            .paragraph(tr!(
+                "emails.mentor_cancelled.body",
+                locale = locale,
+                "id" => session.id,
+                "mentor" => teacher.name,
+                "date" => format_datetime(&session.start_at, &student.timezone, locale),
+                "reason" => reason,
+            ));
+
+            if !alternatives.is_empty() {
+                let alternatives = alternatives
+                    .iter()
+                    .map(|alternative| {
+                        let weekday =
+                            Weekday::from(alternative.with_timezone(&student.timezone.0).weekday());
+                        format!(
+                            "- {} {}",
+                            weekday.to_locale_shorthand_string(locale),
+                            format_datetime(alternative, &student.timezone, locale)
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+                email = email.paragraph(format!(
+                    "{}\n{alternatives}",
+                    tr!("emails.mentor_cancelled.alternatives", locale = locale)
+                ));
+            }
+
+            self.send_to(&student.email, email.mentor_details(teacher).render())
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Send an email to the teacher and to the student notifying that their session was
+    /// rescheduled - if possible. If the session's teacher changed, the previous teacher
+    /// is notified that the session is no longer theirs.
+    pub async fn send_reschedule_emails_for_session(
+        &self,
+        previous_teacher: &Teacher,
+        teacher: &Teacher,
+        user: &User,
+        previous_session: &Session,
+        session: &Session,
+    ) -> Result<()> {
+        if previous_teacher.email != teacher.email {
+            let locale = previous_teacher.notification_locale();
+            self.send_to(
+                &previous_teacher.email,
+                EmailTemplate::new(
+                    locale,
+                    tr!("emails.cancelled.subject", locale = locale, "id" => session.id),
+                )
+                .paragraph(tr!(
+                    "emails.rescheduled.previous_mentor_body",
+                    locale = locale,
+                    "id" => session.id,
+                    "student" => user.name,
+                    "date" => format_datetime(
+                        &previous_session.start_at,
+                        &previous_teacher.timezone,
+                        locale
+                    ),
+                ))
+                .render(),
+            )
+            .await?;
+        }
+
+        let locale = teacher.notification_locale();
+        self.send_to(
+            &teacher.email,
+            EmailTemplate::new(
+                locale,
+                tr!("emails.rescheduled.subject", locale = locale, "id" => session.id),
+            )
+            .paragraph(tr!(
+                "emails.rescheduled.mentor_body",
+                locale = locale,
+                "id" => session.id,
+                "student" => user.name,
+                "previous_date" => format_datetime(
+                    &previous_session.start_at,
+                    &teacher.timezone,
+                    locale
+                ),
+                "date" => format_datetime(&session.start_at, &teacher.timezone, locale),
+            ))
+            .session_details(session, &teacher.timezone)
+            .render(),
         )
         .await?;
 
+        let locale = user.notification_locale();
         self.send_to(
-            sender,
-            [&*user.email],
-            &format!("Mentoria #{} Desmarcada", session.id),
-            &format!(
-                "Sua mentoria com o(a) mentor(a) {}, de número #{} e anteriormente agendada para {start_at}, acaba de ser desmarcada.",
-                teacher.name, session.id
-            ),
+            &user.email,
+            EmailTemplate::new(
+                locale,
+                tr!("emails.rescheduled.subject", locale = locale, "id" => session.id),
+            )
+            .paragraph(tr!(
+                "emails.rescheduled.student_body",
+                locale = locale,
+                "id" => session.id,
+                "mentor" => teacher.name,
+                "previous_date" => format_datetime(
+                    &previous_session.start_at,
+                    &user.timezone,
+                    locale
+                ),
+                "date" => format_datetime(&session.start_at, &user.timezone, locale),
+            ))
+            .session_details(session, &user.timezone)
+            .mentor_details(teacher)
+            .render(),
         )
         .await
     }
 
-    async fn resolve_sender(&self) -> Result<&String> {
-        self.sender
-            .get_or_try_init(|| {
-                Box::pin(async move {
-                    let response = self.gmail.users().get_profile(&self.user_id).doit().await?;
-                    response
-                        .1
-                        .email_address
-                        .ok_or_else(|| Error::Other("Could not fetch sender e-mail address."))
-                })
-            })
-            .await
+    /// Send an email to the teacher and to the session's students reminding them
+    /// that the session will start soon - if possible.
+    pub async fn send_reminder_emails_for_session(
+        &self,
+        teacher: &Teacher,
+        students: &[User],
+        session: &Session,
+        time_left: chrono::Duration,
+    ) -> Result<()> {
+        let student_names = students
+            .iter()
+            .map(|student| &*student.name)
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let locale = teacher.notification_locale();
+        self.send_to(
+            &teacher.email,
+            EmailTemplate::new(
+                locale,
+                tr!("emails.reminder.subject", locale = locale, "id" => session.id),
+            )
+            .paragraph(tr!(
+                "emails.reminder.mentor_body",
+                locale = locale,
+                "id" => session.id,
+                "students" => student_names,
+                "date" => format_datetime(&session.start_at, &teacher.timezone, locale),
+                "time_left" => format_duration(time_left, locale),
+            ))
+            .session_details(session, &teacher.timezone)
+            .render(),
+        )
+        .await?;
+
+        for student in students {
+            let locale = student.notification_locale();
+            self.send_to(
+                &student.email,
+                EmailTemplate::new(
+                    locale,
+                    tr!("emails.reminder.subject", locale = locale, "id" => session.id),
+                )
+                .paragraph(tr!(
+                    "emails.reminder.student_body",
+                    locale = locale,
+                    "id" => session.id,
+                    "mentor" => teacher.name,
+                    "date" => format_datetime(&session.start_at, &student.timezone, locale),
+                    "time_left" => format_duration(time_left, locale),
+                ))
+                .session_details(session, &student.timezone)
+                .mentor_details(teacher)
+                .render(),
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Send an email to the teacher with the code they must provide to the bot
+    /// in order to link their mentor profile to their Discord account, which is valid
+    /// for the given amount of minutes. It is sent in the given locale (the one of
+    /// whoever requested the link).
+    pub async fn send_mentor_link_code_email(
+        &self,
+        teacher: &Teacher,
+        code: &str,
+        validity_minutes: i64,
+        locale: &str,
+    ) -> Result<()> {
+        let locale = util::locale::default_locale(locale);
+
+        self.send_to(
+            &teacher.email,
+            EmailTemplate::new(
+                locale,
+                tr!("emails.mentor_link_code.subject", locale = locale),
+            )
+            .paragraph(tr!(
+                "emails.mentor_link_code.body",
+                locale = locale,
+                "name" => teacher.name,
+                "code" => code,
+                "minutes" => validity_minutes,
+            ))
+            .render(),
+        )
+        .await
+    }
+
+    /// Send an email to a student in the waitlist, offering them a mentor's time which
+    /// became available, held for them until the given moment.
+    pub async fn send_waitlist_offer_email(
+        &self,
+        teacher: &Teacher,
+        user: &User,
+        start_at: chrono::DateTime<chrono::Utc>,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<()> {
+        let locale = user.notification_locale();
+
+        self.send_to(
+            &user.email,
+            EmailTemplate::new(
+                locale,
+                tr!("emails.waitlist_offer.subject", locale = locale),
+            )
+            .paragraph(tr!(
+                "emails.waitlist_offer.body",
+                locale = locale,
+                "name" => user.name,
+                "mentor" => teacher.name,
+                "date" => format_datetime(&start_at, &user.timezone, locale),
+                "expires_at" => format_datetime(&expires_at, &user.timezone, locale),
+            ))
+            .mentor_details(teacher)
+            .render(),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use async_trait::async_trait;
+
+    use super::{EmailContent, EmailManager, EmailSender};
+    use crate::{
+        error::Result,
+        model::{DiscordId, Session, SessionStatus, Teacher, User},
+    };
+
+    /// Keeps the e-mails sent through it, along with their recipients.
+    #[derive(Clone, Default)]
+    struct RecordingSender(Arc<Mutex<Vec<(String, EmailContent)>>>);
+
+    #[async_trait]
+    impl EmailSender for RecordingSender {
+        async fn send(&self, recipients: &[&str], content: &EmailContent) -> Result<()> {
+            let mut sent = self.0.lock().unwrap();
+            for recipient in recipients {
+                sent.push((recipient.to_string(), content.clone()));
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_session_emails_use_recipient_locale() -> Result<()> {
+        let sender = RecordingSender::default();
+        let manager = EmailManager::new(sender.clone());
+
+        let student = User {
+            discord_id: DiscordId(1),
+            name: "Student <1>".to_string(),
+            email: "student@student.com".to_string(),
+            bio: None,
+            timezone: "America/New_York".parse().unwrap(),
+            locale: Some("en-US".to_string()),
+        };
+        let teacher = Teacher {
+            id: 1,
+            name: "Mentor".to_string(),
+            email: "mentor@mentor.com".to_string(),
+            specialty: "Computação".to_string(),
+            applied_at: None,
+            bio: None,
+            course_info: None,
+            company: None,
+            company_role: None,
+            whatsapp: None,
+            linkedin: Some("https://www.linkedin.com/in/mentor".to_string()),
+            comment_general: None,
+            comment_experience: None,
+            discord_id: None,
+            program_id: None,
+            timezone: Default::default(),
+            max_sessions_per_week: None,
+            locale: None,
+        };
+        let start_at = chrono::DateTime::parse_from_rfc3339("2023-09-25T15:00:00Z")
+            .unwrap()
+            .into();
+        let session = Session {
+            id: 42,
+            summary: None,
+            notified: false,
+            teacher_id: teacher.id,
+            student_id: student.discord_id,
+            availability_id: 1,
+            meet_id: None,
+            calendar_event_id: None,
+            start_at,
+            end_at: start_at + chrono::Duration::minutes(40),
+            status: SessionStatus::Scheduled,
+            cancelled_at: None,
+            cancel_reason: None,
+            feedback_requested: false,
+        };
+
+        manager
+            .send_emails_for_session(&teacher, &student, &session, "abc-defg-hij")
+            .await?;
+
+        let sent = sender.0.lock().unwrap();
+        let [(mentor_address, mentor_email), (student_address, student_email)] = &sent[..] else {
+            panic!("expected two e-mails, got {}", sent.len());
+        };
+
+        // the mentor's locale isn't known, so the default one (pt-BR) is used
+        assert_eq!("mentor@mentor.com", mentor_address);
+        assert_eq!("Mentoria #42 Marcada", mentor_email.subject);
+        assert!(mentor_email
+            .text
+            .contains("25/09/2023 às 12:00 (America/Sao_Paulo)"));
+        assert!(mentor_email.text.contains("40 minutos"));
+        assert!(mentor_email.html.contains("Student &lt;1&gt;"));
+
+        assert_eq!("student@student.com", student_address);
+        assert_eq!("Session #42 Scheduled", student_email.subject);
+        assert!(student_email
+            .text
+            .contains("25/09/2023 at 11:00 (America/New_York)"));
+        assert!(student_email
+            .text
+            .contains("Mentor's profile: https://www.linkedin.com/in/mentor"));
+        assert!(student_email.html.contains(
+            r#"<a href="https://meet.google.com/abc-defg-hij">https://meet.google.com/abc-defg-hij</a>"#
+        ));
+
+        Ok(())
     }
 }
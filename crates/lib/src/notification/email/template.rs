@@ -0,0 +1,171 @@
+//! Renders the bot's e-mails from the templates in the `emails` section of the locale files,
+//! in both plain text and HTML.
+use std::fmt::Write;
+
+use once_cell::sync::Lazy;
+
+use crate::{
+    model::{Session, Teacher, Timezone},
+    util::{self, tr},
+};
+
+/// Matches the links in an e-mail's (HTML-escaped) text, so that they can be made clickable.
+static LINK_REGEX: Lazy<regex::Regex> =
+    Lazy::new(|| regex::Regex::new(r#"https?://[^\s<>"]+"#).unwrap());
+
+/// The subject and body of an e-mail, with the body both in plain text and in HTML
+/// (sent as alternatives of each other).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmailContent {
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+/// An e-mail being rendered in some locale: its paragraphs, followed by a list of details
+/// (e.g. the session's number and date) and by the bot's footer.
+#[derive(Debug, Clone)]
+pub(super) struct EmailTemplate {
+    locale: &'static str,
+    subject: String,
+    paragraphs: Vec<String>,
+    details: Vec<(String, String)>,
+}
+
+/// Formats the given moment in the given timezone, in the given locale, such as
+/// '01/02/2023 às 23:59 (America/Sao_Paulo)'.
+pub(super) fn format_datetime(
+    datetime: &chrono::DateTime<chrono::Utc>,
+    timezone: &Timezone,
+    locale: &str,
+) -> String {
+    let datetime = datetime.with_timezone(&timezone.0);
+
+    tr!(
+        "emails.datetime",
+        locale = locale,
+        "date" => util::time::day_month_year_display(&datetime.date_naive()),
+        "time" => util::time::hour_minute_display(datetime.time()),
+        "timezone" => timezone,
+    )
+}
+
+/// Formats the given duration (e.g. '1 hour and 30 minutes') in the given locale.
+pub(super) fn format_duration(duration: chrono::Duration, locale: &str) -> String {
+    util::locale::convert_chrono_duration_to_locale_string(duration, locale)
+}
+
+/// Escapes the given text so that it can be included in HTML.
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Escapes the given text for HTML, making its links clickable and keeping its line breaks.
+fn text_to_html(text: &str) -> String {
+    let text = escape_html(text);
+    LINK_REGEX
+        .replace_all(&text, r#"<a href="$0">$0</a>"#)
+        .replace('\n', "<br>\n")
+}
+
+impl EmailTemplate {
+    /// Starts rendering an e-mail with the given subject in the given locale.
+    pub(super) fn new(locale: &'static str, subject: String) -> Self {
+        Self {
+            locale,
+            subject,
+            paragraphs: Vec::new(),
+            details: Vec::new(),
+        }
+    }
+
+    /// Adds a paragraph of text to the e-mail.
+    pub(super) fn paragraph(mut self, paragraph: String) -> Self {
+        self.paragraphs.push(paragraph);
+        self
+    }
+
+    /// Adds a detail, with the label of the given key in the `emails.details` section,
+    /// to the e-mail.
+    fn detail(mut self, key: &str, value: String) -> Self {
+        let label = tr!(&format!("emails.details.{key}"), locale = self.locale);
+        self.details.push((label, value));
+        self
+    }
+
+    /// Adds the session's number, date (in the given timezone), duration and Google Meet link
+    /// (if any) to the e-mail's details.
+    pub(super) fn session_details(self, session: &Session, timezone: &Timezone) -> Self {
+        let locale = self.locale;
+        let template = self
+            .detail("number", format!("#{}", session.id))
+            .detail("date", format_datetime(&session.start_at, timezone, locale))
+            .detail(
+                "duration",
+                format_duration(session.end_at - session.start_at, locale),
+            );
+
+        match &session.meet_id {
+            Some(meet_id) => template.detail("meet", format!("https://meet.google.com/{meet_id}")),
+            None => template,
+        }
+    }
+
+    /// Adds the teacher's name and profile link (if any) to the e-mail's details.
+    pub(super) fn mentor_details(self, teacher: &Teacher) -> Self {
+        let template = self.detail("mentor", teacher.name.clone());
+
+        match &teacher.linkedin {
+            Some(linkedin) => template.detail("mentor_profile", linkedin.clone()),
+            None => template,
+        }
+    }
+
+    /// Renders the e-mail, in plain text and in HTML.
+    pub(super) fn render(self) -> EmailContent {
+        let footer = tr!("emails.footer", locale = self.locale);
+
+        let mut text = self.paragraphs.join("\n\n");
+        if !self.details.is_empty() {
+            text.push_str("\n\n");
+            for (label, value) in &self.details {
+                let _ = writeln!(text, "{label}: {value}");
+            }
+        }
+        let _ = write!(text, "\n\n--\n{footer}");
+
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html lang=\"{}\">\n<body style=\"font-family: sans-serif;\">\n",
+            self.locale
+        );
+        for paragraph in &self.paragraphs {
+            let _ = writeln!(html, "<p>{}</p>", text_to_html(paragraph));
+        }
+        if !self.details.is_empty() {
+            html.push_str("<table>\n");
+            for (label, value) in &self.details {
+                let _ = writeln!(
+                    html,
+                    "<tr><th align=\"left\">{}</th><td>{}</td></tr>",
+                    escape_html(label),
+                    text_to_html(value)
+                );
+            }
+            html.push_str("</table>\n");
+        }
+        let _ = write!(
+            html,
+            "<p style=\"color: #777777; font-size: small;\">{}</p>\n</body>\n</html>\n",
+            escape_html(&footer)
+        );
+
+        EmailContent {
+            subject: self.subject,
+            text,
+            html,
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! Manages access to the Google Gmail API
+use async_trait::async_trait;
+use google_gmail1::{api as gmail, hyper, hyper_rustls, Gmail};
+use tokio::sync::OnceCell;
+
+use super::email::{build_message, EmailContent, EmailSender};
+use crate::error::{Error, Result};
+
+/// Sends e-mails through the Gmail API, from the Google Account the bot is logged into.
+#[derive(Clone)]
+pub struct GmailManager {
+    gmail: Gmail<hyper_rustls::HttpsConnector<hyper::client::HttpConnector>>,
+    user_id: String,
+    sender: OnceCell<String>,
+}
+
+impl GmailManager {
+    /// Connects to the Gmail API with the given authenticator.
+    pub(super) async fn connect(
+        auth: impl google_apis_common::GetToken + 'static,
+        user_id: &str,
+    ) -> Result<Self> {
+        let gmail = Gmail::new(
+            hyper::Client::builder().build(
+                hyper_rustls::HttpsConnectorBuilder::new()
+                    .with_native_roots()
+                    .https_or_http()
+                    .enable_http1()
+                    .enable_http2()
+                    .build(),
+            ),
+            auth,
+        );
+
+        Ok(Self {
+            gmail,
+            user_id: user_id.to_string(),
+            sender: Default::default(),
+        })
+    }
+
+    async fn resolve_sender(&self) -> Result<&String> {
+        self.sender
+            .get_or_try_init(|| {
+                Box::pin(async move {
+                    let response = self.gmail.users().get_profile(&self.user_id).doit().await?;
+                    response
+                        .1
+                        .email_address
+                        .ok_or_else(|| Error::Other("Could not fetch sender e-mail address."))
+                })
+            })
+            .await
+    }
+}
+
+#[async_trait]
+impl EmailSender for GmailManager {
+    async fn send(&self, recipients: &[&str], content: &EmailContent) -> Result<()> {
+        let sender = self.resolve_sender().await?;
+        let message = build_message(sender, recipients, content)?;
+
+        let message = gmail::Message {
+            raw: Some(message.formatted()),
+            ..Default::default()
+        };
+
+        let message_buffer =
+            tempfile::tempfile().map_err(|_| Error::Other("failed to create tempfile"))?;
+        const EMAIL_MIMETYPE: &str = "message/rfc822";
+
+        self.gmail
+            .users()
+            .messages_send(message, &self.user_id)
+            .upload(
+                message_buffer,
+                EMAIL_MIMETYPE
+                    .parse()
+                    .map_err(|_| Error::Other("failed to parse email mimetype"))?,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
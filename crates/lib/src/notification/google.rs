@@ -1,11 +1,23 @@
-use super::{calendar::CalendarManager, email::GmailManager};
+use std::path::Path;
+
+use super::{
+    calendar::CalendarManager,
+    email::{EmailManager, EmailSender},
+    gmail::GmailManager,
+    google_calendar::GoogleCalendarManager,
+    local::{LocalCalendar, LocalEmailSender},
+};
 use crate::error::Result;
 
 /// Manages Google API-related structs.
 #[derive(Clone)]
 pub struct GoogleApiManager {
+    /// Manages the sessions' events; in Google Calendar, unless local
+    /// (see [`GoogleApiManager::local`]).
     pub calendar: CalendarManager,
-    pub email: GmailManager,
+    /// Sends the bot's e-mails; through Gmail, unless replaced
+    /// (see [`GoogleApiManager::with_email_sender`]).
+    pub email: EmailManager,
 }
 
 impl GoogleApiManager {
@@ -17,8 +29,29 @@ impl GoogleApiManager {
         user_id: &str,
     ) -> Result<Self> {
         Ok(Self {
-            calendar: CalendarManager::connect(auth.clone(), calendar_id).await?,
-            email: GmailManager::connect(auth, user_id).await?,
+            calendar: CalendarManager::new(
+                GoogleCalendarManager::connect(auth.clone(), calendar_id).await?,
+            ),
+            email: EmailManager::new(GmailManager::connect(auth, user_id).await?),
         })
     }
+
+    /// Doesn't connect to Google at all, instead writing e-mails (as `.eml` files) and
+    /// calendar events (as JSON files, with fake Google Meet IDs) to the given directory.
+    /// Meant for development.
+    pub fn local(dir: impl AsRef<Path>) -> Self {
+        Self {
+            calendar: CalendarManager::new(LocalCalendar::new(&dir)),
+            email: EmailManager::new(LocalEmailSender::new(&dir)),
+        }
+    }
+
+    /// Sends the bot's e-mails through the given sender (e.g. an SMTP server)
+    /// instead of Gmail.
+    pub fn with_email_sender(self, sender: impl EmailSender + 'static) -> Self {
+        Self {
+            email: EmailManager::new(sender),
+            ..self
+        }
+    }
 }
@@ -0,0 +1,224 @@
+//! Manages access to the Google Calendar API
+use async_trait::async_trait;
+use google_calendar3::{
+    api::{
+        ConferenceData, ConferenceSolutionKey, CreateConferenceRequest, Event, EventAttendee,
+        EventDateTime,
+    },
+    hyper, hyper_rustls, CalendarHub,
+};
+
+use super::calendar::CalendarBackend;
+use crate::{
+    error::Result,
+    model::{NewSession, Session, Teacher, User},
+};
+
+/// Manages events in a Google Calendar, along with their Google Meet calls.
+#[derive(Clone)]
+pub struct GoogleCalendarManager {
+    hub: CalendarHub<hyper_rustls::HttpsConnector<hyper::client::HttpConnector>>,
+    calendar_id: String,
+}
+
+impl GoogleCalendarManager {
+    /// Connects to the Google Calendar API, creating a new GoogleCalendarManager instance.
+    pub(super) async fn connect(
+        auth: impl google_apis_common::GetToken + 'static,
+        calendar_id: &str,
+    ) -> Result<Self> {
+        let hub = CalendarHub::new(
+            hyper::Client::builder().build(
+                hyper_rustls::HttpsConnectorBuilder::new()
+                    .with_native_roots()
+                    .https_or_http()
+                    .enable_http1()
+                    .enable_http2()
+                    .build(),
+            ),
+            auth,
+        );
+
+        Ok(Self {
+            hub,
+            calendar_id: calendar_id.into(),
+        })
+    }
+
+    /// Fetches the Google Calendar event with the given ID and updates its list
+    /// of attendees through the given function, notifying them.
+    async fn update_event_attendees(
+        &self,
+        event_id: &str,
+        update_attendees: impl FnOnce(&mut Vec<EventAttendee>) + Send,
+    ) -> Result<()> {
+        let (_, event) = self
+            .hub
+            .events()
+            .get(&self.calendar_id, event_id)
+            .doit()
+            .await?;
+
+        let mut attendees = event.attendees.unwrap_or_default();
+        update_attendees(&mut attendees);
+
+        self.hub
+            .events()
+            .patch(
+                Event {
+                    attendees: Some(attendees),
+                    ..Default::default()
+                },
+                &self.calendar_id,
+                event_id,
+            )
+            .send_updates("all")
+            .doit()
+            .await
+            .map(|_| ())
+            .map_err(From::from)
+    }
+}
+
+#[async_trait]
+impl CalendarBackend for GoogleCalendarManager {
+    async fn create_event_for_session(
+        &self,
+        student: &User,
+        teacher: &Teacher,
+        session: &NewSession,
+    ) -> Result<Event> {
+        let event = Event {
+            summary: "Mentoria".to_string().into(),
+            description: "Mentoria".to_string().into(),
+            start: Some(EventDateTime {
+                date_time: Some(session.start_at),
+                ..Default::default()
+            }),
+            end: Some(EventDateTime {
+                date_time: Some(session.end_at),
+                ..Default::default()
+            }),
+            // create Google Meet conference
+            conference_data: Some(ConferenceData {
+                create_request: Some(CreateConferenceRequest {
+                    conference_solution_key: Some(ConferenceSolutionKey {
+                        type_: Some("hangoutsMeet".to_string()),
+                    }),
+                    // a random and unique request ID is necessary for some reason
+                    request_id: Some(
+                        crate::util::time::brazil_now()
+                            .timestamp_millis()
+                            .to_string(),
+                    ),
+                    status: None,
+                }),
+                ..Default::default()
+            }),
+            // invite the student and the teacher
+            attendees: Some(
+                [&student.email, &teacher.email]
+                    .iter()
+                    .map(|email| EventAttendee {
+                        email: Some((*email).clone()),
+                        response_status: Some("needsAction".to_string()),
+                        ..Default::default()
+                    })
+                    .collect(),
+            ),
+            ..Default::default()
+        };
+
+        self.hub
+            .events()
+            .insert(event, &self.calendar_id)
+            .conference_data_version(1) // enables creating conferences
+            .send_notifications(true)
+            .doit()
+            .await
+            .map(|(_, event)| event)
+            .map_err(From::from)
+    }
+
+    async fn add_attendee_to_event(&self, event_id: &str, student: &User) -> Result<()> {
+        self.update_event_attendees(event_id, |attendees| {
+            if !attendees
+                .iter()
+                .any(|attendee| attendee.email.as_ref() == Some(&student.email))
+            {
+                attendees.push(EventAttendee {
+                    email: Some(student.email.clone()),
+                    response_status: Some("needsAction".to_string()),
+                    ..Default::default()
+                });
+            }
+        })
+        .await
+    }
+
+    async fn remove_attendee_from_event(&self, event_id: &str, student: &User) -> Result<()> {
+        self.update_event_attendees(event_id, |attendees| {
+            attendees.retain(|attendee| attendee.email.as_ref() != Some(&student.email))
+        })
+        .await
+    }
+
+    async fn reschedule_event(
+        &self,
+        event_id: &str,
+        session: &Session,
+        previous_teacher: &Teacher,
+        teacher: &Teacher,
+    ) -> Result<()> {
+        let (_, event) = self
+            .hub
+            .events()
+            .get(&self.calendar_id, event_id)
+            .doit()
+            .await?;
+
+        let mut attendees = event.attendees.unwrap_or_default();
+        if previous_teacher.email != teacher.email {
+            attendees.retain(|attendee| attendee.email.as_ref() != Some(&previous_teacher.email));
+            attendees.push(EventAttendee {
+                email: Some(teacher.email.clone()),
+                response_status: Some("needsAction".to_string()),
+                ..Default::default()
+            });
+        }
+
+        self.hub
+            .events()
+            .patch(
+                Event {
+                    start: Some(EventDateTime {
+                        date_time: Some(session.start_at),
+                        ..Default::default()
+                    }),
+                    end: Some(EventDateTime {
+                        date_time: Some(session.end_at),
+                        ..Default::default()
+                    }),
+                    attendees: Some(attendees),
+                    ..Default::default()
+                },
+                &self.calendar_id,
+                event_id,
+            )
+            .send_updates("all")
+            .doit()
+            .await
+            .map(|_| ())
+            .map_err(From::from)
+    }
+
+    async fn cancel_event(&self, event_id: &str) -> Result<()> {
+        self.hub
+            .events()
+            .delete(&self.calendar_id, event_id)
+            .doit()
+            .await
+            .map(|_| ())
+            .map_err(From::from)
+    }
+}
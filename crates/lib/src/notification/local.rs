@@ -0,0 +1,334 @@
+//! Development backends which write e-mails and calendar events to a local directory
+//! instead of sending them through Google, so that the bot can run without Google credentials.
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use async_trait::async_trait;
+use google_calendar3::api::{ConferenceData, Event, EventAttendee, EventDateTime};
+
+use super::{
+    calendar::CalendarBackend,
+    email::{build_message, EmailContent, EmailSender},
+};
+use crate::{
+    error::Result,
+    model::{NewSession, Session, Teacher, User},
+};
+
+/// The address local e-mails are sent from.
+const LOCAL_SENDER: &str = "mentoria@localhost";
+
+/// Writes each e-mail sent to an `.eml` file in the `emails/` subdirectory of some directory.
+#[derive(Clone)]
+pub struct LocalEmailSender {
+    dir: PathBuf,
+    counter: Arc<AtomicU64>,
+}
+
+/// Keeps each calendar event as a JSON file in the `events/` subdirectory of some directory,
+/// with a fake Google Meet ID. Cancelled events are kept, with the "cancelled" status.
+#[derive(Clone)]
+pub struct LocalCalendar {
+    dir: PathBuf,
+    counter: Arc<AtomicU64>,
+}
+
+/// Generates a unique name for a file (or event), from the current time and a counter.
+fn unique_name(counter: &AtomicU64) -> String {
+    let count = counter.fetch_add(1, Ordering::Relaxed);
+    format!("{}-{count}", chrono::Utc::now().format("%Y%m%d%H%M%S%3f"))
+}
+
+/// Generates a fake Google Meet ID, in the form 'abc-defg-hij', for the event with the given ID.
+fn fake_meet_id(event_id: &str) -> String {
+    let mut seed = event_id.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    });
+
+    let mut letters = (0..10).map(|_| {
+        let letter = b'a' + (seed % 26) as u8;
+        seed /= 26;
+        char::from(letter)
+    });
+
+    [3, 4, 3]
+        .map(|len| letters.by_ref().take(len).collect::<String>())
+        .join("-")
+}
+
+/// Creates the given directory (and its parents) if it doesn't exist yet.
+async fn create_dir(dir: &Path) -> Result<()> {
+    tokio::fs::create_dir_all(dir).await?;
+    Ok(())
+}
+
+impl LocalEmailSender {
+    /// Creates a sender writing e-mails to the `emails/` subdirectory of the given directory.
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: dir.as_ref().join("emails"),
+            counter: Default::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl EmailSender for LocalEmailSender {
+    async fn send(&self, recipients: &[&str], content: &EmailContent) -> Result<()> {
+        let message = build_message(LOCAL_SENDER, recipients, content)?;
+
+        create_dir(&self.dir).await?;
+        let path = self.dir.join(format!("{}.eml", unique_name(&self.counter)));
+        tokio::fs::write(&path, message.formatted()).await?;
+
+        Ok(())
+    }
+}
+
+impl LocalCalendar {
+    /// Creates a calendar keeping events in the `events/` subdirectory of the given directory.
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: dir.as_ref().join("events"),
+            counter: Default::default(),
+        }
+    }
+
+    fn event_path(&self, event_id: &str) -> PathBuf {
+        self.dir.join(format!("{event_id}.json"))
+    }
+
+    /// Reads the event with the given ID.
+    pub async fn get_event(&self, event_id: &str) -> Result<Event> {
+        let contents = tokio::fs::read(self.event_path(event_id)).await?;
+        Ok(serde_json::from_slice(&contents).map_err(std::io::Error::from)?)
+    }
+
+    async fn write_event(&self, event: &Event) -> Result<()> {
+        let event_id = event.id.as_deref().unwrap_or_default();
+        let contents = serde_json::to_vec_pretty(event).map_err(std::io::Error::from)?;
+
+        create_dir(&self.dir).await?;
+        tokio::fs::write(self.event_path(event_id), contents).await?;
+        Ok(())
+    }
+
+    /// Reads the event with the given ID, updates it through the given function
+    /// and writes it back.
+    async fn update_event(
+        &self,
+        event_id: &str,
+        update: impl FnOnce(&mut Event) + Send,
+    ) -> Result<()> {
+        let mut event = self.get_event(event_id).await?;
+        update(&mut event);
+        self.write_event(&event).await
+    }
+}
+
+/// An attendee invited to an event.
+fn attendee(email: &str) -> EventAttendee {
+    EventAttendee {
+        email: Some(email.to_string()),
+        response_status: Some("needsAction".to_string()),
+        ..Default::default()
+    }
+}
+
+#[async_trait]
+impl CalendarBackend for LocalCalendar {
+    async fn create_event_for_session(
+        &self,
+        student: &User,
+        teacher: &Teacher,
+        session: &NewSession,
+    ) -> Result<Event> {
+        let event_id = format!("local-{}", unique_name(&self.counter));
+        let event = Event {
+            id: Some(event_id.clone()),
+            status: Some("confirmed".to_string()),
+            summary: Some("Mentoria".to_string()),
+            description: Some("Mentoria".to_string()),
+            start: Some(EventDateTime {
+                date_time: Some(session.start_at),
+                ..Default::default()
+            }),
+            end: Some(EventDateTime {
+                date_time: Some(session.end_at),
+                ..Default::default()
+            }),
+            conference_data: Some(ConferenceData {
+                conference_id: Some(fake_meet_id(&event_id)),
+                ..Default::default()
+            }),
+            attendees: Some(vec![attendee(&student.email), attendee(&teacher.email)]),
+            ..Default::default()
+        };
+        self.write_event(&event).await?;
+
+        Ok(event)
+    }
+
+    async fn add_attendee_to_event(&self, event_id: &str, student: &User) -> Result<()> {
+        self.update_event(event_id, |event| {
+            let attendees = event.attendees.get_or_insert_with(Vec::new);
+            if !attendees
+                .iter()
+                .any(|attendee| attendee.email.as_ref() == Some(&student.email))
+            {
+                attendees.push(attendee(&student.email));
+            }
+        })
+        .await
+    }
+
+    async fn remove_attendee_from_event(&self, event_id: &str, student: &User) -> Result<()> {
+        self.update_event(event_id, |event| {
+            if let Some(attendees) = event.attendees.as_mut() {
+                attendees.retain(|attendee| attendee.email.as_ref() != Some(&student.email));
+            }
+        })
+        .await
+    }
+
+    async fn reschedule_event(
+        &self,
+        event_id: &str,
+        session: &Session,
+        previous_teacher: &Teacher,
+        teacher: &Teacher,
+    ) -> Result<()> {
+        self.update_event(event_id, |event| {
+            event.start = Some(EventDateTime {
+                date_time: Some(session.start_at),
+                ..Default::default()
+            });
+            event.end = Some(EventDateTime {
+                date_time: Some(session.end_at),
+                ..Default::default()
+            });
+
+            if previous_teacher.email != teacher.email {
+                let attendees = event.attendees.get_or_insert_with(Vec::new);
+                attendees
+                    .retain(|attendee| attendee.email.as_ref() != Some(&previous_teacher.email));
+                attendees.push(attendee(&teacher.email));
+            }
+        })
+        .await
+    }
+
+    async fn cancel_event(&self, event_id: &str) -> Result<()> {
+        self.update_event(event_id, |event| {
+            event.status = Some("cancelled".to_string());
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fake_meet_id, LocalCalendar, LocalEmailSender};
+    use crate::{
+        error::Result,
+        model::{DiscordId, NewSession, SessionStatus, Teacher, Timezone, User},
+        notification::{CalendarBackend, EmailContent, EmailSender},
+    };
+
+    #[test]
+    fn test_fake_meet_id_format() {
+        let meet_id = fake_meet_id("local-1");
+        let parts = meet_id.split('-').map(str::len).collect::<Vec<_>>();
+
+        assert_eq!(vec![3, 4, 3], parts);
+        assert!(meet_id.chars().all(|c| c == '-' || c.is_ascii_lowercase()));
+        assert_eq!(meet_id, fake_meet_id("local-1"));
+        assert_ne!(meet_id, fake_meet_id("local-2"));
+    }
+
+    #[tokio::test]
+    async fn test_local_backends_write_to_directory() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let student = User {
+            discord_id: DiscordId(1),
+            name: "Student".to_string(),
+            email: "student@student.com".to_string(),
+            bio: None,
+            timezone: Timezone::default(),
+            locale: None,
+        };
+        let teacher = Teacher {
+            id: 1,
+            name: "Mentor".to_string(),
+            email: "mentor@mentor.com".to_string(),
+            specialty: "Computação".to_string(),
+            applied_at: None,
+            bio: None,
+            course_info: None,
+            company: None,
+            company_role: None,
+            whatsapp: None,
+            linkedin: None,
+            comment_general: None,
+            comment_experience: None,
+            discord_id: None,
+            program_id: None,
+            timezone: Timezone::default(),
+            max_sessions_per_week: None,
+            locale: None,
+        };
+        let start_at = chrono::Utc::now();
+        let session = NewSession {
+            teacher_id: 1,
+            student_id: student.discord_id,
+            availability_id: 1,
+            summary: None,
+            meet_id: None,
+            calendar_event_id: None,
+            start_at,
+            end_at: start_at + chrono::Duration::minutes(40),
+            notified: false,
+            status: SessionStatus::Scheduled,
+            cancelled_at: None,
+            cancel_reason: None,
+        };
+
+        let calendar = LocalCalendar::new(dir.path());
+        let event = calendar
+            .create_event_for_session(&student, &teacher, &session)
+            .await?;
+        let event_id = event.id.clone().unwrap();
+        assert!(event
+            .conference_data
+            .as_ref()
+            .and_then(|conf| conf.conference_id.as_ref())
+            .is_some());
+
+        calendar.cancel_event(&event_id).await?;
+        let event = calendar.get_event(&event_id).await?;
+        assert_eq!(Some("cancelled"), event.status.as_deref());
+        assert_eq!(2, event.attendees.unwrap().len());
+
+        LocalEmailSender::new(dir.path())
+            .send(
+                &[&student.email],
+                &EmailContent {
+                    subject: "Session Scheduled".to_string(),
+                    text: "Scheduled.".to_string(),
+                    html: "<p>Scheduled.</p>".to_string(),
+                },
+            )
+            .await?;
+        let mut emails = std::fs::read_dir(dir.path().join("emails"))?;
+        let email = std::fs::read_to_string(emails.next().unwrap()?.path())?;
+        assert!(email.contains("To: student@student.com"));
+        assert!(email.contains("Scheduled."));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,213 @@
+//! Sends e-mails through an SMTP server.
+use async_trait::async_trait;
+use lettre::{
+    transport::smtp::authentication::Credentials, AsyncSmtpTransport, AsyncTransport,
+    Tokio1Executor,
+};
+use serde::{Deserialize, Serialize};
+
+use super::email::{build_message, EmailContent, EmailSender};
+use crate::error::{Error, Result};
+
+/// How the connection to an SMTP server is encrypted.
+#[derive(Serialize, Deserialize, Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpTls {
+    /// No encryption (e.g. for a local SMTP sink used for testing).
+    /// Port 25 by default.
+    None,
+
+    /// The connection is upgraded to TLS with STARTTLS. Port 587 by default.
+    #[default]
+    StartTls,
+
+    /// The connection uses TLS from the start. Port 465 by default.
+    Tls,
+}
+
+/// The settings to connect to an SMTP server.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SmtpConfig {
+    /// The SMTP server's host (e.g. "smtp.gmail.com").
+    pub host: String,
+
+    /// The SMTP server's port, if not the default for the chosen encryption.
+    #[serde(default)]
+    pub port: Option<u16>,
+
+    /// How the connection is encrypted.
+    #[serde(default)]
+    pub tls: SmtpTls,
+
+    /// The username to log into the SMTP server with, if it requires authentication.
+    #[serde(default)]
+    pub username: Option<String>,
+
+    /// The password to log into the SMTP server with, if it requires authentication.
+    #[serde(default)]
+    pub password: Option<String>,
+
+    /// The address e-mails are sent from.
+    pub from: String,
+}
+
+/// Sends e-mails through an SMTP server.
+#[derive(Clone)]
+pub struct SmtpEmailSender {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: String,
+}
+
+impl SmtpEmailSender {
+    /// Creates a sender for the SMTP server with the given settings.
+    /// The connection is only established when sending e-mails.
+    /// Fails with [`Error::InvalidConfig`] if only one of the username and the password is given.
+    pub fn new(config: &SmtpConfig) -> Result<Self> {
+        let mut builder = match config.tls {
+            SmtpTls::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host),
+            SmtpTls::StartTls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)?
+            }
+            SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host)?,
+        };
+
+        if let Some(port) = config.port {
+            builder = builder.port(port);
+        }
+        match (&config.username, &config.password) {
+            (Some(username), Some(password)) => {
+                builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+            }
+            (None, None) => {}
+            _ => {
+                return Err(Error::InvalidConfig(
+                    "the SMTP username and password must be either both set or both unset",
+                ))
+            }
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            from: config.from.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl EmailSender for SmtpEmailSender {
+    async fn send(&self, recipients: &[&str], content: &EmailContent) -> Result<()> {
+        let message = build_message(&self.from, recipients, content)?;
+        self.transport.send(message).await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+        net::TcpListener,
+    };
+
+    use super::{SmtpConfig, SmtpEmailSender, SmtpTls};
+    use crate::{
+        error::{Error, Result},
+        notification::{EmailContent, EmailSender},
+    };
+
+    /// Accepts a single SMTP connection, accepting every command,
+    /// and returns the data of the first e-mail sent through it.
+    async fn receive_one_email(listener: TcpListener) -> std::io::Result<String> {
+        let (stream, _) = listener.accept().await?;
+        let (read, mut write) = stream.into_split();
+        let mut lines = BufReader::new(read).lines();
+        let mut data = String::new();
+        let mut reading_data = false;
+
+        write.write_all(b"220 localhost ESMTP sink\r\n").await?;
+        while let Some(line) = lines.next_line().await? {
+            if reading_data {
+                if line == "." {
+                    write.write_all(b"250 OK\r\n").await?;
+                    break;
+                }
+                data.push_str(&line);
+                data.push('\n');
+            } else if line.to_uppercase().starts_with("DATA") {
+                reading_data = true;
+                write.write_all(b"354 Send the data\r\n").await?;
+            } else {
+                write.write_all(b"250 OK\r\n").await?;
+            }
+        }
+
+        Ok(data)
+    }
+
+    #[tokio::test]
+    async fn test_smtp_sender_sends_to_local_sink() -> Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let port = listener.local_addr()?.port();
+        let sink = tokio::spawn(receive_one_email(listener));
+
+        let sender = SmtpEmailSender::new(&SmtpConfig {
+            host: "127.0.0.1".to_string(),
+            port: Some(port),
+            tls: SmtpTls::None,
+            username: None,
+            password: None,
+            from: "bot@mentoria.com".to_string(),
+        })?;
+        sender
+            .send(
+                &["student@student.com", "mentor@mentor.com"],
+                &EmailContent {
+                    subject: "Session Scheduled".to_string(),
+                    text: "Your session was scheduled.".to_string(),
+                    html: "<p>Your session was <b>scheduled</b>.</p>".to_string(),
+                },
+            )
+            .await?;
+
+        let data = sink.await.unwrap()?;
+        assert!(data.contains("From: mentoria <bot@mentoria.com>"));
+        assert!(data.contains("To: student@student.com, mentor@mentor.com"));
+        assert!(data.contains("Subject: Session Scheduled"));
+        assert!(data.contains("Content-Type: multipart/alternative"));
+        assert!(data.contains("Your session was scheduled."));
+        assert!(data.contains("<p>Your session was <b>scheduled</b>.</p>"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_smtp_sender_refuses_partial_credentials() {
+        let config = SmtpConfig {
+            host: "127.0.0.1".to_string(),
+            port: None,
+            tls: SmtpTls::None,
+            username: Some("bot".to_string()),
+            password: None,
+            from: "bot@mentoria.com".to_string(),
+        };
+
+        assert!(matches!(
+            SmtpEmailSender::new(&config),
+            Err(Error::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            SmtpEmailSender::new(&SmtpConfig {
+                username: None,
+                password: Some("password".to_string()),
+                ..config.clone()
+            }),
+            Err(Error::InvalidConfig(_))
+        ));
+        assert!(SmtpEmailSender::new(&SmtpConfig {
+            password: Some("password".to_string()),
+            ..config
+        })
+        .is_ok());
+    }
+}
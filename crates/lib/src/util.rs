@@ -11,5 +11,5 @@ mod validation;
 pub use forms::{apply_limits_to_select_menu_spec, apply_limits_to_select_option_spec};
 pub use macros::tr;
 pub use string::limit_string_len;
-pub use time::{HumanParseableDateTime, BRAZIL_TIMEZONE};
+pub use time::{HumanParseableDate, HumanParseableDateTime, BRAZIL_TIMEZONE};
 pub use validation::Unvalidated;
@@ -86,6 +86,17 @@ pub fn convert_chrono_duration_to_brazilian_string(duration: chrono::Duration) -
     }
 }
 
+/// Converts a [`chrono::Duration`] to a string in the given locale.
+pub fn convert_chrono_duration_to_locale_string(
+    duration: chrono::Duration,
+    locale: &str,
+) -> String {
+    match locale {
+        "pt-BR" => convert_chrono_duration_to_brazilian_string(duration),
+        _ => convert_chrono_duration_to_string(duration),
+    }
+}
+
 /// Returns a [`poise::Context`]'s locale, or "en" if it was not found.
 pub fn get_defaulted_locale<D, E>(ctx: poise::Context<'_, D, E>) -> &str {
     ctx.locale()
@@ -107,3 +118,9 @@ pub fn default_locale(locale: &str) -> &'static str {
         _ => "en",
     }
 }
+
+/// Filters unknown locales like [`default_locale`], but returns the bot's default locale
+/// ("pt-BR") if none is given (e.g. if a user's locale isn't known yet).
+pub fn default_optional_locale(locale: Option<&str>) -> &'static str {
+    locale.map_or("pt-BR", default_locale)
+}
@@ -1,12 +1,12 @@
 //! Module for time and [`chrono`] utilities
 use std::fmt::Display;
 
-use chrono::{TimeZone, Timelike};
+use chrono::{Datelike, Offset, TimeZone, Timelike};
 use once_cell::sync::Lazy;
 
 pub mod parse;
 
-pub use parse::HumanParseableDateTime;
+pub use parse::{HumanParseableDate, HumanParseableDateTime};
 
 const HOUR: i32 = 3600;
 
@@ -56,9 +56,112 @@ pub fn datetime_with_time<T: TimeZone>(
         .and_then(|d| d.with_second(time.second()))
 }
 
+/// Converts a local date and time in the given timezone to a concrete moment.
+///
+/// Times which happen twice (when clocks are turned back) resolve to their first occurrence,
+/// while times which are skipped (when clocks are turned forward) are moved forward by the
+/// length of the gap (e.g. 00:30 becomes 01:30 when clocks jump from 00:00 to 01:00).
+pub fn local_datetime_in<T: TimeZone>(
+    timezone: &T,
+    local: &chrono::NaiveDateTime,
+) -> chrono::DateTime<T> {
+    match timezone.from_local_datetime(local) {
+        chrono::LocalResult::Single(datetime) => datetime,
+        chrono::LocalResult::Ambiguous(earliest, _) => earliest,
+        chrono::LocalResult::None => {
+            // use the offset in effect before the gap (transitions are months apart)
+            let offset_before = timezone
+                .offset_from_utc_datetime(&(*local - chrono::Duration::days(1)))
+                .fix();
+            timezone.from_utc_datetime(&(*local - offset_before))
+        }
+    }
+}
+
+/// Returns the moment a date starts (00:00:00) in the Brazil main timezone, in UTC.
+pub fn brazil_day_start_as_utc(date: chrono::NaiveDate) -> chrono::DateTime<chrono::Utc> {
+    day_start_as_utc(date, &*BRAZIL_TIMEZONE)
+}
+
+/// Returns the moment a date ends (23:59:59) in the Brazil main timezone, in UTC.
+pub fn brazil_day_end_as_utc(date: chrono::NaiveDate) -> chrono::DateTime<chrono::Utc> {
+    day_end_as_utc(date, &*BRAZIL_TIMEZONE)
+}
+
+/// Returns the moment a date starts (00:00:00) in the given timezone, in UTC.
+pub fn day_start_as_utc<T: TimeZone>(
+    date: chrono::NaiveDate,
+    timezone: &T,
+) -> chrono::DateTime<chrono::Utc> {
+    datetime_as_utc(&local_datetime_in(
+        timezone,
+        &date.and_time(chrono::NaiveTime::MIN),
+    ))
+}
+
+/// Returns the moment a date ends (23:59:59) in the given timezone, in UTC.
+/// (Days may be shorter or longer than 24 hours due to daylight saving time.)
+pub fn day_end_as_utc<T: TimeZone>(
+    date: chrono::NaiveDate,
+    timezone: &T,
+) -> chrono::DateTime<chrono::Utc> {
+    day_start_as_utc(date + chrono::Duration::days(1), timezone) - chrono::Duration::seconds(1)
+}
+
+/// Returns the first day (Monday) of the week containing the given date.
+pub fn week_start(date: chrono::NaiveDate) -> chrono::NaiveDate {
+    date - chrono::Duration::days(date.weekday().num_days_from_monday().into())
+}
+
 /// Converts a [`chrono::DateTime`] object in a certain timezone to UTC.
 pub fn datetime_as_utc<T: TimeZone>(
     datetime: &chrono::DateTime<T>,
 ) -> chrono::DateTime<chrono::Utc> {
     chrono::Utc.from_utc_datetime(&datetime.naive_utc())
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use chrono_tz::America::New_York;
+
+    use super::local_datetime_in;
+
+    fn naive(month: u32, day: u32, hour: u32, minute: u32) -> chrono::NaiveDateTime {
+        chrono::NaiveDate::from_ymd_opt(2023, month, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_local_datetime_in_keeps_unambiguous_times() {
+        assert_eq!(
+            chrono::Utc
+                .with_ymd_and_hms(2023, 3, 13, 14, 30, 0)
+                .unwrap(),
+            local_datetime_in(&New_York, &naive(3, 13, 10, 30))
+        );
+    }
+
+    #[test]
+    fn test_local_datetime_in_picks_first_occurrence_of_repeated_times() {
+        // clocks go from 02:00 (EDT) back to 01:00 (EST) on 05/11/2023
+        assert_eq!(
+            chrono::Utc.with_ymd_and_hms(2023, 11, 5, 5, 30, 0).unwrap(),
+            local_datetime_in(&New_York, &naive(11, 5, 1, 30))
+        );
+    }
+
+    #[test]
+    fn test_local_datetime_in_moves_skipped_times_past_the_gap() {
+        // clocks go from 02:00 (EST) forward to 03:00 (EDT) on 12/03/2023
+        let datetime = local_datetime_in(&New_York, &naive(3, 12, 2, 30));
+
+        assert_eq!(naive(3, 12, 3, 30), datetime.naive_local());
+        assert_eq!(
+            chrono::Utc.with_ymd_and_hms(2023, 3, 12, 7, 30, 0).unwrap(),
+            datetime
+        );
+    }
+}
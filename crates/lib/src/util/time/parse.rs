@@ -13,7 +13,8 @@ use crate::{
 };
 
 /// Represents a DateTime which can be parsed in a semi-human format.
-/// It is parsed as UTC-3 and converted to UTC.
+/// It is parsed as UTC-3 (or in another timezone, with
+/// [`HumanParseableDateTime::parse_in`]) and converted to UTC.
 ///
 /// # Supported date/time formats
 /// - %Y-%m-%d %H:%M:%S
@@ -25,13 +26,79 @@ use crate::{
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct HumanParseableDateTime(pub chrono::DateTime<chrono::Utc>);
 
-fn try_date_parse(s: &str, fmt: &str) -> Option<HumanParseableDateTime> {
+fn try_date_parse<Tz: chrono::TimeZone>(
+    s: &str,
+    fmt: &str,
+    timezone: &Tz,
+) -> Option<HumanParseableDateTime> {
     let date_time = chrono::NaiveDateTime::parse_from_str(s, fmt).ok()?;
-    let date_time = date_time.and_local_timezone(*BRAZIL_TIMEZONE).single()?;
+    let date_time = date_time.and_local_timezone(timezone.clone()).single()?;
     let date_time = date_time.with_timezone(&chrono::Utc);
     Some(HumanParseableDateTime(date_time))
 }
 
+impl HumanParseableDateTime {
+    /// Attempts to parse a human datetime string as a time in the given timezone,
+    /// and converts it to a **UTC DateTime**. If the date is omitted, today (in
+    /// that timezone) is assumed. Supports the same formats as [`FromStr`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mentoriabot_lib::util::HumanParseableDateTime;
+    /// # use chrono::TimeZone;
+    /// let lisbon = chrono_tz::Europe::Lisbon;
+    /// let expected_date = lisbon.with_ymd_and_hms(2023, 3, 19, 11, 29, 0).unwrap();
+    ///
+    /// let parsed = HumanParseableDateTime::parse_in("19/03/2023 11:29", &lisbon).unwrap();
+    ///
+    /// assert_eq!(parsed.0, expected_date);
+    /// ```
+    pub fn parse_in<Tz: chrono::TimeZone>(s: &str, timezone: &Tz) -> Result<Self> {
+        let s = &s
+            .trim()
+            .replace(", ", "") // some common little mistakes/changes
+            .replace("; ", "")
+            .replace("   ", " ")
+            .replace("  ", " ")
+            .replace([',', ';'], "");
+
+        let curr_date = chrono::Utc::now().with_timezone(timezone);
+
+        let today = format!(
+            "{}-{}-{}",
+            curr_date.year(),
+            curr_date.month(),
+            curr_date.day()
+        );
+
+        // in case date wasn't specified => default to today
+        let string_with_today: &str = &format!("{today} {s}");
+
+        let string_with_year: &str = &format!("{}; {s}", curr_date.year());
+
+        let parse = |s: &str, fmt: &str| try_date_parse(s, fmt, timezone);
+
+        let result = parse(s, "%Y-%m-%d %H:%M:%S")
+            .or_else(|| parse(s, "%Y-%m-%d %H:%M"))
+            .or_else(|| parse(s, "%d/%m/%Y %H:%M:%S"))
+            .or_else(|| parse(s, "%d/%m/%Y %H:%M"))
+            .or_else(|| parse(string_with_year, "%Y; %d/%m %H:%M:%S"))
+            .or_else(|| parse(string_with_year, "%Y; %d/%m %H:%M"))
+            .or_else(|| parse(string_with_today, "%Y-%m-%d %H:%M:%S"))
+            .or_else(|| parse(string_with_today, "%Y-%m-%d %H:%M"))
+            // inverted (time then date)
+            .or_else(|| parse(s, "%H:%M:%S %Y-%m-%d"))
+            .or_else(|| parse(s, "%H:%M %Y-%m-%d"))
+            .or_else(|| parse(s, "%H:%M:%S %d/%m/%Y"))
+            .or_else(|| parse(s, "%H:%M %d/%m/%Y"))
+            .or_else(|| parse(string_with_year, "%Y; %H:%M:%S %d/%m"))
+            .or_else(|| parse(string_with_year, "%Y; %H:%M %d/%m"));
+
+        result.ok_or(Error::DateTimeParse)
+    }
+}
+
 impl FromStr for HumanParseableDateTime {
     type Err = Error;
 
@@ -86,45 +153,7 @@ impl FromStr for HumanParseableDateTime {
     /// assert_eq!(parsed6.0, expected_date4);
     /// ```
     fn from_str(s: &str) -> Result<Self> {
-        let s = &s
-            .trim()
-            .replace(", ", "") // some common little mistakes/changes
-            .replace("; ", "")
-            .replace("   ", " ")
-            .replace("  ", " ")
-            .replace([',', ';'], "");
-
-        let curr_date = brazil_now();
-
-        let today = format!(
-            "{}-{}-{}",
-            curr_date.year(),
-            curr_date.month(),
-            curr_date.day()
-        );
-
-        // in case date wasn't specified => default to today
-        let string_with_today: &str = &format!("{today} {s}");
-
-        let string_with_year: &str = &format!("{}; {s}", curr_date.year());
-
-        let result = try_date_parse(s, "%Y-%m-%d %H:%M:%S")
-            .or_else(|| try_date_parse(s, "%Y-%m-%d %H:%M"))
-            .or_else(|| try_date_parse(s, "%d/%m/%Y %H:%M:%S"))
-            .or_else(|| try_date_parse(s, "%d/%m/%Y %H:%M"))
-            .or_else(|| try_date_parse(string_with_year, "%Y; %d/%m %H:%M:%S"))
-            .or_else(|| try_date_parse(string_with_year, "%Y; %d/%m %H:%M"))
-            .or_else(|| try_date_parse(string_with_today, "%Y-%m-%d %H:%M:%S"))
-            .or_else(|| try_date_parse(string_with_today, "%Y-%m-%d %H:%M"))
-            // inverted (time then date)
-            .or_else(|| try_date_parse(s, "%H:%M:%S %Y-%m-%d"))
-            .or_else(|| try_date_parse(s, "%H:%M %Y-%m-%d"))
-            .or_else(|| try_date_parse(s, "%H:%M:%S %d/%m/%Y"))
-            .or_else(|| try_date_parse(s, "%H:%M %d/%m/%Y"))
-            .or_else(|| try_date_parse(string_with_year, "%Y; %H:%M:%S %d/%m"))
-            .or_else(|| try_date_parse(string_with_year, "%Y; %H:%M %d/%m"));
-
-        result.ok_or(Error::DateTimeParse)
+        Self::parse_in(s, &*BRAZIL_TIMEZONE)
     }
 }
 
@@ -165,9 +194,103 @@ impl DerefMut for HumanParseableDateTime {
     }
 }
 
+/// Represents a Date which can be parsed in a semi-human format.
+///
+/// # Supported date formats
+/// - %Y-%m-%d
+/// - %d/%m/%Y
+/// - %d/%m (current year)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HumanParseableDate(pub chrono::NaiveDate);
+
+impl FromStr for HumanParseableDate {
+    type Err = Error;
+
+    /// Attempts to parse a human date string. If the year is omitted,
+    /// the current year (in UTC-3) is assumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mentoriabot_lib::util::HumanParseableDate;
+    /// let expected_date = chrono::NaiveDate::from_ymd_opt(2023, 9, 11).unwrap();
+    ///
+    /// let parsed1: HumanParseableDate = "2023-09-11".parse().unwrap();
+    /// let parsed2: HumanParseableDate = "11/09/2023".parse().unwrap();
+    ///
+    /// assert_eq!(parsed1.0, expected_date);
+    /// assert_eq!(parsed2.0, expected_date);
+    /// ```
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let string_with_year: &str = &format!("{}; {s}", brazil_now().year());
+
+        chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d")
+            .or_else(|_| chrono::NaiveDate::parse_from_str(s, "%d/%m/%Y"))
+            .or_else(|_| chrono::NaiveDate::parse_from_str(string_with_year, "%Y; %d/%m"))
+            .map(HumanParseableDate)
+            .map_err(|_| Error::DateTimeParse)
+    }
+}
+
+impl From<HumanParseableDate> for chrono::NaiveDate {
+    fn from(value: HumanParseableDate) -> Self {
+        value.0
+    }
+}
+
+impl Display for HumanParseableDate {
+    /// Displays the date as DAY/MONTH/YEAR.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", super::day_month_year_display(&self.0))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    mod date {
+        use super::*;
+
+        #[test]
+        fn parses_yyyy_mm_dd_correctly() {
+            let parsed: HumanParseableDate = "2023-03-19".parse().unwrap();
+
+            assert_eq!(
+                chrono::NaiveDate::from_ymd_opt(2023, 3, 19).unwrap(),
+                parsed.0
+            )
+        }
+
+        #[test]
+        fn parses_dd_mm_yyyy_correctly() {
+            let parsed: HumanParseableDate = "19/03/2023".parse().unwrap();
+
+            assert_eq!(
+                chrono::NaiveDate::from_ymd_opt(2023, 3, 19).unwrap(),
+                parsed.0
+            )
+        }
+
+        #[test]
+        fn parses_dd_mm_with_current_year() {
+            let year = brazil_now().year();
+
+            let parsed: HumanParseableDate = "19/03".parse().unwrap();
+
+            assert_eq!(
+                chrono::NaiveDate::from_ymd_opt(year, 3, 19).unwrap(),
+                parsed.0
+            )
+        }
+
+        #[test]
+        fn fails_to_parse_invalid_dates() {
+            assert!("32/03/2023".parse::<HumanParseableDate>().is_err());
+            assert!("abc".parse::<HumanParseableDate>().is_err());
+        }
+    }
+
     mod date_time {
         use chrono::TimeZone;
 
@@ -383,5 +506,24 @@ mod tests {
                 parsed.0
             )
         }
+
+        #[test]
+        fn parses_in_the_given_timezone() {
+            let manaus = chrono_tz::America::Manaus;
+
+            let parsed = HumanParseableDateTime::parse_in("19/03/2023 11:29", &manaus).unwrap();
+
+            assert_eq!(
+                manaus.with_ymd_and_hms(2023, 3, 19, 11, 29, 0).unwrap(),
+                parsed.0
+            );
+            // Manaus is an hour behind Brasília
+            assert_eq!(
+                "19/03/2023 12:29"
+                    .parse::<HumanParseableDateTime>()
+                    .unwrap(),
+                parsed
+            );
+        }
     }
 }
@@ -14,13 +14,21 @@ pub mod exports {
     }
 }
 
-use exports::lib::{self, db::Repository};
+use exports::lib;
 
 /// Reads teachers from a CSV file and inserts them
-/// in the database.
+/// in the database, attached to the given program (by ID), if any.
+/// Their availabilities will only be valid
+/// between the given dates (inclusive), if specified.
+/// Each teacher is inserted along with their availabilities (and the tags extracted
+/// from their specialty) in a single transaction, so that a failing row is not
+/// partially inserted.
 pub async fn load_teachers_to_db(
     csv_contents: &str,
     db: &lib::db::DatabaseManager,
+    program_id: Option<i64>,
+    valid_from: Option<chrono::NaiveDate>,
+    valid_until: Option<chrono::NaiveDate>,
 ) -> lib::error::Result<ReaderResult<(lib::model::Teacher, Vec<lib::model::Availability>)>> {
     let csv_data = match reader::read_teacher_csv(csv_contents)? {
         Ok(new_teachers) => new_teachers,
@@ -37,45 +45,66 @@ pub async fn load_teachers_to_db(
                 break; // 5 errors max is good enough
             }
 
-            let teacher = match db.teacher_repository().insert(&new_teacher).await {
-                Ok(teacher) => teacher,
-                Err(db_err) => {
-                    errors.push((i + 1, db_err));
-                    continue;
-                }
+            let new_teacher = lib::model::NewTeacher {
+                program_id: new_teacher.program_id.or(program_id),
+                ..new_teacher
             };
 
-            let mut inserted_availabilities = Vec::new();
-
-            for availability in availabilities {
-                let unexpected_availability_structure =
-                    || Error::Other("unexpected availability structure");
-                let new_availability = NewAvailability {
-                    teacher_id: teacher.id,
-                    weekday: availability
-                        .weekday
-                        .ok_or_else(unexpected_availability_structure)?,
-                    time_start: availability
-                        .time_start
-                        .ok_or_else(unexpected_availability_structure)?,
-                    expired: availability
-                        .expired
-                        .ok_or_else(unexpected_availability_structure)?,
-                    duration: availability
-                        .duration
-                        .ok_or_else(unexpected_availability_structure)?,
-                };
-
-                match db.availability_repository().insert(&new_availability).await {
-                    Ok(availability) => inserted_availabilities.push(availability),
-                    Err(db_err) => {
-                        errors.push((i + 1, db_err));
-                        continue;
+            let unexpected_availability_structure =
+                || Error::Other("unexpected availability structure");
+            let new_availabilities = availabilities
+                .into_iter()
+                .map(|availability| {
+                    Ok(NewAvailability {
+                        // replaced once the teacher is inserted
+                        teacher_id: 0,
+                        weekday: availability
+                            .weekday
+                            .ok_or_else(unexpected_availability_structure)?,
+                        time_start: availability
+                            .time_start
+                            .ok_or_else(unexpected_availability_structure)?,
+                        expired: availability
+                            .expired
+                            .ok_or_else(unexpected_availability_structure)?,
+                        duration: availability
+                            .duration
+                            .ok_or_else(unexpected_availability_structure)?,
+                        valid_from: availability.valid_from.unwrap_or(valid_from),
+                        valid_until: availability.valid_until.unwrap_or(valid_until),
+                        program_id: availability.program_id.unwrap_or(new_teacher.program_id),
+                        capacity: availability.capacity.unwrap_or(1),
+                    })
+                })
+                .collect::<lib::error::Result<Vec<_>>>()?;
+
+            // the teacher and their availabilities are inserted atomically,
+            // so a failure doesn't leave a teacher with missing availabilities behind
+            let loaded = db
+                .transaction(|db| async move {
+                    let teacher = db.teacher_repository().insert(&new_teacher).await?;
+                    db.tag_repository().tag_from_specialty(&teacher).await?;
+
+                    let mut inserted_availabilities = Vec::with_capacity(new_availabilities.len());
+                    for new_availability in new_availabilities {
+                        let availability = db
+                            .availability_repository()
+                            .insert(&NewAvailability {
+                                teacher_id: teacher.id,
+                                ..new_availability
+                            })
+                            .await?;
+                        inserted_availabilities.push(availability);
                     }
-                }
-            }
 
-            results.push((teacher, inserted_availabilities));
+                    Ok((teacher, inserted_availabilities))
+                })
+                .await;
+
+            match loaded {
+                Ok(loaded) => results.push(loaded),
+                Err(db_err) => errors.push((i + 1, db_err)),
+            }
         }
 
         if errors.is_empty() {
@@ -85,3 +114,77 @@ pub async fn load_teachers_to_db(
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{exports::lib, load_teachers_to_db};
+
+    const HEADER: &str = "Carimbo de data/hora,Endereço de e-mail,Nome e sobrenome,Whatsapp,Linkedin,Formação acadêmica (curso e instituição),Empresa/Instituição que trabalha,Cargo/Ocupação atual,Mini bio,Qual sua experiência?,Quais os conhecimentos/habilidades você pode compartilhar com as pessoas mentoradas?,→ Arraste para o lado para ver todos os horários  [Seg (11/09)],→ Arraste para o lado para ver todos os horários  [Ter (12/09)],→ Arraste para o lado para ver todos os horários  [Qua (13/09)],→ Arraste para o lado para ver todos os horários  [Qui (14/09)],→ Arraste para o lado para ver todos os horários  [Sex (15/09)],→ Arraste para o lado para ver todos os horários  [Sáb (26/08)],Gostaria de fazer algum comentário ou sugestão?";
+
+    #[tokio::test]
+    async fn test_load_teachers_to_db_inserts_teachers_with_availabilities(
+    ) -> lib::error::Result<()> {
+        let csv_contents = format!(
+            "{HEADER}
+11/05/2023 18:43:55,first@mentor.com,José Silva,(41)912345678,https://www.linkedin.com/sus,\"Engenharia da Computação, USP\",Empadas & Cia.,Gerente de Software,Bio,Nada a declarar,Álgebra,\"09:00, 10:00\",,,,10:00,,
+11/05/2023 18:50:00,second@mentor.com,Maria Souza,(41)987654321,https://www.linkedin.com/sos,\"Ciência da Computação, UFPR\",Pastéis & Cia.,Engenheira de Software,Bio,Nada a declarar,Cálculo,,20:00,,,,,"
+        );
+
+        let db = lib::db::DatabaseManager::in_memory();
+        let valid_until = chrono::NaiveDate::from_ymd_opt(2023, 9, 30);
+        let loaded = load_teachers_to_db(&csv_contents, &db, None, None, valid_until)
+            .await?
+            .unwrap();
+
+        assert_eq!(2, loaded.len());
+        assert_eq!(
+            loaded
+                .iter()
+                .map(|(teacher, _)| teacher.clone())
+                .collect::<Vec<_>>(),
+            db.teacher_repository().find_all().await?
+        );
+        for (teacher, availabilities) in &loaded {
+            assert!(!availabilities.is_empty());
+            assert!(availabilities
+                .iter()
+                .all(|avail| avail.valid_until == valid_until));
+            assert_eq!(
+                availabilities,
+                &db.availability_repository()
+                    .find_by_teacher(teacher)
+                    .await?
+            );
+        }
+
+        // each teacher is tagged with the areas in their specialty
+        assert_eq!(
+            vec!["cálculo", "álgebra"],
+            db.tag_repository()
+                .find_all()
+                .await?
+                .iter()
+                .map(|tag| &*tag.name)
+                .collect::<Vec<_>>()
+        );
+
+        // already registered teachers are reported, and nothing else is inserted for them
+        let errors = load_teachers_to_db(&csv_contents, &db, None, None, None)
+            .await?
+            .unwrap_err();
+        assert_eq!(
+            vec![1, 2],
+            errors.iter().map(|(line, _)| *line).collect::<Vec<_>>()
+        );
+        assert_eq!(2, db.teacher_repository().find_all().await?.len());
+        assert_eq!(
+            loaded
+                .iter()
+                .map(|(_, availabilities)| availabilities.len())
+                .sum::<usize>(),
+            db.availability_repository().find_all().await?.len()
+        );
+
+        Ok(())
+    }
+}
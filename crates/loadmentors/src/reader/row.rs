@@ -19,6 +19,9 @@ lazy_static::lazy_static! {
     // "09:00, 10:00, 13:00"  /  "11:00"
     pub static ref AVAILABILITY_REGEX: regex::Regex = regex::Regex::new("^(?:\\d{1,2}:\\d{1,2}, ?)*(?:\\d{1,2}:\\d{1,2})?$").unwrap();
 
+    // "3"  /  ""
+    pub static ref WEEKLY_CAP_REGEX: regex::Regex = regex::Regex::new("^ *\\d* *$").unwrap();
+
     // "11/12/2023 13:34:20"
     pub static ref TIMESTAMP_REGEX: regex::Regex =
         regex::Regex::new("^(\\d{1,2})/(\\d{1,2})/(\\d{4}) (\\d{2}):(\\d{2}):(\\d{2})$").unwrap();
@@ -128,6 +131,15 @@ pub(crate) struct TeacherRow {
     #[validate(length(min = 0, max = 8192))]
     #[serde(rename = "Qual sua experiência?")]
     pub(crate) comment_experience: String,
+
+    /// How many sessions the teacher wants to give per week at most,
+    /// if they answered (optional column).
+    #[validate(regex = "WEEKLY_CAP_REGEX")]
+    #[serde(
+        rename = "Quantas mentorias você pode dar por semana, no máximo?",
+        default
+    )]
+    pub(crate) max_sessions_per_week: String,
 }
 
 impl TeacherRow {
@@ -161,6 +173,16 @@ impl TeacherRow {
             comment_experience: wrap_string_option_based_on_emptiness(
                 self.comment_experience.clone(),
             ),
+            discord_id: None,
+            program_id: None,
+            timezone: Default::default(),
+            max_sessions_per_week: self
+                .max_sessions_per_week
+                .trim()
+                .parse::<i16>()
+                .ok()
+                .filter(|max| *max > 0),
+            locale: None,
         };
 
         let mut availabilities = Vec::new();
@@ -195,6 +217,10 @@ impl TeacherRow {
                     time_start: Some(time.unwrap()),
                     expired: Some(false),
                     duration: Some(1i16),
+                    valid_from: None,
+                    valid_until: None,
+                    program_id: None,
+                    capacity: None,
                 });
 
             availabilities.extend(new_availabilities);
@@ -257,6 +283,7 @@ mod test {
                 availability_saturday: "17:00, 18:00,19:00, 20:00, 21:00".into(),
                 comment_general: "".into(),
                 comment_experience: "Nada a declarar".into(),
+                max_sessions_per_week: "".into(),
             }]
         );
     }
@@ -282,6 +309,7 @@ mod test {
             availability_saturday: "17:00, 18:00,19:00, 20:00, 21:00".into(),
             comment_general: "".into(),
             comment_experience: "Nada a declarar".into(),
+            max_sessions_per_week: "2".into(),
         };
 
         let (teacher, _) = row.try_parse().unwrap();
@@ -308,6 +336,11 @@ mod test {
                 linkedin: Some("https://linkedin.com/sus".into()),
                 comment_general: None,
                 comment_experience: Some("Nada a declarar".into()),
+                discord_id: None,
+                program_id: None,
+                timezone: Default::default(),
+                max_sessions_per_week: Some(2),
+                locale: None,
             }
         );
     }
@@ -333,6 +366,7 @@ mod test {
             availability_saturday: "17:00, 18:00,19:00, 20:00, 21:00".into(),
             comment_general: "".into(),
             comment_experience: "Nada a declarar".into(),
+            max_sessions_per_week: "2".into(),
         };
 
         let (_, availabilities) = row.try_parse().unwrap();
@@ -348,6 +382,10 @@ mod test {
                     time_start: Some(time_hm(9, 0)),
                     expired: Some(false),
                     duration: Some(1i16),
+                    valid_from: None,
+                    valid_until: None,
+                    program_id: None,
+                    capacity: None,
                 },
                 PartialAvailability {
                     id: None,
@@ -356,6 +394,10 @@ mod test {
                     time_start: Some(time_hm(10, 0)),
                     expired: Some(false),
                     duration: Some(1i16),
+                    valid_from: None,
+                    valid_until: None,
+                    program_id: None,
+                    capacity: None,
                 },
                 PartialAvailability {
                     id: None,
@@ -364,6 +406,10 @@ mod test {
                     time_start: Some(time_hm(20, 0)),
                     expired: Some(false),
                     duration: Some(1i16),
+                    valid_from: None,
+                    valid_until: None,
+                    program_id: None,
+                    capacity: None,
                 },
                 PartialAvailability {
                     id: None,
@@ -372,6 +418,10 @@ mod test {
                     time_start: Some(time_hm(21, 0)),
                     expired: Some(false),
                     duration: Some(1i16),
+                    valid_from: None,
+                    valid_until: None,
+                    program_id: None,
+                    capacity: None,
                 },
                 PartialAvailability {
                     id: None,
@@ -380,6 +430,10 @@ mod test {
                     time_start: Some(time_hm(12, 0)),
                     expired: Some(false),
                     duration: Some(1i16),
+                    valid_from: None,
+                    valid_until: None,
+                    program_id: None,
+                    capacity: None,
                 },
                 PartialAvailability {
                     id: None,
@@ -388,6 +442,10 @@ mod test {
                     time_start: Some(time_hm(13, 0)),
                     expired: Some(false),
                     duration: Some(1i16),
+                    valid_from: None,
+                    valid_until: None,
+                    program_id: None,
+                    capacity: None,
                 },
                 PartialAvailability {
                     id: None,
@@ -396,6 +454,10 @@ mod test {
                     time_start: Some(time_hm(10, 0)),
                     expired: Some(false),
                     duration: Some(1i16),
+                    valid_from: None,
+                    valid_until: None,
+                    program_id: None,
+                    capacity: None,
                 },
                 PartialAvailability {
                     id: None,
@@ -404,6 +466,10 @@ mod test {
                     time_start: Some(time_hm(17, 0)),
                     expired: Some(false),
                     duration: Some(1i16),
+                    valid_from: None,
+                    valid_until: None,
+                    program_id: None,
+                    capacity: None,
                 },
                 PartialAvailability {
                     id: None,
@@ -412,6 +478,10 @@ mod test {
                     time_start: Some(time_hm(18, 0)),
                     expired: Some(false),
                     duration: Some(1i16),
+                    valid_from: None,
+                    valid_until: None,
+                    program_id: None,
+                    capacity: None,
                 },
                 PartialAvailability {
                     id: None,
@@ -420,6 +490,10 @@ mod test {
                     time_start: Some(time_hm(19, 0)),
                     expired: Some(false),
                     duration: Some(1i16),
+                    valid_from: None,
+                    valid_until: None,
+                    program_id: None,
+                    capacity: None,
                 },
                 PartialAvailability {
                     id: None,
@@ -428,6 +502,10 @@ mod test {
                     time_start: Some(time_hm(20, 0)),
                     expired: Some(false),
                     duration: Some(1i16),
+                    valid_from: None,
+                    valid_until: None,
+                    program_id: None,
+                    capacity: None,
                 },
                 PartialAvailability {
                     id: None,
@@ -436,6 +514,10 @@ mod test {
                     time_start: Some(time_hm(21, 0)),
                     expired: Some(false),
                     duration: Some(1i16),
+                    valid_from: None,
+                    valid_until: None,
+                    program_id: None,
+                    capacity: None,
                 },
             ]
         );
@@ -0,0 +1,2 @@
+-- Allows mentors to interact with the bot as themselves
+ALTER TABLE teachers ADD COLUMN discord_id VARCHAR UNIQUE CHECK(discord_id ~ '^\d{1,20}$');
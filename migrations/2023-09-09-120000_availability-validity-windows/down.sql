@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE availability
+  DROP CONSTRAINT availability_validity_window_check,
+  DROP COLUMN valid_from,
+  DROP COLUMN valid_until;
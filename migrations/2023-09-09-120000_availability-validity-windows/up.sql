@@ -0,0 +1,6 @@
+-- Restricts the dates in which each availability can be scheduled (inclusive)
+ALTER TABLE availability
+  ADD COLUMN valid_from DATE,
+  ADD COLUMN valid_until DATE,
+  ADD CONSTRAINT availability_validity_window_check
+    CHECK (valid_from IS NULL OR valid_until IS NULL OR valid_from <= valid_until);
@@ -0,0 +1,8 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE availability
+  DROP COLUMN program_id;
+
+ALTER TABLE teachers
+  DROP COLUMN program_id;
+
+DROP TABLE programs;
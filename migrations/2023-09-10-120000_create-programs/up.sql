@@ -0,0 +1,28 @@
+-- Mentoring programs (cohorts), to which teachers and their availabilities are attached
+CREATE TABLE programs (
+  id BIGSERIAL PRIMARY KEY,
+  name VARCHAR NOT NULL UNIQUE CHECK (name <> ''),
+  start_date DATE NOT NULL,
+  end_date DATE NOT NULL,
+  status SMALLINT NOT NULL DEFAULT 0,
+  enrollment_open BOOLEAN NOT NULL DEFAULT FALSE,
+  CONSTRAINT programs_dates_check CHECK (start_date <= end_date)
+);
+
+ALTER TABLE teachers
+  ADD COLUMN program_id BIGINT REFERENCES programs (id) ON DELETE SET NULL;
+
+ALTER TABLE availability
+  ADD COLUMN program_id BIGINT REFERENCES programs (id) ON DELETE SET NULL;
+
+-- Existing teachers and availabilities are attached to a default open program,
+-- so that they remain schedulable after upgrading
+INSERT INTO programs (name, start_date, end_date, enrollment_open)
+  SELECT 'Default', CURRENT_DATE, DATE '9999-12-31', TRUE
+  WHERE EXISTS (SELECT 1 FROM teachers) OR EXISTS (SELECT 1 FROM availability);
+
+UPDATE teachers
+  SET program_id = (SELECT id FROM programs WHERE name = 'Default');
+
+UPDATE availability
+  SET program_id = (SELECT id FROM programs WHERE name = 'Default');
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE session_students;
+
+ALTER TABLE availability
+  DROP COLUMN capacity;
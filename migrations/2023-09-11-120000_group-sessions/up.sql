@@ -0,0 +1,14 @@
+-- How many students can attend the same session at each available time
+ALTER TABLE availability
+  ADD COLUMN capacity SMALLINT NOT NULL DEFAULT 1 CHECK (capacity >= 1);
+
+-- Students attending each session; 'sessions.student_id' is kept as the student who booked it
+CREATE TABLE session_students (
+  session_id BIGINT NOT NULL REFERENCES sessions (id) ON DELETE CASCADE,
+  student_id VARCHAR NOT NULL REFERENCES users (discord_id),
+  joined_at timestamp with time zone NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (session_id, student_id)
+);
+
+INSERT INTO session_students (session_id, student_id)
+  SELECT id, student_id FROM sessions;
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE sessions
+    DROP COLUMN cancel_reason,
+    DROP COLUMN cancelled_at,
+    DROP COLUMN status;
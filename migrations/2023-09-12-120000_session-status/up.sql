@@ -0,0 +1,5 @@
+-- 0 = scheduled, 1 = cancelled by student, 2 = cancelled by mentor, 3 = completed, 4 = no-show
+ALTER TABLE sessions
+    ADD COLUMN status SMALLINT NOT NULL DEFAULT 0 CHECK (status BETWEEN 0 AND 4),
+    ADD COLUMN cancelled_at TIMESTAMPTZ,
+    ADD COLUMN cancel_reason TEXT;
@@ -0,0 +1,8 @@
+-- Reminders already sent for each session, one per configured offset
+-- (in minutes before the session starts), so that they aren't sent twice.
+CREATE TABLE session_reminders (
+    session_id BIGINT NOT NULL REFERENCES sessions (id) ON DELETE CASCADE,
+    offset_minutes INTEGER NOT NULL CHECK (offset_minutes > 0),
+    sent_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (session_id, offset_minutes)
+);
@@ -0,0 +1,5 @@
+-- At most one scheduled session may claim each occurrence of an available time,
+-- so concurrent bookings of the same time can't both succeed
+CREATE UNIQUE INDEX sessions_scheduled_occurrence_key
+  ON sessions (availability_id, start_at)
+  WHERE status = 0;
@@ -0,0 +1,2 @@
+-- The IANA timezone in which times are shown to (and parsed from) each user
+ALTER TABLE users ADD COLUMN timezone VARCHAR NOT NULL DEFAULT 'America/Sao_Paulo';
@@ -0,0 +1,2 @@
+-- The IANA timezone in which each mentor's availability times are defined
+ALTER TABLE teachers ADD COLUMN timezone VARCHAR NOT NULL DEFAULT 'America/Sao_Paulo';
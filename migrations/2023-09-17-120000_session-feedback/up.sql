@@ -0,0 +1,16 @@
+-- Feedback (a rating from 1 to 5 and an optional comment) given on each session
+-- by its students and by its mentor, at most once per person
+CREATE TABLE session_feedback (
+    session_id BIGINT NOT NULL REFERENCES sessions (id) ON DELETE CASCADE,
+    author_id VARCHAR NOT NULL,
+    from_mentor BOOLEAN NOT NULL,
+    rating SMALLINT NOT NULL CHECK (rating BETWEEN 1 AND 5),
+    comment TEXT,
+    submitted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (session_id, author_id)
+);
+
+-- Whether the participants of each session were asked for feedback after it ended
+-- (existing sessions are considered as asked, so that past sessions aren't asked about)
+ALTER TABLE sessions ADD COLUMN feedback_requested BOOLEAN NOT NULL DEFAULT TRUE;
+ALTER TABLE sessions ALTER COLUMN feedback_requested SET DEFAULT FALSE;
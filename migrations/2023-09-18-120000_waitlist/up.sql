@@ -0,0 +1,20 @@
+-- Students waiting for a time to be available, optionally only with a specific mentor
+-- or specialty, in the order they joined. Each is offered (held for a limited time)
+-- a matching time when one opens up; statuses are 0 (waiting), 1 (offered),
+-- 2 (fulfilled), 3 (expired) and 4 (left).
+CREATE TABLE waitlist_entries (
+    id BIGSERIAL PRIMARY KEY,
+    student_id VARCHAR NOT NULL REFERENCES users (discord_id) ON DELETE CASCADE,
+    teacher_id BIGINT REFERENCES teachers (id) ON DELETE CASCADE,
+    specialty VARCHAR,
+    status SMALLINT NOT NULL DEFAULT 0,
+    joined_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    offered_availability_id BIGINT REFERENCES availability (id) ON DELETE SET NULL,
+    offered_start_at TIMESTAMPTZ,
+    offer_expires_at TIMESTAMPTZ
+);
+
+-- Each student may only be waiting (or have an offer) once at a time
+CREATE UNIQUE INDEX waitlist_entries_active_student_key
+    ON waitlist_entries (student_id)
+    WHERE status IN (0, 1);
@@ -0,0 +1,30 @@
+-- Normalized areas (e.g. "machine learning") extracted from the mentors' specialties,
+-- by which students can filter mentors
+CREATE TABLE tags (
+    id BIGSERIAL PRIMARY KEY,
+    name VARCHAR NOT NULL UNIQUE
+);
+
+-- The tags of each mentor
+CREATE TABLE teacher_tags (
+    teacher_id BIGINT NOT NULL REFERENCES teachers (id) ON DELETE CASCADE,
+    tag_id BIGINT NOT NULL REFERENCES tags (id) ON DELETE CASCADE,
+    PRIMARY KEY (teacher_id, tag_id)
+);
+
+-- Extract the tags of existing mentors, in the same way the bot does
+-- (splitting their specialty at separators, then lowercasing and collapsing spaces)
+CREATE TEMPORARY TABLE extracted_tags AS
+SELECT DISTINCT teachers.id AS teacher_id, btrim(regexp_replace(part, '\s+', ' ', 'g'), ' .') AS name
+FROM teachers,
+    regexp_split_to_table(lower(teachers.specialty), '[,;/|&\n]|\s(?:e|and)\s') AS part;
+
+DELETE FROM extracted_tags WHERE name = '';
+
+INSERT INTO tags (name) SELECT DISTINCT name FROM extracted_tags;
+
+INSERT INTO teacher_tags (teacher_id, tag_id)
+SELECT extracted_tags.teacher_id, tags.id
+FROM extracted_tags INNER JOIN tags ON tags.name = extracted_tags.name;
+
+DROP TABLE extracted_tags;
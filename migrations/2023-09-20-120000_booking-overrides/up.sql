@@ -0,0 +1,7 @@
+-- Students allowed by an administrator to book their next session regardless of
+-- the booking limits (the override is used up once they book it).
+CREATE TABLE booking_overrides (
+    student_id VARCHAR PRIMARY KEY REFERENCES users (discord_id) ON DELETE CASCADE,
+    granted_by VARCHAR NOT NULL,
+    granted_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
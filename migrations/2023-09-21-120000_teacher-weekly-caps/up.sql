@@ -0,0 +1,5 @@
+-- The most sessions each mentor wants to deliver per week (Monday to Sunday, in their
+-- timezone), after which their remaining times in that week are hidden; NULL for no cap
+ALTER TABLE teachers
+    ADD COLUMN max_sessions_per_week SMALLINT
+    CONSTRAINT teachers_max_sessions_per_week_check CHECK (max_sessions_per_week > 0);
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE teachers DROP COLUMN locale;
+ALTER TABLE users DROP COLUMN locale;
@@ -0,0 +1,4 @@
+-- The locale (e.g. 'pt-BR') in which each student and mentor gets their e-mails,
+-- taken from their Discord client; NULL if not known yet
+ALTER TABLE users ADD COLUMN locale VARCHAR(16);
+ALTER TABLE teachers ADD COLUMN locale VARCHAR(16);
@@ -0,0 +1,6 @@
+-- Discord users (students or linked mentors) who don't want to be notified of their
+-- sessions through DMs (they are still notified by e-mail).
+CREATE TABLE dm_opt_outs (
+    discord_id VARCHAR PRIMARY KEY,
+    opted_out_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);